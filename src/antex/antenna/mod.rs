@@ -39,6 +39,19 @@ impl std::str::FromStr for CalibrationMethod {
     }
 }
 
+impl std::fmt::Display for CalibrationMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Unknown => f.write_str(""),
+            Self::Chamber => f.write_str("CHAMBER"),
+            Self::Field => f.write_str("FIELD"),
+            Self::Robot => f.write_str("ROBOT"),
+            Self::Copied => f.write_str("COPIED"),
+            Self::Converted => f.write_str("CONVERTED"),
+        }
+    }
+}
+
 /// Calibration information
 #[derive(Default, Clone, Debug, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
@@ -0,0 +1,272 @@
+use std::io::{BufWriter, Write};
+
+use crate::{
+    antex::{
+        record::AntennaPhasePattern, Antenna, AntennaSpecific, FrequencyDependentData, Record,
+    },
+    fmt_rinex,
+    prelude::{Carrier, Epoch, FormattingError, Header},
+};
+
+use std::collections::HashMap;
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+/// Formats calibration date, reciprocal of the date parser.
+fn format_calibration_date(date: Epoch) -> String {
+    let (y, m, d, _, _, _, _) = date.to_gregorian_utc();
+    format!("{:02}-{}-{:02}", y - 2000, MONTHS[(m - 1) as usize], d)
+}
+
+/// Formats calibration validity (FROM/UNTIL) epoch
+fn format_validity_epoch(epoch: Epoch) -> String {
+    let (y, m, d, hh, mm, ss, nanos) = epoch.to_gregorian_utc();
+    format!(
+        "{:6}{:6}{:6}{:6}{:6}{:13.7}",
+        y,
+        m,
+        d,
+        hh,
+        mm,
+        ss as f64 + nanos as f64 * 1.0E-9
+    )
+}
+
+/// Formats one frequency block
+fn format_frequency<W: Write>(
+    w: &mut BufWriter<W>,
+    carrier: &Carrier,
+    data: &FrequencyDependentData,
+) -> Result<(), FormattingError> {
+    let sv = carrier.to_antex_sv()?;
+
+    writeln!(
+        w,
+        "{}",
+        fmt_rinex(&format!("   {:x}", sv), "START OF FREQUENCY")
+    )?;
+
+    let (north, east, up) = data.apc_eccentricity;
+    writeln!(
+        w,
+        "{}",
+        fmt_rinex(
+            &format!("{:10.2}{:10.2}{:10.2}", north, east, up),
+            "NORTH / EAST / UP"
+        )
+    )?;
+
+    match &data.phase_pattern {
+        AntennaPhasePattern::AzimuthIndependentPattern(values) => {
+            if !values.is_empty() {
+                let mut line = "   NOAZI".to_string();
+                for value in values.iter() {
+                    line.push_str(&format!("{:8.2}", value));
+                }
+                writeln!(w, "{:<60}", line)?;
+            }
+        },
+    }
+
+    writeln!(
+        w,
+        "{}",
+        fmt_rinex(&format!("   {:x}", sv), "END OF FREQUENCY")
+    )?;
+    Ok(())
+}
+
+/// Formats one [Antenna] block, with all its frequency dependent data
+fn format_antenna<W: Write>(
+    w: &mut BufWriter<W>,
+    antenna: &Antenna,
+    frequencies: &HashMap<Carrier, FrequencyDependentData>,
+) -> Result<(), FormattingError> {
+    writeln!(w, "{}", fmt_rinex("", "START OF ANTENNA"))?;
+
+    let specs = match &antenna.specific {
+        AntennaSpecific::RxAntenna(rx) => format!(
+            "{:<20}{:<20}",
+            rx.igs_type,
+            rx.serial_number.as_deref().unwrap_or("NONE")
+        ),
+        AntennaSpecific::SvAntenna(sv) => format!(
+            "{:<20}{:<20}{:<10}{:<10}",
+            sv.igs_type,
+            format!("{:x}", sv.sv),
+            "",
+            sv.cospar
+        ),
+    };
+
+    writeln!(w, "{}", fmt_rinex(&specs, "TYPE / SERIAL NO"))?;
+
+    let calibration = &antenna.calibration;
+    writeln!(
+        w,
+        "{}",
+        fmt_rinex(
+            &format!(
+                "{:<20}{:<20}{:>6}    {}",
+                calibration.method.to_string(),
+                calibration.agency,
+                calibration.number,
+                format_calibration_date(calibration.date),
+            ),
+            "METH / BY / # / DATE"
+        )
+    )?;
+
+    writeln!(
+        w,
+        "{}",
+        fmt_rinex(&format!("  {:6.1}", antenna.azi_inc), "DAZI")
+    )?;
+
+    let grid = &antenna.zenith_grid;
+    writeln!(
+        w,
+        "{}",
+        fmt_rinex(
+            &format!("  {:6.1}{:6.1}{:6.1}", grid.start, grid.end, grid.spacing),
+            "ZEN1 / ZEN2 / DZEN"
+        )
+    )?;
+
+    writeln!(
+        w,
+        "{}",
+        fmt_rinex(&format!("{:6}", frequencies.len()), "# OF FREQUENCIES")
+    )?;
+
+    if let Some((from, until)) = calibration.validity_period {
+        writeln!(
+            w,
+            "{}",
+            fmt_rinex(&format_validity_epoch(from), "VALID FROM")
+        )?;
+        writeln!(
+            w,
+            "{}",
+            fmt_rinex(&format_validity_epoch(until), "VALID UNTIL")
+        )?;
+    }
+
+    if !antenna.sinex_code.is_empty() {
+        writeln!(w, "{}", fmt_rinex(&antenna.sinex_code, "SINEX CODE"))?;
+    }
+
+    let mut carriers = frequencies.keys().collect::<Vec<_>>();
+    carriers.sort();
+
+    for carrier in carriers {
+        format_frequency(w, carrier, &frequencies[carrier])?;
+    }
+
+    writeln!(w, "{}", fmt_rinex("", "END OF ANTENNA"))?;
+    Ok(())
+}
+
+/// Formats ANTEX [Record] into [BufWriter]
+pub fn format<W: Write>(
+    w: &mut BufWriter<W>,
+    record: &Record,
+    _header: &Header,
+) -> Result<(), FormattingError> {
+    for (antenna, frequencies) in record.iter() {
+        format_antenna(w, antenna, frequencies)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::format_antenna;
+    use crate::{
+        antex::{
+            record::parse_antenna, Antenna, AntennaSpecific, Calibration, CalibrationMethod,
+            FrequencyDependentData, RxAntenna, SvAntenna,
+        },
+        linspace::Linspace,
+        prelude::{Carrier, Epoch, COSPAR, SV},
+        tests::formatting::Utf8Buffer,
+    };
+
+    use std::{collections::HashMap, io::BufWriter, str::FromStr};
+
+    #[test]
+    fn antex_antenna_reciprocal() {
+        let mut frequencies = HashMap::new();
+        frequencies.insert(
+            Carrier::L1,
+            FrequencyDependentData {
+                apc_eccentricity: (-0.22, -0.01, 154.88),
+                ..Default::default()
+            },
+        );
+        frequencies.insert(
+            Carrier::L5,
+            FrequencyDependentData {
+                apc_eccentricity: (0.34, -0.62, 164.34),
+                ..Default::default()
+            },
+        );
+
+        for antenna in [
+            Antenna::default()
+                .with_specificities(AntennaSpecific::RxAntenna(RxAntenna {
+                    igs_type: "TROSAR25.R4".to_string(),
+                    serial_number: Some("LEIT727259".to_string()),
+                }))
+                .with_calibration(Calibration {
+                    method: CalibrationMethod::Chamber,
+                    agency: "IGG, Univ. Bonn".to_string(),
+                    number: 1,
+                    date: Epoch::from_str("2023-09-20T00:00:00 UTC").unwrap(),
+                    validity_period: None,
+                })
+                .with_zenith_grid(Linspace {
+                    start: 0.0,
+                    end: 90.0,
+                    spacing: 5.0,
+                }),
+            Antenna::default()
+                .with_specificities(AntennaSpecific::SvAntenna(SvAntenna {
+                    igs_type: "BLOCK IIA".to_string(),
+                    sv: SV::from_str("G01").unwrap(),
+                    cospar: COSPAR::from_str("1992-079A").unwrap(),
+                }))
+                .with_calibration(Calibration {
+                    method: CalibrationMethod::Copied,
+                    agency: "ESA/CODE".to_string(),
+                    number: 0,
+                    date: Epoch::from_str("2017-01-10T00:00:00 UTC").unwrap(),
+                    validity_period: None,
+                })
+                .with_validity_period(
+                    Epoch::from_str("1992-11-22T00:00:00 UTC").unwrap(),
+                    Epoch::from_str("2008-10-16T23:59:59 UTC").unwrap(),
+                )
+                .with_zenith_grid(Linspace {
+                    start: 0.0,
+                    end: 17.0,
+                    spacing: 1.0,
+                }),
+        ] {
+            let mut buf = BufWriter::new(Utf8Buffer::new(4096));
+            format_antenna(&mut buf, &antenna, &frequencies).unwrap();
+
+            let formatted = buf.into_inner().unwrap().to_ascii_utf8();
+
+            for line in formatted.lines() {
+                assert!(line.len() >= 60, "invalid line \"{}\"", line);
+            }
+
+            let (parsed, parsed_frequencies) = parse_antenna(&formatted).unwrap();
+            assert_eq!(parsed, antenna);
+            assert_eq!(parsed_frequencies, frequencies);
+        }
+    }
+}
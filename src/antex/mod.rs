@@ -1,5 +1,6 @@
 //! Antex (ATX) - special RINEX, for antenna caracteristics
 pub mod antenna;
+mod formatting;
 pub mod frequency;
 pub mod pcv;
 pub mod record;
@@ -13,7 +14,9 @@ pub use antenna::{
 
 pub use record::{FrequencyDependentData, Record};
 
-use crate::prelude::FormattingError;
+pub(crate) use formatting::format;
+
+use crate::{fmt_rinex, prelude::FormattingError};
 
 use std::io::{BufWriter, Write};
 
@@ -32,7 +35,17 @@ pub struct HeaderFields {
 
 impl HeaderFields {
     /// Formats [HeaderFields] into [BufWriter].
-    pub(crate) fn format<W: Write>(&self, _w: &mut BufWriter<W>) -> Result<(), FormattingError> {
+    pub(crate) fn format<W: Write>(&self, w: &mut BufWriter<W>) -> Result<(), FormattingError> {
+        let mut content = match &self.pcv_type {
+            Pcv::Absolute => format!("{:<20}{:<20}", "A", ""),
+            Pcv::Relative(rel_type) => format!("{:<20}{:<20}", "R", rel_type),
+        };
+
+        if let Some(sn) = &self.reference_ant_sn {
+            content.push_str(sn);
+        }
+
+        writeln!(w, "{}", fmt_rinex(&content, "PCV TYPE / REFANT"))?;
         Ok(())
     }
 
@@ -55,4 +55,13 @@ pub enum Bibliography {
     /// [B2b](http://www.beidou.gov.cn/xt/gfxz/202008/P020230516558683155109.pdf)
     /// [B3I](http://www.beidou.gov.cn/xt/gfxz/201802/P020180209623601401189.pdf)
     BeiDouICD,
+
+    /// GLONASS Interface Control Document, Edition 5.1 (2008).
+    /// Orbit propagation (Runge-Kutta) in Appendix A.3.1.2.
+    /// [DOI](https://www.unavco.org/help/glossary/docs/ICD_GLONASS_5.1_(2008)_en.pdf)
+    GlonassICD,
+
+    /// RTCA DO-229: *Minimum Operational Performance Standards
+    /// for GPS/SBAS Airborne Equipment*. GEO orbit in Appendix A.4.4.11.
+    RtcaDo229,
 }
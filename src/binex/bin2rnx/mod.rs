@@ -0,0 +1,654 @@
+//! BINEX to RINEX deserialization
+use std::{collections::VecDeque, io::Read};
+
+use crate::{
+    binex::{
+        prototype::{self, MeteoRecord, ObservationRecord, PrototypeRecord},
+        rnx2bin, StreamMessage,
+    },
+    hardware::{Antenna, Receiver},
+    marker::GeodeticMarker,
+    navigation::NavFrame,
+    prelude::{Constellation, Duration, Epoch, Header, MeteoKey, Rinex, Version},
+    production::{Postponing, SnapshotMode},
+};
+
+use binex::prelude::{
+    EphemerisFrame, Error, GeoStringFrame, MonumentGeoMetadata, MonumentGeoRecord, Record,
+    Solutions, SolutionsFrame,
+};
+
+mod nav;
+
+#[cfg(feature = "log")]
+use log::{debug, error, info};
+
+/// Internal buffer size, that fits any open source message.
+/// It grows to fit larger prototyping records.
+const BUF_SIZE: usize = 4096;
+
+/// Largest frame we accept: prototyping records up to CRC32 protection.
+const MAX_FRAME_SIZE: usize = 1_048_576 + 16;
+
+/// [GeoStringFrame] identifiers are private to the BINEX library:
+/// we identify a frame by comparison with the one this builder would form.
+fn is_geo_frame(
+    frame: &GeoStringFrame,
+    builder: fn(&MonumentGeoRecord, &str) -> MonumentGeoRecord,
+) -> bool {
+    builder(&MonumentGeoRecord::default(), &frame.string)
+        .frames
+        .first()
+        .is_some_and(|fr| fr == frame)
+}
+
+/// BIN2RNX is a RINEX producer from a BINEX stream.
+/// It interprets the streamed messages and collects them as RINEX.
+/// The production behavior is defined by [SnapshotMode]: each [Iterator::next]
+/// call returns a [Rinex] once the snapshot is complete. The pending snapshot
+/// is published when reaching end of stream. Use [Rinex::to_file] (or any
+/// formatting method) to archive the published snapshots.
+///
+/// Currently supported messages:
+/// - decoded GPS, Galileo, Glonass and SBAS ephemerides,
+///   collected as Navigation [Rinex] (V4 by default, refer to [Self::with_nav_version]).
+///   Snapshot boundaries are defined by the time of transmission.
+///   Raw GPS subframes are ignored: the BINEX library does not expose their content.
+/// - Observation prototyping records (0x7f) forged by [crate::prelude::binex::RNX2BIN],
+///   collected as Observation [Rinex].
+/// - Ancillary site data prototyping records (0x7e) forged by [crate::prelude::binex::RNX2BIN],
+///   collected as Meteo [Rinex].
+/// - Geodetic monument messages, that define the [Header] fields
+///   (geodetic marker, observer, agency, receiver and antenna) and comments.
+/// - Solutions messages: the antenna ECEF position defines [Header::rx_position].
+pub struct BIN2RNX<R: Read> {
+    /// True when collecting is feasible
+    pub active: bool,
+    /// Collected size, for postponing mechanism
+    size: usize,
+    /// Snapshot mode
+    pub snapshot_mode: SnapshotMode,
+    /// Postponing option
+    pub postponing: Postponing,
+    /// Deploy time
+    deploy_t: Epoch,
+    /// Starting point of current snapshot
+    snapshot_t0: Option<Epoch>,
+    /// True when collecting CRINEX
+    crinex: bool,
+    /// Site and hardware description, shared by all snapshots
+    site: Header,
+    /// Pending NAV [Rinex]
+    nav_rinex: Rinex,
+    /// Pending OBS [Rinex]
+    obs_rinex: Rinex,
+    /// Pending Meteo [Rinex]
+    meteo_rinex: Rinex,
+    /// Published [Rinex] snapshots
+    published: VecDeque<Rinex>,
+    /// internal buffer
+    buf: Vec<u8>,
+    /// Number of available bytes in buffer
+    avail: usize,
+    /// True once the end of stream has been reached
+    eos: bool,
+    /// [Read]able interface
+    reader: R,
+}
+
+impl<R: Read> Iterator for BIN2RNX<R> {
+    type Item = Rinex;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(rinex) = self.published.pop_front() {
+                return Some(rinex);
+            }
+
+            let size = match self.next_frame() {
+                Some(size) => size,
+                None => {
+                    // end of stream: publish pending snapshot
+                    self.publish();
+                    return self.published.pop_front();
+                },
+            };
+
+            // frame is discarded once interpreted, sync byte only otherwise
+            let consumed = match StreamMessage::decode(&self.buf[..size]) {
+                Ok(msg) => {
+                    if self.active {
+                        self.collect(msg);
+                    } else {
+                        self.postponed(size);
+                    }
+                    size
+                },
+                Err(Error::ClosedSourceMessage(_meta)) => {
+                    #[cfg(feature = "log")]
+                    error!(
+                        "received closed source message: cannot interprate {:?}",
+                        _meta
+                    );
+                    size
+                },
+                Err(Error::NonSupportedMesssage(_)) => size,
+                Err(_e) => {
+                    #[cfg(feature = "log")]
+                    debug!("binex decoding error: {:?}", _e);
+                    1
+                },
+            };
+
+            self.buf.copy_within(consumed..self.avail, 0);
+            self.avail -= consumed;
+        }
+    }
+}
+
+impl<R: Read> BIN2RNX<R> {
+    /// Creates a new [BIN2RNX] working from [Read]able interface.
+    /// It will stream Tokens as long as the interface is alive.
+    ///
+    /// NB:
+    /// - [BIN2RNX] needs the system time to be determined for the postponing
+    /// mechanism. If determination fails, this method will panic.
+    /// We propose [Self::new_system_time] if you want to manually
+    /// define "now".
+    /// - since RINEX is fully open source, only open source BINEX messages
+    /// may be picked up and collected: closed source elements are discarded.
+    ///
+    /// ## Inputs
+    /// - crinex: set to true if you want to use the CRINEX compression
+    /// algorithm when collecting Observation RINEX.
+    /// - production rate control as [SnapshotMode]
+    /// - [Postponing] option
+    /// - read: [Read]able interface
+    pub fn new(crinex: bool, snapshot_mode: SnapshotMode, postponing: Postponing, read: R) -> Self {
+        Self::new_system_time(
+            crinex,
+            Epoch::now().unwrap_or_else(|e| panic!("system time determination failed with {}", e)),
+            snapshot_mode,
+            postponing,
+            read,
+        )
+    }
+
+    /// Infaillible [BIN2RNX] creation, use this if you have no means to access system time.
+    /// Define it yourself with "now". Refer to [Self::new] for more information.
+    ///
+    /// ## Inputs
+    /// - crinex: set to true if you want to use the CRINEX compression
+    /// algorithm when collecting Observation RINEX.
+    pub fn new_system_time(
+        crinex: bool,
+        now: Epoch,
+        snapshot_mode: SnapshotMode,
+        postponing: Postponing,
+        read: R,
+    ) -> Self {
+        Self {
+            size: 0,
+            crinex,
+            postponing,
+            snapshot_mode,
+            deploy_t: now,
+            snapshot_t0: None,
+            site: Header::default(),
+            nav_rinex: Rinex::basic_nav(),
+            obs_rinex: Self::basic_obs(crinex),
+            meteo_rinex: Rinex::basic_meteo(),
+            published: VecDeque::new(),
+            buf: vec![0; BUF_SIZE],
+            avail: 0,
+            eos: false,
+            reader: read,
+            active: postponing == Postponing::None,
+        }
+    }
+
+    /// Creates a new [BIN2RNX] that will collect a [Rinex] once a day at midnight,
+    /// with deployment possibly postponed.
+    ///
+    /// ## Inputs
+    /// - crinex: set to true if you want to use the CRINEX compression
+    /// algorithm when collecting Observation RINEX.
+    /// - [Postponing] option
+    /// - read: [Read]able interface
+    pub fn new_daily(crinex: bool, postponing: Postponing, read: R) -> Self {
+        Self::new(crinex, SnapshotMode::DailyMidnight, postponing, read)
+    }
+
+    /// Creates a new [BIN2RNX] that will collect a [Rinex] twice a day at midnight and noon,
+    /// with deployment possibly postponed.
+    ///
+    /// ## Inputs
+    /// - crinex: set to true if you want to use the CRINEX compression
+    /// algorithm when collecting Observation RINEX.
+    /// - [Postponing] option
+    /// - read: [Read]able interface
+    pub fn new_midnight_noon(crinex: bool, postponing: Postponing, read: R) -> Self {
+        Self::new(crinex, SnapshotMode::DailyMidnightNoon, postponing, read)
+    }
+
+    /// Creates a new [BIN2RNX] that will collect a [Rinex] hourly
+    /// with deployment possibly postponed.
+    ///
+    /// ## Inputs
+    /// - crinex: set to true if you want to use the CRINEX compression
+    /// algorithm when collecting Observation RINEX.
+    /// - [Postponing] option
+    /// - read: [Read]able interface
+    pub fn new_hourly(crinex: bool, postponing: Postponing, read: R) -> Self {
+        Self::new(crinex, SnapshotMode::Hourly, postponing, read)
+    }
+
+    /// Creates a new [BIN2RNX] that will collect a [Rinex] periodically,
+    /// with deployment possibly postponed.
+    /// ## Inputs
+    /// - crinex: set to true if you want to use the CRINEX compression
+    /// algorithm when collecting Observation RINEX.
+    /// - period: production period, as [Duration]
+    /// - [Postponing] option
+    /// - read: [Read]able interface
+    pub fn new_periodic(crinex: bool, period: Duration, postponing: Postponing, read: R) -> Self {
+        Self::new(crinex, SnapshotMode::Periodic(period), postponing, read)
+    }
+
+    fn postponed(&mut self, msg_size: usize) {
+        match self.postponing {
+            Postponing::SystemTime(t) => self.system_time_postponing(t),
+            Postponing::Size(size) => self.bytewise_postponing(msg_size, size),
+            Postponing::Messages(size) => self.protocol_postponing(size),
+            _ => unreachable!("no postponing!"),
+        }
+    }
+
+    /// Holds production until system time as reached specific instant
+    fn system_time_postponing(&mut self, t: Epoch) {
+        let now =
+            Epoch::now().unwrap_or_else(|e| panic!("system time determination failure: {}", e));
+
+        if now > t {
+            // todo log message
+            self.active = true;
+            self.deploy_t = now;
+        }
+    }
+
+    /// Collect "size" bytes until production is allowed
+    fn bytewise_postponing(&mut self, msg_size: usize, size: usize) {
+        self.size += msg_size;
+        if self.size >= size {
+            #[cfg(feature = "log")]
+            info!("bin2rnx now deployed: production is pending");
+            let now =
+                Epoch::now().unwrap_or_else(|e| panic!("system time determination failure: {}", e));
+            self.active = true;
+            self.deploy_t = now;
+        } else {
+            #[cfg(feature = "log")]
+            info!("binex postponing..");
+        }
+    }
+
+    /// Collect "size" messages until production is allowed
+    fn protocol_postponing(&mut self, size: usize) {
+        self.size += 1;
+        #[cfg(feature = "log")]
+        info!("binex postponing {}/{} messages", self.size, size);
+        if self.size >= size {
+            let now =
+                Epoch::now().unwrap_or_else(|e| panic!("system time determination failure: {}", e));
+            self.active = true;
+            self.deploy_t = now;
+            #[cfg(feature = "log")]
+            info!("bin2rnx now deployed: production is pending");
+        }
+    }
+
+    /// Obtain reference to collected Observation RINEX
+    pub fn obs_rinex(&self) -> &Rinex {
+        &self.obs_rinex
+    }
+
+    /// Obtain reference to collected Navigation RINEX
+    pub fn nav_rinex(&self) -> &Rinex {
+        &self.nav_rinex
+    }
+
+    /// Obtain reference to collected Meteo RINEX
+    pub fn meteo_rinex(&self) -> &Rinex {
+        &self.meteo_rinex
+    }
+
+    /// Returns [Epoch] of deployment
+    pub fn deploy_time(&self) -> Epoch {
+        self.deploy_t
+    }
+
+    /// Defines the Navigation RINEX revision to be produced.
+    /// Only V3 and V4 are supported, V4 being the default.
+    pub fn with_nav_version(mut self, version: Version) -> Self {
+        self.nav_rinex.header.version = version;
+        self
+    }
+
+    fn basic_obs(crinex: bool) -> Rinex {
+        if crinex {
+            Rinex::basic_crinex()
+        } else {
+            Rinex::basic_obs()
+        }
+    }
+
+    /// Locates the next BINEX frame, moved to the start of the internal buffer.
+    /// Returns its size, or None once the stream has been fully consumed.
+    fn next_frame(&mut self) -> Option<usize> {
+        loop {
+            // discard everything preceding the sync byte
+            let sync = self.buf[..self.avail]
+                .iter()
+                .position(|byte| prototype::is_sync_byte(*byte))
+                .unwrap_or(self.avail);
+
+            self.buf.copy_within(sync..self.avail, 0);
+            self.avail -= sync;
+
+            let resync = match prototype::frame_size(&self.buf[..self.avail]) {
+                Ok(size) if size > MAX_FRAME_SIZE => true,
+                Ok(size) if size <= self.avail => return Some(size),
+                Ok(size) => {
+                    if self.buf.len() < size {
+                        self.buf.resize(size, 0);
+                    }
+                    self.eos
+                },
+                Err(Error::NotEnoughBytes) => self.eos,
+                // reversed streams are not supported
+                Err(_) => true,
+            };
+
+            if resync {
+                if self.avail == 0 {
+                    return None;
+                }
+                // invalid or truncated frame: skip this sync byte
+                self.buf.copy_within(1..self.avail, 0);
+                self.avail -= 1;
+                continue;
+            }
+
+            match self.reader.read(&mut self.buf[self.avail..]) {
+                Ok(0) => self.eos = true,
+                Ok(size) => self.avail += size,
+                Err(_e) => {
+                    #[cfg(feature = "log")]
+                    error!("bin2rnx: i/o error: {}", _e);
+                    self.eos = true;
+                },
+            }
+        }
+    }
+
+    /// Collects this [StreamMessage]
+    fn collect(&mut self, msg: StreamMessage) {
+        match msg {
+            StreamMessage::Message(msg) => match msg.record {
+                Record::EphemerisFrame(fr) => self.collect_ephemeris(&fr),
+                Record::MonumentGeo(geo) => self.collect_monument_geo(&geo),
+                Record::Solutions(pvt) => self.collect_solutions(&pvt),
+            },
+            StreamMessage::Prototype(msg) => match msg.record {
+                PrototypeRecord::Observation(rec) => self.collect_observations(rec),
+                PrototypeRecord::Meteo(rec) => self.collect_meteo(rec),
+            },
+        }
+    }
+
+    /// Collects this [ObservationRecord] as Observation
+    fn collect_observations(&mut self, rec: ObservationRecord) {
+        self.new_snapshot(rec.key.epoch);
+
+        let obs_header = self
+            .obs_rinex
+            .header
+            .obs
+            .get_or_insert_with(Default::default);
+
+        for signal in rec.observations.signals.iter() {
+            let constellation = if signal.sv.constellation.is_sbas() {
+                Constellation::SBAS
+            } else {
+                signal.sv.constellation
+            };
+
+            let codes = obs_header.codes.entry(constellation).or_default();
+
+            if !codes.contains(&signal.observable) {
+                codes.push(signal.observable.clone());
+            }
+        }
+
+        let record = self
+            .obs_rinex
+            .record
+            .as_mut_obs()
+            .expect("internal error: invalid obs record");
+
+        record.insert(rec.key, rec.observations);
+    }
+
+    /// Collects this [MeteoRecord] as Meteo
+    fn collect_meteo(&mut self, rec: MeteoRecord) {
+        self.new_snapshot(rec.epoch);
+
+        let meteo_header = self
+            .meteo_rinex
+            .header
+            .meteo
+            .get_or_insert_with(Default::default);
+
+        for (observable, _) in rec.observations.iter() {
+            if !meteo_header.codes.contains(observable) {
+                meteo_header.codes.push(observable.clone());
+            }
+        }
+
+        let record = self
+            .meteo_rinex
+            .record
+            .as_mut_meteo()
+            .expect("internal error: invalid meteo record");
+
+        for (observable, value) in rec.observations {
+            let key = MeteoKey {
+                epoch: rec.epoch,
+                observable,
+            };
+            record.insert(key, value);
+        }
+    }
+
+    /// Collects this [EphemerisFrame] as Navigation
+    fn collect_ephemeris(&mut self, fr: &EphemerisFrame) {
+        let version = self.nav_rinex.header.version;
+
+        let (key, ephemeris, t_tm) = match nav::ephemeris_frame(fr, version) {
+            Some(content) => content,
+            None => {
+                #[cfg(feature = "log")]
+                debug!("bin2rnx: raw ephemeris frames are not supported");
+                return;
+            },
+        };
+
+        self.new_snapshot(t_tm);
+
+        let record = self
+            .nav_rinex
+            .record
+            .as_mut_nav()
+            .expect("internal error: invalid nav record");
+
+        // ephemerides are periodically repeated: latest copy prevails
+        record.insert(key, NavFrame::EPH(ephemeris));
+    }
+
+    /// Collects this [MonumentGeoRecord] as [Header] fields
+    fn collect_monument_geo(&mut self, geo: &MonumentGeoRecord) {
+        let site = &mut self.site;
+
+        // our own stream announcements are not RINEX comments
+        let announced = geo.meta == MonumentGeoMetadata::RNX2BIN;
+
+        site.comments.extend(
+            geo.comments
+                .iter()
+                .filter(|comment| !announced || !rnx2bin::is_announcement(comment))
+                .cloned(),
+        );
+
+        for fr in geo.frames.iter() {
+            let value = fr.string.trim();
+
+            if is_geo_frame(fr, MonumentGeoRecord::with_geodetic_marker_name) {
+                let marker = site
+                    .geodetic_marker
+                    .get_or_insert_with(GeodeticMarker::default);
+                *marker = marker.with_name(value);
+            } else if is_geo_frame(fr, MonumentGeoRecord::with_geodetic_marker_number) {
+                let marker = site
+                    .geodetic_marker
+                    .get_or_insert_with(GeodeticMarker::default);
+                *marker = marker.with_number(value);
+            } else if is_geo_frame(fr, MonumentGeoRecord::with_observer) {
+                site.observer = Some(value.to_string());
+            } else if is_geo_frame(fr, MonumentGeoRecord::with_agency) {
+                site.agency = Some(value.to_string());
+            } else if is_geo_frame(fr, MonumentGeoRecord::with_receiver_model) {
+                let rcvr = site.rcvr.get_or_insert_with(Receiver::default);
+                *rcvr = rcvr.with_model(value);
+            } else if is_geo_frame(fr, MonumentGeoRecord::with_receiver_serial_number) {
+                let rcvr = site.rcvr.get_or_insert_with(Receiver::default);
+                *rcvr = rcvr.with_serial_number(value);
+            } else if is_geo_frame(fr, MonumentGeoRecord::with_receiver_firmware_version) {
+                let rcvr = site.rcvr.get_or_insert_with(Receiver::default);
+                *rcvr = rcvr.with_firmware(value);
+            } else if is_geo_frame(fr, MonumentGeoRecord::with_antenna_model) {
+                let antenna = site.rcvr_antenna.get_or_insert_with(Antenna::default);
+                *antenna = antenna.with_model(value);
+            } else if is_geo_frame(fr, MonumentGeoRecord::with_antenna_serial_number) {
+                let antenna = site.rcvr_antenna.get_or_insert_with(Antenna::default);
+                *antenna = antenna.with_serial_number(value);
+            } else if is_geo_frame(fr, MonumentGeoRecord::with_software_name) {
+                site.program = Some(value.to_string());
+            }
+        }
+    }
+
+    /// Collects these [Solutions] as [Header] fields
+    fn collect_solutions(&mut self, pvt: &Solutions) {
+        for fr in pvt.frames.iter() {
+            match fr {
+                SolutionsFrame::AntennaEcefPosition(ecef) => {
+                    self.site.rx_position = Some((ecef.x_ecef_m, ecef.y_ecef_m, ecef.z_ecef_m));
+                },
+                SolutionsFrame::Comment(comment) => {
+                    self.site.comments.push(comment.clone());
+                },
+                _ => {},
+            }
+        }
+    }
+
+    /// Publishes current snapshot, when this [Epoch] no longer belongs to it.
+    fn new_snapshot(&mut self, epoch: Epoch) {
+        match self.snapshot_t0 {
+            Some(t0) => {
+                if self.snapshot_mode.is_new_snapshot(t0, epoch) {
+                    self.publish();
+                    self.snapshot_t0 = Some(epoch);
+                }
+            },
+            None => self.snapshot_t0 = Some(epoch),
+        }
+    }
+
+    /// Describes the site in this [Header]
+    fn describe_site(&self, header: &mut Header) {
+        header.program = self.site.program.clone();
+        header.observer = self.site.observer.clone();
+        header.agency = self.site.agency.clone();
+        header.geodetic_marker = self.site.geodetic_marker.clone();
+        header.rcvr = self.site.rcvr.clone();
+        header.rcvr_antenna = self.site.rcvr_antenna.clone();
+        header.rx_position = self.site.rx_position;
+        header.comments = self.site.comments.clone();
+    }
+
+    /// Publishes pending [Rinex] (if any)
+    fn publish(&mut self) {
+        let mut published = false;
+
+        let obs_rinex = Self::basic_obs(self.crinex);
+        let mut obs_rinex = std::mem::replace(&mut self.obs_rinex, obs_rinex);
+
+        let bounds = obs_rinex
+            .record
+            .as_obs()
+            .and_then(|rec| Some((rec.keys().next()?.epoch, rec.keys().last()?.epoch)));
+
+        if let Some((first, last)) = bounds {
+            if let Some(obs) = &mut obs_rinex.header.obs {
+                obs.timeof_first_obs = Some(first);
+                obs.timeof_last_obs = Some(last);
+            }
+
+            self.describe_site(&mut obs_rinex.header);
+
+            #[cfg(feature = "log")]
+            info!(
+                "bin2rnx: publishing {} - {} observation snapshot",
+                first, last
+            );
+
+            self.published.push_back(obs_rinex);
+            published = true;
+        }
+
+        let mut meteo_rinex = std::mem::replace(&mut self.meteo_rinex, Rinex::basic_meteo());
+
+        if meteo_rinex
+            .record
+            .as_meteo()
+            .is_some_and(|rec| !rec.is_empty())
+        {
+            self.describe_site(&mut meteo_rinex.header);
+
+            #[cfg(feature = "log")]
+            info!("bin2rnx: publishing meteo snapshot");
+
+            self.published.push_back(meteo_rinex);
+            published = true;
+        }
+
+        let mut nav_rinex = Rinex::basic_nav();
+        nav_rinex.header.version = self.nav_rinex.header.version;
+        let mut nav_rinex = std::mem::replace(&mut self.nav_rinex, nav_rinex);
+
+        if nav_rinex.record.as_nav().is_some_and(|rec| !rec.is_empty()) {
+            self.describe_site(&mut nav_rinex.header);
+
+            #[cfg(feature = "log")]
+            info!("bin2rnx: publishing navigation snapshot");
+
+            self.published.push_back(nav_rinex);
+            published = true;
+        }
+
+        // comments are only published once
+        if published {
+            self.site.comments.clear();
+        }
+    }
+}
@@ -0,0 +1,290 @@
+//! BINEX ephemeris frames interpretation
+use std::{f64::consts::PI, str::FromStr};
+
+use crate::{
+    navigation::{Ephemeris, NavFrameType, NavKey, NavMessageType},
+    prelude::{Constellation, Epoch, TimeScale, Version, SV},
+};
+
+use binex::prelude::{EphemerisFrame, GALEphemeris, GLOEphemeris, GPSEphemeris, SBASEphemeris};
+
+use hifitime::Unit;
+
+/// Half a week, in seconds
+const HALF_WEEK_S: f64 = 302_400.0;
+
+/// Galileo data source (bit 1): F/NAV E5a-I
+const GAL_FNAV_SOURCE: u16 = 0x02;
+
+/// Forms the [Epoch] at this time of week, in this [TimeScale]
+fn time_of_week(week: u32, seconds: f64, ts: TimeScale) -> Epoch {
+    Epoch::from_time_of_week(week, 0, ts) + seconds * Unit::Second
+}
+
+/// Time of transmission, expressed in the week of the reference [Epoch]
+/// (the transmission may belong to the previous week).
+fn transmission(t_ref: Epoch, week: u32, tow: f64, ts: TimeScale) -> Epoch {
+    let t = time_of_week(week, tow, ts);
+    if (t - t_ref).to_seconds() > HALF_WEEK_S {
+        t - 1.0 * Unit::Week
+    } else {
+        t
+    }
+}
+
+/// Seconds within the UTC week (starting on sunday) of this [Epoch]
+fn seconds_of_utc_week(t: Epoch) -> f64 {
+    let t = t.to_time_scale(TimeScale::UTC);
+    let days = (t.weekday_utc() as u8 + 1) % 7;
+    days as f64 * 86_400.0 + (t - t.floor(1.0 * Unit::Day)).to_seconds()
+}
+
+/// Builds the [Ephemeris] described by these clock terms and named values,
+/// following the NAV standards of this revision.
+fn forge_ephemeris(
+    sv: SV,
+    msgtype: NavMessageType,
+    version: Version,
+    clock: (f64, f64, f64),
+    values: &[(&str, f64)],
+) -> Ephemeris {
+    Ephemeris {
+        clock_bias: clock.0,
+        clock_drift: clock.1,
+        clock_drift_rate: clock.2,
+        orbits: Default::default(),
+    }
+    .with_standard_orbits(sv.constellation, version, msgtype, values)
+}
+
+fn gps_ephemeris(eph: &GPSEphemeris, version: Version) -> (NavKey, Ephemeris, Epoch) {
+    let sv = SV::new(Constellation::GPS, eph.sv_prn);
+    let week = eph.toe as u32;
+    let toc = time_of_week(week, eph.toc as f64, TimeScale::GPST);
+    let t_tm = transmission(toc, week, eph.tow as f64, TimeScale::GPST);
+
+    let ephemeris = forge_ephemeris(
+        sv,
+        NavMessageType::LNAV,
+        version,
+        (
+            eph.clock_offset as f64,
+            eph.clock_drift as f64,
+            eph.clock_drift_rate as f64,
+        ),
+        &[
+            ("iode", eph.iode as f64),
+            ("crs", eph.crs as f64),
+            ("deltaN", eph.delta_n_rad_s as f64),
+            ("m0", eph.m0_rad),
+            ("cuc", eph.cuc as f64),
+            ("e", eph.e),
+            ("cus", eph.cus as f64),
+            ("sqrta", eph.sqrt_a),
+            ("toe", eph.toc as f64),
+            ("cic", eph.cic as f64),
+            ("omega0", eph.omega_0_rad),
+            ("cis", eph.cis as f64),
+            ("i0", eph.i0_rad),
+            ("crc", eph.crc as f64),
+            ("omega", eph.omega_rad),
+            ("omegaDot", eph.omega_dot_rad_s as f64),
+            ("idot", eph.i_dot_rad_s as f64),
+            ("week", week as f64),
+            ("accuracy", eph.ura_m as f64),
+            ("health", eph.sv_health as f64),
+            ("tgd", eph.tgd as f64),
+            ("iodc", eph.iodc as f64),
+            ("t_tm", eph.tow as f64),
+        ],
+    );
+
+    let key = NavKey {
+        epoch: toc,
+        sv,
+        msgtype: NavMessageType::LNAV,
+        frmtype: NavFrameType::Ephemeris,
+    };
+
+    (key, ephemeris, t_tm)
+}
+
+fn gal_ephemeris(eph: &GALEphemeris, version: Version) -> (NavKey, Ephemeris, Epoch) {
+    let sv = SV::new(Constellation::Galileo, eph.sv_prn);
+    let week = eph.toe_week as u32;
+    let toc = time_of_week(week, eph.toe_s as f64, TimeScale::GST);
+    let t_tm = transmission(toc, week, eph.tow as f64, TimeScale::GST);
+
+    let msgtype = if version.major < 4 {
+        NavMessageType::LNAV
+    } else if eph.source & GAL_FNAV_SOURCE > 0 {
+        NavMessageType::FNAV
+    } else {
+        NavMessageType::INAV
+    };
+
+    let ephemeris = forge_ephemeris(
+        sv,
+        msgtype,
+        version,
+        (
+            eph.clock_offset as f64,
+            eph.clock_drift as f64,
+            eph.clock_drift_rate as f64,
+        ),
+        &[
+            ("iodnav", eph.iodnav as f64),
+            ("crs", eph.crs as f64),
+            ("deltaN", eph.delta_n_semi_circles_s as f64 * PI),
+            ("m0", eph.m0_rad),
+            ("cuc", eph.cuc as f64),
+            ("e", eph.e),
+            ("cus", eph.cus as f64),
+            ("sqrta", eph.sqrt_a),
+            ("toe", eph.toe_s as f64),
+            ("cic", eph.cic as f64),
+            ("omega0", eph.omega_0_rad),
+            ("cis", eph.cis as f64),
+            ("i0", eph.i0_rad),
+            ("crc", eph.crc as f64),
+            ("omega", eph.omega_rad),
+            ("omegaDot", eph.omega_dot_semi_circles as f64 * PI),
+            ("idot", eph.idot_semi_circles_s as f64 * PI),
+            ("source", eph.source as f64),
+            // RINEX: continuous GPS week counter
+            ("week", (week + 1024) as f64),
+            ("sisa", eph.sisa as f64),
+            ("health", eph.sv_health as f64),
+            ("bgdE5aE1", eph.bgd_e5a_e1_s as f64),
+            ("bgdE5bE1", eph.bgd_e5b_e1_s as f64),
+            ("t_tm", eph.tow as f64),
+        ],
+    );
+
+    let key = NavKey {
+        epoch: toc,
+        sv,
+        msgtype,
+        frmtype: NavFrameType::Ephemeris,
+    };
+
+    (key, ephemeris, t_tm)
+}
+
+fn glo_ephemeris(eph: &GLOEphemeris, version: Version) -> (NavKey, Ephemeris, Epoch) {
+    let sv = SV::new(Constellation::Glonass, eph.slot);
+
+    // day counter (since GPS origin) and time of day, in UTC
+    let day = Epoch::from_gregorian_utc_at_midnight(1980, 1, 6) + (eph.day as f64) * Unit::Day;
+    let toc = day + (eph.tod_s as f64) * Unit::Second;
+    let t_k = day + (eph.t_k_sec as f64) * Unit::Second;
+
+    let msgtype = if version.major < 4 {
+        NavMessageType::LNAV
+    } else {
+        NavMessageType::FDMA
+    };
+
+    let ephemeris = forge_ephemeris(
+        sv,
+        msgtype,
+        version,
+        (
+            eph.clock_offset_s,
+            eph.clock_rel_freq_bias,
+            seconds_of_utc_week(t_k),
+        ),
+        &[
+            ("satPosX", eph.x_km),
+            ("velX", eph.vel_x_km),
+            ("accelX", eph.acc_x_km),
+            ("health", eph.sv_health as f64),
+            ("satPosY", eph.y_km),
+            ("velY", eph.vel_y_km),
+            ("accelY", eph.acc_y_km),
+            ("channel", eph.freq_channel as f64),
+            ("satPosZ", eph.z_km),
+            ("velZ", eph.vel_z_km),
+            ("accelZ", eph.acc_z_km),
+            ("ageOp", eph.age_op_days as f64),
+            ("L1L2GrpDelay", eph.l1_l2_gd),
+        ],
+    );
+
+    let key = NavKey {
+        epoch: toc,
+        sv,
+        msgtype,
+        frmtype: NavFrameType::Ephemeris,
+    };
+
+    (key, ephemeris, t_k)
+}
+
+fn sbas_ephemeris(eph: &SBASEphemeris, version: Version) -> Option<(NavKey, Ephemeris, Epoch)> {
+    // RINEX describes SBAS vehicles by PRN - 100
+    let prn = if eph.sbas_prn >= 100 {
+        eph.sbas_prn - 100
+    } else {
+        eph.sbas_prn
+    };
+
+    let sv = SV::from_str(&format!("S{:02}", prn)).ok()?;
+
+    let week = eph.toe as u32;
+    let toc = time_of_week(week, eph.tow as f64, TimeScale::GPST);
+
+    let msgtype = if version.major < 4 {
+        NavMessageType::LNAV
+    } else {
+        NavMessageType::SBAS
+    };
+
+    let ephemeris = forge_ephemeris(
+        sv,
+        msgtype,
+        version,
+        (eph.clock_offset, eph.clock_drift, eph.tow as f64),
+        &[
+            ("satPosX", eph.x_km),
+            ("velX", eph.vel_x_km),
+            ("accelX", eph.acc_x_km),
+            ("health", eph.uint1 as f64),
+            ("satPosY", eph.y_km),
+            ("velY", eph.vel_y_km),
+            ("accelY", eph.acc_y_km),
+            ("accuracyCode", eph.ura as f64),
+            ("satPosZ", eph.z_km),
+            ("velZ", eph.vel_z_km),
+            ("accelZ", eph.acc_z_km),
+            ("iodn", eph.iodn as f64),
+        ],
+    );
+
+    let key = NavKey {
+        epoch: toc,
+        sv,
+        msgtype,
+        frmtype: NavFrameType::Ephemeris,
+    };
+
+    Some((key, ephemeris, toc))
+}
+
+/// Interprets this [EphemerisFrame], following the NAV standards of this revision.
+/// Returns the [NavKey], the [Ephemeris] and the [Epoch] of transmission,
+/// or None for raw (undecoded) frames.
+pub fn ephemeris_frame(
+    fr: &EphemerisFrame,
+    version: Version,
+) -> Option<(NavKey, Ephemeris, Epoch)> {
+    match fr {
+        EphemerisFrame::GPS(eph) => Some(gps_ephemeris(eph, version)),
+        EphemerisFrame::GAL(eph) => Some(gal_ephemeris(eph, version)),
+        EphemerisFrame::GLO(eph) => Some(glo_ephemeris(eph, version)),
+        EphemerisFrame::SBAS(eph) => sbas_ephemeris(eph, version),
+        // raw subframes are not exposed by the BINEX library (private content),
+        // they cannot be decoded
+        EphemerisFrame::GPSRaw(_) => None,
+    }
+}
@@ -3,3 +3,9 @@ pub use bin2rnx::BIN2RNX;
 
 mod rnx2bin;
 pub use rnx2bin::RNX2BIN;
+
+mod prototype;
+pub use prototype::{MeteoRecord, ObservationRecord, PrototypeMessage, PrototypeRecord};
+
+mod stream;
+pub use stream::StreamMessage;
@@ -0,0 +1,63 @@
+//! Ancillary site data prototyping record (0x7e)
+use std::str::FromStr;
+
+use crate::prelude::{Epoch, Observable};
+
+use binex::prelude::Error;
+
+use super::{ByteReader, ByteWriter};
+
+/// [MeteoRecord] wraps one Meteo RINEX epoch.
+/// Payload (following the subrecord ID):
+/// - time tag (1 byte timescale, 4 byte minutes, 2 byte ms, 4 byte ns)
+/// - 1 byte number of observations, each one described by
+///   a length prefixed [Observable] and its f64 value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeteoRecord {
+    /// [Epoch] of observation
+    pub epoch: Epoch,
+    /// Observations at this epoch
+    pub observations: Vec<(Observable, f64)>,
+}
+
+impl MeteoRecord {
+    /// Builds a new [MeteoRecord]
+    pub fn new(epoch: Epoch, observations: Vec<(Observable, f64)>) -> Self {
+        Self {
+            epoch,
+            observations,
+        }
+    }
+
+    pub(super) fn encode(&self, w: &mut ByteWriter) -> Result<(), Error> {
+        if self.observations.len() > u8::MAX as usize {
+            return Err(Error::TooLargeInternalLimitation);
+        }
+
+        w.epoch(self.epoch)?;
+        w.u8(self.observations.len() as u8);
+
+        for (observable, value) in self.observations.iter() {
+            w.string(&observable.to_string());
+            w.f64(*value);
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn decode(r: &mut ByteReader) -> Result<Self, Error> {
+        let epoch = r.epoch()?;
+        let mut observations = Vec::new();
+
+        for _ in 0..r.u8()? {
+            let observable =
+                Observable::from_str(r.string()?).map_err(|_| Error::NonSupportedSubRecord)?;
+            observations.push((observable, r.f64()?));
+        }
+
+        Ok(Self {
+            epoch,
+            observations,
+        })
+    }
+}
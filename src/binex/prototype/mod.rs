@@ -0,0 +1,585 @@
+//! BINEX prototyping records, that our BINEX library does not describe.
+//! We use them to stream Observation (0x7f) and Meteo (0x7e) RINEX records.
+use crate::prelude::{Duration, Epoch, TimeScale};
+
+use binex::prelude::{Error, Meta};
+
+mod meteo;
+mod obs;
+
+pub use meteo::MeteoRecord;
+pub use obs::ObservationRecord;
+
+/// Forward stream, big endian, standard CRC
+const SYNC_BE: u8 = 0xe2;
+
+/// Forward stream, little endian, standard CRC
+const SYNC_LE: u8 = 0xc2;
+
+/// Sync bytes of the enhanced CRC forward streams (BE, LE)
+const SYNC_ENHANCED: [u8; 2] = [0xe8, 0xc8];
+
+/// Sync bytes of the reversed streams
+const SYNC_REVERSED: [u8; 4] = [0xf2, 0xd2, 0xf8, 0xd8];
+
+/// Observation prototyping record ID
+const OBSERVATION_MID: u8 = 0x7f;
+
+/// Ancillary site data (meteo) prototyping record ID
+const METEO_MID: u8 = 0x7e;
+
+/// Subrecord ID we use to describe RINEX records
+const RINEX_SUBRECORD: u8 = 0x80;
+
+/// Largest payload we support: MD5 protected records are not supported.
+const MAX_MLEN: usize = 1_048_576;
+
+/// [TimeScale]s our time tags may be expressed in (ID = index)
+const TIMESCALES: [TimeScale; 6] = [
+    TimeScale::GPST,
+    TimeScale::GST,
+    TimeScale::BDT,
+    TimeScale::QZSST,
+    TimeScale::UTC,
+    TimeScale::TAI,
+];
+
+/// Builds the CRC table of this 16 bit polynomial
+const fn crc16_table(poly: u16) -> [u16; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = (i as u16) << 8;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ poly
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// Builds the CRC table of this 32 bit polynomial
+const fn crc32_table(poly: u32) -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = (i as u32) << 24;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x8000_0000 != 0 {
+                (crc << 1) ^ poly
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// CRC-16 (CCITT) table
+const CRC16_TABLE: [u16; 256] = crc16_table(0x1021);
+
+/// CRC-32 (IEEE 802.3) table
+const CRC32_TABLE: [u32; 256] = crc32_table(0x04c1_1db7);
+
+/// Checksum length for this payload length
+fn checksum_len(mlen: usize, enhanced_crc: bool) -> usize {
+    if enhanced_crc {
+        if mlen < 128 {
+            2
+        } else if mlen < MAX_MLEN {
+            4
+        } else {
+            16
+        }
+    } else if mlen < 128 {
+        1
+    } else if mlen < 4096 {
+        2
+    } else if mlen < MAX_MLEN {
+        4
+    } else {
+        16
+    }
+}
+
+/// CRC-16 of these bytes
+fn crc16(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0, |crc, b| {
+        (crc << 8) ^ CRC16_TABLE[((crc >> 8) as u8 ^ b) as usize]
+    })
+}
+
+/// CRC-32 of these bytes
+fn crc32(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0, |crc, b| {
+        (crc << 8) ^ CRC32_TABLE[((crc >> 24) as u8 ^ b) as usize]
+    })
+}
+
+/// Standard checksum of these bytes, as (value, length)
+fn checksum(bytes: &[u8]) -> (u32, usize) {
+    let mlen = bytes.len();
+    if mlen < 128 {
+        (bytes.iter().fold(0, |xor, b| xor ^ b) as u32, 1)
+    } else if mlen < 4096 {
+        (crc16(bytes) as u32, 2)
+    } else {
+        (crc32(bytes), 4)
+    }
+}
+
+/// Number of bytes to encode this value as 1-4 BNXI
+fn bnxi_size(val: u32) -> usize {
+    if val < 0x80 {
+        1
+    } else if val < 0x4000 {
+        2
+    } else if val < 0x20_0000 {
+        3
+    } else {
+        4
+    }
+}
+
+/// Encodes this value as 1-4 BNXI: continuation bytes come first.
+fn encode_bnxi(val: u32, buf: &mut Vec<u8>) {
+    match bnxi_size(val) {
+        1 => buf.push(val as u8),
+        2 => buf.extend([(val >> 7) as u8 | 0x80, val as u8 & 0x7f]),
+        3 => buf.extend([
+            (val >> 14) as u8 | 0x80,
+            (val >> 7) as u8 | 0x80,
+            val as u8 & 0x7f,
+        ]),
+        _ => buf.extend([
+            (val >> 22) as u8 | 0x80,
+            (val >> 15) as u8 | 0x80,
+            (val >> 8) as u8 | 0x80,
+            val as u8,
+        ]),
+    }
+}
+
+/// Decodes 1-4 BNXI, returns (value, size)
+fn decode_bnxi(buf: &[u8]) -> Result<(u32, usize), Error> {
+    let mut val = 0_u32;
+    for (i, byte) in buf.iter().take(4).enumerate() {
+        if i == 3 {
+            return Ok(((val << 8) | *byte as u32, 4));
+        }
+        val = (val << 7) | (*byte & 0x7f) as u32;
+        if byte & 0x80 == 0 {
+            return Ok((val, i + 1));
+        }
+    }
+    Err(Error::NotEnoughBytes)
+}
+
+/// Returns the total size of the frame starting at this sync byte,
+/// once its header can be interpreted.
+pub(crate) fn frame_size(buf: &[u8]) -> Result<usize, Error> {
+    let sync = *buf.first().ok_or(Error::NotEnoughBytes)?;
+
+    let enhanced_crc = if sync == SYNC_BE || sync == SYNC_LE {
+        false
+    } else if SYNC_ENHANCED.contains(&sync) {
+        true
+    } else if SYNC_REVERSED.contains(&sync) {
+        return Err(Error::ReversedStream);
+    } else {
+        return Err(Error::NoSyncByte);
+    };
+
+    let (_, mid_size) = decode_bnxi(&buf[1..])?;
+    let (mlen, mlen_size) = decode_bnxi(&buf[1 + mid_size..])?;
+    let mlen = mlen as usize;
+
+    Ok(1 + mid_size + mlen_size + mlen + checksum_len(mlen, enhanced_crc))
+}
+
+/// Returns true if this byte may start a BINEX frame
+pub(crate) fn is_sync_byte(byte: u8) -> bool {
+    byte == SYNC_BE
+        || byte == SYNC_LE
+        || SYNC_ENHANCED.contains(&byte)
+        || SYNC_REVERSED.contains(&byte)
+}
+
+/// Returns true if this frame is a prototyping record we describe
+pub(crate) fn is_prototype(frame: &[u8]) -> bool {
+    matches!(frame.get(1), Some(&OBSERVATION_MID) | Some(&METEO_MID))
+}
+
+/// Encodes multi-byte values, in the stream endianness
+struct ByteWriter {
+    big_endian: bool,
+    buf: Vec<u8>,
+}
+
+impl ByteWriter {
+    fn new(big_endian: bool) -> Self {
+        Self {
+            big_endian,
+            buf: Vec::new(),
+        }
+    }
+
+    fn u8(&mut self, val: u8) {
+        self.buf.push(val);
+    }
+
+    fn u16(&mut self, val: u16) {
+        if self.big_endian {
+            self.buf.extend(val.to_be_bytes());
+        } else {
+            self.buf.extend(val.to_le_bytes());
+        }
+    }
+
+    fn u32(&mut self, val: u32) {
+        if self.big_endian {
+            self.buf.extend(val.to_be_bytes());
+        } else {
+            self.buf.extend(val.to_le_bytes());
+        }
+    }
+
+    fn f64(&mut self, val: f64) {
+        if self.big_endian {
+            self.buf.extend(val.to_be_bytes());
+        } else {
+            self.buf.extend(val.to_le_bytes());
+        }
+    }
+
+    /// Encodes this string, prefixed by its length
+    fn string(&mut self, val: &str) {
+        let bytes = val.as_bytes();
+        let len = bytes.len().min(u8::MAX as usize);
+        self.u8(len as u8);
+        self.buf.extend(&bytes[..len]);
+    }
+
+    /// Encodes this [Epoch] as time tag: [TimeScale] ID, minutes since
+    /// the GPS origin, milliseconds within the minute and nanoseconds within the millisecond.
+    fn epoch(&mut self, t: Epoch) -> Result<(), Error> {
+        let ts_id = TIMESCALES
+            .iter()
+            .position(|ts| *ts == t.time_scale)
+            .ok_or(Error::NonSupportedTimescale)?;
+
+        let origin = Epoch::from_gregorian(1980, 1, 6, 0, 0, 0, 0, t.time_scale);
+
+        let nanos = (t - origin).total_nanoseconds();
+        if nanos < 0 {
+            return Err(Error::NonSupportedTimescale);
+        }
+
+        let minutes = nanos / 60_000_000_000;
+        let rem = nanos % 60_000_000_000;
+
+        self.u8(ts_id as u8);
+        self.u32(minutes as u32);
+        self.u16((rem / 1_000_000) as u16);
+        self.u32((rem % 1_000_000) as u32);
+        Ok(())
+    }
+}
+
+/// Decodes multi-byte values, in the stream endianness
+struct ByteReader<'a> {
+    big_endian: bool,
+    buf: &'a [u8],
+    ptr: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(big_endian: bool, buf: &'a [u8]) -> Self {
+        Self {
+            big_endian,
+            buf,
+            ptr: 0,
+        }
+    }
+
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let bytes = self
+            .buf
+            .get(self.ptr..self.ptr + N)
+            .ok_or(Error::NotEnoughBytes)?;
+        self.ptr += N;
+        let mut array = [0; N];
+        array.copy_from_slice(bytes);
+        Ok(array)
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.bytes::<1>()?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, Error> {
+        let bytes = self.bytes()?;
+        Ok(if self.big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        })
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        let bytes = self.bytes()?;
+        Ok(if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+
+    fn f64(&mut self) -> Result<f64, Error> {
+        let bytes = self.bytes()?;
+        Ok(if self.big_endian {
+            f64::from_be_bytes(bytes)
+        } else {
+            f64::from_le_bytes(bytes)
+        })
+    }
+
+    /// Decodes a length prefixed string
+    fn string(&mut self) -> Result<&'a str, Error> {
+        let len = self.u8()? as usize;
+        let bytes = self
+            .buf
+            .get(self.ptr..self.ptr + len)
+            .ok_or(Error::NotEnoughBytes)?;
+        self.ptr += len;
+        std::str::from_utf8(bytes).map_err(|_| Error::Utf8Error)
+    }
+
+    /// Decodes a time tag, refer to [ByteWriter::epoch]
+    fn epoch(&mut self) -> Result<Epoch, Error> {
+        let ts = *TIMESCALES
+            .get(self.u8()? as usize)
+            .ok_or(Error::NonSupportedTimescale)?;
+
+        let minutes = self.u32()? as i128;
+        let millis = self.u16()? as i128;
+        let nanos = self.u32()? as i128;
+
+        let origin = Epoch::from_gregorian(1980, 1, 6, 0, 0, 0, 0, ts);
+        let nanos = minutes * 60_000_000_000 + millis * 1_000_000 + nanos;
+
+        Ok(origin + Duration::from_total_nanoseconds(nanos))
+    }
+}
+
+/// BINEX prototyping record
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrototypeRecord {
+    /// Observation prototyping record (0x7f), wrapping one RINEX epoch
+    Observation(ObservationRecord),
+    /// Ancillary site data prototyping record (0x7e), wrapping one Meteo RINEX epoch
+    Meteo(MeteoRecord),
+}
+
+impl PrototypeRecord {
+    /// Message ID of this record
+    fn mid(&self) -> u8 {
+        match self {
+            Self::Observation(_) => OBSERVATION_MID,
+            Self::Meteo(_) => METEO_MID,
+        }
+    }
+
+    /// Encodes the payload of this record
+    fn payload(&self, big_endian: bool) -> Result<Vec<u8>, Error> {
+        let mut w = ByteWriter::new(big_endian);
+        w.u8(RINEX_SUBRECORD);
+        match self {
+            Self::Observation(rec) => rec.encode(&mut w)?,
+            Self::Meteo(rec) => rec.encode(&mut w)?,
+        }
+        Ok(w.buf)
+    }
+}
+
+/// [PrototypeMessage] describes a BINEX prototyping record (0x7e or 0x7f),
+/// with the BINEX framing conventions. Only forward streams with standard
+/// CRC are supported, like our BINEX library.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrototypeMessage {
+    /// Encoding [Meta]
+    pub meta: Meta,
+    /// [PrototypeRecord]
+    pub record: PrototypeRecord,
+}
+
+impl PrototypeMessage {
+    /// Builds a new [PrototypeMessage]
+    pub fn new(meta: Meta, record: PrototypeRecord) -> Self {
+        Self { meta, record }
+    }
+
+    /// Total encoding size of this [PrototypeMessage], in bytes.
+    pub fn encoding_size(&self) -> usize {
+        match self.record.payload(self.meta.big_endian) {
+            Ok(payload) => {
+                let mlen = payload.len();
+                1 + 1 + bnxi_size(mlen as u32) + mlen + checksum_len(mlen, false)
+            },
+            Err(_) => 0,
+        }
+    }
+
+    /// Encodes this [PrototypeMessage] into this buffer of given size.
+    /// Returns the total encoded size.
+    pub fn encode(&self, buf: &mut [u8], size: usize) -> Result<usize, Error> {
+        if self.meta.reversed {
+            return Err(Error::ReversedStream);
+        }
+        if self.meta.enhanced_crc {
+            return Err(Error::EnhancedCrc);
+        }
+
+        let payload = self.record.payload(self.meta.big_endian)?;
+        let mlen = payload.len();
+
+        if mlen >= MAX_MLEN {
+            return Err(Error::TooLargeInternalLimitation);
+        }
+
+        let mut frame = vec![if self.meta.big_endian {
+            SYNC_BE
+        } else {
+            SYNC_LE
+        }];
+
+        frame.push(self.record.mid());
+        encode_bnxi(mlen as u32, &mut frame);
+        frame.extend(payload);
+
+        let (crc, crc_len) = checksum(&frame[1..]);
+        let crc = if self.meta.big_endian {
+            crc.to_be_bytes()
+        } else {
+            crc.to_le_bytes()
+        };
+
+        if self.meta.big_endian {
+            frame.extend(&crc[4 - crc_len..]);
+        } else {
+            frame.extend(&crc[..crc_len]);
+        }
+
+        let total = frame.len();
+        if size < total || buf.len() < total {
+            return Err(Error::NotEnoughBytes);
+        }
+
+        buf[..total].copy_from_slice(&frame);
+        Ok(total)
+    }
+
+    /// Decodes a [PrototypeMessage] from this frame, that must start with the sync byte.
+    pub fn decode(buf: &[u8]) -> Result<Self, Error> {
+        let size = frame_size(buf)?;
+        if buf.len() < size {
+            return Err(Error::IncompleteMessage(size));
+        }
+
+        let big_endian = match buf[0] {
+            SYNC_BE => true,
+            SYNC_LE => false,
+            _ => return Err(Error::EnhancedCrc),
+        };
+
+        let mid = buf[1];
+        let (mlen, mlen_size) = decode_bnxi(&buf[2..])?;
+        let end = 2 + mlen_size + mlen as usize;
+
+        let (crc, crc_len) = checksum(&buf[1..end]);
+        let mut received = [0; 4];
+        if big_endian {
+            received[4 - crc_len..].copy_from_slice(&buf[end..end + crc_len]);
+        } else {
+            received[..crc_len].copy_from_slice(&buf[end..end + crc_len]);
+        }
+
+        let received = if big_endian {
+            u32::from_be_bytes(received)
+        } else {
+            u32::from_le_bytes(received)
+        };
+
+        if received != crc {
+            return Err(Error::CorrupctBadCRC);
+        }
+
+        let mut r = ByteReader::new(big_endian, &buf[2 + mlen_size..end]);
+
+        if r.u8()? != RINEX_SUBRECORD {
+            return Err(Error::NonSupportedSubRecord);
+        }
+
+        let record = match mid {
+            OBSERVATION_MID => PrototypeRecord::Observation(ObservationRecord::decode(&mut r)?),
+            METEO_MID => PrototypeRecord::Meteo(MeteoRecord::decode(&mut r)?),
+            _ => return Err(Error::UnknownMessage),
+        };
+
+        let meta = Meta {
+            big_endian,
+            reversed: false,
+            enhanced_crc: false,
+        };
+
+        Ok(Self { meta, record })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{checksum, crc16, crc32, decode_bnxi, encode_bnxi};
+
+    #[test]
+    fn bnxi() {
+        for val in [
+            0,
+            1,
+            127,
+            128,
+            300,
+            16383,
+            16384,
+            2097151,
+            2097152,
+            0x1fff_ffff,
+        ] {
+            let mut buf = Vec::new();
+            encode_bnxi(val, &mut buf);
+            assert_eq!(decode_bnxi(&buf).unwrap(), (val, buf.len()));
+        }
+    }
+
+    #[test]
+    fn checksums() {
+        // CRC-16/XMODEM check value
+        assert_eq!(crc16(b"123456789"), 0x31c3);
+        // CRC-32/POSIX check value, without output inversion
+        assert_eq!(crc32(b"123456789"), !0x765e_7680);
+
+        assert_eq!(checksum(&[1, 2, 3]), (0, 1));
+        assert_eq!(checksum(&[0; 128]).1, 2);
+        assert_eq!(checksum(&[0; 4096]).1, 4);
+    }
+}
@@ -0,0 +1,160 @@
+//! Observation prototyping record (0x7f)
+use std::str::FromStr;
+
+use crate::{
+    observation::{ClockObservation, EpochFlag, LliFlags, ObsKey, Observations, SignalObservation},
+    prelude::{Observable, SV},
+};
+
+use binex::prelude::Error;
+
+use super::{ByteReader, ByteWriter};
+
+/// Absence of LLI or SNR flag
+const NONE: u8 = 0xff;
+
+/// Clock offset is present
+const CLOCK_OFFSET: u8 = 0x01;
+
+/// Clock drift is present
+const CLOCK_DRIFT: u8 = 0x02;
+
+/// [ObservationRecord] wraps one Observation RINEX epoch.
+/// Payload (following the subrecord ID):
+/// - time tag (1 byte timescale, 4 byte minutes, 2 byte ms, 4 byte ns)
+/// - 1 byte [EpochFlag]
+/// - 1 byte clock mask, followed by the clock offset and drift (f64) when present
+/// - 1 byte number of special records, each one as length prefixed string
+/// - 2 byte number of signals, each one described by:
+///   length prefixed [SV] and [Observable], f64 value,
+///   1 byte LLI and 1 byte SNR (0xff when missing).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObservationRecord {
+    /// [ObsKey]
+    pub key: ObsKey,
+    /// [Observations] at this epoch
+    pub observations: Observations,
+}
+
+fn encode_flag(flag: EpochFlag) -> u8 {
+    match flag {
+        EpochFlag::Ok => 0,
+        EpochFlag::PowerFailure => 1,
+        EpochFlag::AntennaBeingMoved => 2,
+        EpochFlag::NewSiteOccupation => 3,
+        EpochFlag::HeaderInformationFollows => 4,
+        EpochFlag::ExternalEvent => 5,
+        EpochFlag::CycleSlip => 6,
+    }
+}
+
+fn decode_flag(flag: u8) -> Result<EpochFlag, Error> {
+    match flag {
+        0 => Ok(EpochFlag::Ok),
+        1 => Ok(EpochFlag::PowerFailure),
+        2 => Ok(EpochFlag::AntennaBeingMoved),
+        3 => Ok(EpochFlag::NewSiteOccupation),
+        4 => Ok(EpochFlag::HeaderInformationFollows),
+        5 => Ok(EpochFlag::ExternalEvent),
+        6 => Ok(EpochFlag::CycleSlip),
+        _ => Err(Error::NonSupportedSubRecord),
+    }
+}
+
+impl ObservationRecord {
+    /// Builds a new [ObservationRecord]
+    pub fn new(key: ObsKey, observations: Observations) -> Self {
+        Self { key, observations }
+    }
+
+    pub(super) fn encode(&self, w: &mut ByteWriter) -> Result<(), Error> {
+        w.epoch(self.key.epoch)?;
+        w.u8(encode_flag(self.key.flag));
+
+        match &self.observations.clock {
+            Some(clock) => {
+                let mask = match clock.drift_s_s {
+                    Some(_) => CLOCK_OFFSET | CLOCK_DRIFT,
+                    None => CLOCK_OFFSET,
+                };
+                w.u8(mask);
+                w.f64(clock.offset_s);
+                if let Some(drift) = clock.drift_s_s {
+                    w.f64(drift);
+                }
+            },
+            None => w.u8(0),
+        }
+
+        let special_records = &self.observations.special_records;
+        if special_records.len() > u8::MAX as usize {
+            return Err(Error::TooLargeInternalLimitation);
+        }
+
+        w.u8(special_records.len() as u8);
+        for record in special_records.iter() {
+            w.string(record);
+        }
+
+        let signals = &self.observations.signals;
+        if signals.len() > u16::MAX as usize {
+            return Err(Error::TooLargeInternalLimitation);
+        }
+
+        w.u16(signals.len() as u16);
+        for signal in signals.iter() {
+            w.string(&signal.sv.to_string());
+            w.string(&signal.observable.to_string());
+            w.f64(signal.value);
+            w.u8(signal.lli.map(|lli| lli.bits()).unwrap_or(NONE));
+            w.u8(signal.snr.map(u8::from).unwrap_or(NONE));
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn decode(r: &mut ByteReader) -> Result<Self, Error> {
+        let epoch = r.epoch()?;
+        let flag = decode_flag(r.u8()?)?;
+
+        let mut observations = Observations::default();
+
+        let mask = r.u8()?;
+        if mask & CLOCK_OFFSET > 0 {
+            let mut clock = ClockObservation::default().with_offset_s(epoch, r.f64()?);
+            if mask & CLOCK_DRIFT > 0 {
+                clock.drift_s_s = Some(r.f64()?);
+            }
+            observations.clock = Some(clock);
+        }
+
+        for _ in 0..r.u8()? {
+            observations.special_records.push(r.string()?.to_string());
+        }
+
+        for _ in 0..r.u16()? {
+            let sv = SV::from_str(r.string()?).map_err(|_| Error::NonSupportedSubRecord)?;
+            let observable =
+                Observable::from_str(r.string()?).map_err(|_| Error::NonSupportedSubRecord)?;
+
+            let mut signal = SignalObservation::new(sv, observable, r.f64()?);
+
+            signal.lli = match r.u8()? {
+                NONE => None,
+                bits => Some(LliFlags::from_bits_truncate(bits)),
+            };
+
+            signal.snr = match r.u8()? {
+                NONE => None,
+                snr => Some(snr.into()),
+            };
+
+            observations.signals.push(signal);
+        }
+
+        Ok(Self {
+            key: ObsKey { epoch, flag },
+            observations,
+        })
+    }
+}
@@ -0,0 +1,42 @@
+use std::{collections::btree_map::Iter, iter::Peekable};
+
+use crate::{
+    binex::prototype::{MeteoRecord, PrototypeMessage, PrototypeRecord},
+    meteo::MeteoKey,
+    prelude::Rinex,
+};
+
+use binex::prelude::Meta;
+
+/// Meteo Record Streamer: one ancillary site data prototyping record per epoch
+pub struct Streamer<'a> {
+    meta: Meta,
+    iter: Peekable<Iter<'a, MeteoKey, f64>>,
+}
+
+impl<'a> Streamer<'a> {
+    pub fn new(meta: Meta, rinex: &'a Rinex) -> Self {
+        Self {
+            meta,
+            iter: rinex.meteo_observations_iter().peekable(),
+        }
+    }
+}
+
+impl Iterator for Streamer<'_> {
+    type Item = PrototypeMessage;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.iter.next()?;
+        let mut record = MeteoRecord::new(key.epoch, vec![(key.observable.clone(), *value)]);
+
+        // record is sorted by epoch: gather this epoch
+        while let Some((key, value)) = self.iter.next_if(|(k, _)| k.epoch == record.epoch) {
+            record.observations.push((key.observable.clone(), *value));
+        }
+
+        Some(PrototypeMessage::new(
+            self.meta,
+            PrototypeRecord::Meteo(record),
+        ))
+    }
+}
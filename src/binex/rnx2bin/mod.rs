@@ -1,32 +1,72 @@
 //! RINEX to BINEX serialization
-use crate::prelude::{Epoch, Header, Rinex};
-use binex::prelude::{Message, Meta, MonumentGeoMetadata, MonumentGeoRecord};
+use crate::{
+    binex::StreamMessage,
+    prelude::{Epoch, Header, Rinex},
+};
 
+use binex::prelude::{
+    Message, Meta, MonumentGeoMetadata, MonumentGeoRecord, Record, Solutions, SolutionsFrame,
+};
+
+mod meteo;
 mod nav;
+mod obs;
+
+use meteo::Streamer as MeteoStreamer;
 use nav::Streamer as NavStreamer;
+use obs::Streamer as ObsStreamer;
+
+/// Announces the beginning of a stream
+const STREAM_ANNOUNCE: &str = "Stream starting!";
+
+/// Announces the [Header] comments
+const HEADER_COMMENTS_ANNOUNCE: &str = "RINEX Header comments following!";
+
+/// Announces the beginning of the record stream
+const RECORD_ANNOUNCE: &str = "RINEX Record starting!";
+
+/// Returns true if this comment is one of our stream announcements
+pub(crate) fn is_announcement(comment: &str) -> bool {
+    [STREAM_ANNOUNCE, HEADER_COMMENTS_ANNOUNCE, RECORD_ANNOUNCE].contains(&comment)
+}
 
 /// RINEX Type dependant record streamer
 enum TypeDependentStreamer<'a> {
     /// NAV Record streamer
     Nav(NavStreamer<'a>),
+    /// OBS Record streamer
+    Obs(ObsStreamer<'a>),
+    /// Meteo Record streamer
+    Meteo(MeteoStreamer<'a>),
 }
 
 impl<'a> TypeDependentStreamer<'a> {
-    pub fn new(meta: Meta, rinex: &'a Rinex) -> Self {
-        Self::Nav(NavStreamer::new(meta, rinex))
+    /// Returns None when BINEX cannot describe this RINEX format
+    pub fn new(meta: Meta, rinex: &'a Rinex) -> Option<Self> {
+        if rinex.is_navigation_rinex() {
+            Some(Self::Nav(NavStreamer::new(meta, rinex)))
+        } else if rinex.is_observation_rinex() {
+            Some(Self::Obs(ObsStreamer::new(meta, rinex)))
+        } else if rinex.is_meteo_rinex() {
+            Some(Self::Meteo(MeteoStreamer::new(meta, rinex)))
+        } else {
+            None
+        }
     }
 }
 
 impl<'a> Iterator for TypeDependentStreamer<'a> {
-    type Item = Message;
+    type Item = StreamMessage;
     fn next(&mut self) -> Option<Self::Item> {
         match self {
-            Self::Nav(streamer) => streamer.next(),
+            Self::Nav(streamer) => streamer.next().map(StreamMessage::Message),
+            Self::Obs(streamer) => streamer.next().map(StreamMessage::Prototype),
+            Self::Meteo(streamer) => streamer.next().map(StreamMessage::Prototype),
         }
     }
 }
 
-/// RNX2BIN can serialize a [Rinex] into a stream of BINEX [Message]s
+/// RNX2BIN can serialize a [Rinex] into a stream of BINEX [StreamMessage]s
 pub struct RNX2BIN<'a> {
     /// First [Epoch] or [Epoch] of publication
     t0: Epoch,
@@ -50,6 +90,8 @@ enum State {
     #[default]
     HeaderPkgVersion,
     MonumentGeo,
+    /// Describes the site location
+    SiteLocation,
     AnnounceHeaderComments,
     HeaderComments,
     AnnounceRecord,
@@ -57,83 +99,60 @@ enum State {
 }
 
 impl<'a> Iterator for RNX2BIN<'a> {
-    type Item = Message;
+    type Item = StreamMessage;
     fn next(&mut self) -> Option<Self::Item> {
-        let content = match self.state {
-            State::HeaderPkgVersion => {
-                let mut geo = self.forge_monument_geo();
-                if let Some(custom) = &self.custom_announce {
-                    geo.comments.push(custom.clone());
-                }
-                // announce stream beginning
-                geo.comments.push("Stream starting!".to_string());
-                self.state = State::MonumentGeo;
-                Some(geo)
-            },
-            State::MonumentGeo => {
-                let mut geo = self.forge_monument_geo();
-                if let Some(agency) = &self.header.agency {
-                    geo = geo.with_agency(agency);
-                }
-                if let Some(observer) = &self.header.observer {
-                    geo = geo.with_observer(observer);
-                }
-                if let Some(_marker) = &self.header.geodetic_marker {
-                    //geo = geo.with_marker_name();
-                    //geo = geo.with_marker_number();
-                }
-                if let Some(rx) = &self.header.rcvr {
-                    geo = geo.with_receiver_model(&rx.model);
-                    geo = geo.with_receiver_serial_number(&rx.sn);
-                    geo = geo.with_receiver_firmware_version(&rx.firmware);
-                }
-                if let Some(_cospar) = &self.header.cospar {
-                    //geo = geo.with_reference_number(cospar);
-                }
-                if let Some(_position) = &self.header.rx_position {
-                    //geo = geo.with_site_location();
-                }
-                if !self.header.comments.is_empty() && !self.skip_header {
-                    self.state = State::AnnounceHeaderComments;
-                } else {
+        loop {
+            let record = match self.state {
+                State::HeaderPkgVersion => {
+                    let mut geo = self.forge_monument_geo();
+                    if let Some(custom) = &self.custom_announce {
+                        geo.comments.push(custom.clone());
+                    }
+                    // announce stream beginning
+                    geo.comments.push(STREAM_ANNOUNCE.to_string());
+                    self.state = State::MonumentGeo;
+                    Some(Record::new_monument_geo(geo))
+                },
+                State::MonumentGeo => {
+                    self.state = State::SiteLocation;
+                    Some(Record::new_monument_geo(self.forge_site_description()))
+                },
+                State::SiteLocation => {
+                    if !self.header.comments.is_empty() && !self.skip_header {
+                        self.state = State::AnnounceHeaderComments;
+                    } else {
+                        self.state = State::AnnounceRecord;
+                    }
+                    self.forge_site_location().map(Record::new_solutions)
+                },
+                State::AnnounceHeaderComments => {
+                    let geo = self
+                        .forge_monument_geo()
+                        .with_comment(HEADER_COMMENTS_ANNOUNCE);
+                    self.state = State::HeaderComments;
+                    Some(Record::new_monument_geo(geo))
+                },
+                State::HeaderComments => {
+                    let mut geo = self.forge_monument_geo();
+                    for comment in self.header.comments.iter() {
+                        geo = geo.with_comment(comment);
+                    }
                     self.state = State::AnnounceRecord;
-                }
-                Some(geo)
-            },
-            State::AnnounceHeaderComments => {
-                let mut geo = self.forge_monument_geo();
-                geo = geo.with_comment("RINEX Header comments following!");
-                self.state = State::HeaderComments;
-                Some(geo)
-            },
-            State::HeaderComments => {
-                let mut geo = self.forge_monument_geo();
-                for comment in self.header.comments.iter() {
-                    geo = geo.with_comment(comment);
-                }
-                self.state = State::AnnounceRecord;
-                Some(geo)
-            },
-            State::AnnounceRecord => {
-                let mut geo = self.forge_monument_geo();
-                geo = geo.with_comment("RINEX Record starting!");
-                self.state = State::RecordStream;
-                Some(geo)
-            },
-            State::RecordStream => {
-                let msg = self.streamer.next()?;
-                return Some(msg);
-            },
-        };
-
-        if let Some(content) = content {
-            // forge new message
-            Some(Message {
-                meta: self.meta,
-                record: content.into(),
-            })
-        } else {
-            None
+                    Some(Record::new_monument_geo(geo))
+                },
+                State::AnnounceRecord => {
+                    let geo = self.forge_monument_geo().with_comment(RECORD_ANNOUNCE);
+                    self.state = State::RecordStream;
+                    Some(Record::new_monument_geo(geo))
+                },
+                State::RecordStream => {
+                    return self.streamer.next();
+                },
+            };
+
+            if let Some(record) = record {
+                return Some(Message::new(self.meta, record).into());
+            }
         }
     }
 }
@@ -146,30 +165,75 @@ impl<'a> RNX2BIN<'a> {
         geo = geo.with_software_name(&format!("rtk-rs/rinex v{}", env!("CARGO_PKG_VERSION")));
         geo
     }
+
+    /// Describes the site and hardware, from [Header]
+    fn forge_site_description(&self) -> MonumentGeoRecord {
+        let mut geo = self.forge_monument_geo();
+        if let Some(agency) = &self.header.agency {
+            geo = geo.with_agency(agency);
+        }
+        if let Some(observer) = &self.header.observer {
+            geo = geo.with_observer(observer);
+        }
+        if let Some(marker) = &self.header.geodetic_marker {
+            geo = geo.with_geodetic_marker_name(&marker.name);
+            if let Some(number) = marker.number() {
+                geo = geo.with_geodetic_marker_number(&number);
+            }
+        }
+        if let Some(rx) = &self.header.rcvr {
+            geo = geo.with_receiver_model(&rx.model);
+            geo = geo.with_receiver_serial_number(&rx.sn);
+            geo = geo.with_receiver_firmware_version(&rx.firmware);
+        }
+        if let Some(antenna) = &self.header.rcvr_antenna {
+            geo = geo.with_antenna_model(&antenna.model);
+            geo = geo.with_antenna_serial_number(&antenna.sn);
+        }
+        geo
+    }
+
+    /// Describes the site location (if known), from [Header]
+    fn forge_site_location(&self) -> Option<Solutions> {
+        let (x, y, z) = self.header.rx_position?;
+        let mut solutions = Solutions::new(self.t0);
+        solutions
+            .frames
+            .push(SolutionsFrame::new_antenna_wgs84_ecef_position(x, y, z));
+        Some(solutions)
+    }
 }
 
 impl Rinex {
     /// Create a [RNX2BIN] streamer to convert this [Rinex]
-    /// into a stream of BINEX [Message]s. You can then use the Iterator implementation
+    /// into a stream of BINEX [StreamMessage]s. You can then use the Iterator implementation
     /// to forge the stream.
     /// The stream will be made of
     /// - One geo monument message describing this software package
     /// - One geo monument message announcing the Header fields
     /// - One geo monument message describing all [Header] fields
+    /// - One solutions message describing the site location, when known
     /// - One geo monument message wrapping all comments contained in [Header]
     /// - One geo monument message announcing the start of Record stream
     /// - One RINEX format depending by record entry. For example,
-    /// one Ephemeris frame per decoded Navigation message.
+    ///   one Ephemeris frame per decoded Navigation message, or one prototyping
+    ///   record (refer to [crate::prelude::binex::PrototypeMessage]) per Observation or Meteo epoch.
     ///
     /// ## Inputs:
     /// - meta: BINEX encoding [Meta]
     /// ## Output
-    /// - [RNX2BIN]: a BINEX [Message] Iterator
+    /// - [RNX2BIN]: a BINEX [StreamMessage] Iterator, or None if
+    ///   this RINEX format cannot be described by BINEX.
+    ///
+    /// We support the streaming of
+    /// - GPS (legacy), Galileo, Glonass and SBAS Navigation Ephemeris
+    /// - Observation RINEX, as observation prototyping records (0x7f)
+    /// - Meteo RINEX, as ancillary site data prototyping records (0x7e)
     ///
-    /// This is work in progress. Currently, we support
-    /// the streaming of Navigation Ephemeris.
+    /// BINEX has no clock record: Clock RINEX cannot be streamed (None is returned).
     pub fn rnx2bin<'a>(&'a self, meta: Meta) -> Option<RNX2BIN<'a>> {
         let t0 = self.first_epoch()?;
+        let streamer = TypeDependentStreamer::new(meta, self)?;
         Some(RNX2BIN {
             t0,
             meta,
@@ -177,7 +241,7 @@ impl Rinex {
             state: State::default(),
             skip_header: false,
             custom_announce: Default::default(),
-            streamer: TypeDependentStreamer::new(meta, self),
+            streamer,
         })
     }
 }
@@ -1,251 +1,203 @@
+use std::f64::consts::PI;
+
 use crate::{
-    navigation::{Ephemeris, NavKey},
-    prelude::{Constellation, Epoch, Rinex, SV},
+    navigation::{Ephemeris, NavKey, NavMessageType},
+    prelude::{Constellation, Epoch, Rinex, TimeScale},
 };
 
 use binex::prelude::{
     EphemerisFrame, GALEphemeris, GLOEphemeris, GPSEphemeris, Message, Meta, Record, SBASEphemeris,
 };
 
+use hifitime::Unit;
+
 /// NAV Record Streamer
 pub struct Streamer<'a> {
     meta: Meta,
     ephemeris_iter: Box<dyn Iterator<Item = (&'a NavKey, &'a Ephemeris)> + 'a>,
 }
 
-fn forge_gps_ephemeris_frame(_toc: &Epoch, sv: SV, eph: &Ephemeris) -> Option<EphemerisFrame> {
-    let clock_offset = eph.clock_bias as f32;
-    let clock_drift = eph.clock_drift as f32;
-    let clock_drift_rate = eph.clock_drift_rate as f32;
-
-    let toe = eph.orbits.get("toe")?.as_f64() as u16;
-
-    let cic = eph.orbits.get("cic")?.as_f64() as f32;
-    let crc = eph.orbits.get("crc")?.as_f64() as f32;
-    let cis = eph.orbits.get("cis")?.as_f64() as f32;
-    let crs = eph.orbits.get("crs")?.as_f64() as f32;
-    let cuc = eph.orbits.get("cuc")?.as_f64() as f32;
-    let cus = eph.orbits.get("cus")?.as_f64() as f32;
-
-    let e = eph.orbits.get("e")?.as_f64();
-    let m0_rad = eph.orbits.get("m0")?.as_f64();
-    let i0_rad = eph.orbits.get("i0")?.as_f64();
-    let sqrt_a = eph.orbits.get("sqrta")?.as_f64();
-    let omega_rad = eph.orbits.get("omega")?.as_f64();
-    let omega_0_rad = eph.orbits.get("omega0")?.as_f64();
-    let omega_dot_rad_s = eph.orbits.get("oemgaDot")?.as_f64() as f32;
-    let i_dot_rad_s = eph.orbits.get("idot")?.as_f64() as f32;
-    let delta_n_rad_s = eph.orbits.get("delta_n")?.as_f64() as f32;
-
-    let tgd = eph.orbits.get("tgd")?.as_f64() as f32;
-    let iode = eph.orbits.get("iode")?.as_u32() as i32;
-    let iodc = eph.orbits.get("iodc")?.as_u32() as i32;
-
-    Some(EphemerisFrame::GPS(GPSEphemeris {
-        sv_prn: sv.prn,
-        iode,
-        iodc,
-        toe,
-        tow: 0,
-        toc: 0,
-        tgd,
-        clock_offset,
-        clock_drift,
-        clock_drift_rate,
-        delta_n_rad_s,
-        m0_rad,
-        e,
-        sqrt_a,
-        cic,
-        crc,
-        cis,
-        crs,
-        cuc,
-        cus,
-        omega_0_rad,
-        omega_rad,
-        i_dot_rad_s,
-        omega_dot_rad_s,
-        i0_rad,
-        ura_m: 0.0,
-        sv_health: 0,
-        uint2: 0,
-    }))
+/// Returns (week, seconds of week) of this [Epoch], in this [TimeScale]
+fn time_of_week(t: Epoch, ts: TimeScale) -> (u32, f64) {
+    let (week, nanos) = t.to_time_scale(ts).to_time_of_week();
+    (week, nanos as f64 * 1.0E-9)
+}
+
+/// Seconds within the UTC week (starting on sunday) of this [Epoch]
+fn seconds_of_utc_week(t: Epoch) -> f64 {
+    let t = t.to_time_scale(TimeScale::UTC);
+    let days = (t.weekday_utc() as u8 + 1) % 7;
+    days as f64 * 86_400.0 + (t - t.floor(1.0 * Unit::Day)).to_seconds()
+}
+
+fn orbit(eph: &Ephemeris, field: &str) -> f64 {
+    eph.get_orbit_f64(field).unwrap_or_default()
 }
 
-fn forge_sbas_ephemeris_frame(_toc: &Epoch, sv: SV, eph: &Ephemeris) -> Option<EphemerisFrame> {
-    let sbas_prn = sv.prn;
-
-    let clock_offset = eph.clock_bias;
-    let clock_drift = eph.clock_drift;
-
-    let x_km = eph.orbits.get("satPosX")?.as_f64();
-    let vel_x_km = eph.orbits.get("velX")?.as_f64();
-    let acc_x_km = eph.orbits.get("accelX")?.as_f64();
-
-    let y_km = eph.orbits.get("satPosX")?.as_f64();
-    let vel_y_km = eph.orbits.get("velY")?.as_f64();
-    let acc_y_km = eph.orbits.get("accelY")?.as_f64();
-
-    let z_km = eph.orbits.get("satPosX")?.as_f64();
-    let vel_z_km = eph.orbits.get("velZ")?.as_f64();
-    let acc_z_km = eph.orbits.get("accelZ")?.as_f64();
-
-    let iodn = eph.orbits.get("iodn")?.as_u8();
-
-    Some(EphemerisFrame::SBAS(SBASEphemeris {
-        sbas_prn,
-        toe: 0,
-        tow: 0,
-        clock_offset,
-        clock_drift,
-        x_km,
-        vel_x_km,
-        acc_x_km,
-        y_km,
-        vel_y_km,
-        acc_y_km,
-        z_km,
-        vel_z_km,
-        acc_z_km,
-        uint1: 0,
-        ura: 0,
-        iodn,
-    }))
+fn forge_gps_ephemeris_frame(key: &NavKey, eph: &Ephemeris) -> EphemerisFrame {
+    let (week, toc) = time_of_week(key.epoch, TimeScale::GPST);
+    let week = eph.get_week().unwrap_or(week);
+
+    EphemerisFrame::GPS(GPSEphemeris {
+        sv_prn: key.sv.prn,
+        toe: week as u16,
+        tow: eph.get_orbit_f64("t_tm").unwrap_or(toc) as i32,
+        toc: toc as i32,
+        tgd: orbit(eph, "tgd") as f32,
+        iodc: orbit(eph, "iodc") as i32,
+        clock_offset: eph.clock_bias as f32,
+        clock_drift: eph.clock_drift as f32,
+        clock_drift_rate: eph.clock_drift_rate as f32,
+        iode: orbit(eph, "iode") as i32,
+        delta_n_rad_s: orbit(eph, "deltaN") as f32,
+        m0_rad: orbit(eph, "m0"),
+        e: orbit(eph, "e"),
+        sqrt_a: orbit(eph, "sqrta"),
+        cic: orbit(eph, "cic") as f32,
+        crc: orbit(eph, "crc") as f32,
+        cis: orbit(eph, "cis") as f32,
+        crs: orbit(eph, "crs") as f32,
+        cuc: orbit(eph, "cuc") as f32,
+        cus: orbit(eph, "cus") as f32,
+        omega_0_rad: orbit(eph, "omega0"),
+        omega_rad: orbit(eph, "omega"),
+        i0_rad: orbit(eph, "i0"),
+        omega_dot_rad_s: orbit(eph, "omegaDot") as f32,
+        i_dot_rad_s: orbit(eph, "idot") as f32,
+        ura_m: orbit(eph, "accuracy") as f32,
+        sv_health: orbit(eph, "health") as u16,
+        uint2: 0,
+    })
 }
 
-fn forge_gal_ephemeris_frame(_toc: &Epoch, sv: SV, eph: &Ephemeris) -> Option<EphemerisFrame> {
-    let _sv_prn = sv.prn;
-
-    let clock_offset = eph.clock_bias as f32;
-    let clock_drift = eph.clock_drift as f32;
-    let clock_drift_rate = eph.clock_drift_rate as f32;
-
-    let cic = eph.orbits.get("cic")?.as_f64() as f32;
-    let crc = eph.orbits.get("crc")?.as_f64() as f32;
-    let cis = eph.orbits.get("cis")?.as_f64() as f32;
-    let crs = eph.orbits.get("crs")?.as_f64() as f32;
-    let cuc = eph.orbits.get("cuc")?.as_f64() as f32;
-    let cus = eph.orbits.get("cus")?.as_f64() as f32;
-
-    let e = eph.orbits.get("e")?.as_f64();
-    let m0_rad = eph.orbits.get("m0")?.as_f64();
-    let i0_rad = eph.orbits.get("i0")?.as_f64();
-    let sqrt_a = eph.orbits.get("sqrta")?.as_f64();
-    let omega_rad = eph.orbits.get("omega")?.as_f64();
-    let omega_0_rad = eph.orbits.get("omega0")?.as_f64();
-
-    let omega_dot_rad_s = eph.orbits.get("oemgaDot")?.as_f64() as f32;
-    let omega_dot_semi_circles = omega_dot_rad_s;
-
-    let i_dot_rad_s = eph.orbits.get("idot")?.as_f64() as f32;
-    let idot_semi_circles_s = i_dot_rad_s;
-
-    let delta_n_rad_s = eph.orbits.get("delta_n")?.as_f64() as f32;
-    let delta_n_semi_circles_s = delta_n_rad_s;
-
-    Some(EphemerisFrame::GAL(GALEphemeris {
-        sv_prn: 0,
-        toe_week: 0,
-        tow: 0,
-        toe_s: 0,
-        bgd_e5a_e1_s: 0.0,
-        bgd_e5b_e1_s: 0.0,
-        iodnav: 0,
-        clock_drift_rate,
-        clock_drift,
-        clock_offset,
-        delta_n_semi_circles_s,
-        m0_rad,
-        e,
-        sqrt_a,
-        cic,
-        crc,
-        cis,
-        cuc,
-        cus,
-        crs,
-        omega_0_rad,
-        omega_rad,
-        i0_rad,
-        omega_dot_semi_circles,
-        idot_semi_circles_s,
-        sisa: 0.0,
-        sv_health: 0,
-        source: 0,
-    }))
+fn forge_gal_ephemeris_frame(key: &NavKey, eph: &Ephemeris) -> EphemerisFrame {
+    // GST week counter
+    let (week, toe_s) = time_of_week(key.epoch, TimeScale::GST);
+
+    EphemerisFrame::GAL(GALEphemeris {
+        sv_prn: key.sv.prn,
+        toe_week: week as u16,
+        tow: eph.get_orbit_f64("t_tm").unwrap_or(toe_s) as i32,
+        toe_s: toe_s as i32,
+        bgd_e5a_e1_s: orbit(eph, "bgdE5aE1") as f32,
+        bgd_e5b_e1_s: orbit(eph, "bgdE5bE1") as f32,
+        iodnav: orbit(eph, "iodnav") as i32,
+        clock_drift_rate: eph.clock_drift_rate as f32,
+        clock_drift: eph.clock_drift as f32,
+        clock_offset: eph.clock_bias as f32,
+        delta_n_semi_circles_s: (orbit(eph, "deltaN") / PI) as f32,
+        m0_rad: orbit(eph, "m0"),
+        e: orbit(eph, "e"),
+        sqrt_a: orbit(eph, "sqrta"),
+        cic: orbit(eph, "cic") as f32,
+        crc: orbit(eph, "crc") as f32,
+        cis: orbit(eph, "cis") as f32,
+        crs: orbit(eph, "crs") as f32,
+        cuc: orbit(eph, "cuc") as f32,
+        cus: orbit(eph, "cus") as f32,
+        omega_0_rad: orbit(eph, "omega0"),
+        omega_rad: orbit(eph, "omega"),
+        i0_rad: orbit(eph, "i0"),
+        omega_dot_semi_circles: (orbit(eph, "omegaDot") / PI) as f32,
+        idot_semi_circles_s: (orbit(eph, "idot") / PI) as f32,
+        sisa: orbit(eph, "sisa") as f32,
+        sv_health: orbit(eph, "health") as u16,
+        source: orbit(eph, "source") as u16,
+    })
 }
 
-fn forge_glo_ephemeris_frame(eph: &Ephemeris) -> Option<EphemerisFrame> {
-    let clock_offset_s = eph.clock_bias;
-    let clock_rel_freq_bias = eph.clock_drift;
-
-    let x_km = eph.orbits.get("satPosX")?.as_f64();
-    let vel_x_km = eph.orbits.get("velX")?.as_f64();
-    let acc_x_km = eph.orbits.get("accelX")?.as_f64();
-
-    let y_km = eph.orbits.get("satPosX")?.as_f64();
-    let vel_y_km = eph.orbits.get("velY")?.as_f64();
-    let acc_y_km = eph.orbits.get("accelY")?.as_f64();
-
-    let z_km = eph.orbits.get("satPosX")?.as_f64();
-    let vel_z_km = eph.orbits.get("velZ")?.as_f64();
-    let acc_z_km = eph.orbits.get("accelZ")?.as_f64();
-
-    Some(EphemerisFrame::GLO(GLOEphemeris {
-        slot: 0,
-        day: 0,
-        tod_s: 0,
-        clock_offset_s,
-        clock_rel_freq_bias,
-        t_k_sec: 0,
-        x_km,
-        vel_x_km,
-        acc_x_km,
-        y_km,
-        vel_y_km,
-        acc_y_km,
-        z_km,
-        vel_z_km,
-        acc_z_km,
-        sv_health: 0,
-        freq_channel: 0,
-        age_op_days: 0,
+fn forge_glo_ephemeris_frame(key: &NavKey, eph: &Ephemeris) -> EphemerisFrame {
+    // day counter (since GPS origin) and time of day, in UTC
+    let t = key.epoch.to_time_scale(TimeScale::UTC);
+    let midnight = t.floor(1.0 * Unit::Day);
+    let day = (midnight - Epoch::from_gregorian_utc_at_midnight(1980, 1, 6)).to_unit(Unit::Day);
+
+    // message frame time, expressed within this day
+    let t_k = eph.clock_drift_rate - seconds_of_utc_week(midnight);
+    let t_k = if t_k < 0.0 { t_k + 604_800.0 } else { t_k };
+
+    EphemerisFrame::GLO(GLOEphemeris {
+        slot: key.sv.prn,
+        day: day.round() as u16,
+        tod_s: (t - midnight).to_seconds().round() as u32,
+        clock_offset_s: eph.clock_bias,
+        clock_rel_freq_bias: eph.clock_drift,
+        t_k_sec: t_k.round() as u32,
+        x_km: orbit(eph, "satPosX"),
+        vel_x_km: orbit(eph, "velX"),
+        acc_x_km: orbit(eph, "accelX"),
+        y_km: orbit(eph, "satPosY"),
+        vel_y_km: orbit(eph, "velY"),
+        acc_y_km: orbit(eph, "accelY"),
+        z_km: orbit(eph, "satPosZ"),
+        vel_z_km: orbit(eph, "velZ"),
+        acc_z_km: orbit(eph, "accelZ"),
+        sv_health: orbit(eph, "health") as u8,
+        freq_channel: eph.glonass_freq_channel().unwrap_or_default(),
+        age_op_days: orbit(eph, "ageOp") as u8,
         leap_s: 0,
         tau_gps_s: 0.0,
-        l1_l2_gd: 0.0,
-    }))
+        l1_l2_gd: orbit(eph, "L1L2GrpDelay"),
+    })
+}
+
+fn forge_sbas_ephemeris_frame(key: &NavKey, eph: &Ephemeris) -> EphemerisFrame {
+    let (week, tow) = time_of_week(key.epoch, TimeScale::GPST);
+
+    EphemerisFrame::SBAS(SBASEphemeris {
+        // RINEX describes SBAS vehicles by PRN - 100
+        sbas_prn: key.sv.prn + 100,
+        toe: week as u16,
+        tow: tow as i32,
+        clock_offset: eph.clock_bias,
+        clock_drift: eph.clock_drift,
+        x_km: orbit(eph, "satPosX"),
+        vel_x_km: orbit(eph, "velX"),
+        acc_x_km: orbit(eph, "accelX"),
+        y_km: orbit(eph, "satPosY"),
+        vel_y_km: orbit(eph, "velY"),
+        acc_y_km: orbit(eph, "accelY"),
+        z_km: orbit(eph, "satPosZ"),
+        vel_z_km: orbit(eph, "velZ"),
+        acc_z_km: orbit(eph, "accelZ"),
+        uint1: orbit(eph, "health") as u8,
+        ura: orbit(eph, "accuracyCode") as u8,
+        iodn: orbit(eph, "iodn") as u8,
+    })
 }
 
 impl<'a> Streamer<'a> {
     pub fn new(meta: Meta, rinex: &'a Rinex) -> Self {
         Self {
-            meta: meta,
+            meta,
             ephemeris_iter: rinex.nav_ephemeris_frames_iter(),
         }
     }
 }
 
-impl<'a> Iterator for Streamer<'a> {
+impl Iterator for Streamer<'_> {
     type Item = Message;
     fn next(&mut self) -> Option<Self::Item> {
-        let (key, eph) = self.ephemeris_iter.next()?;
-
-        let frame = if key.sv.constellation.is_sbas() {
-            forge_sbas_ephemeris_frame(&key.epoch, key.sv, eph)
-        } else {
-            match key.sv.constellation {
-                Constellation::GPS => forge_gps_ephemeris_frame(&key.epoch, key.sv, eph),
-                Constellation::Galileo => forge_gal_ephemeris_frame(&key.epoch, key.sv, eph),
-                Constellation::Glonass => forge_glo_ephemeris_frame(eph),
-                _ => None,
+        loop {
+            let (key, eph) = self.ephemeris_iter.next()?;
+
+            let frame = if key.sv.constellation.is_sbas() {
+                Some(forge_sbas_ephemeris_frame(key, eph))
+            } else {
+                match key.sv.constellation {
+                    // BINEX describes legacy messages only
+                    Constellation::GPS if key.msgtype == NavMessageType::LNAV => {
+                        Some(forge_gps_ephemeris_frame(key, eph))
+                    },
+                    Constellation::Galileo => Some(forge_gal_ephemeris_frame(key, eph)),
+                    Constellation::Glonass => Some(forge_glo_ephemeris_frame(key, eph)),
+                    _ => None,
+                }
+            };
+
+            if let Some(frame) = frame {
+                return Some(Message::new(self.meta, Record::new_ephemeris_frame(frame)));
             }
-        };
-
-        let frame = frame?;
-        Some(Message {
-            meta: self.meta,
-            record: Record::new_ephemeris_frame(frame),
-        })
+        }
     }
 }
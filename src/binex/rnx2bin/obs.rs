@@ -0,0 +1,36 @@
+use std::collections::btree_map::Iter;
+
+use crate::{
+    binex::prototype::{ObservationRecord, PrototypeMessage, PrototypeRecord},
+    observation::{ObsKey, Observations},
+    prelude::Rinex,
+};
+
+use binex::prelude::Meta;
+
+/// OBS Record Streamer: one observation prototyping record per epoch
+pub struct Streamer<'a> {
+    meta: Meta,
+    iter: Iter<'a, ObsKey, Observations>,
+}
+
+impl<'a> Streamer<'a> {
+    pub fn new(meta: Meta, rinex: &'a Rinex) -> Self {
+        Self {
+            meta,
+            iter: rinex.observations_iter(),
+        }
+    }
+}
+
+impl Iterator for Streamer<'_> {
+    type Item = PrototypeMessage;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, observations) = self.iter.next()?;
+        let record = ObservationRecord::new(*key, observations.clone());
+        Some(PrototypeMessage::new(
+            self.meta,
+            PrototypeRecord::Observation(record),
+        ))
+    }
+}
@@ -0,0 +1,53 @@
+//! BINEX stream elements
+use binex::prelude::{Error, Message};
+
+use crate::binex::prototype::{self, PrototypeMessage};
+
+/// [StreamMessage] is one element of a BINEX stream we can interpret.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamMessage {
+    /// [Message] described by our BINEX library
+    Message(Message),
+    /// [PrototypeMessage], described by this crate
+    Prototype(PrototypeMessage),
+}
+
+impl From<Message> for StreamMessage {
+    fn from(msg: Message) -> Self {
+        Self::Message(msg)
+    }
+}
+
+impl From<PrototypeMessage> for StreamMessage {
+    fn from(msg: PrototypeMessage) -> Self {
+        Self::Prototype(msg)
+    }
+}
+
+impl StreamMessage {
+    /// Total encoding size of this [StreamMessage], in bytes.
+    pub fn encoding_size(&self) -> usize {
+        match self {
+            Self::Message(msg) => msg.encoding_size(),
+            Self::Prototype(msg) => msg.encoding_size(),
+        }
+    }
+
+    /// Encodes this [StreamMessage] into this buffer of given size.
+    /// Returns the total encoded size.
+    pub fn encode(&self, buf: &mut [u8], size: usize) -> Result<usize, Error> {
+        match self {
+            Self::Message(msg) => msg.encode(buf, size),
+            Self::Prototype(msg) => msg.encode(buf, size),
+        }
+    }
+
+    /// Decodes a [StreamMessage] from this frame, that must start with the sync byte.
+    pub fn decode(buf: &[u8]) -> Result<Self, Error> {
+        if prototype::is_prototype(buf) {
+            Ok(PrototypeMessage::decode(buf)?.into())
+        } else {
+            Ok(Message::decode(buf)?.into())
+        }
+    }
+}
@@ -1,5 +1,5 @@
 //! Carrier channels and associated methods
-use crate::prelude::{Constellation, Error, Observable, ParsingError, SV};
+use crate::prelude::{Constellation, Error, FormattingError, Observable, ParsingError, SV};
 
 lazy_static! {
     pub(crate) static ref KNOWN_CODES: Vec<&'static str> = vec![
@@ -744,6 +744,7 @@ impl Carrier {
                 5 => Ok(Self::E5a),
                 6 => Ok(Self::E6),
                 7 => Ok(Self::E5b),
+                8 => Ok(Self::E5a5b),
                 _ => Ok(Self::E1),
             },
             Constellation::SBAS => match sv.prn {
@@ -777,6 +778,34 @@ impl Carrier {
         }
     }
 
+    /// Converts [Carrier] to standardized [SV] (#PRN), as used
+    /// in ATX format to specify antenna frequency. Reciprocal of [Self::from_sv].
+    pub(crate) fn to_antex_sv(self) -> Result<SV, FormattingError> {
+        let (constellation, prn) = match self {
+            Self::L1 => (Constellation::GPS, 1),
+            Self::L2 => (Constellation::GPS, 2),
+            Self::L5 => (Constellation::GPS, 5),
+            Self::L6 => (Constellation::QZSS, 6),
+            Self::G1(_) => (Constellation::Glonass, 1),
+            Self::G2(_) => (Constellation::Glonass, 2),
+            Self::G3 => (Constellation::Glonass, 3),
+            Self::E1 => (Constellation::Galileo, 1),
+            Self::E5a => (Constellation::Galileo, 5),
+            Self::E6 => (Constellation::Galileo, 6),
+            Self::E5b => (Constellation::Galileo, 7),
+            Self::E5a5b => (Constellation::Galileo, 8),
+            Self::B1c => (Constellation::BeiDou, 1),
+            Self::B1 => (Constellation::BeiDou, 2),
+            Self::B2a => (Constellation::BeiDou, 5),
+            Self::B3 => (Constellation::BeiDou, 6),
+            Self::B2b => (Constellation::BeiDou, 7),
+            Self::B2 => (Constellation::BeiDou, 8),
+            Self::S => (Constellation::IRNSS, 9),
+            _ => return Err(FormattingError::AntexFrequency),
+        };
+        Ok(SV::new(constellation, prn))
+    }
+
     /// Builds [Carrier] from DORIS [Observable]
     pub fn from_doris_observable(obs: &Observable) -> Result<Self, Error> {
         let obs = obs.to_string();
@@ -1048,4 +1077,33 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn antex_frequency_reciprocal() {
+        for carrier in [
+            Carrier::L1,
+            Carrier::L2,
+            Carrier::L5,
+            Carrier::L6,
+            Carrier::G1(None),
+            Carrier::G2(None),
+            Carrier::G3,
+            Carrier::E1,
+            Carrier::E5a,
+            Carrier::E5b,
+            Carrier::E5a5b,
+            Carrier::E6,
+            Carrier::B1c,
+            Carrier::B1,
+            Carrier::B2a,
+            Carrier::B2b,
+            Carrier::B2,
+            Carrier::B3,
+            Carrier::S,
+        ] {
+            let sv = carrier.to_antex_sv().unwrap();
+            assert_eq!(Carrier::from_sv(sv).unwrap(), carrier);
+        }
+        assert!(Carrier::S1.to_antex_sv().is_err());
+    }
 }
@@ -0,0 +1,200 @@
+use std::io::{BufWriter, Write};
+
+use crate::{
+    clock::{ClockKey, ClockProfile, Record},
+    epoch::epoch_decompose,
+    prelude::{Epoch, FormattingError, Header, Version},
+};
+
+/// Formats clock profile value, following the E19.12 standard specs
+fn format_value(value: f64) -> String {
+    let sign_str = if value.is_sign_positive() { " " } else { "" };
+    let formatted = format!("{:.12E}", value);
+
+    let (base, exponent) = formatted.split_once('E').unwrap_or((&formatted, "0"));
+
+    let exp_sign = if exponent.starts_with('-') { "-" } else { "+" };
+    let exp_value = exponent
+        .trim_start_matches(&['+', '-'][..])
+        .parse::<i32>()
+        .unwrap_or(0);
+
+    format!("{}{}E{}{:02}", sign_str, base, exp_sign, exp_value)
+}
+
+/// Formats one [ClockProfile] (single line or two lines) into [BufWriter]
+fn format_profile<W: Write>(
+    w: &mut BufWriter<W>,
+    version: Version,
+    epoch: Epoch,
+    key: &ClockKey,
+    profile: &ClockProfile,
+) -> Result<(), FormattingError> {
+    const LIMIT: Version = Version { major: 3, minor: 4 };
+
+    let (y, m, d, hh, mm, ss, nanos) = epoch_decompose(epoch);
+    let seconds = ss as f64 + nanos as f64 * 1.0E-9;
+
+    // Second line is positional: drift, drift(dev), drift change, drift change (dev).
+    // Intermediate missing values are replaced by null values.
+    let second_line = [
+        profile.drift,
+        profile.drift_dev,
+        profile.drift_change,
+        profile.drift_change_dev,
+    ];
+
+    let num_second_line = second_line
+        .iter()
+        .rposition(|value| value.is_some())
+        .map(|pos| pos + 1)
+        .unwrap_or(0);
+
+    let mut num_values = 1 + num_second_line;
+    if profile.bias_dev.is_some() || num_second_line > 0 {
+        num_values += 1;
+    }
+
+    let name = key.clock_type.to_string();
+
+    if version < LIMIT {
+        write!(w, "{} {:<4} ", key.profile_type, name)?;
+    } else {
+        write!(w, "{} {:<9} ", key.profile_type, name)?;
+    }
+
+    write!(
+        w,
+        "{:04} {:02} {:02} {:02} {:02} {:9.6} {:2}   {}",
+        y,
+        m,
+        d,
+        hh,
+        mm,
+        seconds,
+        num_values,
+        format_value(profile.bias)
+    )?;
+
+    if let Some(sigma) = profile.bias_dev {
+        write!(w, " {}", format_value(sigma))?;
+    } else if num_second_line > 0 {
+        write!(w, " {}", format_value(0.0))?;
+    }
+
+    writeln!(w)?;
+
+    if num_second_line > 0 {
+        write!(w, "   ")?;
+
+        for value in second_line.iter().take(num_second_line) {
+            write!(w, " {}", format_value(value.unwrap_or(0.0)))?;
+        }
+
+        writeln!(w)?;
+    }
+
+    Ok(())
+}
+
+/// Formats Clock [Record] into [BufWriter]
+pub fn format<W: Write>(
+    w: &mut BufWriter<W>,
+    record: &Record,
+    header: &Header,
+) -> Result<(), FormattingError> {
+    for (epoch, profiles) in record.iter() {
+        for (key, profile) in profiles.iter() {
+            format_profile(w, header.version, *epoch, key, profile)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{format_profile, format_value};
+    use crate::{
+        clock::{record::parse_epoch, ClockKey, ClockProfile, ClockProfileType, ClockType},
+        prelude::{Epoch, TimeScale, Version, SV},
+        tests::formatting::Utf8Buffer,
+    };
+
+    use std::io::BufWriter;
+    use std::str::FromStr;
+
+    #[test]
+    fn clock_value_formatting() {
+        for (value, expected) in [
+            (0.0, " 0.000000000000E+00"),
+            (-0.364887538519E-03, "-3.648875385190E-04"),
+            (0.835484069663E-11, " 8.354840696630E-12"),
+            (1.0, " 1.000000000000E+00"),
+        ] {
+            assert_eq!(format_value(value), expected);
+        }
+    }
+
+    #[test]
+    fn clock_profile_formatting() {
+        for (version, key, profile, expected) in [
+            (
+                Version::new(2, 0),
+                ClockKey {
+                    clock_type: ClockType::SV(SV::from_str("R20").unwrap()),
+                    profile_type: ClockProfileType::AS,
+                },
+                ClockProfile {
+                    bias: -0.364887538519E-03,
+                    ..Default::default()
+                },
+                "AS R20  2019 01 08 00 03 30.000000  1   -3.648875385190E-04\n",
+            ),
+            (
+                Version::new(3, 0),
+                ClockKey {
+                    clock_type: ClockType::Station("USNO".to_string()),
+                    profile_type: ClockProfileType::DR,
+                },
+                ClockProfile {
+                    bias: -0.123456789012E+00,
+                    bias_dev: Some(-0.123456789012E+01),
+                    drift: Some(-0.123456789012E-03),
+                    drift_dev: Some(-0.123456789012E-04),
+                    ..Default::default()
+                },
+                "DR USNO 2019 01 08 00 03 30.000000  4   -1.234567890120E-01 -1.234567890120E+00
+    -1.234567890120E-04 -1.234567890120E-05\n",
+            ),
+            (
+                Version::new(3, 4),
+                ClockKey {
+                    clock_type: ClockType::SV(SV::from_str("G16").unwrap()),
+                    profile_type: ClockProfileType::AS,
+                },
+                ClockProfile {
+                    bias: -0.123456789012E+00,
+                    bias_dev: Some(-0.123456789012E+01),
+                    ..Default::default()
+                },
+                "AS G16       2019 01 08 00 03 30.000000  2   -1.234567890120E-01 -1.234567890120E+00\n",
+            ),
+        ] {
+            let epoch = Epoch::from_str("2019-01-08T00:03:30 GPST").unwrap();
+
+            let mut buf = BufWriter::new(Utf8Buffer::new(1024));
+            format_profile(&mut buf, version, epoch, &key, &profile).unwrap();
+
+            let formatted = buf.into_inner().unwrap().to_ascii_utf8();
+            assert_eq!(formatted, expected);
+
+            // reciprocal
+            let (parsed_epoch, parsed_key, parsed_profile) =
+                parse_epoch(version, &formatted, TimeScale::GPST).unwrap();
+
+            assert_eq!(parsed_epoch, epoch);
+            assert_eq!(parsed_key, key);
+            assert_eq!(parsed_profile, profile);
+        }
+    }
+}
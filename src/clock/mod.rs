@@ -1,8 +1,11 @@
 //! RINEX Clock files parser & analysis
+mod formatting;
 pub mod record;
 
 pub use record::{ClockKey, ClockProfile, ClockProfileType, ClockType, Record};
 
+pub(crate) use formatting::format;
+
 use std::{
     io::{BufWriter, Write},
     str::FromStr,
@@ -87,20 +90,37 @@ impl WorkClock {
             }
         }
     }
-}
 
-impl HeaderFields {
-    /// Formats [HeaderFields] into [BufWriter].
-    pub(crate) fn format<W: Write>(&self, w: &mut BufWriter<W>) -> Result<(), FormattingError> {
-        write!(w, "{:6}", self.codes.len())?;
+    /// Formats [WorkClock] description, mirroring [WorkClock::parse].
+    pub(crate) fn format(&self, version: Version) -> String {
+        const LIMIT: Version = Version { major: 3, minor: 4 };
 
-        for (nth, code) in self.codes.iter().enumerate() {
-            write!(w, "{:6}", code,)?;
+        let domes = match &self.domes {
+            Some(domes) => domes.to_string(),
+            None => String::new(),
+        };
 
-            if nth % 9 == 8 {
-                writeln!(w, "# / TYPES OF DATA\n      ")?;
-            }
+        let constraint = match self.constraint {
+            Some(value) => format!("{:19.12E}", value),
+            None => String::new(),
+        };
+
+        if version < LIMIT {
+            format!("{:<4} {:<35}{:>20}", self.name, domes, constraint)
+        } else {
+            format!("{:<10}{:<10}{:>20}", self.name, domes, constraint)
         }
+    }
+}
+
+impl HeaderFields {
+    /// Formats [HeaderFields] into [BufWriter].
+    pub(crate) fn format<W: Write>(
+        &self,
+        w: &mut BufWriter<W>,
+        version: Version,
+    ) -> Result<(), FormattingError> {
+        const LIMIT: Version = Version { major: 3, minor: 4 };
 
         // possible timescale
         if let Some(ts) = self.timescale {
@@ -111,6 +131,46 @@ impl HeaderFields {
             )?;
         }
 
+        let mut types = format!("{:6}", self.codes.len());
+        for code in self.codes.iter() {
+            types.push_str(&format!("{:>6}", code.to_string()));
+        }
+
+        writeln!(w, "{}", fmt_rinex(&types, "# / TYPES OF DATA"))?;
+
+        if let Some(site) = &self.site {
+            let domes = match &self.domes {
+                Some(domes) => domes.to_string(),
+                None => String::new(),
+            };
+
+            let content = if version < LIMIT {
+                format!("{:<4} {}", site, domes)
+            } else {
+                format!("{:<9} {}", site, domes)
+            };
+
+            writeln!(w, "{}", fmt_rinex(&content, "STATION NAME / NUM"))?;
+        }
+
+        if let Some(clk) = &self.ref_clock {
+            writeln!(w, "{}", fmt_rinex(clk, "STATION CLK REF"))?;
+        }
+
+        if self.igs.is_some() || self.full_name.is_some() {
+            let igs = self.igs.as_deref().unwrap_or("");
+            let name = self.full_name.as_deref().unwrap_or("");
+            writeln!(
+                w,
+                "{}",
+                fmt_rinex(&format!("{:<3}  {}", igs, name), "ANALYSIS CENTER")
+            )?;
+        }
+
+        for clk in self.work_clock.iter() {
+            writeln!(w, "{}", fmt_rinex(&clk.format(version), "ANALYSIS CLK REF"))?;
+        }
+
         Ok(())
     }
 
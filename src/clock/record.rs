@@ -10,6 +10,9 @@ use crate::{
 #[cfg(feature = "processing")]
 use qc_traits::{DecimationFilter, DecimationFilterType, FilterItem, MaskFilter, MaskOperand};
 
+#[cfg(feature = "processing")]
+use crate::processing::{unsupported_target, TargetDecimator};
+
 /// [`ClockKey`] describes each [`ClockProfile`] at a specific [Epoch].
 #[derive(Error, PartialEq, Eq, Hash, Clone, Debug, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -265,50 +268,6 @@ pub(crate) fn parse_epoch(
     ))
 }
 
-/// Writes epoch into stream
-pub(crate) fn fmt_epoch(epoch: &Epoch, key: &ClockKey, prof: &ClockProfile) -> String {
-    let mut lines = String::with_capacity(60);
-    let (y, m, d, hh, mm, ss, _) = epoch.to_gregorian_utc();
-
-    let mut n = 1;
-    if prof.drift.is_some() {
-        n += 1;
-    }
-    if prof.drift_dev.is_some() {
-        n += 1;
-    }
-    if prof.drift_change.is_some() {
-        n += 1;
-    }
-    if prof.drift_change_dev.is_some() {
-        n += 1;
-    }
-
-    lines.push_str(&format!(
-        "{} {}  {} {:02} {:02} {:02} {:02} {:02}.000000  {}   {:.12E}",
-        key.profile_type, key.clock_type, y, m, d, hh, mm, ss, n, prof.bias
-    ));
-
-    if let Some(sigma) = prof.bias_dev {
-        lines.push_str(&format!("{:.13E} ", sigma));
-    }
-    lines.push('\n');
-    if let Some(drift) = prof.drift {
-        lines.push_str(&format!("   {:.13E} ", drift));
-        if let Some(sigma) = prof.drift_dev {
-            lines.push_str(&format!("{:.13E} ", sigma));
-        }
-        if let Some(drift_change) = prof.drift_change {
-            lines.push_str(&format!("{:.13E} ", drift_change));
-        }
-        if let Some(sigma) = prof.drift_change_dev {
-            lines.push_str(&format!("{:.13E} ", sigma));
-        }
-        lines.push('\n');
-    }
-    lines
-}
-
 #[cfg(feature = "processing")]
 pub(crate) fn clock_mask_mut(rec: &mut Record, mask: &MaskFilter) {
     match mask.operand {
@@ -353,8 +312,36 @@ pub(crate) fn clock_mask_mut(rec: &mut Record, mask: &MaskFilter) {
 
 #[cfg(feature = "processing")]
 pub(crate) fn clock_decim_mut(rec: &mut Record, f: &DecimationFilter) {
-    if f.item.is_some() {
-        todo!("targetted decimation not supported yet");
+    if let Some(item) = &f.item {
+        if !matches!(
+            item,
+            FilterItem::SvItem(_) | FilterItem::ConstellationItem(_) | FilterItem::ComplexItem(_)
+        ) {
+            unsupported_target("Clock", item);
+            return;
+        }
+        let mut decimator = TargetDecimator::new(&f.filter);
+        rec.retain(|t, clocks| {
+            clocks.retain(|key, _| {
+                let targeted = match (item, &key.clock_type) {
+                    (FilterItem::SvItem(svs), ClockType::SV(sv)) => svs.contains(sv),
+                    (FilterItem::ConstellationItem(constellations), ClockType::SV(sv)) => {
+                        constellations.contains(&sv.constellation)
+                    },
+                    (FilterItem::ComplexItem(items), ClockType::Station(name)) => {
+                        items.contains(name)
+                    },
+                    _ => false,
+                };
+                if targeted {
+                    decimator.retain(key.clone(), *t)
+                } else {
+                    true
+                }
+            });
+            !clocks.is_empty()
+        });
+        return;
     }
     match f.filter {
         DecimationFilterType::Modulo(r) => {
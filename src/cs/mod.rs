@@ -1,414 +1,478 @@
-use crate::{prelude::*, Carrier};
-use std::collections::HashMap;
+//! Cycle slip detection
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
-#[derive(Default, Debug, Clone, PartialEq)]
+use crate::{
+    observation::{Combination, LliFlags, SignalObservation},
+    prelude::{Carrier, Duration, Epoch, Observable, Rinex, SV},
+};
+
+/// Cycle slip event: [Epoch] of detection, [SV] and [Carrier] signal
+pub type CycleSlip = (Epoch, SV, Carrier);
+
+/// Cycle slip detection strategy
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum CsStrategy {
-    /// Ultimate detection method, not particularly more intense
-    /// than others, but requires modern Multi carrier frequencies
-    #[default]
-    GfAdvanced,
-    /// Simple GF based CS detection method.
-    /// This does not work well in case of high ionospheric
-    /// activity
-    GfSimple,
-    /// MW recombination based CS detection method
-    Mw,
-    /// PhaseDoppler method works every single context,
-    /// as long as doppler shifts were observed.
+    /// Geometry Free (GF) phase combination, compared between successive epochs.
+    /// Requires phase observations on at least two carriers. With two carriers, both
+    /// are reported. With three carriers or more, a jump found in every combination is attributed
+    /// to the pivot (L1) carrier, otherwise to the carriers of the combinations that jumped.
+    /// This method is sensitive to rapid ionospheric variations.
+    GeometryFree,
+    /// Melbourne-Wübbena (MW) combination, compared to the average of the current arc.
+    /// Requires phase and pseudo range observations on at least two carriers.
+    /// Carriers are identified like [CsStrategy::GeometryFree].
+    /// This method is insensitive to the ionosphere but noisy: only large slips are detected.
+    MelbourneWubbena,
+    /// Phase variation compared to the integrated Doppler shift, for each carrier.
+    /// Requires Doppler observations and works best at high sampling rates.
     PhaseDoppler,
-    /// Last option, works on every single context,
-    /// very dependent on tuning parameters.
+    /// Code minus phase, compared between successive epochs, for each carrier.
+    /// Always feasible, but only detects large slips and requires fine tuning.
     SingleFrequency,
 }
 
-#[derive(Default, Debug, Clone, PartialEq)]
+/// [CsStrategy] by order of preference
+const STRATEGIES: [CsStrategy; 4] = [
+    CsStrategy::GeometryFree,
+    CsStrategy::MelbourneWubbena,
+    CsStrategy::PhaseDoppler,
+    CsStrategy::SingleFrequency,
+];
+
+/// [CsStrategy] selection method, applied to each satellite
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
 pub enum CsSelectionMethod {
-    /// Selects most suited method automatically
+    /// Selects most suited [CsStrategy] automatically
     #[default]
     Auto,
-    /// Use this stragegy ideally
+    /// Use this [CsStrategy] when feasible, select automatically otherwise
     Prefered(CsStrategy),
-    /// Use this stragegy and only this one
+    /// Use this [CsStrategy] and only this one
     Manual(CsStrategy),
 }
 
-#[derive(Debug, Default, Clone)]
+/// [CsDetector] detects phase cycle slips in Observation [Rinex].
+/// ```
+/// use rinex::prelude::{obs::CsDetector, Rinex};
+///
+/// let rinex = Rinex::from_file("data/OBS/V3/DUTH0630.22O")
+///     .unwrap();
+///
+/// let detector = CsDetector::default()
+///     .with_gf_threshold(0.1);
+///
+/// for (epoch, sv, carrier) in detector.cs_detection(&rinex) {
+///     // cycle slip on this carrier signal
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
 pub struct CsDetector {
-    /// CS detection strategy
-    pub strategy: CsStrategy,
-    /// CS strategy selection method
+    /// [CsStrategy] selection method
     pub method: CsSelectionMethod,
+    /// GF variation threshold between successive epochs, in meters
+    pub gf_threshold_m: f64,
+    /// MW deviation threshold from the arc average, in meters
+    pub mw_threshold_m: f64,
+    /// Phase residual threshold against integrated Doppler, in cycles
+    pub doppler_threshold_cycles: f64,
+    /// Code minus phase variation threshold between successive epochs, in meters
+    pub cmc_threshold_m: f64,
+}
+
+impl Default for CsDetector {
+    /// Builds a [CsDetector] suited for 30s sampling
+    fn default() -> Self {
+        Self {
+            method: Default::default(),
+            gf_threshold_m: 0.05,
+            mw_threshold_m: 0.75,
+            doppler_threshold_cycles: 1.0,
+            cmc_threshold_m: 5.0,
+        }
+    }
+}
+
+/// Outcome of one [CsStrategy]
+#[derive(Default)]
+struct Detections {
+    /// [SV]s for which this strategy was feasible
+    feasible: HashSet<SV>,
+    /// Detected [CycleSlip]s
+    slips: BTreeSet<CycleSlip>,
+}
+
+/// Combination jumps at (Epoch, SV): pivot [Carrier], number of evaluated
+/// combinations, and [Carrier]s of the combinations that jumped.
+type Jumps = BTreeMap<(Epoch, SV), (Carrier, usize, Vec<Carrier>)>;
+
+/// Identifies the slipping [Carrier]s from the combinations that jumped
+fn attribute(jumps: Jumps, slips: &mut BTreeSet<CycleSlip>) {
+    for ((epoch, sv), (pivot, evaluated, jumped)) in jumps {
+        if jumped.is_empty() {
+            continue;
+        }
+
+        if evaluated > 1 && jumped.len() == evaluated {
+            // common to all combinations
+            slips.insert((epoch, sv, pivot));
+        } else {
+            if evaluated == 1 {
+                // ambiguous
+                slips.insert((epoch, sv, pivot));
+            }
+            for carrier in jumped {
+                slips.insert((epoch, sv, carrier));
+            }
+        }
+    }
+}
+
+/// Locates the observation of same [SV] and [Carrier] as this phase observation,
+/// matching this [Observable] kind. Identical codes are prefered.
+fn same_carrier<'a>(
+    signals: &'a [SignalObservation],
+    phase: &SignalObservation,
+    carrier: Carrier,
+    kind: fn(&Observable) -> bool,
+) -> Option<&'a SignalObservation> {
+    let code = phase.observable.code();
+
+    let mut candidates = signals.iter().filter(|sig| {
+        sig.sv == phase.sv
+            && kind(&sig.observable)
+            && sig.observable.to_carrier(sig.sv.constellation).ok() == Some(carrier)
+    });
+
+    let first = candidates.next()?;
+
+    if first.observable.code() == code {
+        Some(first)
+    } else {
+        Some(
+            candidates
+                .find(|sig| sig.observable.code() == code)
+                .unwrap_or(first),
+        )
+    }
 }
 
 impl CsDetector {
-    /// Returns list of Epoch where detector determined a CS happened
-    /// rnx: should be Observation RINEX
-    pub fn cs_detection(&mut self, rnx: &Rinex) -> HashMap<Carrier, HashMap<Sv, Vec<Epoch>>> {
-        let ret: HashMap<Carrier, HashMap<Sv, Vec<Epoch>>> = HashMap::new();
-        let _permutation_allowed = match self.method {
-            CsSelectionMethod::Auto | CsSelectionMethod::Prefered(_) => true,
-            _ => false,
+    /// Copies and returns [CsDetector] with updated [CsSelectionMethod]
+    pub fn with_selection_method(&self, method: CsSelectionMethod) -> Self {
+        let mut s = self.clone();
+        s.method = method;
+        s
+    }
+
+    /// Copies and returns [CsDetector] with updated GF threshold (meters)
+    pub fn with_gf_threshold(&self, threshold_m: f64) -> Self {
+        let mut s = self.clone();
+        s.gf_threshold_m = threshold_m;
+        s
+    }
+
+    /// Copies and returns [CsDetector] with updated MW threshold (meters)
+    pub fn with_mw_threshold(&self, threshold_m: f64) -> Self {
+        let mut s = self.clone();
+        s.mw_threshold_m = threshold_m;
+        s
+    }
+
+    /// Copies and returns [CsDetector] with updated Phase/Doppler threshold (cycles)
+    pub fn with_doppler_threshold(&self, threshold_cycles: f64) -> Self {
+        let mut s = self.clone();
+        s.doppler_threshold_cycles = threshold_cycles;
+        s
+    }
+
+    /// Copies and returns [CsDetector] with updated code minus phase threshold (meters)
+    pub fn with_cmc_threshold(&self, threshold_m: f64) -> Self {
+        let mut s = self.clone();
+        s.cmc_threshold_m = threshold_m;
+        s
+    }
+
+    /// Detects phase cycle slips in this Observation [Rinex].
+    /// Returns the [CycleSlip]s in chronological order.
+    /// Detection restarts after each data gap (larger than the sampling interval),
+    /// and on abnormal sampling conditions.
+    pub fn cs_detection(&self, rinex: &Rinex) -> Vec<CycleSlip> {
+        let dt = match rinex
+            .header
+            .sampling_interval
+            .or_else(|| rinex.dominant_sampling_interval())
+        {
+            Some(dt) => dt,
+            None => return Vec::new(),
+        };
+
+        let strategies = match self.method {
+            CsSelectionMethod::Auto => STRATEGIES.to_vec(),
+            CsSelectionMethod::Prefered(strategy) => {
+                let mut strategies = vec![strategy];
+                strategies.extend(STRATEGIES.iter().filter(|s| **s != strategy));
+                strategies
+            },
+            CsSelectionMethod::Manual(strategy) => vec![strategy],
         };
 
-        match self.method {
-            CsSelectionMethod::Auto
-            | CsSelectionMethod::Prefered(CsStrategy::GfAdvanced)
-            | CsSelectionMethod::Prefered(CsStrategy::GfSimple)
-            | CsSelectionMethod::Manual(CsStrategy::GfAdvanced)
-            | CsSelectionMethod::Manual(CsStrategy::GfSimple) => {
-                /*
-                 * Compute GF recombinations
-                 */
-                let iono = rnx.observation_iono_detector();
-                // op is feasible if at least two independent phase combinations were created
-                let mut _is_feasible = false;
-                let mut candidates: Vec<String> = Vec::with_capacity(iono.len());
-                let mut confirmed: Vec<String> = Vec::with_capacity(iono.len());
-                for (op, _) in &iono {
-                    let items: Vec<&str> = op.split("-").collect();
-                    let (_lhs_op, rhs_op) = (items[0], items[1]);
-                    if candidates.contains(&rhs_op.to_string())
-                        && !confirmed.contains(&rhs_op.to_string())
-                    {
-                        confirmed.push(rhs_op.to_string());
-                    } else {
-                        candidates.push(rhs_op.to_string());
+        let detections = strategies
+            .iter()
+            .map(|strategy| match strategy {
+                CsStrategy::GeometryFree => self.geometry_free(rinex, dt),
+                CsStrategy::MelbourneWubbena => self.melbourne_wubbena(rinex, dt),
+                CsStrategy::PhaseDoppler => self.phase_doppler(rinex, dt),
+                CsStrategy::SingleFrequency => self.single_frequency(rinex, dt),
+            })
+            .collect::<Vec<_>>();
+
+        let satellites = detections
+            .iter()
+            .flat_map(|detection| detection.feasible.iter())
+            .copied()
+            .collect::<HashSet<_>>();
+
+        let mut slips = BTreeSet::new();
+
+        for sv in satellites {
+            // most suited strategy, for this satellite
+            if let Some(detection) = detections.iter().find(|d| d.feasible.contains(&sv)) {
+                slips.extend(detection.slips.iter().filter(|(_, s, _)| *s == sv));
+            }
+        }
+
+        slips.into_iter().collect()
+    }
+
+    /// Detects phase cycle slips like [Self::cs_detection], and
+    /// marks them with [LliFlags::LOCK_LOSS] in this Observation [Rinex].
+    pub fn cs_detection_mut(&self, rinex: &mut Rinex) -> Vec<CycleSlip> {
+        let slips = self.cs_detection(rinex);
+
+        let lookup = slips.iter().copied().collect::<HashSet<_>>();
+
+        if let Some(rec) = rinex.record.as_mut_obs() {
+            for (k, v) in rec.iter_mut() {
+                for sig in v.signals.iter_mut() {
+                    if !sig.observable.is_phase_range_observable() {
+                        continue;
                     }
-                }
 
-                println!(
-                    "cs_detection - combinations: {:?}",
-                    iono.keys().map(|s| format!("{} ", s)).collect::<String>()
-                ); //DEBUG
-                println!("{:?} - confirmed: {:?}", CsStrategy::GfSimple, confirmed); //DEBUG
-
-                // for each iono confirmed candidates,
-                //  determine if a 3rd phase combination exists
-                for op in confirmed {
-                    let ops: Vec<_> = iono
-                        .keys()
-                        .filter_map(|k| if k.contains(&op) { Some(k) } else { None })
-                        .collect();
-                    if ops.len() > 0 {
-                        println!("{:?} - is possible for \"{}\"", CsStrategy::GfAdvanced, op);
-                        // DEBUG
+                    if let Ok(carrier) = sig.observable.to_carrier(sig.sv.constellation) {
+                        if lookup.contains(&(k.epoch, sig.sv, carrier)) {
+                            let lli = sig.lli.get_or_insert(LliFlags::OK_OR_UNKNOWN);
+                            *lli |= LliFlags::LOCK_LOSS;
+                        }
                     }
                 }
-            },
-            _ => {},
+            }
         }
-        ret
+
+        slips
     }
-}
-/*
-    fn single_frequency_cs_detection(
-        &self,
-        sample_rate: Duration,
-        opts: CsOpts,
-    ) -> BTreeMap<Epoch, HashMap<Sv, String>> {
-        let mut ret: BTreeMap<Epoch, HashMap<Sv, String>> = BTreeMap::new();
-        // 1. Form Phase/PR recombinations
-        // 2. Study recombination evolution over time
-        let mut prev_data: HashMap<Sv, (Epoch, f64)> = HashMap::new();
-        if let Some(r) = self.record.as_obs() {
-            for ((epoch, _), (_, svs)) in r {
-                for (sv, observations) in svs {
-                    for (lhs_code, lhs_data) in observations {
-                        let lhs_carrier = &lhs_code[1..1];
-                        if is_phase_observation(lhs_code) {
-                            // locate a PR OBS
-                            for (rhs_code, rhs_data) in observations {
-                                let rhs_carrier = &rhs_code[1..];
-                                if lhs_carrier == rhs_carrier {
-                                    if is_pseudorange_observation(rhs_code) {
-                                        let recomb_y = lhs_data.obs - rhs_data.obs;
-                                        //let op = rhs_code[1..].to_owned() + &lhs_code[1..];
-                                        if let Some((p_epoch, p_data)) = prev_data.get_mut(sv) {
-                                            let dy = recomb_y - *p_data;
-                                            let dt = *epoch - *p_epoch;
-                                            if dy.abs() > opts.sensitivity(dt, sample_rate) {
-                                                println!(
-                                                    "{} (dt={}) - GAP DETECTED |{} - {}| = {}",
-                                                    epoch,
-                                                    dt,
-                                                    recomb_y,
-                                                    p_data,
-                                                    dy.abs()
-                                                ); //DEBUG
-                                            }
-                                        } else {
-                                            prev_data.insert(*sv, (*epoch, recomb_y));
-                                        }
-                                    }
-                                }
-                            }
-                        }
+
+    /// GF variations between successive epochs
+    fn geometry_free(&self, rinex: &Rinex, dt: Duration) -> Detections {
+        let mut ret = Detections::default();
+        let mut jumps = Jumps::new();
+        let mut last = HashMap::<(SV, Observable, Observable), (Epoch, f64)>::new();
+
+        for (k, value) in rinex.signals_combination(Combination::GeometryFree) {
+            if !k.flag.is_ok() || !k.lhs.is_phase_range_observable() {
+                continue;
+            }
+
+            let (carrier, pivot) = match (
+                k.lhs.to_carrier(k.sv.constellation),
+                k.reference.to_carrier(k.sv.constellation),
+            ) {
+                (Ok(carrier), Ok(pivot)) => (carrier, pivot),
+                _ => continue,
+            };
+
+            ret.feasible.insert(k.sv);
+
+            let arc = (k.sv, k.lhs, k.reference);
+
+            if let Some((t, prev)) = last.insert(arc, (k.epoch, value)) {
+                if k.epoch - t <= dt {
+                    let (_, evaluated, jumped) =
+                        jumps.entry((k.epoch, k.sv)).or_insert((pivot, 0, vec![]));
+
+                    *evaluated += 1;
+
+                    if (value - prev).abs() > self.gf_threshold_m {
+                        jumped.push(carrier);
                     }
                 }
             }
         }
+
+        attribute(jumps, &mut ret.slips);
         ret
     }
-*/
-
-/*
-/*
- * Selects an algorithm for each vehicle and each observation
- */
-pub (crate)fn select_algorithm(&self, consistency: u8, sv: Sv, record: &Record) -> HashMap<Sv, HashMap<String, bool>> {
-    let mut count: HashMap<String, (u32, u32)>; // (nb_epoch_seen, total_nb_epoch) per code
-    let total_epochs = record.len();
-    for ((epoch, _), (_, svs)) in record {
-        for (sv, observations) in svs {
-            let mut remaining = observations.keys().collect();
-            for (code, (seen, total)) in count {
-                if let Some(_) = observations.get(code) {
-                    // code is seen again
-                    seen += 1;
-                    remaining.remove(code);
-                }
-                total += 1;
+
+    /// MW deviations from the arc average
+    fn melbourne_wubbena(&self, rinex: &Rinex, dt: Duration) -> Detections {
+        let mut ret = Detections::default();
+        let mut jumps = Jumps::new();
+
+        // latest epoch, arc average and arc length
+        let mut arcs = HashMap::<(SV, Observable, Observable), (Epoch, f64, f64)>::new();
+
+        for (k, value) in rinex.signals_combination(Combination::MelbourneWubbena) {
+            if !k.flag.is_ok() {
+                continue;
             }
-            for k in remaining {
-                if count.get(k).is_none() {
-                    // new code, never before seen
-                    count.insert(k.to_string(), (1, 1));
+
+            let (carrier, pivot) = match (
+                k.lhs.to_carrier(k.sv.constellation),
+                k.reference.to_carrier(k.sv.constellation),
+            ) {
+                (Ok(carrier), Ok(pivot)) => (carrier, pivot),
+                _ => continue,
+            };
+
+            ret.feasible.insert(k.sv);
+
+            let arc = arcs
+                .entry((k.sv, k.lhs, k.reference))
+                .or_insert((k.epoch, value, 0.0));
+
+            let (t, mean, len) = *arc;
+
+            if len > 0.0 && k.epoch - t <= dt {
+                let (_, evaluated, jumped) =
+                    jumps.entry((k.epoch, k.sv)).or_insert((pivot, 0, vec![]));
+
+                *evaluated += 1;
+
+                if (value - mean).abs() > self.mw_threshold_m {
+                    jumped.push(carrier);
+                    // new arc
+                    *arc = (k.epoch, value, 1.0);
+                } else {
+                    *arc = (k.epoch, (mean * len + value) / (len + 1.0), len + 1.0);
                 }
+            } else {
+                // new arc
+                *arc = (k.epoch, value, 1.0);
             }
         }
 
-        /*
-         * if all codes are already above threshold
-         * => end this loop early
-         * ==> makes decision process quickness related to designed consistency
-         */
-        let mut above = true;
-        for (code, (seen, _)) in count {
-            above &= (seen * 100 / total_epochs as u32) > consistency as u32;
-        }
-        if above {
-            break;
-        }
+        attribute(jumps, &mut ret.slips);
+        ret
     }
-    count.iter()
-        .map(|(code, (seen, total))| {
-            (*code, (seen * 100 / total) > consistency as u32)
-        })
-        .collect()
-}
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum CsAlgorithm {
-    /// Geometry Free reombination based method.
-    /// Is only feasible if carrier phase was estimated for at least
-    /// 3 carrier signals.
-    GfCombinationAdvanced,
-    /// Geometry Free reombination based method.
-    /// This method is possible on modern RINEX where carrier phase was estimated
-    /// for at least two carrier signals.
-    /// The lower the sampling rate, the better this method will work.
-    /// This is the prefered method amongst all other
-    GfCombinationSimple,
-    /// Melbourne Wubbena recombination based method.
-    /// This method is possible on modern RINEX where carrier phase was estimated
-    /// for at least two carrier signals.
-    /// This is the preferred method.
-    MwCombination,
-    /// Preferred Fallback method for old RINEX context
-    /// or 1D contexts.
-    SingleFrequencyDoppler,
-    /// Fallback method, always feasible even on old RINEX
-    /// or single channel receivers.
-    /// Highly subject to parameters fine tuning
-    SingleFrequencyRecombination,
-}
+    /// Phase variations against integrated Doppler shifts
+    fn phase_doppler(&self, rinex: &Rinex, dt: Duration) -> Detections {
+        let mut ret = Detections::default();
 
-impl Default for CsAlgorithm {
-    fn default() -> Self {
-        Self::GfCombination
-    }
-}
+        // latest epoch, phase and doppler
+        let mut last = HashMap::<(SV, Observable), (Epoch, f64, f64)>::new();
 
-impl Into<u8> for CsAlgorithm {
-    fn into(&self) -> u8 {
-        match self {
-            Self::GfCombinationAdvanced => 255,
-            Self::GfCombinationSimple => 255,
-            Self::MwCombinationMultipath => 128,
-            Self::MwCombination => 64,
-            Self::SingleFrequencyDoppler => 8,
-            Self::SingleFrequencyRecombination => 1,
-        }
-    }
-}
+        let rec = match rinex.record.as_obs() {
+            Some(rec) => rec,
+            None => return ret,
+        };
 
-//impl PartialOrd for CsAlgorithm {}
-
-impl CsAlgorithm {
-    /// Returns true if self is feasible, on given context
-    pub (crate)fn is_feasible(&self, context: &HashMap<String, ObservationData>) -> bool {
-        match self {
-            Self::SingleFrequencyRecombination => {
-                for (code, _) in context {
-                    let carrier_nb = &code[1..1];
-                    if is_phase_carrier_obs_code!(code) {
-                        // search for a PR code
-                        for (others, _) in context {
-                            let other_carrier = &code[1..1];
-                            if others != code { // different code
-                                if carrier_nb == other_carrier { // same carrier
-                                    if is_pseudo_range_obs_code!(code) {
-                                        return true;
-                                    }
-                                }
-                            }
-                        }
-                    } else if is_pseudo_range_obs_code!(code) {
-                        // search for a PH code
-                        for (others, _) in context {
-                            let other_carrier = &code[1..1];
-                            if others != code { // different code
-                                if carrier_nb == other_carrier { // same carrier
-                                    if is_phase_carrier_obs_code!(code) {
-                                        return true;
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            },
-            Self::SingleFrequencyDoppler => {
-                let single_freq_ok = CsAlgorithm::SingleFrequency.is_feasible(context);
-                if !single_freq_ok {
-                    return false;
-                }
-                // search for doppler obs
-                for (code, _) in context {
-                    if is_doppler_obs_code!(code) {
-                        return true;
-                    }
-                }
-            },
-            Self::GfCombinationSimple => {
-                // identify at least 2 Carrier Phase
-                let mut nb_phase = 0;
-                for (code, _) in context {
-                    if is_phase_carrier_obs_code!(code) {
-                        nb_phase += 1;
-                    }
-                }
-            },
-            Self::GfCombinationAdvanced => {
-                let simple_ok = CsAlgorithm::GfCombinationSimple.is_feasible(context);
-                if !simple_ok {
-                    return false;
+        for (k, v) in rec.iter() {
+            if !k.flag.is_ok() {
+                continue;
+            }
+
+            for phase in v.signals.iter() {
+                if !phase.observable.is_phase_range_observable() {
+                    continue;
                 }
-                // identify at least 3 different PH observations
-                let mut phase_codes = 0;
-                for (code, _) in context {
-                    if is_phase_carrier_obs_code!(code) {
-                        phase_codes += 1;
+
+                let carrier = match phase.observable.to_carrier(phase.sv.constellation) {
+                    Ok(carrier) => carrier,
+                    Err(_) => continue,
+                };
+
+                let doppler = match same_carrier(
+                    &v.signals,
+                    phase,
+                    carrier,
+                    Observable::is_doppler_observable,
+                ) {
+                    Some(doppler) => doppler.value,
+                    None => continue,
+                };
+
+                ret.feasible.insert(phase.sv);
+
+                let arc = (phase.sv, phase.observable.clone());
+
+                if let Some((t, prev_phase, prev_doppler)) =
+                    last.insert(arc, (k.epoch, phase.value, doppler))
+                {
+                    let elapsed = k.epoch - t;
+                    if elapsed <= dt {
+                        // positive doppler shifts decrease the phase range
+                        let integrated = (doppler + prev_doppler) / 2.0 * elapsed.to_seconds();
+                        let residual = phase.value - prev_phase + integrated;
+
+                        if residual.abs() > self.doppler_threshold_cycles {
+                            ret.slips.insert((k.epoch, phase.sv, carrier));
+                        }
                     }
                 }
-                return phase_codes > 2;
-            },
-            Self::MwCombination => {
-
             }
-            _ => false,
         }
-        false
-    }
-}
-*/
-
-/*
-#[derive(Debug, Clone)]
-pub enum CsMethod {
-    /// The calculator decides which method to use,
-    /// by order of preference and decides to use this method
-    /// for all vehicles in the provided context
-    Auto,
-    /// The calculator elects 1 method that suites best
-    /// every single vehicle context
-    AutoSv,
-    /// The calculator uses given algorithm for every single Sv
-    Prefered(CsAlgorithm),
-    /// The calculator uses given algorithm if 1 method
-    /// has not been determined as better suited.
-    PreferedSv(CsAlgorithm),
-}*/
-
-/*
-#[derive(Debug, Clone)]
-pub struct OptsThreshold {
-    pub a0: f64,
-    pub a1: f64,
-}
 
-impl Default for OptsThreshold {
-    /// Builds defaults OptsThreshold, best suited for L1/L2 studies
-    /// with ~1min observation interval
-    fn default() -> Self {
-        let a0 = 3.0 * (Carrier::L2.carrier_wavelength() - Carrier::L1.carrier_wavelength()) / 2.0;
-        Self { a0, a1: a0 / 2.0 }
+        ret
     }
-}
 
-#[derive(Debug, Clone)]
-pub struct CsOpts {
-    // /// Decision method
-    // pub method: CsMethod,
-    // /// Percentage of epoch of the total record
-    // /// the method should remain feasible for it to be elected.
-    // /// A lower percentage gives quicker election process.
-    // pub method_consistency_pct: u8,
-    /// Sample rate dependent threshold,
-    /// in the form a0 - a1 e^-dt
-    /// to control the detector sensitivity against symbol rate
-    pub threshold: OptsThreshold,
-}
+    /// Code minus phase variations between successive epochs
+    fn single_frequency(&self, rinex: &Rinex, dt: Duration) -> Detections {
+        let mut ret = Detections::default();
+        let mut last = HashMap::<(SV, Observable), (Epoch, f64)>::new();
 
-impl Default for CsOpts {
-    fn default() -> Self {
-        Self {
-            /*method: CsMethod::Auto,
-            method_consistency_pct: 33,
-            threshold: OptsThreshold::default(),*/
+        let rec = match rinex.record.as_obs() {
+            Some(rec) => rec,
+            None => return ret,
+        };
+
+        for (k, v) in rec.iter() {
+            if !k.flag.is_ok() {
+                continue;
+            }
+
+            for phase in v.signals.iter() {
+                if !phase.observable.is_phase_range_observable() {
+                    continue;
+                }
+
+                let carrier = match phase.observable.to_carrier(phase.sv.constellation) {
+                    Ok(carrier) => carrier,
+                    Err(_) => continue,
+                };
+
+                let code = match same_carrier(
+                    &v.signals,
+                    phase,
+                    carrier,
+                    Observable::is_pseudo_range_observable,
+                ) {
+                    Some(code) => code.value,
+                    None => continue,
+                };
+
+                ret.feasible.insert(phase.sv);
+
+                let cmc = phase.value * carrier.wavelength() - code;
+                let arc = (phase.sv, phase.observable.clone());
+
+                if let Some((t, prev)) = last.insert(arc, (k.epoch, cmc)) {
+                    if k.epoch - t <= dt && (cmc - prev).abs() > self.cmc_threshold_m {
+                        ret.slips.insert((k.epoch, phase.sv, carrier));
+                    }
+                }
+            }
         }
-    }
-}
 
-impl CsOpts {
-    /*pub fn with_method(&self, method: CsMethod) -> Self {
-        let mut s = self.clone();
-        s.method = method;
-        s
-    }*/
-    /*
-    pub fn with_threshold(&self, threshold: OptsThreshold) -> Self {
-        let mut s = self.clone();
-        s.threshold = threshold;
-        s
-    }
-    */
-    /*
-    /// Returns detector sensitivity at current context, in meter,
-    /// based on given (a0, a1) parameters
-    pub fn sensitivity(&self, dt: Duration, sample_rate: Duration) -> f64 {
-        self.threshold.a0
-            - self.threshold.a1
-                * (-dt.to_unit(hifitime::Unit::Second)
-                    / sample_rate.to_unit(hifitime::Unit::Second))
-                .exp()
+        ret
     }
-    */
-    /*
-    /// Returns detector sensitivity [in ideal data scenario], in meter,
-    /// based on given (a0, a1) parameters
-    pub fn best_sensitivity(&self) -> f64 {
-        self.threshold.a0 - self.threshold.a1 * (-1.0_f64).exp()
-    }*/
 }
-*/
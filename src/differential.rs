@@ -1,20 +1,45 @@
-use super::*;
-//use navigation::*;
+//! Base / Rover differential processing
+use crate::{
+    observation::Record,
+    prelude::{Constellation, Epoch, Observable, ParsingError, Rinex, SV},
+};
+
+use std::collections::{BTreeMap, HashMap};
+
 use thiserror::Error;
-//use crate::observation::record::ObservationData;
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("`base` data must be Observation RINEX for this operation")]
     NotObservationBase,
-    #[error("`base` data must be Navigation RINEX for this operation")]
-    NotNavigationBase,
     #[error("`rover` must be Observation RINEX for this operation")]
     NotObservationRover,
-    #[error("`rover` must be Navigation RINEX for this operation")]
-    NotNavigationRover,
     #[error("failed to parse RINEX data")]
-    RinexError(#[from] super::Error),
+    ParsingError(#[from] ParsingError),
+}
+
+/// [SingleDiffKey] is how we sort between-receiver single differences
+#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
+pub struct SingleDiffKey {
+    /// [Epoch] of sampling
+    pub epoch: Epoch,
+    /// [SV]: signal source
+    pub sv: SV,
+    /// Differentiated [Observable]
+    pub observable: Observable,
+}
+
+/// [DoubleDiffKey] is how we sort double differences
+#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
+pub struct DoubleDiffKey {
+    /// [Epoch] of sampling
+    pub epoch: Epoch,
+    /// [SV]: signal source
+    pub sv: SV,
+    /// Reference [SV] (pivot) this [SV] is differentiated against
+    pub reference: SV,
+    /// Differentiated [Observable]
+    pub observable: Observable,
 }
 
 /// Advanced RINEX processing algorithms require
@@ -25,7 +50,22 @@ pub enum Error {
 /// We use this structure for operations / analysis that involve two file.
 /// When substracting data (A-B), B is always the "base" or "reference station",
 /// and A is the "rover" or "raw data".
-#[derive(Debug, Clone, PartialEq)]
+///
+/// ```
+/// use rinex::prelude::{*, obs::DiffContext};
+///
+/// let base = Rinex::from_file("data/OBS/V3/DUTH0630.22O")
+///     .unwrap();
+///
+/// // differentiating a receiver against itself
+/// let ctx = DiffContext::new(&base, &base)
+///     .unwrap();
+///
+/// for (k, sd) in ctx.single_differences() {
+///     assert_eq!(sd, 0.0);
+/// }
+/// ```
+#[derive(Debug, Clone)]
 pub struct DiffContext {
     /// "base": reference RINEX
     pub base: Rinex,
@@ -33,174 +73,174 @@ pub struct DiffContext {
     pub rover: Rinex,
 }
 
+/// Retains shared epochs, [SV]s and ranging observations only
+fn retain_shared(rec: &mut Record, rhs: &Record) {
+    rec.retain(|k, v| {
+        if !k.flag.is_ok() {
+            return false;
+        }
+
+        let rhs = match rhs.get(k) {
+            Some(rhs) => rhs,
+            None => return false,
+        };
+
+        v.signals.retain(|sig| {
+            let ranging = sig.observable.is_phase_range_observable()
+                || sig.observable.is_pseudo_range_observable();
+
+            ranging
+                && rhs
+                    .signals
+                    .iter()
+                    .any(|rhs| rhs.sv == sig.sv && rhs.observable == sig.observable)
+        });
+
+        !v.signals.is_empty()
+    });
+}
+
 impl DiffContext {
-    /// Builds a new DiffContext from two mutable RINEX.
-    /// Sample rate is reworked to perfectly match:
-    /// non shared epochs are dropped, to facilitate further processing.
-    /// For Observation context: we only retain Phase and Pseudo Range observations,
+    /// Builds a new [DiffContext] from two Observation [Rinex].
+    /// Both are aligned on their shared (sane) epochs, [SV]s and observables,
+    /// any other content is dropped to facilitate further processing.
+    /// Epochs are matched exactly, without tolerance: both receivers should
+    /// sample synchronously (you may decimate them to a common rate beforehand).
+    /// We only retain Phase and Pseudo Range observations,
     /// as we don't know of Differential analysis involving other observations.
-    /// For Navigation context: we only retain Ephemeris frames,
-    /// as we don't know of Differential analysis involving other frames.
-    pub fn new(base: &Rinex, rover: &Rinex) -> Self {
-        let base = base.clone();
-        let rover = rover.clone();
-        /*
-            base.decim_match_mut(&rover);
-            rover.decim_match_mut(&base);
-            // match /adjust sample rates
-            // For Navigation RINEX
-            //  retain ephemeris frames only
-            base.retain_navigation_ephemeris_mut();
-            rover.retain_navigation_ephemeris_mut();
-            // For Observation RINEX
-            //  retain Phase and Pseudo Range observations only
-            //    and only shared vehicles
-            if let Some(record) = base.record.as_mut_obs() {
-                record.retain(|e, (_, vehicles)| {
-                    vehicles.retain(|sv, observations| {
-                        if let Some(obs_rov) = rover.record.as_obs() {
-                            let (_, rov_vehicles) = obs_rov.get(e)
-                                .unwrap();
-                            let mut shared = false;
-                            for (rov_sv, _) in rov_vehicles {
-                                shared |= rov_sv == sv;
-                            }
-                            if shared {
-                                observations.retain(|code, _| {
-                                    is_pseudo_range_obs_code!(code) || is_phase_carrier_obs_code!(code)
-                                });
-                                observations.len() > 0
-                            } else {
-                                false
-                            }
-                        } else if let Some(nav_rov) = rover.record.as_nav() {
-                            let mut shared = false;
-                            let rov_classes = nav_rov.get(e)
-                                .unwrap();
-                            for (rov_class, rov_frames) in rov_classes {
-                                for rov_frame in rov_frames {
-                                    let (_, rov_sv, _) = rov_frame.as_eph()
-                                        .unwrap();
-                                    shared |= rov_sv == sv;
-                                }
-                            }
-                            if shared {
-                                observations.retain(|code, _| {
-                                    is_pseudo_range_obs_code!(code) || is_phase_carrier_obs_code!(code)
-                                });
-                                observations.len() > 0
-                            } else {
-                                false
-                            }
-                        } else {
-                            false
-                        }
-                    });
-                    vehicles.len() > 0
-                });
-            }
-            // For Navigation (ephemeris..) RINEX
-            //  retain only shared vehicles
-            else if let Some(record) = base.record.as_mut_nav() {
-                record.retain(|e, classes| {
-                    classes.retain(|class, frames| {
-                        frames.retain(|fr| {
-                            let mut shared = false;
-                            let (_, sv, _) = fr.as_eph()
-                                .unwrap();
-                            if let Some(obs_rec) = rover.record.as_obs() {
-                                let (_, obs_vehicles) = obs_rec.get(e)
-                                    .unwrap();
-                                for (obs_sv, _) in obs_vehicles {
-                                    shared |= obs_sv == sv;
-                                }
-                            } else if let Some(nav_rec) = rover.record.as_nav() {
-                                let classes = nav_rec.get(e)
-                                    .unwrap();
-                                for (class, frames) in classes {
-                                    for fr in frames {
-                                        let (_, nav_sv, _) = fr.as_eph()
-                                            .unwrap();
-                                        shared |= nav_sv == sv;
-                                    }
-                                }
-                            }
-                            shared
-                        });
-                        frames.len() > 0
-                    });
-                    classes.len() > 0
-                });
-            }
-            /*
-             * at this point "base" is ready for processing,
-             * let's strip "rover" to identical vehicles to
-             * to speed up and eventually facilitate further operations
-             */
-            if let Some(record) = rover.record.as_mut_obs() {
-                record.retain(|e, (_, vehicles)| {
-                    vehicles.retain(|sv, _| {
-                        let mut shared = false;
-                        if let Some(obs_base) = base.record.as_obs() {
-                            let (_, base_vehicles) = obs_base.get(e)
-                                .unwrap();
-                            for (base_sv, _) in base_vehicles {
-                                shared |= base_sv == sv;
-                            }
-                        } else if let Some(nav_base) = base.record.as_nav() {
-                            let base_classes = nav_base.get(e)
-                                .unwrap();
-                            for (class, frames) in base_classes {
-                                for fr in frames {
-                                    let (_, nav_sv, _) = fr.as_eph()
-                                        .unwrap();
-                                    shared |= nav_sv == sv;
-                                }
-                            }
-                        }
-                        false
-                    });
-                    vehicles.len() > 0
-                });
-            } else if let Some(record) = rover.record.as_mut_nav() {
-                record.retain(|e, classes| {
-                    classes.retain(|class, frames| {
-                        frames.retain(|fr| {
-                            let mut shared = false;
-                            let (_, sv, _) = fr.as_eph()
-                                .unwrap();
-                            if let Some(obs_base) = base.record.as_obs() {
-                                let (_, base_vehicles) = obs_base.get(e)
-                                    .unwrap();
-                                for (base_sv, _) in base_vehicles {
-                                    shared |= sv == base_sv;
-                                }
-                            } else if let Some(nav_base) = base.record.as_nav() {
-                                let nav_classes = nav_base.get(e)
-                                    .unwrap();
-                                for (class, frames) in nav_classes {
-                                    for fr in frames {
-                                        let (_, base_sv, _) = fr.as_eph()
-                                            .unwrap();
-                                        shared |= sv == base_sv;
-                                    }
-                                }
-                            }
-                            shared
-                        });
-                        frames.len() > 0
-                    });
-                    classes.len() > 0
-                });
-            }
-        */
-        Self { base, rover }
+    pub fn new(base: &Rinex, rover: &Rinex) -> Result<Self, Error> {
+        let base_rec = base.record.as_obs().ok_or(Error::NotObservationBase)?;
+        let rover_rec = rover.record.as_obs().ok_or(Error::NotObservationRover)?;
+
+        let mut ctx = Self {
+            base: base.clone(),
+            rover: rover.clone(),
+        };
+
+        if let Some(rec) = ctx.base.record.as_mut_obs() {
+            retain_shared(rec, rover_rec);
+        }
+
+        if let Some(rec) = ctx.rover.record.as_mut_obs() {
+            retain_shared(rec, base_rec);
+        }
+
+        Ok(ctx)
     }
 
     /// Builds Self from two local files
     pub fn from_files(fp: &str, rover_fp: &str) -> Result<Self, Error> {
         let rnx = Rinex::from_file(fp)?;
         let rover = Rinex::from_file(rover_fp)?;
-        Ok(Self::new(&rnx, &rover))
+        Self::new(&rnx, &rover)
+    }
+
+    /// Forms between-receiver single differences (rover - base),
+    /// for each shared epoch, [SV] and [Observable].
+    /// Values are expressed in the [Observable] unit: cycles for phase
+    /// and meters for pseudo range. SV clock errors cancel out.
+    pub fn single_differences(&self) -> BTreeMap<SingleDiffKey, f64> {
+        let mut ret = BTreeMap::new();
+
+        let (base, rover) = match (self.base.record.as_obs(), self.rover.record.as_obs()) {
+            (Some(base), Some(rover)) => (base, rover),
+            _ => return ret,
+        };
+
+        for (k, base) in base.iter() {
+            let rover = match rover.get(k) {
+                Some(rover) => rover,
+                None => continue,
+            };
+
+            for rover in rover.signals.iter() {
+                let base = base
+                    .signals
+                    .iter()
+                    .find(|base| base.sv == rover.sv && base.observable == rover.observable);
+
+                if let Some(base) = base {
+                    let key = SingleDiffKey {
+                        epoch: k.epoch,
+                        sv: rover.sv,
+                        observable: rover.observable.clone(),
+                    };
+
+                    ret.insert(key, rover.value - base.value);
+                }
+            }
+        }
+
+        ret
+    }
+
+    /// Forms double differences, by differentiating each single difference
+    /// against the single difference of a reference [SV], for the same [Observable].
+    /// The reference [SV] is selected per [Constellation] and [Observable]:
+    /// it is retained as long as it remains in sight, otherwise the lowest PRN
+    /// in sight is picked. Receiver clock errors cancel out.
+    /// NB: Glonass (FDMA) phase observations are not double differentiated:
+    /// each vehicle has its own wavelength, so phase single differences
+    /// expressed in cycles are not comparable to one another.
+    pub fn double_differences(&self) -> BTreeMap<DoubleDiffKey, f64> {
+        let mut ret = BTreeMap::new();
+
+        let mut epochs =
+            BTreeMap::<Epoch, HashMap<(Constellation, Observable), Vec<(SV, f64)>>>::new();
+
+        for (k, sd) in self.single_differences() {
+            if k.sv.constellation == Constellation::Glonass
+                && k.observable.is_phase_range_observable()
+            {
+                continue;
+            }
+
+            epochs
+                .entry(k.epoch)
+                .or_default()
+                .entry((k.sv.constellation, k.observable))
+                .or_default()
+                .push((k.sv, sd));
+        }
+
+        let mut references = HashMap::<(Constellation, Observable), SV>::new();
+
+        for (epoch, groups) in epochs {
+            for ((constellation, observable), sds) in groups {
+                if sds.len() < 2 {
+                    continue;
+                }
+
+                let pivot = (constellation, observable.clone());
+
+                let (reference, reference_sd) = match references
+                    .get(&pivot)
+                    .and_then(|sv| sds.iter().find(|(sd_sv, _)| sd_sv == sv))
+                {
+                    Some(reference) => *reference,
+                    None => {
+                        // single differences are sorted by PRN
+                        let reference = sds[0];
+                        references.insert(pivot, reference.0);
+                        reference
+                    },
+                };
+
+                for (sv, sd) in sds.iter() {
+                    if *sv != reference {
+                        let key = DoubleDiffKey {
+                            epoch,
+                            sv: *sv,
+                            reference,
+                            observable: observable.clone(),
+                        };
+
+                        ret.insert(key, sd - reference_sd);
+                    }
+                }
+            }
+        }
+
+        ret
     }
 }
@@ -1,9 +1,46 @@
-use crate::{doris::Record, prelude::Epoch};
-use qc_traits::{DecimationFilter, DecimationFilterType};
+use crate::{
+    doris::{Record, SignalKey},
+    prelude::{Epoch, Observable},
+    processing::{unsupported_target, TargetDecimator},
+};
+
+use qc_traits::{DecimationFilter, DecimationFilterType, FilterItem};
+
+use std::str::FromStr;
+
+/// Returns true if this [SignalKey] is targeted by [FilterItem].
+/// Complex items are either [Observable]s or station labels.
+fn targeted(item: &FilterItem, key: &SignalKey) -> bool {
+    match item {
+        FilterItem::ComplexItem(items) => items.iter().any(|item| {
+            if let Ok(observable) = Observable::from_str(item) {
+                observable == key.observable
+            } else {
+                key.station.label == *item
+            }
+        }),
+        _ => false,
+    }
+}
 
 pub fn decim_mut(rec: &mut Record, f: &DecimationFilter) {
-    if f.item.is_some() {
-        todo!("targetted decimation not supported yet");
+    if let Some(item) = &f.item {
+        if !matches!(item, FilterItem::ComplexItem(_)) {
+            unsupported_target("DORIS", item);
+            return;
+        }
+        let mut decimator = TargetDecimator::new(&f.filter);
+        rec.retain(|k, obs| {
+            obs.signals.retain(|key, _| {
+                if targeted(item, key) {
+                    decimator.retain(key.clone(), k.epoch)
+                } else {
+                    true
+                }
+            });
+            !obs.signals.is_empty()
+        });
+        return;
     }
 
     match f.filter {
@@ -150,6 +150,10 @@ pub enum ParsingError {
     KlobucharData,
     #[error("nav:ion nequick-g data")]
     NequickGData,
+    #[error("nequick-g: invalid modip table")]
+    NequickGModipTable,
+    #[error("nequick-g: invalid ccir table")]
+    NequickGCcirTable,
     #[error("nav:ion bdgim data")]
     BdgimData,
     #[error("nav:sto data")]
@@ -160,8 +164,8 @@ pub enum ParsingError {
     IonexModel,
     #[error("antex: calibration method")]
     AntexCalibrationMethod,
-    #[error("obs: hardware events not supported yet")]
-    ObsHardwareEvent,
+    #[error("obs: missing special event records")]
+    ObsEventMissingData,
     #[error("obs: bad v2 satellites description")]
     BadV2SatellitesDescription,
     #[error("obs: numsat parsing")]
@@ -176,6 +180,8 @@ pub enum ParsingError {
     DorisClockParsing,
     #[error("ionex scaling exponent")]
     IonexScalingExponent,
+    #[error("i/o: input error")]
+    InputError(#[from] IoError),
 }
 
 /// Errors that may rise in Formatting process
@@ -195,6 +201,12 @@ pub enum FormattingError {
     NoNavigationDefinition,
     #[error("nav: missing grid defs")]
     NoGridDefinition,
+    #[error("antex: non supported frequency")]
+    AntexFrequency,
+    #[error("record entry not supported by this stream")]
+    UnsupportedRecordEntry,
+    #[error("nav (V2/V3): header was already formatted")]
+    NavHeaderAlreadyFormatted,
 }
 
 /// General error (processing, analysis..)
@@ -1,11 +1,11 @@
 //! RINEX compression module
 
 use crate::{
-    epoch::epoch_decompose as epoch_decomposition,
+    epoch::format as epoch_format,
     error::FormattingError,
     hatanaka::{NumDiff, TextDiff},
-    observation::{HeaderFields, Record},
-    prelude::{Constellation, Observable, SV},
+    observation::{HeaderFields, ObsKey, Observations, Record},
+    prelude::{Constellation, Observable, RinexType, SV},
     BufWriter,
 };
 
@@ -33,8 +33,8 @@ pub struct CompressorExpert<const M: usize> {
     /// Flag textdiff
     /// Compression kernels (per SV and signal)
     sv_kernels: HashMap<(SV, Observable), NumDiff<M>>,
-    // /// Clock [NumDiff]
-    // clock_diff: NumDiff<M>,
+    /// Clock [NumDiff], while clock offsets are being compressed
+    clock_diff: Option<NumDiff<M>>,
 }
 
 impl<const M: usize> Default for CompressorExpert<M> {
@@ -47,6 +47,7 @@ impl<const M: usize> Default for CompressorExpert<M> {
             flags_buf: String::with_capacity(128),
             sv_kernels: HashMap::with_capacity(8),
             flags_diff: HashMap::with_capacity(8),
+            clock_diff: None,
         }
     }
 }
@@ -62,176 +63,240 @@ impl<const M: usize> CompressorExpert<M> {
         header: &HeaderFields,
     ) -> Result<(), FormattingError> {
         for (k, v) in record.iter() {
-            if !k.flag.is_ok() {
-                // TODO not 100% correct, verify > 1
-                self.epoch_compression = false;
-            }
-
-            let (y, m, d, hh, mm, ss, ns) = epoch_decomposition(k.epoch);
+            self.format_epoch(w, k, v, header)?;
+        }
+        Ok(())
+    }
 
-            // form unique SV list
-            let svnn = v
-                .signals
-                .iter()
-                .map(|sig| sig.sv)
-                .unique()
-                .sorted()
-                .collect::<Vec<_>>();
+    /// Compresses a single epoch using mutable [CompressorExpert],
+    /// which needs to be presented in chronological order.
+    /// Compressed bytes are dumped in mutable [BufWriter].
+    pub fn format_epoch<W: Write>(
+        &mut self,
+        w: &mut BufWriter<W>,
+        k: &ObsKey,
+        v: &Observations,
+        header: &HeaderFields,
+    ) -> Result<(), FormattingError> {
+        // Special events are not compressed:
+        // epoch is described as is, then compression is re-initialized
+        if k.flag.is_event() {
+            return self.format_event(w, k, v);
+        }
 
-            if !self.epoch_compression {
+        // form unique SV list, in the order of the readable record:
+        // V3 lists one constellation after the other
+        let svnn = v
+            .signals
+            .iter()
+            .map(|sig| sig.sv)
+            .unique()
+            .sorted_by_key(|sv| {
                 if self.v3 {
-                    write!(w, "> ")?;
+                    (Some(sv.constellation), *sv)
                 } else {
-                    write!(w, "&")?;
+                    (None, *sv)
                 }
+            })
+            .collect::<Vec<_>>();
+
+        if self.v3 {
+            self.epoch_buf.push_str(&format!(
+                "{}  {}{:3}      ",
+                epoch_format(k.epoch, RinexType::ObservationData, 3),
+                k.flag,
+                svnn.len(),
+            ));
+        } else {
+            self.epoch_buf.push_str(&format!(
+                "{}  {}{:3}",
+                epoch_format(k.epoch, RinexType::ObservationData, 2),
+                k.flag,
+                svnn.len(),
+            ));
+        }
+
+        // Append each SV to epoch description
+        for sv in svnn.iter() {
+            self.epoch_buf.push_str(&format!("{:x}", sv));
+        }
+
+        // Epoch compression
+        if !self.epoch_compression {
+            // every kernel is re-initialized with the epoch descriptor
+            self.sv_kernels.clear();
+            self.flags_diff.clear();
+            self.epoch_diff.force_init(&self.epoch_buf);
+            if self.v3 {
+                writeln!(w, "> {}", self.epoch_buf.trim_end())?;
             } else {
-                if self.v3 {
-                    write!(w, "  ")?;
-                } else {
-                    write!(w, " ")?;
-                }
+                writeln!(w, "&{}", self.epoch_buf.trim_end())?;
             }
-
+        } else {
+            let compressed = self.epoch_diff.compress(&self.epoch_buf);
             if self.v3 {
-                self.epoch_buf.push_str(&format!(
-                    "{:04} {:02} {:02} {:02} {:02} {:02}.{:07}  {}{:3}      ",
-                    y,
-                    m,
-                    d,
-                    hh,
-                    mm,
-                    ss,
-                    ns / 100,
-                    k.flag,
-                    svnn.len(),
-                ));
+                writeln!(w, "  {}", compressed.trim_end())?;
             } else {
-                self.epoch_buf.push_str(&format!(
-                    "{:02} {:02} {:02} {:02} {:02} {:02}.{:07}  {}{:3}      ",
-                    y,
-                    m,
-                    d,
-                    hh,
-                    mm,
-                    ss,
-                    ns / 100,
-                    k.flag,
-                    svnn.len(),
-                ));
+                writeln!(w, " {}", compressed.trim_end())?;
             }
+        }
 
-            // Append each SV to epoch description
-            for sv in svnn.iter() {
-                self.epoch_buf.push_str(&format!("{:x}", sv));
-            }
+        // Clock offset, expressed in the last digit of the RINEX field
+        if let Some(clk) = v.clock {
+            let scaling = if self.v3 { 1.0E12 } else { 1.0E9 };
+            let quantized = (clk.offset_s * scaling).round() as i64;
 
-            // Epoch compression
-            if !self.epoch_compression {
-                self.epoch_diff.force_init(&self.epoch_buf);
-                writeln!(w, "{}", self.epoch_buf.trim_end())?;
-            } else {
-                let compressed = self.epoch_diff.compress(&self.epoch_buf);
-                writeln!(w, "{}", compressed.trim_end())?;
+            match &mut self.clock_diff {
+                Some(kernel) if self.epoch_compression => {
+                    writeln!(w, "{}", kernel.compress(quantized))?;
+                },
+                _ => {
+                    self.clock_diff = Some(NumDiff::<M>::new(quantized, 3));
+                    writeln!(w, "{}&{}", 3, quantized)?;
+                },
             }
+        } else {
+            // No clock: BLANKed line
+            self.clock_diff = None;
+            writeln!(w)?;
+        }
 
-            if let Some(clk) = v.clock {
-                // TODO: clock is not correctly supported yet
-                if !self.epoch_compression {
-                    writeln!(w, "{}", clk.offset_s)?;
-                }
-            } else {
-                // No clock: BLANKed line
-                write!(w, "\n")?;
-            }
+        // For each SV
+        for sv in svnn.iter() {
+            // Following header specs
+            let sv_observables = header.codes.get(&sv.constellation);
 
-            // For each SV
-            for sv in svnn.iter() {
-                // Following header specs
-                let sv_observables = header.codes.get(&sv.constellation);
-
-                let sv_observables = match sv_observables {
-                    Some(observables) => observables, // correctly identified,
-                    None => {
-                        // handles SBAS case
-                        if sv.constellation.is_sbas() {
-                            match header.codes.get(&Constellation::SBAS) {
-                                Some(observables) => observables,
-                                None => {
-                                    // correctly formatted RINEX will never
-                                    // end up here
-                                    continue;
-                                },
-                            }
-                        } else {
-                            // correctly formatted RINEX will never
-                            // end up here
-                            continue;
+            let sv_observables = match sv_observables {
+                Some(observables) => observables, // correctly identified,
+                None => {
+                    // handles SBAS case
+                    if sv.constellation.is_sbas() {
+                        match header.codes.get(&Constellation::SBAS) {
+                            Some(observables) => observables,
+                            None => {
+                                // correctly formatted RINEX will never
+                                // end up here
+                                continue;
+                            },
                         }
-                    },
-                };
-
-                for observable in sv_observables.iter() {
-                    if let Some(signal) = v
-                        .signals
-                        .iter()
-                        .filter(|sig| sig.sv == *sv && &sig.observable == observable)
-                        .reduce(|k, _| k)
+                    } else {
+                        // correctly formatted RINEX will never
+                        // end up here
+                        continue;
+                    }
+                },
+            };
+
+            for observable in sv_observables.iter() {
+                if let Some(signal) = v
+                    .signals
+                    .iter()
+                    .filter(|sig| sig.sv == *sv && &sig.observable == observable)
+                    .reduce(|k, _| k)
+                {
+                    let quantized = (signal.value * 1000.0).round() as i64;
+
+                    // retrieve or build compression kernel
+                    if let Some(sv_kernel) = self
+                        .sv_kernels
+                        .get_mut(&(signal.sv, signal.observable.clone()))
                     {
-                        let quantized = (signal.value * 1000.0).round() as i64;
-
-                        // retrieve or build compression kernel
-                        if let Some((_, sv_kernel)) = self
-                            .sv_kernels
-                            .iter_mut()
-                            .filter(|((sv, obs), _)| *sv == signal.sv && obs == &signal.observable)
-                            .reduce(|k, _| k)
-                        {
-                            let compressed = sv_kernel.compress(quantized);
-                            write!(w, "{} ", compressed)?;
-                        } else {
-                            // first encounter: build kernel
-                            let kernel = NumDiff::<M>::new(quantized, 3);
-                            self.sv_kernels
-                                .insert((signal.sv, signal.observable.clone()), kernel);
-
-                            write!(w, "{}&{} ", 3, quantized)?;
-                        }
+                        let compressed = sv_kernel.compress(quantized);
+                        write!(w, "{} ", compressed)?;
+                    } else {
+                        // first encounter: build kernel
+                        let kernel = NumDiff::<M>::new(quantized, 3);
+                        self.sv_kernels
+                            .insert((signal.sv, signal.observable.clone()), kernel);
 
-                        if let Some(lli) = signal.lli {
-                            self.flags_buf.push_str(&format!("{}", lli.bits() as u8));
-                        } else {
-                            self.flags_buf.push_str(" ");
-                        }
+                        write!(w, "{}&{} ", 3, quantized)?;
+                    }
 
-                        if let Some(snr) = signal.snr {
-                            self.flags_buf.push_str(&format!("{}", snr as u8));
-                        } else {
-                            self.flags_buf.push_str(" ");
-                        }
+                    if let Some(lli) = signal.lli {
+                        self.flags_buf.push_str(&format!("{}", lli.bits() as u8));
                     } else {
-                        // BLANK is a single ' '
-                        write!(w, "{}", ' ')?;
-                        self.flags_buf.push_str("  ");
+                        self.flags_buf.push_str(" ");
                     }
-                }
 
-                // Flags compression
-                if let Some(flags_kernel) = self.flags_diff.get_mut(&sv) {
-                    let compressed = flags_kernel.compress(&self.flags_buf);
-                    writeln!(w, "{}", compressed)?;
+                    if let Some(snr) = signal.snr {
+                        self.flags_buf.push_str(&format!("{}", snr as u8));
+                    } else {
+                        self.flags_buf.push_str(" ");
+                    }
                 } else {
-                    let mut kernel = TextDiff::new("");
-                    let compressed = kernel.compress(&self.flags_buf);
-                    writeln!(w, "{}", compressed)?;
-                    self.flags_diff.insert(*sv, kernel);
+                    // BLANK is a single ' '
+                    write!(w, "{}", ' ')?;
+                    self.flags_buf.push_str("  ");
                 }
-                self.flags_buf.clear();
             }
 
-            // prepare for next epoch
-            self.epoch_compression = true;
-            self.epoch_buf.clear();
+            // Flags compression. V3: readable lines describe all observables
+            // (blanked when missing), V2: trailing blanks are omitted.
+            let flags = if self.v3 {
+                self.flags_buf.as_str()
+            } else {
+                self.flags_buf.trim_end()
+            };
+
+            if let Some(flags_kernel) = self.flags_diff.get_mut(sv) {
+                let compressed = flags_kernel.compress(flags);
+                // unchanged flags at the end of the line are omitted
+                writeln!(w, "{}", compressed.trim_end())?;
+            } else {
+                let mut kernel = TextDiff::new("");
+                let compressed = kernel.compress(flags);
+                writeln!(w, "{}", compressed.trim_end())?;
+                self.flags_diff.insert(*sv, kernel);
+            }
+            self.flags_buf.clear();
+        }
+
+        // interrupted signals (and satellites) are re-initialized on next appearance
+        self.sv_kernels.retain(|(sv, observable), _| {
+            v.signals
+                .iter()
+                .any(|sig| sig.sv == *sv && sig.observable == *observable)
+        });
+        self.flags_diff.retain(|sv, _| svnn.contains(sv));
+
+        // prepare for next epoch
+        self.epoch_compression = true;
+        self.epoch_buf.clear();
+        Ok(())
+    }
+
+    /// Formats a special event, which is not compressed: the epoch descriptor and
+    /// special records are written as is, then the following epoch is re-initialized.
+    fn format_event<W: Write>(
+        &mut self,
+        w: &mut BufWriter<W>,
+        k: &ObsKey,
+        v: &Observations,
+    ) -> Result<(), FormattingError> {
+        if self.v3 {
+            writeln!(
+                w,
+                "> {}  {}{:3}",
+                epoch_format(k.epoch, RinexType::ObservationData, 3),
+                k.flag,
+                v.special_records.len(),
+            )?;
+        } else {
+            writeln!(
+                w,
+                "&{}  {}{:3}",
+                epoch_format(k.epoch, RinexType::ObservationData, 2),
+                k.flag,
+                v.special_records.len(),
+            )?;
         }
+
+        for record in v.special_records.iter() {
+            writeln!(w, "{}", record)?;
+        }
+
+        self.epoch_compression = false;
+        self.clock_diff = None;
         Ok(())
     }
 }
@@ -38,6 +38,8 @@ pub enum State {
     Clock,
     /// Observations gathering and recovering.
     Observation,
+    /// Special event records, that are not compressed.
+    Event,
 }
 
 impl State {
@@ -113,6 +115,8 @@ pub struct DecompressorExpert<const M: usize> {
     sv_ptr: usize,  // inside epoch
     numobs: usize,  // total
     obs_ptr: usize, // inside epoch
+    /// Special event records, pending
+    num_events: usize,
     /// [TextDiff] that works on entire Epoch line
     epoch_diff: TextDiff,
     /// Epoch descriptor, for single allocation
@@ -141,6 +145,7 @@ impl<const M: usize> Default for DecompressorExpert<M> {
             sv_ptr: 0,
             numobs: 0,
             obs_ptr: 0,
+            num_events: 0,
             first_epoch: true,
             epoch_desc_len: 0,
             sv: Default::default(),
@@ -243,6 +248,7 @@ impl<const M: usize> DecompressorExpert<M> {
             sv_ptr: 0,
             numobs: 0,
             obs_ptr: 0,
+            num_events: 0,
             constellation,
             gnss_observables,
             first_epoch: true,
@@ -288,14 +294,15 @@ impl<const M: usize> DecompressorExpert<M> {
 
         // println!("STATE={:?}", self.state); //DEBUG
         match self.state {
-            State::Epoch => self.run_epoch(line, len),
-            State::Clock => self.run_clock(line, len, buf),
+            State::Epoch => self.run_epoch(line, len, buf),
+            State::Clock => self.run_clock(line, buf),
             State::Observation => self.run_observation(line, len, buf),
+            State::Event => self.run_event(line, buf),
         }
     }
 
     /// Process following line, in [State::Epoch]
-    fn run_epoch(&mut self, line: &str, len: usize) -> Result<usize, Error> {
+    fn run_epoch(&mut self, line: &str, len: usize, buf: &mut [u8]) -> Result<usize, Error> {
         let min_len = if self.v3 {
             State::MIN_COMPRESSED_EPOCH_SIZE_V3
         } else {
@@ -337,10 +344,50 @@ impl<const M: usize> DecompressorExpert<M> {
         // because it is used to determine the next production size
         self.numsat = self.epoch_numsat().expect("bad recovered content (numsat)");
 
+        // Special events are not compressed: they are not followed by a clock offset,
+        // numsat is the number of special records that follow.
+        if self.epoch_is_event() {
+            self.num_events = self.numsat;
+            self.state = if self.num_events > 0 {
+                State::Event
+            } else {
+                State::Epoch
+            };
+            return Ok(self.format_epoch(None, buf));
+        }
+
         self.state = State::Clock;
         Ok(0)
     }
 
+    /// Returns true if recovered epoch descriptor describes a special event (flag 2-5).
+    fn epoch_is_event(&self) -> bool {
+        let offset = if self.v3 {
+            Self::V3_NUMSAT_OFFSET
+        } else {
+            Self::V1_NUMSAT_OFFSET
+        };
+
+        matches!(
+            self.epoch_descriptor.as_bytes().get(offset - 1),
+            Some(b'2'..=b'5')
+        )
+    }
+
+    /// Process following line, in [State::Event]: special records are copied as is.
+    fn run_event(&mut self, line: &str, buf: &mut [u8]) -> Result<usize, Error> {
+        let line = line.trim_end();
+        let size = line.len().min(buf.len());
+        buf[..size].copy_from_slice(&line.as_bytes()[..size]);
+
+        self.num_events -= 1;
+        if self.num_events == 0 {
+            self.state = State::Epoch;
+        }
+
+        Ok(size)
+    }
+
     /// Fills user buffer with recovered epoch, following either V1 or V3 standards
     fn format_epoch(&self, clock_data: Option<i64>, buf: &mut [u8]) -> usize {
         if self.v3 {
@@ -367,8 +414,9 @@ impl<const M: usize> DecompressorExpert<M> {
 
         // provide clock data, if any
         if let Some(clock_data) = clock_data {
-            let value = clock_data as f64 / 1000.0;
-            let formatted = format!("       {:.12}", value);
+            let value = clock_data as f64 * 1.0E-12;
+            // RINEX3 receiver clock offset: 6X,F15.12 (s)
+            let formatted = format!("      {:15.12}", value);
             let fmt_len = formatted.len(); // TODO improve: this is constant
             let bytes = formatted.as_bytes();
             buf[produced..produced + fmt_len].copy_from_slice(&bytes);
@@ -395,10 +443,16 @@ impl<const M: usize> DecompressorExpert<M> {
 
         // push clock offset (if any)
         if let Some(clock_data) = clock_data {
-            let formatted_ck = format!(" {:15.12}", clock_data);
+            // padded to column 68
+            while produced < 68 {
+                buf[produced] = b' ';
+                produced += 1;
+            }
+            // RINEX2 receiver clock offset: F12.9 (s)
+            let formatted_ck = format!("{:12.9}", clock_data as f64 * 1.0E-9);
             let fmt_len = formatted_ck.len(); // TODO: improve (constant)
             let formatted_ck = formatted_ck.as_bytes();
-            buf[produced..produced + fmt_len].copy_from_slice(&formatted_ck);
+            buf[produced..produced + fmt_len].copy_from_slice(formatted_ck);
             produced += fmt_len;
         }
 
@@ -437,30 +491,23 @@ impl<const M: usize> DecompressorExpert<M> {
     }
 
     /// Process following line, in [State::Clock]
-    fn run_clock(&mut self, line: &str, len: usize, buf: &mut [u8]) -> Result<usize, Error> {
+    fn run_clock(&mut self, line: &str, buf: &mut [u8]) -> Result<usize, Error> {
         let mut clock_data = Option::<i64>::None;
 
         // attempts to recover clock data (if it exists)
-        if len > 2 {
-            if line[1..].starts_with('&') {
-                if let Ok(order) = line[..1].parse::<usize>() {
-                    if let Ok(val) = line[2..].parse::<i64>() {
-                        // valid kernel reset
-                        self.clock_diff.force_init(val, order);
-                        clock_data = Some(val);
-                    }
-                }
-            }
-        }
-        if len == 1 {
-            // highly compressed clock data
-            match line.trim().parse::<i64>() {
-                Ok(val) => {
-                    let val = self.clock_diff.decompress(val);
+        let line = line.trim();
+        if line.len() > 2 && line[1..].starts_with('&') {
+            if let Ok(order) = line[..1].parse::<usize>() {
+                if let Ok(val) = line[2..].parse::<i64>() {
+                    // valid kernel reset
+                    self.clock_diff.force_init(val, order);
                     clock_data = Some(val);
-                },
-                Err(_) => {},
+                }
             }
+        } else if let Ok(val) = line.parse::<i64>() {
+            // compressed clock data
+            let val = self.clock_diff.decompress(val);
+            clock_data = Some(val);
         }
 
         // now that we have potentially recovered clock data
@@ -71,8 +71,11 @@ impl TextDiff {
         let len = data.len();
         let mut buf_len = self.buffer.len();
 
-        if buf_len > len {
-            // special case: shrink history
+        // special case: shrink history,
+        // deleted bytes are explicitly erased
+        let deleted = buf_len.saturating_sub(len);
+
+        if deleted > 0 {
             self.buffer = self.buffer[..len].to_string();
             buf_len = len; // update size
         }
@@ -116,6 +119,10 @@ impl TextDiff {
             }
         }
 
+        for _ in 0..deleted {
+            self.compressed.push('&');
+        }
+
         &self.compressed
     }
 }
@@ -203,7 +210,7 @@ mod test {
         assert_eq!(diff.compress("4 "), " &");
         assert_eq!(diff.compress("4 "), "  ");
         assert_eq!(diff.compress("4  "), "  &");
-        assert_eq!(diff.compress("0"), "0");
+        assert_eq!(diff.compress("0"), "0&&");
         assert_eq!(diff.compress("0"), " ");
         assert_eq!(diff.compress("   "), "&&&");
 
@@ -211,5 +218,6 @@ mod test {
         assert_eq!(diff.compress("DEfault 1234"), " E          ");
         assert_eq!(diff.compress("DEfault 1234"), "            ");
         assert_eq!(diff.compress("             "), "&&&&&&& &&&&&");
+        assert_eq!(diff.compress("   1"), "   1&&&&&&&&&");
     }
 }
@@ -203,18 +203,63 @@ impl Header {
                 )?;
             },
             Type::ClockData => {
+                let constellation = match self.constellation {
+                    Some(c) => format!("{:x}", c),
+                    None => String::new(),
+                };
                 writeln!(
                     w,
                     "{}",
                     fmt_rinex(
-                        &format!("{:6}.{:02}           CLOCK DATA", major, minor),
+                        &format!(
+                            "{:6}.{:02}           C                   {}",
+                            major, minor, constellation
+                        ),
                         "RINEX VERSION / TYPE"
                     )
                 )?;
             },
-            Type::DORIS => {},
-            Type::AntennaData => {},
-            Type::IonosphereMaps => {},
+            Type::DORIS => {
+                writeln!(
+                    w,
+                    "{}",
+                    fmt_rinex(
+                        &format!("{:6}.{:02}           O                   D", major, minor),
+                        "RINEX VERSION / TYPE"
+                    )
+                )?;
+            },
+            Type::AntennaData => {
+                let constellation = match self.constellation {
+                    Some(c) => format!("{:x}", c),
+                    None => String::new(),
+                };
+                writeln!(
+                    w,
+                    "{}",
+                    fmt_rinex(
+                        &format!("{:6}.{}            {}", major, minor, constellation),
+                        "ANTEX VERSION / SYST"
+                    )
+                )?;
+            },
+            Type::IonosphereMaps => {
+                let system = match &self.ionex {
+                    Some(ionex) => ionex.reference.to_string(),
+                    None => String::new(),
+                };
+                writeln!(
+                    w,
+                    "{}",
+                    fmt_rinex(
+                        &format!(
+                            "{:6}.{}            IONOSPHERE MAPS     {}",
+                            major, minor, system
+                        ),
+                        "IONEX VERSION / TYPE"
+                    )
+                )?;
+            },
         }
 
         Ok(())
@@ -232,7 +277,7 @@ impl Header {
         } else if let Some(meteo) = &self.meteo {
             meteo.format(w)
         } else if let Some(clock) = &self.clock {
-            clock.format(w)
+            clock.format(w, self.version)
         } else if let Some(ionex) = &self.ionex {
             ionex.format(w)
         } else if let Some(antex) = &self.antex {
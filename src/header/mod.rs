@@ -222,6 +222,13 @@ impl Header {
             .with_observation_fields(ObservationHeader::default().with_crinex(CRINEX::default()))
     }
 
+    /// Builds a basic [Header] to describe a Meteo RINEX
+    pub fn basic_meteo() -> Self {
+        let mut s = Self::default().with_type(Type::MeteoData);
+        s.meteo = Some(MeteoHeader::default());
+        s
+    }
+
     /// Builds a basic [Header] for IONEX
     pub fn basic_ionex() -> Self {
         Self::default().with_ionex_fields(IonexHeader::default())
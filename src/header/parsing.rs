@@ -827,6 +827,10 @@ impl Header {
                 if let Ok(f) = f32::from_str(content.trim()) {
                     ionex = ionex.with_base_radius(f);
                 }
+            } else if marker.contains("MAP DIMENSION") {
+                if let Ok(d) = u8::from_str(content.trim()) {
+                    ionex = ionex.with_map_dimension(d);
+                }
             } else if marker.contains("MAPPING FUCTION") {
                 let mapf = IonexMappingFunction::from_str(content.trim())?;
                 ionex = ionex.with_mapping_function(mapf);
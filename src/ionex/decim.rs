@@ -1,37 +1,124 @@
-use crate::{ionex::Record, prelude::Epoch};
+use crate::{
+    ionex::{IonexKey, Record},
+    prelude::Epoch,
+    processing::{unsupported_target, TargetDecimator},
+};
 
-use qc_traits::{DecimationFilter, DecimationFilterType};
+use qc_traits::{DecimationFilter, FilterItem};
 
+/// Geographic bounds of a targeted decimation, described by
+/// "lat:min:max", "long:min:max" and "alt:min:max" complex items,
+/// in decimal degrees and kilometers.
+#[derive(Default)]
+struct Bounds {
+    latitude: Option<(f64, f64)>,
+    longitude: Option<(f64, f64)>,
+    altitude: Option<(f64, f64)>,
+}
+
+impl Bounds {
+    /// Parses [Bounds] from complex items. Returns None if one item
+    /// does not describe a bound.
+    fn from_items(items: &[String]) -> Option<Self> {
+        let mut bounds = Self::default();
+
+        for item in items.iter() {
+            let mut fields = item.trim().split(':');
+            let (name, min, max) = (fields.next()?, fields.next()?, fields.next()?);
+
+            if fields.next().is_some() {
+                return None;
+            }
+
+            let (min, max) = (
+                min.trim().parse::<f64>().ok()?,
+                max.trim().parse::<f64>().ok()?,
+            );
+
+            match name.trim() {
+                "lat" => bounds.latitude = Some((min, max)),
+                "long" => bounds.longitude = Some((min, max)),
+                "alt" => bounds.altitude = Some((min, max)),
+                _ => return None,
+            }
+        }
+
+        Some(bounds)
+    }
+
+    /// True if this [IonexKey] lies within these [Bounds].
+    fn contains(&self, key: &IonexKey) -> bool {
+        let within = |bounds: Option<(f64, f64)>, value: f64| match bounds {
+            Some((min, max)) => value >= min && value <= max,
+            None => true,
+        };
+
+        within(self.latitude, key.coordinates.latitude_ddeg())
+            && within(self.longitude, key.coordinates.longitude_ddeg())
+            && within(self.altitude, key.coordinates.altitude_km())
+    }
+}
+
+/// Part of the IONEX record that may be targeted
+enum Target {
+    /// Maps published from this [Epoch] onwards
+    Since(Epoch),
+    /// Grid points within these [Bounds]
+    Within(Bounds),
+}
+
+impl Target {
+    fn from_item(item: &FilterItem) -> Option<Self> {
+        match item {
+            FilterItem::EpochItem(t) => Some(Self::Since(*t)),
+            FilterItem::ComplexItem(items) => Some(Self::Within(Bounds::from_items(items)?)),
+            _ => None,
+        }
+    }
+
+    fn contains(&self, key: &IonexKey) -> bool {
+        match self {
+            Self::Since(t) => key.epoch >= *t,
+            Self::Within(bounds) => bounds.contains(key),
+        }
+    }
+}
+
+/// IONEX decimation. Targeted decimation applies to the time series
+/// of each grid point, either from an [Epoch] onwards (epoch item), or within
+/// geographic bounds (complex items, like "lat:-10:10,long:0:20").
 pub fn decim_mut(rec: &mut Record, f: &DecimationFilter) {
-    if f.item.is_some() {
-        todo!("targetted decimation not supported yet");
+    if let Some(item) = &f.item {
+        let target = match Target::from_item(item) {
+            Some(target) => target,
+            None => {
+                unsupported_target("IONEX", item);
+                return;
+            },
+        };
+
+        let mut decimator = TargetDecimator::new(&f.filter);
+
+        rec.retain(|k, _| {
+            if target.contains(k) {
+                decimator.retain(k.coordinates, k.epoch)
+            } else {
+                true
+            }
+        });
+        return;
     }
 
-    match f.filter {
-        DecimationFilterType::Modulo(r) => {
-            let mut i = 0;
-            rec.retain(|_, _| {
-                let retained = (i % r) == 0;
-                i += 1;
-                retained
-            });
-        },
-        DecimationFilterType::Duration(interval) => {
-            let mut last_retained = Option::<Epoch>::None;
-            rec.retain(|k, _| {
-                if let Some(last) = last_retained {
-                    let dt = k.epoch - last;
-                    if dt >= interval {
-                        last_retained = Some(k.epoch);
-                        true
-                    } else {
-                        false
-                    }
-                } else {
-                    last_retained = Some(k.epoch);
-                    true // always retain 1st epoch
-                }
-            });
+    // decimation applies to entire maps
+    let mut decimator = TargetDecimator::new(&f.filter);
+    let mut current = Option::<(Epoch, bool)>::None;
+
+    rec.retain(|k, _| match current {
+        Some((epoch, retained)) if epoch == k.epoch => retained,
+        _ => {
+            let retained = decimator.retain((), k.epoch);
+            current = Some((k.epoch, retained));
+            retained
         },
-    }
+    });
 }
@@ -1,7 +1,8 @@
 use crate::{
     fmt_rinex,
-    ionex::{IonexKey, QuantizedCoordinates, Record},
-    prelude::Header,
+    ionex::{IonexKey, Quantized, QuantizedCoordinates, Record, TEC},
+    linspace::Linspace,
+    prelude::{Epoch, Header},
     FormattingError,
 };
 
@@ -9,253 +10,188 @@ use itertools::Itertools;
 
 use std::io::{BufWriter, Write};
 
-pub fn format<W: Write>(
+/// Maximal number of values per line
+const NUM_VALUES_PER_LINE: usize = 16;
+
+/// Non available data point
+const NON_AVAILABLE_KEYWORD: i64 = 9999;
+
+/// Formats IONEX [Epoch] (6I6), as used in map and header descriptions
+pub(crate) fn format_epoch(t: Epoch) -> String {
+    let (y, m, d, hh, mm, ss, _) = t.to_gregorian_utc();
+    format!("{:6}{:6}{:6}{:6}{:6}{:6}", y, m, d, hh, mm, ss)
+}
+
+/// Returns all points of this [Linspace], `end` included.
+pub(crate) fn linspace_points(linspace: &Linspace) -> Vec<f64> {
+    if linspace.spacing == 0.0 {
+        return vec![linspace.start];
+    }
+
+    let n = ((linspace.end - linspace.start) / linspace.spacing).round() as i64;
+
+    (0..=n.max(0))
+        .map(|i| linspace.start + i as f64 * linspace.spacing)
+        .collect()
+}
+
+/// Formats one map (TEC or RMS) for this [Epoch] into [BufWriter]
+fn format_map<W: Write, F: Fn(&TEC) -> Option<f64>>(
     w: &mut BufWriter<W>,
     record: &Record,
     header: &Header,
+    nth_map: usize,
+    t: Epoch,
+    marker: &str,
+    value: F,
 ) -> Result<(), FormattingError> {
-    const NUM_LONGITUDES_PER_LINE: usize = 16;
-
     let specs = header
         .ionex
         .as_ref()
         .ok_or(FormattingError::NoGridDefinition)?;
 
-    // browse grid:
-    // - for each altitude (km)
-    // - - browse latitude (starting on northernmost.. to southernmost)
-    // - - - browse longitude (starting on easternmost.. to westernmost)
     let grid = &specs.grid;
 
-    let (altitude_low_km, altitude_high_km, altitude_spacing_km) =
-        (grid.height.start, grid.height.end, grid.height.spacing);
-
-    let (latitude_north_ddeg, latitude_south_ddeg, latitude_spacing_ddeg) = (
-        grid.latitude.start,
-        grid.latitude.end,
-        grid.latitude.spacing,
-    );
-
-    let (longitude_east_ddeg, longitude_west_ddeg, longitude_spacing_ddeg) = (
-        grid.longitude.start,
-        grid.longitude.end,
-        grid.longitude.spacing,
-    );
-
-    let nth_map = 0;
-    let has_h = false;
-    let mut has_rms = false;
-
-    for t in record.keys().map(|k| k.epoch).unique().sorted() {
-        // format all TEC maps
-        writeln!(
-            w,
-            "{}",
-            fmt_rinex(&format!("{:6}", nth_map), "START OF TEC MAP")
-        )?;
-
-        let mut altitude_km = altitude_low_km;
-
-        while altitude_km < altitude_high_km {
-            let mut latitude_ddeg = latitude_north_ddeg;
-            while latitude_ddeg < latitude_south_ddeg {
-                let mut longitude_nth = 0;
-                let mut longitude_ddeg = longitude_west_ddeg;
-
-                writeln!(
-                    w,
-                    "{}",
-                    fmt_rinex(
-                        &format!(
-                            "  {:3.1}{:3.1}{:3.1}   {:3.1} {:3.1}",
-                            latitude_ddeg,
-                            longitude_east_ddeg,
-                            longitude_west_ddeg,
-                            longitude_spacing_ddeg,
-                            altitude_km,
-                        ),
-                        "LAT/LON1/LON2/DLON/H"
-                    )
-                )?;
-
-                while longitude_ddeg < longitude_east_ddeg {
-                    let coords = QuantizedCoordinates::new(
-                        latitude_ddeg,
-                        -1,
-                        longitude_ddeg,
-                        -1,
-                        altitude_km,
-                        -1,
-                    );
-
-                    let key = IonexKey {
-                        epoch: t,
-                        coordinates: coords,
-                    };
+    let lat_exponent = Quantized::find_exponent(grid.latitude.spacing);
+    let long_exponent = Quantized::find_exponent(grid.longitude.spacing);
+    let alt_exponent = Quantized::find_exponent(grid.height.spacing);
 
-                    if let Some(tec) = record.get(&key) {
-                        write!(w, "{:5}", tec.tecu())?;
-                        has_rms |= tec.rms_tec().is_some();
-                    } else {
-                        write!(w, "9999 ")?;
-                    }
+    let scaling = 10.0_f64.powi(-specs.exponent as i32);
 
-                    longitude_ddeg += longitude_spacing_ddeg;
+    writeln!(
+        w,
+        "{}",
+        fmt_rinex(&format!("{:6}", nth_map), &format!("START OF {}", marker))
+    )?;
 
-                    longitude_nth += 1;
-                    longitude_nth = longitude_nth % NUM_LONGITUDES_PER_LINE;
+    writeln!(w, "{}", fmt_rinex(&format_epoch(t), "EPOCH OF CURRENT MAP"))?;
 
-                    if longitude_nth == 0 {
-                        write!(w, "{}", '\n')?;
-                    }
-                }
-
-                latitude_ddeg += latitude_spacing_ddeg;
-            }
-
-            altitude_km += altitude_spacing_km;
-        }
-        writeln!(w, "{}", fmt_rinex("", "END OF TEC MAP"))?;
+    let longitudes = linspace_points(&grid.longitude);
 
-        if has_rms {
-            // format RMS map
+    for altitude_km in linspace_points(&grid.height) {
+        for latitude_ddeg in linspace_points(&grid.latitude) {
             writeln!(
                 w,
                 "{}",
-                fmt_rinex(&format!("{:6}", nth_map), "START OF RMS MAP")
+                fmt_rinex(
+                    &format!(
+                        "  {:6.1}{:6.1}{:6.1}{:6.1}{:6.1}",
+                        latitude_ddeg,
+                        grid.longitude.start,
+                        grid.longitude.end,
+                        grid.longitude.spacing,
+                        altitude_km,
+                    ),
+                    "LAT/LON1/LON2/DLON/H"
+                )
             )?;
 
-            while altitude_km < altitude_high_km {
-                let mut latitude_ddeg = latitude_north_ddeg;
-
-                while latitude_ddeg < latitude_south_ddeg {
-                    let mut longitude_nth = 0;
-                    let mut longitude_ddeg = longitude_west_ddeg;
-
-                    writeln!(
-                        w,
-                        "{}",
-                        fmt_rinex(
-                            &format!(
-                                "  {:3.1}{:3.1}{:3.1}   {:3.1} {:3.1}",
-                                latitude_ddeg,
-                                longitude_east_ddeg,
-                                longitude_west_ddeg,
-                                longitude_spacing_ddeg,
-                                altitude_km,
-                            ),
-                            "LAT/LON1/LON2/DLON/H"
-                        )
-                    )?;
-
-                    while longitude_ddeg < longitude_east_ddeg {
-                        let coords = QuantizedCoordinates::new(
+            for chunk in longitudes.chunks(NUM_VALUES_PER_LINE) {
+                for longitude_ddeg in chunk {
+                    let key = IonexKey {
+                        epoch: t,
+                        coordinates: QuantizedCoordinates::new(
                             latitude_ddeg,
-                            -1,
-                            longitude_ddeg,
-                            -1,
+                            lat_exponent,
+                            *longitude_ddeg,
+                            long_exponent,
                             altitude_km,
-                            -1,
-                        );
-
-                        let key = IonexKey {
-                            epoch: t,
-                            coordinates: coords,
-                        };
-
-                        if let Some(tec) = record.get(&key) {
-                            write!(w, "{:5}", tec.tecu())?;
-                        } else {
-                            write!(w, "9999 ")?;
-                        }
-
-                        longitude_ddeg += longitude_spacing_ddeg;
-
-                        longitude_nth += 1;
-                        longitude_nth = longitude_nth % NUM_LONGITUDES_PER_LINE;
+                            alt_exponent,
+                        ),
+                    };
 
-                        if longitude_nth == 0 {
-                            write!(w, "{}", '\n')?;
-                        }
-                    }
+                    let quantized = match record.get(&key).and_then(&value) {
+                        Some(value) => (value * scaling).round() as i64,
+                        None => NON_AVAILABLE_KEYWORD,
+                    };
 
-                    latitude_ddeg += latitude_spacing_ddeg;
+                    write!(w, "{:5}", quantized)?;
                 }
-
-                altitude_km += altitude_spacing_km;
+                writeln!(w)?;
             }
-            writeln!(w, "{}", fmt_rinex("", "END OF RMS MAP"))?;
         }
+    }
 
-        if has_h {
-            // format H map
-            writeln!(
-                w,
-                "{}",
-                fmt_rinex(&format!("{:6}", nth_map), "START OF HEIGHT MAP")
-            )?;
-
-            while altitude_km < altitude_high_km {
-                let mut latitude_ddeg = latitude_north_ddeg;
-
-                while latitude_ddeg < latitude_south_ddeg {
-                    let mut longitude_nth = 0;
-                    let mut longitude_ddeg = longitude_west_ddeg;
-
-                    writeln!(
-                        w,
-                        "{}",
-                        fmt_rinex(
-                            &format!(
-                                "  {:3.1}{:3.1}{:3.1}   {:3.1} {:3.1}",
-                                latitude_ddeg,
-                                longitude_east_ddeg,
-                                longitude_west_ddeg,
-                                longitude_spacing_ddeg,
-                                altitude_km,
-                            ),
-                            "LAT/LON1/LON2/DLON/H"
-                        )
-                    )?;
-
-                    while longitude_ddeg < longitude_east_ddeg {
-                        let coords = QuantizedCoordinates::new(
-                            latitude_ddeg,
-                            -1,
-                            longitude_ddeg,
-                            -1,
-                            altitude_km,
-                            -1,
-                        );
+    writeln!(
+        w,
+        "{}",
+        fmt_rinex(&format!("{:6}", nth_map), &format!("END OF {}", marker))
+    )?;
 
-                        let key = IonexKey {
-                            epoch: t,
-                            coordinates: coords,
-                        };
+    Ok(())
+}
 
-                        if let Some(tec) = record.get(&key) {
-                            write!(w, "{:5}", tec.tecu())?;
-                        } else {
-                            write!(w, "9999 ")?;
-                        }
+/// Formats IONEX [Record] into [BufWriter].
+/// All TEC maps are formatted first, followed by possible RMS and Height maps.
+pub fn format<W: Write>(
+    w: &mut BufWriter<W>,
+    record: &Record,
+    header: &Header,
+) -> Result<(), FormattingError> {
+    let epochs = record.keys().map(|k| k.epoch).unique().collect::<Vec<_>>();
 
-                        longitude_ddeg += longitude_spacing_ddeg;
+    for (nth, t) in epochs.iter().enumerate() {
+        format_map(w, record, header, nth + 1, *t, "TEC MAP", |tec| {
+            Some(tec.tecu())
+        })?;
+    }
 
-                        longitude_nth += 1;
-                        longitude_nth = longitude_nth % NUM_LONGITUDES_PER_LINE;
+    for (nth, t) in epochs.iter().enumerate() {
+        let has_rms = record
+            .iter()
+            .any(|(k, tec)| k.epoch == *t && tec.rms_tec().is_some());
 
-                        if longitude_nth == 0 {
-                            write!(w, "{}", '\n')?;
-                        }
-                    }
+        if has_rms {
+            format_map(w, record, header, nth + 1, *t, "RMS MAP", |tec| {
+                tec.rms_tec()
+            })?;
+        }
+    }
 
-                    latitude_ddeg += latitude_spacing_ddeg;
-                }
+    for (nth, t) in epochs.iter().enumerate() {
+        let has_height = record
+            .iter()
+            .any(|(k, tec)| k.epoch == *t && tec.height_km().is_some());
 
-                altitude_km += altitude_spacing_km;
-            }
-            writeln!(w, "{}", fmt_rinex("", "END OF HEIGHT MAP"))?;
+        if has_height {
+            format_map(w, record, header, nth + 1, *t, "HEIGHT MAP", |tec| {
+                tec.height_km()
+            })?;
         }
     }
+
+    writeln!(w, "{}", fmt_rinex("", "END OF FILE"))?;
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::{format_epoch, linspace_points};
+    use crate::{linspace::Linspace, prelude::Epoch};
+    use std::str::FromStr;
+
+    #[test]
+    fn ionex_epoch_formatting() {
+        let t = Epoch::from_str("2022-01-02T00:15:00 UTC").unwrap();
+        assert_eq!(format_epoch(t), "  2022     1     2     0    15     0");
+    }
+
+    #[test]
+    fn ionex_linspace_points() {
+        let points = linspace_points(&Linspace {
+            start: 87.5,
+            end: -87.5,
+            spacing: -2.5,
+        });
+        assert_eq!(points.len(), 71);
+        assert_eq!(points[0], 87.5);
+        assert_eq!(points[70], -87.5);
+
+        let points = linspace_points(&Linspace {
+            start: 450.0,
+            end: 450.0,
+            spacing: 0.0,
+        });
+        assert_eq!(points, vec![450.0]);
+    }
+}
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     fmt_rinex,
-    ionex::{BiasSource, Grid, MappingFunction, RefSystem},
+    ionex::{formatting::format_epoch, BiasSource, Grid, MappingFunction, RefSystem},
     linspace::Linspace,
     prelude::{Epoch, FormattingError},
 };
@@ -93,83 +93,114 @@ impl Default for HeaderFields {
 impl HeaderFields {
     /// Formats [HeaderFields] into [BufWriter].
     pub(crate) fn format<W: Write>(&self, w: &mut BufWriter<W>) -> Result<(), FormattingError> {
+        if let Some(description) = &self.description {
+            let mut line = String::new();
+            for word in description.split_ascii_whitespace() {
+                if !line.is_empty() && line.len() + word.len() + 1 > 60 {
+                    writeln!(w, "{}", fmt_rinex(&line, "DESCRIPTION"))?;
+                    line.clear();
+                }
+                if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(word);
+            }
+            if !line.is_empty() {
+                writeln!(w, "{}", fmt_rinex(&line, "DESCRIPTION"))?;
+            }
+        }
+
         writeln!(
             w,
             "{}",
-            fmt_rinex(&format!("{:6}", self.map_dimension), "MAP DIMENSION")
+            fmt_rinex(&format_epoch(self.epoch_of_first_map), "EPOCH OF FIRST MAP")
         )?;
 
-        // altitude grid
-        let (start, end, spacing) = (
-            self.grid.height.start,
-            self.grid.height.end,
-            self.grid.height.spacing,
-        );
+        writeln!(
+            w,
+            "{}",
+            fmt_rinex(&format_epoch(self.epoch_of_last_map), "EPOCH OF LAST MAP")
+        )?;
 
         writeln!(
             w,
             "{}",
-            fmt_rinex(
-                &format!("{} {} {}", start, end, spacing),
-                "HGT1 / HGT2 / DHGT"
-            )
+            fmt_rinex(&format!("{:6}", self.number_of_maps), "# OF MAPS IN FILE")
         )?;
 
-        // latitude grid
-        let (start, end, spacing) = (
-            self.grid.latitude.start,
-            self.grid.latitude.end,
-            self.grid.latitude.spacing,
-        );
+        let mapping = match self.mapping {
+            Some(MappingFunction::CosZ) => "COSZ",
+            Some(MappingFunction::QFac) => "QFAC",
+            None => "NONE",
+        };
+
+        writeln!(
+            w,
+            "{}",
+            fmt_rinex(&format!("  {}", mapping), "MAPPING FUNCTION")
+        )?;
 
         writeln!(
             w,
             "{}",
             fmt_rinex(
-                &format!("{} {} {}", start, end, spacing),
-                "LAT1 / LAT2 / DLAT"
+                &format!("{:8.1}", self.elevation_cutoff),
+                "ELEVATION CUTOFF"
             )
         )?;
 
-        // longitude grid
-        let (start, end, spacing) = (
-            self.grid.longitude.start,
-            self.grid.longitude.end,
-            self.grid.longitude.spacing,
-        );
+        if let Some(observables) = &self.observables {
+            writeln!(w, "{}", fmt_rinex(observables, "OBSERVABLES USED"))?;
+        }
+
+        if self.nb_stations > 0 {
+            writeln!(
+                w,
+                "{}",
+                fmt_rinex(&format!("{:6}", self.nb_stations), "# OF STATIONS")
+            )?;
+        }
+
+        if self.nb_satellites > 0 {
+            writeln!(
+                w,
+                "{}",
+                fmt_rinex(&format!("{:6}", self.nb_satellites), "# OF SATELLITES")
+            )?;
+        }
 
         writeln!(
             w,
             "{}",
-            fmt_rinex(
-                &format!("{} {} {}", start, end, spacing),
-                "LON1 / LON2 / DLON"
-            )
+            fmt_rinex(&format!("{:8.1}", self.base_radius), "BASE RADIUS")
         )?;
 
-        // elevation cutoff
         writeln!(
             w,
             "{}",
-            fmt_rinex(&format!("{}", self.elevation_cutoff), "ELEVATION CUTOFF")
+            fmt_rinex(&format!("{:6}", self.map_dimension), "MAP DIMENSION")
         )?;
 
-        // mapping function
-        if let Some(map_f) = &self.mapping {
+        for (grid, marker) in [
+            (&self.grid.height, "HGT1 / HGT2 / DHGT"),
+            (&self.grid.latitude, "LAT1 / LAT2 / DLAT"),
+            (&self.grid.longitude, "LON1 / LON2 / DLON"),
+        ] {
             writeln!(
                 w,
                 "{}",
-                fmt_rinex(&format!("{:?}", map_f), "MAPPING FUNCTION")
+                fmt_rinex(
+                    &format!("  {:6.1}{:6.1}{:6.1}", grid.start, grid.end, grid.spacing),
+                    marker
+                )
             )?;
-        } else {
-            writeln!(w, "{}", fmt_rinex("NONE", "MAPPING FUNCTION"))?;
         }
 
-        // time of first map
-        writeln!(w, "{}", fmt_rinex("TODO", "EPOCH OF FIRST MAP"))?;
-
-        // time of last map
-        writeln!(w, "{}", fmt_rinex("TODO", "EPOCH OF LAST MAP"))?;
+        writeln!(
+            w,
+            "{}",
+            fmt_rinex(&format!("{:6}", self.exponent), "EXPONENT")
+        )?;
 
         Ok(())
     }
@@ -40,14 +40,14 @@ pub use system::RefSystem;
 pub use tec::TEC;
 
 pub(crate) use parsing::{
-    is_new_height_map,
-    is_new_rms_map,
-    is_new_tec_map,
-    //parse_height_map,
-    parse_rms_map,
+    is_new_height_map, is_new_rms_map, is_new_tec_map, parse_height_map, parse_rms_map,
     parse_tec_map,
 };
 
+pub(crate) use formatting::format;
+
+#[cfg(feature = "ionex")]
+pub(crate) use formatting::linspace_points;
 pub(crate) use quantized::Quantized;
 
 #[cfg(feature = "serde")]
@@ -173,7 +173,7 @@ pub fn parse_rms_map(
 
     for line in lines {
         if line.len() > 60 {
-            let marker = line.split_at(60).1;
+            let (content, marker) = line.split_at(60);
             if marker.contains("END OF RMS MAP") {
                 return Ok(());
             } else if marker.contains("EXPONENT") {
@@ -222,54 +222,79 @@ pub fn parse_rms_map(
     Ok(())
 }
 
-// /// Parses all Height map contained in following content.
-// /// Adjust the previously parsed isosurface to turn them into a real volume definition.
-// /// ## Inputs
-// ///   - content: readable content (ASCII UTF-8)
-// ///   - lat_exponent: deduced from IONEX header for coordinates quantization
-// ///   - long_exponent: deduced from IONEX header for coordinates quantization
-// ///   - tec_exponent: kept up to date, for correct data interpretation
-// ///   - epoch: epoch of current map
-// pub fn parse_height_map(
-//     content: &str,
-//     lat_exponent: i8,
-//     long_exponent: i8,
-//     alt_exponent: i8,
-//     tec_exponent: i8,
-//     epoch: Epoch,
-//     record: &mut Record,
-// ) -> Result<(), ParsingError> {
-//     let lines = content.lines();
-//     let mut epoch = Epoch::default();
-
-//     let mut fixed_lat = 0.0_f64;
-//     let (mut long1, mut long_spacing) = (0.0_f64, 0.0_f64);
-//     let mut fixed_alt = 0.0_f64;
-
-//     let mut long = 0.0_f64; // current longitude (pointer)
-
-//     for line in lines {
-//         if line.len() > 60 {
-//             let marker = line.split_at(60).1;
-//             if marker.contains("END OF HEIGHT MAP") {
-//                 return Ok(());
-//             } else if marker.contains("EXPONENT") {
-//                 // should not have been presented (handled @ higher level)
-//                 continue; // avoid parsing
-//             }
-//         }
-
-//         // proceed to parsing
-//         for item in line.split_ascii_whitespace() {
-//             if let Ok(h_km) = item.trim().parse::<i32>() {
-//                 // Should adjust the altitude we previously parsed
-//             }
-
-//             long += long_spacing;
-//         }
-//     }
-//     Ok(())
-// }
+/// Parses all Height maps contained in following content.
+/// This describes the altitude of each TEC previously parsed, for current isosurface.
+/// ## Inputs
+///   - content: readable content (ASCII UTF-8)
+///   - lat_exponent: deduced from IONEX header for coordinates quantization
+///   - long_exponent: deduced from IONEX header for coordinates quantization
+///   - tec_exponent: kept up to date, for correct data interpretation
+///   - epoch: epoch of current map
+pub fn parse_height_map(
+    content: &str,
+    lat_exponent: i8,
+    long_exponent: i8,
+    alt_exponent: i8,
+    tec_exponent: i8,
+    epoch: Epoch,
+    record: &mut Record,
+) -> Result<(), ParsingError> {
+    const NON_AVAILABLE_HEIGHT_KEYWORD: &str = "9999";
+
+    let lines = content.lines();
+
+    let mut fixed_lat = 0.0_f64;
+    let mut fixed_alt = 0.0_f64;
+    let mut long_spacing = 0.0_f64;
+
+    let mut long = 0.0_f64; // current longitude (pointer)
+
+    for line in lines {
+        if line.len() > 60 {
+            let (content, marker) = line.split_at(60);
+            if marker.contains("END OF HEIGHT MAP") {
+                return Ok(());
+            } else if marker.contains("EXPONENT") {
+                // should not have been presented (handled @ higher level)
+                continue; // avoid parsing
+            } else if marker.contains("START OF") {
+                continue; // avoid parsing
+            } else if marker.contains("LAT/LON1/LON2/DLON/H") {
+                // grid specs (to follow)
+                (fixed_lat, long, long_spacing, fixed_alt) = parse_grid_specs(content)?;
+                continue; // avoid parsing
+            }
+        }
+
+        // proceed to parsing
+        for item in line.split_ascii_whitespace() {
+            let item = item.trim();
+
+            if item != NON_AVAILABLE_HEIGHT_KEYWORD {
+                if let Ok(height) = item.parse::<i64>() {
+                    let quantized_lat = Quantized::new(fixed_lat, lat_exponent);
+                    let quantized_long = Quantized::new(long, long_exponent);
+                    let quantized_alt = Quantized::new(fixed_alt, alt_exponent);
+
+                    let coordinates = QuantizedCoordinates::from_quantized(
+                        quantized_lat,
+                        quantized_long,
+                        quantized_alt,
+                    );
+
+                    // we only augment previously parsed TEC values
+                    let key = IonexKey { epoch, coordinates };
+                    if let Some(v) = record.get_mut(&key) {
+                        v.set_quantized_height(height, tec_exponent);
+                    }
+                }
+            }
+
+            long += long_spacing;
+        }
+    }
+    Ok(())
+}
 
 #[cfg(test)]
 mod test {
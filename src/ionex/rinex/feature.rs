@@ -1,5 +1,5 @@
 use crate::{
-    ionex::{IonexKey, TEC},
+    ionex::{linspace_points, IonexKey, Quantized, TEC},
     prelude::{Epoch, Rinex},
 };
 
@@ -71,17 +71,48 @@ impl Rinex {
         Some((header.grid.height.start, header.grid.height.end))
     }
 
+    /// Returns altitude of each isosurface (in km) defined by the IONEX grid,
+    /// from lowest to highest altitude.
+    fn ionex_isosurfaces_km(&self) -> Vec<f64> {
+        let specs = match &self.header.ionex {
+            Some(specs) => specs,
+            None => return Vec::new(),
+        };
+
+        // same quantization as record content
+        let alt_exponent = Quantized::find_exponent(specs.grid.height.spacing);
+
+        let mut altitudes = linspace_points(&specs.grid.height)
+            .into_iter()
+            .map(|alt_km| Quantized::new(alt_km, alt_exponent).real_value())
+            .collect::<Vec<_>>();
+
+        altitudes.sort_by(|a, b| a.total_cmp(b));
+        altitudes
+    }
+
     /// Designs an TEC isosurface iterator starting at lowest altitude,
-    /// ending at highest altitude. See [Self::ionex_tec_maps_altitude_range] for
-    /// useful information.
+    /// ending at highest altitude. Each isosurface is described in chronological order.
+    /// See [Self::ionex_altitude_range_km] for useful information.
     pub fn ionex_tec_isosurface_iter(&self) -> Box<dyn Iterator<Item = (IonexKey, TEC)> + '_> {
-        Box::new([].into_iter())
+        Box::new(
+            self.ionex_isosurfaces_km()
+                .into_iter()
+                .flat_map(move |altitude_km| {
+                    self.ionex_tec_maps_iter()
+                        .filter(move |(k, _)| k.coordinates.altitude_km() == altitude_km)
+                        .map(|(k, tec)| (k, tec.clone()))
+                }),
+        )
     }
 
     /// Designs an RMS TEC isosurface iterator starting at lowest altitude,
-    /// ending at highest altitude. See [Self::ionex_tec_maps_altitude_range] for
-    /// useful information.
+    /// ending at highest altitude. Only [TEC] estimates with RMS are proposed.
+    /// See [Self::ionex_altitude_range_km] for useful information.
     pub fn ionex_rms_tec_isosurface_iter(&self) -> Box<dyn Iterator<Item = (IonexKey, TEC)> + '_> {
-        Box::new([].into_iter())
+        Box::new(
+            self.ionex_tec_isosurface_iter()
+                .filter(|(_, tec)| tec.rms_tec().is_some()),
+        )
     }
 }
@@ -54,7 +54,12 @@ impl std::str::FromStr for ObsSystem {
 
 impl std::fmt::Display for ObsSystem {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        f.write_str(&self.to_string())
+        match self {
+            Self::BENt => f.write_str("BEN"),
+            Self::ENVisat => f.write_str("ENV"),
+            Self::ERS => f.write_str("ERS"),
+            Self::IRI => f.write_str("IRI"),
+        }
     }
 }
 
@@ -87,7 +92,11 @@ impl std::str::FromStr for Model {
 
 impl std::fmt::Display for Model {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        f.write_str(&self.to_string())
+        match self {
+            Self::MIX => f.write_str("MIX"),
+            Self::NNS => f.write_str("NNS"),
+            Self::TOP => f.write_str("TOP"),
+        }
     }
 }
 
@@ -100,6 +109,7 @@ impl Default for RefSystem {
 impl std::fmt::Display for RefSystem {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
+            Self::GnssConstellation(Constellation::Mixed) => f.write_str("GNSS"),
             Self::GnssConstellation(c) => c.fmt(f),
             Self::ObservationSystem(s) => s.fmt(f),
             Self::Model(m) => m.fmt(f),
@@ -135,4 +145,23 @@ mod test {
             RefSystem::GnssConstellation(Constellation::default())
         );
     }
+
+    #[test]
+    fn refsystem_reciprocal() {
+        for system in [
+            RefSystem::GnssConstellation(Constellation::GPS),
+            RefSystem::GnssConstellation(Constellation::Glonass),
+            RefSystem::GnssConstellation(Constellation::Mixed),
+            RefSystem::ObservationSystem(ObsSystem::BENt),
+            RefSystem::ObservationSystem(ObsSystem::ENVisat),
+            RefSystem::ObservationSystem(ObsSystem::ERS),
+            RefSystem::ObservationSystem(ObsSystem::IRI),
+            RefSystem::Model(Model::NNS),
+            RefSystem::Model(Model::TOP),
+        ] {
+            let formatted = system.to_string();
+            let parsed = RefSystem::from_str(&formatted).unwrap();
+            assert_eq!(parsed, system, "failed to parse \"{}\"", formatted);
+        }
+    }
 }
@@ -11,7 +11,7 @@ pub struct TEC {
     tecu: Quantized,
     /// RMS (TEC)
     rms: Option<Quantized>,
-    /// Altitude offset for complex 3D height map
+    /// Altitude, described by height maps
     height: Option<Quantized>,
 }
 
@@ -45,6 +45,14 @@ impl TEC {
         s
     }
 
+    /// Builds new [TEC] estimate with associated altitude (in km)
+    pub fn with_height_km(&self, height_km: f64) -> Self {
+        let mut s = self.clone();
+        let exponent = Quantized::find_exponent(height_km);
+        s.height = Some(Quantized::new(height_km, exponent));
+        s
+    }
+
     /// Builds new [TEC] from TEC quantization in TECu
     pub(crate) fn from_quantized(tecu: i64, exponent: i8) -> Self {
         // IONEX stores quantized TEC as i=10*-k TECu
@@ -66,6 +74,14 @@ impl TEC {
         });
     }
 
+    /// Updates altitude of this [TEC] estimate
+    pub(crate) fn set_quantized_height(&mut self, height: i64, exponent: i8) {
+        self.height = Some(Quantized {
+            exponent: -exponent,
+            quantized: height,
+        });
+    }
+
     /// Returns Total Electron Content estimate, in TECu (=10^-16 m-2)
     pub fn tecu(&self) -> f64 {
         self.tecu.real_value()
@@ -82,11 +98,11 @@ impl TEC {
         Some(rms.real_value())
     }
 
-    // /// Returns possible altitude offset [km]
-    // pub(crate) fn height_offset_km(&self) -> Option<f64> {
-    //     let height = self.height?;
-    //     Some(height.real_value())
-    // }
+    /// Returns altitude of this [TEC] estimate (in km), if it was described by a height map.
+    pub fn height_km(&self) -> Option<f64> {
+        let height = self.height?;
+        Some(height.real_value())
+    }
 }
 
 #[cfg(feature = "qc")]
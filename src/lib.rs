@@ -41,9 +41,11 @@ pub mod meteo;
 pub mod navigation;
 pub mod observation;
 pub mod production;
+pub mod reader;
 pub mod record;
 pub mod types;
 pub mod version;
+pub mod writer;
 
 mod bibliography;
 mod constants;
@@ -54,6 +56,14 @@ mod linspace;
 mod observable;
 mod sampling;
 
+#[cfg(feature = "obs")]
+#[cfg_attr(docsrs, doc(cfg(feature = "obs")))]
+mod cs;
+
+#[cfg(feature = "obs")]
+#[cfg_attr(docsrs, doc(cfg(feature = "obs")))]
+pub mod differential;
+
 #[cfg(feature = "qc")]
 #[cfg_attr(docsrs, doc(cfg(feature = "qc")))]
 mod qc;
@@ -114,8 +124,11 @@ pub mod prelude {
         header::Header,
         leap::Leap,
         observable::Observable,
+        reader::Reader,
+        record::RecordEntry,
         types::Type as RinexType,
         version::Version,
+        writer::Writer,
         Rinex,
     };
 
@@ -145,6 +158,10 @@ pub mod prelude {
     pub mod obs {
         pub use crate::carrier::Carrier;
 
+        pub use crate::cs::{CsDetector, CsSelectionMethod, CsStrategy, CycleSlip};
+
+        pub use crate::differential::{DiffContext, DoubleDiffKey, SingleDiffKey};
+
         pub use crate::observation::{
             ClockObservation, Combination, CombinationKey, EpochFlag, LliFlags, ObsKey,
             Observations, SignalObservation, SNR,
@@ -154,7 +171,10 @@ pub mod prelude {
     #[cfg(feature = "binex")]
     #[cfg_attr(docsrs, doc(cfg(feature = "binex")))]
     pub mod binex {
-        pub use crate::binex::{BIN2RNX, RNX2BIN};
+        pub use crate::binex::{
+            MeteoRecord, ObservationRecord, PrototypeMessage, PrototypeRecord, StreamMessage,
+            BIN2RNX, RNX2BIN,
+        };
         pub use binex::prelude::{Message, Meta};
     }
 
@@ -183,6 +203,7 @@ pub mod prelude {
     #[cfg(feature = "qc")]
     #[cfg_attr(docsrs, doc(cfg(feature = "qc")))]
     pub mod qc {
+        pub use crate::qc::{MergeConflict, MergePolicy, MergeReport, RinexMergeError};
         pub use qc_traits::{Merge, MergeError};
     }
 
@@ -201,7 +222,7 @@ pub mod prelude {
 
     #[cfg(feature = "rtcm")]
     #[cfg_attr(docsrs, doc(cfg(feature = "rtcm")))]
-    pub use crate::rtcm::RTCM2RNX;
+    pub use crate::rtcm::{RNX2RTCM, RTCM2RNX};
 }
 
 /// Package dedicated to file production.
@@ -347,6 +368,16 @@ impl Rinex {
         }
     }
 
+    /// Builds a default Meteo [Rinex], useful in data production context.
+    pub fn basic_meteo() -> Self {
+        Self {
+            header: Header::basic_meteo(),
+            comments: Default::default(),
+            production: ProductionAttributes::default(),
+            record: Record::MeteoRecord(Default::default()),
+        }
+    }
+
     /// Copy and return this [Rinex] with updated [Header].
     pub fn with_header(&self, header: Header) -> Self {
         Self {
@@ -844,6 +875,17 @@ impl Rinex {
     /// and following standard specifications. The revision to be followed is defined
     /// in [Header] section. This is the mirror operation of [Self::parse].
     pub fn format<W: Write>(&self, writer: &mut BufWriter<W>) -> Result<(), FormattingError> {
+        // V2/V3 NAV: system time offsets and ionosphere models are header fields
+        if self.header.version.major < 4 {
+            if let Some(rec) = self.record.as_nav() {
+                let header = navigation::v2v3_header(&self.header, rec);
+                header.format(writer)?;
+                self.record.format(writer, &header)?;
+                writer.flush()?;
+                return Ok(());
+            }
+        }
+
         self.header.format(writer)?;
         self.record.format(writer, &self.header)?;
         writer.flush()?;
@@ -1,9 +1,36 @@
-use crate::{meteo::Record, prelude::Epoch};
-use qc_traits::{DecimationFilter, DecimationFilterType};
+use crate::{
+    meteo::Record,
+    prelude::{Epoch, Observable},
+    processing::{unsupported_target, TargetDecimator},
+};
+
+use qc_traits::{DecimationFilter, DecimationFilterType, FilterItem};
+
+use std::str::FromStr;
 
 pub(crate) fn decim_mut(rec: &mut Record, f: &DecimationFilter) {
-    if f.item.is_some() {
-        todo!("targetted decimation not supported yet");
+    if let Some(item) = &f.item {
+        // only observables may be targeted
+        let observables = match item {
+            FilterItem::ComplexItem(items) => items
+                .iter()
+                .filter_map(|item| Observable::from_str(item).ok())
+                .collect::<Vec<_>>(),
+            _ => {
+                unsupported_target("Meteo", item);
+                return;
+            },
+        };
+
+        let mut decimator = TargetDecimator::new(&f.filter);
+        rec.retain(|k, _| {
+            if observables.contains(&k.observable) {
+                decimator.retain(k.observable.clone(), k.epoch)
+            } else {
+                true
+            }
+        });
+        return;
     }
     match f.filter {
         DecimationFilterType::Modulo(r) => {
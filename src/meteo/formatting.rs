@@ -1,46 +1,57 @@
 use std::io::{BufWriter, Write};
 
 use crate::{
-    epoch::format as format_epoch,
+    epoch::format as format_epoch_descriptor,
     meteo::{MeteoKey, Record},
-    prelude::{FormattingError, Header, RinexType},
+    prelude::{Epoch, FormattingError, Header, RinexType},
 };
 
 use itertools::Itertools;
 
-/// Formats Meteo epoch into [BufWriter]
+/// Formats Meteo [Record] into [BufWriter]
 pub fn format<W: Write>(
     w: &mut BufWriter<W>,
     record: &Record,
     header: &Header,
+) -> Result<(), FormattingError> {
+    for epoch in record.keys().map(|k| k.epoch).unique().sorted() {
+        format_epoch(w, epoch, record, header)?;
+    }
+    Ok(())
+}
+
+/// Formats this Meteo [Epoch] into [BufWriter]
+pub(crate) fn format_epoch<W: Write>(
+    w: &mut BufWriter<W>,
+    epoch: Epoch,
+    record: &Record,
+    header: &Header,
 ) -> Result<(), FormattingError> {
     let observables = &header
         .meteo
         .as_ref()
         .ok_or(FormattingError::UndefinedObservables)?;
 
-    for epoch in record.keys().map(|k| k.epoch).unique().sorted() {
-        write!(
-            w,
-            " {}",
-            format_epoch(epoch, RinexType::MeteoData, header.version.major)
-        )?;
-
-        // follow header definitions
-        for observable in observables.codes.iter() {
-            let key = MeteoKey {
-                epoch,
-                observable: observable.clone(),
-            };
-
-            if let Some(observation) = record.get(&key) {
-                write!(w, "{:7.1}", observation)?;
-            } else {
-                write!(w, "           ")?;
-            }
+    write!(
+        w,
+        " {}",
+        format_epoch_descriptor(epoch, RinexType::MeteoData, header.version.major)
+    )?;
+
+    // follow header definitions
+    for observable in observables.codes.iter() {
+        let key = MeteoKey {
+            epoch,
+            observable: observable.clone(),
+        };
+
+        if let Some(observation) = record.get(&key) {
+            write!(w, "{:7.1}", observation)?;
+        } else {
+            write!(w, "           ")?;
         }
-        write!(w, "{}", '\n')?;
     }
 
+    write!(w, "{}", '\n')?;
     Ok(())
 }
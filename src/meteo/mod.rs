@@ -11,7 +11,7 @@ pub use sensor::Sensor;
 use crate::prelude::{Epoch, Observable};
 use std::collections::BTreeMap;
 
-pub(crate) use formatting::format;
+pub(crate) use formatting::{format, format_epoch};
 pub(crate) use parsing::{is_new_epoch, parse_epoch};
 
 #[cfg(feature = "processing")]
@@ -1,9 +1,47 @@
-use crate::{navigation::Record, prelude::Epoch};
-use qc_traits::{DecimationFilter, DecimationFilterType};
+use crate::{
+    navigation::{NavFrameType, NavKey, Record},
+    prelude::{Constellation, Epoch},
+    processing::{unsupported_target, TargetDecimator},
+};
+
+use qc_traits::{DecimationFilter, DecimationFilterType, FilterItem};
+
+use std::str::FromStr;
+
+/// Returns true if this [NavKey] is targeted by [FilterItem]
+fn targeted(item: &FilterItem, k: &NavKey) -> bool {
+    match item {
+        FilterItem::SvItem(svs) => svs.contains(&k.sv),
+        FilterItem::ConstellationItem(constellations) => {
+            constellations.contains(&k.sv.constellation)
+                || (k.sv.constellation.is_sbas() && constellations.contains(&Constellation::SBAS))
+        },
+        FilterItem::ComplexItem(items) => items
+            .iter()
+            .filter_map(|item| NavFrameType::from_str(item).ok())
+            .any(|frmtype| frmtype == k.frmtype),
+        _ => false,
+    }
+}
 
 pub(crate) fn decim_mut(rec: &mut Record, f: &DecimationFilter) {
-    if f.item.is_some() {
-        todo!("targetted decimation not supported yet");
+    if let Some(item) = &f.item {
+        if !matches!(
+            item,
+            FilterItem::SvItem(_) | FilterItem::ConstellationItem(_) | FilterItem::ComplexItem(_)
+        ) {
+            unsupported_target("Navigation", item);
+            return;
+        }
+        let mut decimator = TargetDecimator::new(&f.filter);
+        rec.retain(|k, _| {
+            if targeted(item, k) {
+                decimator.retain((k.sv, k.frmtype), k.epoch)
+            } else {
+                true
+            }
+        });
+        return;
     }
     match f.filter {
         DecimationFilterType::Modulo(r) => {
@@ -1,14 +1,28 @@
 //! Earth Orientation NAV frames
 
+mod provider;
+
+#[cfg(feature = "nav")]
+mod rotation;
+
+pub use provider::EopProvider;
+
 use crate::{
     epoch::parse_in_timescale as parse_epoch_in_timescale,
-    prelude::{Epoch, ParsingError, TimeScale},
+    error::FormattingError,
+    navigation::formatting::{format_frame_epoch_v4, NavFormatter},
+    prelude::{Duration, Epoch, ParsingError, TimeScale},
 };
 
+use hifitime::Unit;
+
 #[cfg(feature = "serde")]
 use serde::Serialize;
 
-use std::str::FromStr;
+use std::{
+    io::{BufWriter, Write},
+    str::FromStr,
+};
 
 /// Earth Orientation Message
 #[derive(Debug, Clone, Default, PartialEq, PartialOrd)]
@@ -75,6 +89,84 @@ impl EarthOrientation {
             },
         ))
     }
+
+    /// Format this [EarthOrientation] as V4 frame body, published at [Epoch].
+    pub(crate) fn format_v4<W: Write>(
+        &self,
+        w: &mut BufWriter<W>,
+        t: Epoch,
+    ) -> Result<(), FormattingError> {
+        writeln!(
+            w,
+            "{}{}{}{}",
+            format_frame_epoch_v4(t),
+            NavFormatter::new(self.x.0),
+            NavFormatter::new(self.x.1),
+            NavFormatter::new(self.x.2),
+        )?;
+
+        writeln!(
+            w,
+            "{:23}{}{}{}",
+            "",
+            NavFormatter::new(self.y.0),
+            NavFormatter::new(self.y.1),
+            NavFormatter::new(self.y.2),
+        )?;
+
+        writeln!(
+            w,
+            "    {}{}{}{}",
+            NavFormatter::new(self.t_tm as f64),
+            NavFormatter::new(self.delta_ut1.0),
+            NavFormatter::new(self.delta_ut1.1),
+            NavFormatter::new(self.delta_ut1.2),
+        )?;
+
+        Ok(())
+    }
+
+    /// Resolves the transmission [Epoch] of these parameters published at `t_ref`.
+    /// The transmission time is only given within the week, we resolve it
+    /// as the one closest to the reference time, which may
+    /// belong to the previous or following week.
+    pub fn transmission_epoch(&self, t_ref: Epoch) -> Epoch {
+        // GNSS week (GPST week for non GNSS timescales)
+        let ts = if t_ref.time_scale.is_gnss() {
+            t_ref.time_scale
+        } else {
+            TimeScale::GPST
+        };
+
+        let (week, _) = t_ref.to_time_scale(ts).to_time_of_week();
+        let half_week = Duration::from_days(3.5);
+
+        let t = Epoch::from_time_of_week(week, self.t_tm as u64 * 1_000_000_000, ts)
+            .to_time_scale(t_ref.time_scale);
+
+        if t - t_ref > half_week {
+            t - Duration::from_days(7.0)
+        } else if t_ref - t > half_week {
+            t + Duration::from_days(7.0)
+        } else {
+            t
+        }
+    }
+
+    /// Evaluates the pole coordinates and UT1-UTC at [Epoch], from these
+    /// parameters published at `t_ref`.
+    /// ## Returns
+    /// - (x (arc-sec), y (arc-sec), UT1-UTC (s)) triplet
+    pub fn evaluate(&self, t_ref: Epoch, t: Epoch) -> (f64, f64, f64) {
+        let dt = (t - t_ref).to_unit(Unit::Day);
+        let polynomial = |(a0, a1, a2): (f64, f64, f64)| a0 + a1 * dt + a2 * dt.powi(2);
+
+        (
+            polynomial(self.x),
+            polynomial(self.y),
+            polynomial(self.delta_ut1),
+        )
+    }
 }
 
 #[cfg(test)]
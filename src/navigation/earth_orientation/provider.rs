@@ -0,0 +1,171 @@
+//! Earth Orientation Parameters provider
+
+use crate::{
+    navigation::EarthOrientation,
+    prelude::{Duration, Epoch},
+};
+
+use std::collections::BTreeMap;
+
+#[cfg(feature = "ut1")]
+use crate::prelude::ut1::Ut1Provider;
+
+#[cfg(feature = "ut1")]
+use hifitime::HifitimeError;
+
+#[cfg(feature = "nav")]
+use super::rotation::{eme2000_to_itrf, itrf_to_eme2000};
+
+#[cfg(feature = "nav")]
+use crate::prelude::nav::Orbit;
+
+/// [EopProvider] gathers the Earth Orientation Parameters (EOP)
+/// broadcast in NAV V4 files, indexed by transmission [Epoch].
+/// At any [Epoch], the most recently transmitted publication applies.
+/// Usually obtained with [crate::prelude::Rinex::nav_eop_provider].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EopProvider {
+    /// [EarthOrientation] parameters and their reference [Epoch],
+    /// indexed by transmission [Epoch]
+    parameters: BTreeMap<Epoch, (Epoch, EarthOrientation)>,
+    /// Celestial pole offsets (dψ, dε) in arc-seconds
+    celestial_pole_offsets: (f64, f64),
+}
+
+impl EopProvider {
+    /// Copies and returns [EopProvider] with [EarthOrientation] parameters
+    /// referenced to this [Epoch]. Replaces any previous publication transmitted
+    /// at the same [Epoch], see [EarthOrientation::transmission_epoch].
+    pub fn with_parameters(&self, t_ref: Epoch, eop: EarthOrientation) -> Self {
+        let mut s = self.clone();
+        s.add_parameters(t_ref, eop);
+        s
+    }
+
+    /// Adds [EarthOrientation] parameters referenced to this [Epoch].
+    pub fn add_parameters(&mut self, t_ref: Epoch, eop: EarthOrientation) {
+        let t_tm = eop.transmission_epoch(t_ref);
+        self.parameters.insert(t_tm, (t_ref, eop));
+    }
+
+    /// Copies and returns [EopProvider] with celestial pole offsets (dψ, dε)
+    /// in arc-seconds, as published by the IERS (Bulletin A, EOP 14 C04).
+    /// They are not broadcast, without them the IAU 1980 nutation model
+    /// is off by a few tens of milli arc-seconds, that is a few meters at GNSS altitude.
+    pub fn with_celestial_pole_offsets(&self, dpsi_arcsec: f64, deps_arcsec: f64) -> Self {
+        let mut s = self.clone();
+        s.celestial_pole_offsets = (dpsi_arcsec, deps_arcsec);
+        s
+    }
+
+    /// Returns true if this [EopProvider] does not contain any publication.
+    pub fn is_empty(&self) -> bool {
+        self.parameters.is_empty()
+    }
+
+    /// Returns the most recently transmitted publication, at this [Epoch],
+    /// as its reference [Epoch] and [EarthOrientation] parameters.
+    pub fn selection(&self, t: Epoch) -> Option<(Epoch, &EarthOrientation)> {
+        let (_, (t_ref, eop)) = self.parameters.range(..=t).next_back()?;
+        Some((*t_ref, eop))
+    }
+
+    /// Evaluates the pole coordinates and UT1-UTC at this [Epoch].
+    /// ## Returns
+    /// - (x (arc-sec), y (arc-sec), UT1-UTC (s)) triplet
+    pub fn parameters(&self, t: Epoch) -> Option<(f64, f64, f64)> {
+        let (t_ref, eop) = self.selection(t)?;
+        Some(eop.evaluate(t_ref, t))
+    }
+
+    /// Pole coordinates (x, y) in arc-seconds, at this [Epoch].
+    pub fn polar_motion_arcsec(&self, t: Epoch) -> Option<(f64, f64)> {
+        let (x, y, _) = self.parameters(t)?;
+        Some((x, y))
+    }
+
+    /// UT1-UTC as [Duration], at this [Epoch].
+    pub fn ut1_utc(&self, t: Epoch) -> Option<Duration> {
+        let (_, _, dut1) = self.parameters(t)?;
+        Some(Duration::from_seconds(dut1))
+    }
+
+    /// TAI-UT1 as [Duration], at this [Epoch].
+    pub fn tai_ut1(&self, t: Epoch) -> Option<Duration> {
+        let tai_utc = Duration::from_seconds(t.leap_seconds(true)?);
+        Some(tai_utc - self.ut1_utc(t)?)
+    }
+
+    /// Converts this [EopProvider] to [Ut1Provider], sampling each publication
+    /// at this rate, from its transmission until the next one (or one day, for the last one).
+    #[cfg(feature = "ut1")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ut1")))]
+    pub fn to_ut1_provider(&self, sampling: Duration) -> Result<Ut1Provider, HifitimeError> {
+        let one_day = Duration::from_days(1.0);
+
+        let mut content = String::from("EOP2=\n");
+
+        let mut publications = self.parameters.keys().peekable();
+
+        while let Some(t_tm) = publications.next() {
+            let t_end = publications
+                .peek()
+                .map(|t_next| **t_next)
+                .unwrap_or(*t_tm + one_day);
+
+            let mut t = *t_tm;
+
+            while t < t_end {
+                if let Some(tai_ut1) = self.tai_ut1(t) {
+                    // rounded down, so each record applies from its transmission
+                    let mjd = (t.to_mjd_tai_days() * 1.0E9).floor() / 1.0E9;
+
+                    content.push_str(&format!(
+                        "{:.9}, 0, 0, {:.6}\n",
+                        mjd,
+                        tai_ut1.to_seconds() * 1.0E3,
+                    ));
+                }
+
+                t += sampling;
+            }
+        }
+
+        content.push_str("$END\n");
+
+        Ut1Provider::from_eop_data(content)
+    }
+
+    /// Expresses this Earth fixed (ITRF) [Orbit], like [crate::prelude::Rinex::sv_orbit]
+    /// results, in the inertial EME2000 frame, using the Earth Orientation Parameters
+    /// that apply at this [Orbit] [Epoch] (IAU 1976 precession, IAU 1980 nutation
+    /// truncated to 63 of its 106 terms). At GNSS altitude, this agrees with SOFA
+    /// within 10 cm using [Self::with_celestial_pole_offsets], within 5 m otherwise.
+    #[cfg(feature = "nav")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "nav")))]
+    pub fn itrf_to_eme2000(&self, orbit: Orbit) -> Option<Orbit> {
+        let (x, y, dut1) = self.parameters(orbit.epoch)?;
+        Some(itrf_to_eme2000(
+            orbit,
+            (x, y),
+            dut1,
+            self.celestial_pole_offsets,
+        ))
+    }
+
+    /// Expresses this inertial (EME2000) [Orbit] in the Earth fixed frame,
+    /// tagged `EARTH_ITRF93`, using the Earth Orientation
+    /// Parameters that apply at this [Orbit] [Epoch].
+    /// This is the reciprocal of [Self::itrf_to_eme2000].
+    #[cfg(feature = "nav")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "nav")))]
+    pub fn eme2000_to_itrf(&self, orbit: Orbit) -> Option<Orbit> {
+        let (x, y, dut1) = self.parameters(orbit.epoch)?;
+        Some(eme2000_to_itrf(
+            orbit,
+            (x, y),
+            dut1,
+            self.celestial_pole_offsets,
+        ))
+    }
+}
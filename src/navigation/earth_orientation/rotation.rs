@@ -0,0 +1,234 @@
+//! ITRF / EME2000 rotations (equinox based, IERS conventions)
+
+use crate::prelude::{nav::Orbit, Epoch};
+
+use anise::{
+    constants::frames::{EARTH_ITRF93, EARTH_J2000},
+    math::{Matrix3, Vector3},
+};
+
+use std::f64::consts::{PI, TAU};
+
+/// Arc-second to radians
+const ARCSEC_TO_RAD: f64 = PI / 180.0 / 3600.0;
+
+/// Nominal Earth rotation rate [rad.s⁻¹]
+const EARTH_ROTATION_RATE_RAD_S: f64 = 7.292115146706979E-5;
+
+/// J2000 Julian date
+const J2000_JD: f64 = 2_451_545.0;
+
+/// IAU 1980 nutation series, truncated to its 63 main terms: multipliers of (D, M, M', F, Ω),
+/// then Δψ (sine, rate) and Δε (cosine, rate) coefficients [0.1 mas], [0.1 mas/century].
+const NUTATION_TERMS: [([f64; 5], (f64, f64), (f64, f64)); 63] = [
+    (
+        [0.0, 0.0, 0.0, 0.0, 1.0],
+        (-171996.0, -174.2),
+        (92025.0, 8.9),
+    ),
+    ([-2.0, 0.0, 0.0, 2.0, 2.0], (-13187.0, -1.6), (5736.0, -3.1)),
+    ([0.0, 0.0, 0.0, 2.0, 2.0], (-2274.0, -0.2), (977.0, -0.5)),
+    ([0.0, 0.0, 0.0, 0.0, 2.0], (2062.0, 0.2), (-895.0, 0.5)),
+    ([0.0, 1.0, 0.0, 0.0, 0.0], (1426.0, -3.4), (54.0, -0.1)),
+    ([0.0, 0.0, 1.0, 0.0, 0.0], (712.0, 0.1), (-7.0, 0.0)),
+    ([-2.0, 1.0, 0.0, 2.0, 2.0], (-517.0, 1.2), (224.0, -0.6)),
+    ([0.0, 0.0, 0.0, 2.0, 1.0], (-386.0, -0.4), (200.0, 0.0)),
+    ([0.0, 0.0, 1.0, 2.0, 2.0], (-301.0, 0.0), (129.0, -0.1)),
+    ([-2.0, -1.0, 0.0, 2.0, 2.0], (217.0, -0.5), (-95.0, 0.3)),
+    ([-2.0, 0.0, 1.0, 0.0, 0.0], (-158.0, 0.0), (0.0, 0.0)),
+    ([-2.0, 0.0, 0.0, 2.0, 1.0], (129.0, 0.1), (-70.0, 0.0)),
+    ([0.0, 0.0, -1.0, 2.0, 2.0], (123.0, 0.0), (-53.0, 0.0)),
+    ([2.0, 0.0, 0.0, 0.0, 0.0], (63.0, 0.0), (0.0, 0.0)),
+    ([0.0, 0.0, 1.0, 0.0, 1.0], (63.0, 0.1), (-33.0, 0.0)),
+    ([2.0, 0.0, -1.0, 2.0, 2.0], (-59.0, 0.0), (26.0, 0.0)),
+    ([0.0, 0.0, -1.0, 0.0, 1.0], (-58.0, -0.1), (32.0, 0.0)),
+    ([0.0, 0.0, 1.0, 2.0, 1.0], (-51.0, 0.0), (27.0, 0.0)),
+    ([-2.0, 0.0, 2.0, 0.0, 0.0], (48.0, 0.0), (0.0, 0.0)),
+    ([0.0, 0.0, -2.0, 2.0, 1.0], (46.0, 0.0), (-24.0, 0.0)),
+    ([2.0, 0.0, 0.0, 2.0, 2.0], (-38.0, 0.0), (16.0, 0.0)),
+    ([0.0, 0.0, 2.0, 2.0, 2.0], (-31.0, 0.0), (13.0, 0.0)),
+    ([0.0, 0.0, 2.0, 0.0, 0.0], (29.0, 0.0), (0.0, 0.0)),
+    ([-2.0, 0.0, 1.0, 2.0, 2.0], (29.0, 0.0), (-12.0, 0.0)),
+    ([0.0, 0.0, 0.0, 2.0, 0.0], (26.0, 0.0), (0.0, 0.0)),
+    ([-2.0, 0.0, 0.0, 2.0, 0.0], (-22.0, 0.0), (0.0, 0.0)),
+    ([0.0, 0.0, -1.0, 2.0, 1.0], (21.0, 0.0), (-10.0, 0.0)),
+    ([0.0, 2.0, 0.0, 0.0, 0.0], (17.0, -0.1), (0.0, 0.0)),
+    ([2.0, 0.0, -1.0, 0.0, 1.0], (16.0, 0.0), (-8.0, 0.0)),
+    ([-2.0, 2.0, 0.0, 2.0, 2.0], (-16.0, 0.1), (7.0, 0.0)),
+    ([0.0, 1.0, 0.0, 0.0, 1.0], (-15.0, 0.0), (9.0, 0.0)),
+    ([-2.0, 0.0, 1.0, 0.0, 1.0], (-13.0, 0.0), (7.0, 0.0)),
+    ([0.0, -1.0, 0.0, 0.0, 1.0], (-12.0, 0.0), (6.0, 0.0)),
+    ([0.0, 0.0, 2.0, -2.0, 0.0], (11.0, 0.0), (0.0, 0.0)),
+    ([2.0, 0.0, -1.0, 2.0, 1.0], (-10.0, 0.0), (5.0, 0.0)),
+    ([2.0, 0.0, 1.0, 2.0, 2.0], (-8.0, 0.0), (3.0, 0.0)),
+    ([0.0, 1.0, 0.0, 2.0, 2.0], (7.0, 0.0), (-3.0, 0.0)),
+    ([-2.0, 1.0, 1.0, 0.0, 0.0], (-7.0, 0.0), (0.0, 0.0)),
+    ([0.0, -1.0, 0.0, 2.0, 2.0], (-7.0, 0.0), (3.0, 0.0)),
+    ([2.0, 0.0, 0.0, 2.0, 1.0], (-7.0, 0.0), (3.0, 0.0)),
+    ([2.0, 0.0, 1.0, 0.0, 0.0], (6.0, 0.0), (0.0, 0.0)),
+    ([-2.0, 0.0, 2.0, 2.0, 2.0], (6.0, 0.0), (-3.0, 0.0)),
+    ([-2.0, 0.0, 1.0, 2.0, 1.0], (6.0, 0.0), (-3.0, 0.0)),
+    ([2.0, 0.0, -2.0, 0.0, 1.0], (-6.0, 0.0), (3.0, 0.0)),
+    ([2.0, 0.0, 0.0, 0.0, 1.0], (-6.0, 0.0), (3.0, 0.0)),
+    ([0.0, -1.0, 1.0, 0.0, 0.0], (5.0, 0.0), (0.0, 0.0)),
+    ([-2.0, -1.0, 0.0, 2.0, 1.0], (-5.0, 0.0), (3.0, 0.0)),
+    ([-2.0, 0.0, 0.0, 0.0, 1.0], (-5.0, 0.0), (3.0, 0.0)),
+    ([0.0, 0.0, 2.0, 2.0, 1.0], (-5.0, 0.0), (3.0, 0.0)),
+    ([-2.0, 0.0, 2.0, 0.0, 1.0], (4.0, 0.0), (0.0, 0.0)),
+    ([-2.0, 1.0, 0.0, 2.0, 1.0], (4.0, 0.0), (0.0, 0.0)),
+    ([0.0, 0.0, 1.0, -2.0, 0.0], (4.0, 0.0), (0.0, 0.0)),
+    ([-1.0, 0.0, 1.0, 0.0, 0.0], (-4.0, 0.0), (0.0, 0.0)),
+    ([-2.0, 1.0, 0.0, 0.0, 0.0], (-4.0, 0.0), (0.0, 0.0)),
+    ([1.0, 0.0, 0.0, 0.0, 0.0], (-4.0, 0.0), (0.0, 0.0)),
+    ([0.0, 0.0, 1.0, 2.0, 0.0], (3.0, 0.0), (0.0, 0.0)),
+    ([0.0, 0.0, -2.0, 2.0, 2.0], (-3.0, 0.0), (0.0, 0.0)),
+    ([-1.0, -1.0, 1.0, 0.0, 0.0], (-3.0, 0.0), (0.0, 0.0)),
+    ([0.0, 1.0, 1.0, 0.0, 0.0], (-3.0, 0.0), (0.0, 0.0)),
+    ([0.0, -1.0, 1.0, 2.0, 2.0], (-3.0, 0.0), (0.0, 0.0)),
+    ([2.0, -1.0, -1.0, 2.0, 2.0], (-3.0, 0.0), (0.0, 0.0)),
+    ([0.0, 0.0, 3.0, 2.0, 2.0], (-3.0, 0.0), (0.0, 0.0)),
+    ([2.0, -1.0, 0.0, 2.0, 2.0], (-3.0, 0.0), (0.0, 0.0)),
+];
+
+/// Rotation of the reference frame about the X axis
+fn r1(angle: f64) -> Matrix3 {
+    let (sin, cos) = angle.sin_cos();
+    Matrix3::new(1.0, 0.0, 0.0, 0.0, cos, sin, 0.0, -sin, cos)
+}
+
+/// Rotation of the reference frame about the Y axis
+fn r2(angle: f64) -> Matrix3 {
+    let (sin, cos) = angle.sin_cos();
+    Matrix3::new(cos, 0.0, -sin, 0.0, 1.0, 0.0, sin, 0.0, cos)
+}
+
+/// Rotation of the reference frame about the Z axis
+fn r3(angle: f64) -> Matrix3 {
+    let (sin, cos) = angle.sin_cos();
+    Matrix3::new(cos, sin, 0.0, -sin, cos, 0.0, 0.0, 0.0, 1.0)
+}
+
+/// Returns (nutation in longitude, true obliquity, mean obliquity, equation of the equinoxes) [rad],
+/// at this Julian century (TT) since J2000, corrected by the celestial pole offsets (dψ, dε) [arc-sec].
+fn nutation(t: f64, offsets_arcsec: (f64, f64)) -> (f64, f64, f64, f64) {
+    let t2 = t.powi(2);
+    let t3 = t.powi(3);
+
+    // fundamental arguments (D, M, M', F, Ω)
+    let args = [
+        297.85036 + 445267.111480 * t - 0.0019142 * t2 + t3 / 189474.0,
+        357.52772 + 35999.050340 * t - 0.0001603 * t2 - t3 / 300000.0,
+        134.96298 + 477198.867398 * t + 0.0086972 * t2 + t3 / 56250.0,
+        93.27191 + 483202.017538 * t - 0.0036825 * t2 + t3 / 327270.0,
+        125.04452 - 1934.136261 * t + 0.0020708 * t2 + t3 / 450000.0,
+    ]
+    .map(|deg: f64| deg.to_radians());
+
+    let (mut dpsi, mut deps) = (0.0, 0.0);
+
+    for (multipliers, (psi, psi_rate), (eps, eps_rate)) in NUTATION_TERMS.iter() {
+        let arg = multipliers
+            .iter()
+            .zip(args.iter())
+            .map(|(m, a)| m * a)
+            .sum::<f64>();
+
+        dpsi += (psi + psi_rate * t) * arg.sin();
+        deps += (eps + eps_rate * t) * arg.cos();
+    }
+
+    let dpsi = (dpsi * 1.0E-4 + offsets_arcsec.0) * ARCSEC_TO_RAD;
+    let deps = (deps * 1.0E-4 + offsets_arcsec.1) * ARCSEC_TO_RAD;
+
+    let eps0 = (84381.448 - 46.8150 * t - 0.00059 * t2 + 0.001813 * t3) * ARCSEC_TO_RAD;
+
+    // IAU 1994 equation of the equinoxes, with its complementary terms
+    let omega = args[4];
+    let eqeq = dpsi * eps0.cos()
+        + (0.00264 * omega.sin() + 0.000063 * (2.0 * omega).sin()) * ARCSEC_TO_RAD;
+
+    (dpsi, eps0 + deps, eps0, eqeq)
+}
+
+/// Returns the (precession-nutation, polar motion) matrices and the
+/// Greenwich apparent sidereal time [rad], at this [Epoch].
+fn celestial_terrestrial(
+    t: Epoch,
+    pole_arcsec: (f64, f64),
+    dut1: f64,
+    offsets_arcsec: (f64, f64),
+) -> (Matrix3, Matrix3, f64) {
+    let t_tt = (t.to_jde_tt_days() - J2000_JD) / 36525.0;
+    let d_ut1 = t.to_jde_utc_days() - J2000_JD + dut1 / 86400.0;
+    let t_ut1 = d_ut1 / 36525.0;
+
+    // IAU 1976 precession
+    let zeta =
+        (2306.2181 * t_tt + 0.30188 * t_tt.powi(2) + 0.017998 * t_tt.powi(3)) * ARCSEC_TO_RAD;
+    let z = (2306.2181 * t_tt + 1.09468 * t_tt.powi(2) + 0.018203 * t_tt.powi(3)) * ARCSEC_TO_RAD;
+    let theta =
+        (2004.3109 * t_tt - 0.42665 * t_tt.powi(2) - 0.041833 * t_tt.powi(3)) * ARCSEC_TO_RAD;
+
+    let precession = r3(-z) * r2(theta) * r3(-zeta);
+
+    // nutation
+    let (dpsi, eps, eps0, eqeq) = nutation(t_tt, offsets_arcsec);
+    let nutation = r1(-eps) * r3(-dpsi) * r1(eps0);
+
+    // IAU 1982 GMST, and equation of the equinoxes
+    let gmst_s =
+        67310.54841 + (876600.0 * 3600.0 + 8640184.812866) * t_ut1 + 0.093104 * t_ut1.powi(2)
+            - 6.2E-6 * t_ut1.powi(3);
+
+    let gast = (gmst_s.rem_euclid(86400.0) / 86400.0 * TAU + eqeq).rem_euclid(TAU);
+
+    // polar motion
+    let (xp, yp) = (pole_arcsec.0 * ARCSEC_TO_RAD, pole_arcsec.1 * ARCSEC_TO_RAD);
+    let s_prime = -47.0E-6 * t_tt * ARCSEC_TO_RAD;
+    let polar_motion = r3(-s_prime) * r2(xp) * r1(yp);
+
+    (nutation * precession, polar_motion, gast)
+}
+
+/// Rotates this Earth fixed [Orbit] to EME2000.
+pub(crate) fn itrf_to_eme2000(
+    orbit: Orbit,
+    pole_arcsec: (f64, f64),
+    dut1: f64,
+    offsets_arcsec: (f64, f64),
+) -> Orbit {
+    let (np, w, gast) = celestial_terrestrial(orbit.epoch, pole_arcsec, dut1, offsets_arcsec);
+    let omega = Vector3::new(0.0, 0.0, EARTH_ROTATION_RATE_RAD_S);
+
+    let r_pef = w * orbit.radius_km;
+    let v_pef = w * orbit.velocity_km_s;
+
+    let r_tod = r3(-gast) * r_pef;
+    let v_tod = r3(-gast) * (v_pef + omega.cross(&r_pef));
+
+    let mut inertial = orbit;
+    inertial.radius_km = np.transpose() * r_tod;
+    inertial.velocity_km_s = np.transpose() * v_tod;
+    inertial.frame = EARTH_J2000;
+    inertial
+}
+
+/// Rotates this EME2000 [Orbit] to the Earth fixed frame.
+pub(crate) fn eme2000_to_itrf(
+    orbit: Orbit,
+    pole_arcsec: (f64, f64),
+    dut1: f64,
+    offsets_arcsec: (f64, f64),
+) -> Orbit {
+    let (np, w, gast) = celestial_terrestrial(orbit.epoch, pole_arcsec, dut1, offsets_arcsec);
+    let omega = Vector3::new(0.0, 0.0, EARTH_ROTATION_RATE_RAD_S);
+
+    let r_pef = r3(gast) * np * orbit.radius_km;
+    let v_pef = r3(gast) * np * orbit.velocity_km_s - omega.cross(&r_pef);
+
+    let mut fixed = orbit;
+    fixed.radius_km = w.transpose() * r_pef;
+    fixed.velocity_km_s = w.transpose() * v_pef;
+    fixed.frame = EARTH_ITRF93;
+    fixed
+}
@@ -43,18 +43,21 @@ impl Ephemeris {
             NavFormatter::new(self.clock_drift_rate),
         )?;
 
+        // orbits are indented by 3X in V2, 4X in newer revisions
+        let indent = if version.major < 3 { "   " } else { "    " };
+
         // following standard specs
         let data_fields = &standard_specs.items;
         for i in 0..data_fields.len() {
             if let Some(value) = self.get_orbit_f64(data_fields[i].0) {
                 if i % 4 == 0 {
-                    write!(w, "\n   {}", NavFormatter::new(value))?;
+                    write!(w, "\n{}{}", indent, NavFormatter::new(value))?;
                 } else {
                     write!(w, "{}", NavFormatter::new(value))?;
                 }
             } else {
                 if i % 4 == 0 {
-                    write!(w, "\n   {}", NavFormatter::new(0.0))?;
+                    write!(w, "\n{}{}", indent, NavFormatter::new(0.0))?;
                 } else {
                     write!(w, "{}", NavFormatter::new(0.0))?;
                 }
@@ -0,0 +1,285 @@
+//! GLONASS and SBAS orbital state propagation
+use crate::prelude::{nav::Orbit, Constellation, Epoch, SV};
+
+use crate::navigation::Ephemeris;
+
+use anise::{
+    constants::frames::IAU_EARTH_FRAME,
+    math::{Vector3, Vector6},
+};
+
+#[cfg(doc)]
+use crate::bibliography::Bibliography;
+
+/// PZ-90 Earth gravitational constant (km³.s⁻²)
+const GM_KM3_S2: f64 = 398600.4418;
+
+/// PZ-90 Earth equatorial radius (km)
+const AE_KM: f64 = 6378.136;
+
+/// PZ-90 second zonal harmonic
+const J2: f64 = 1082625.75E-9;
+
+/// PZ-90 Earth rotation rate (rad.s⁻¹)
+const OMEGA_E_RAD_S: f64 = 7.2921151467E-5;
+
+/// Maximal integration step (s)
+const MAX_STEP_S: f64 = 60.0;
+
+/// Orbital state: position (km) and velocity (km/s), in ECEF.
+type State = (Vector3, Vector3);
+
+/// Returns the time derivative of this [State], in PZ-90 ECEF, according
+/// to the equations of motion defined in [Bibliography::GlonassICD] A.3.1.2.
+/// The luni-solar accelerations (km.s⁻²) are broadcasted and assumed constant
+/// over the integration interval.
+fn glonass_derivative(state: &State, acc_ls: &Vector3) -> State {
+    let (pos, vel) = state;
+
+    let r2 = pos.norm_squared();
+    let r = r2.sqrt();
+
+    let mu = GM_KM3_S2 / r2 / r;
+    let j2 = 1.5 * J2 * GM_KM3_S2 * AE_KM.powi(2) / r2.powi(2) / r;
+    let z2_r2 = 5.0 * pos[2].powi(2) / r2;
+    let omega2 = OMEGA_E_RAD_S.powi(2);
+
+    let acc = Vector3::new(
+        -mu * pos[0] - j2 * pos[0] * (1.0 - z2_r2)
+            + omega2 * pos[0]
+            + 2.0 * OMEGA_E_RAD_S * vel[1]
+            + acc_ls[0],
+        -mu * pos[1] - j2 * pos[1] * (1.0 - z2_r2) + omega2 * pos[1] - 2.0 * OMEGA_E_RAD_S * vel[0]
+            + acc_ls[1],
+        -mu * pos[2] - j2 * pos[2] * (3.0 - z2_r2) + acc_ls[2],
+    );
+
+    (*vel, acc)
+}
+
+/// Runge-Kutta (4th order) single step of dt (s)
+fn rk4_step(state: &State, acc_ls: &Vector3, dt: f64) -> State {
+    let shift = |k: &State, h: f64| (state.0 + k.0 * h, state.1 + k.1 * h);
+
+    let k1 = glonass_derivative(state, acc_ls);
+    let k2 = glonass_derivative(&shift(&k1, dt / 2.0), acc_ls);
+    let k3 = glonass_derivative(&shift(&k2, dt / 2.0), acc_ls);
+    let k4 = glonass_derivative(&shift(&k3, dt), acc_ls);
+
+    (
+        state.0 + (k1.0 + k2.0 * 2.0 + k3.0 * 2.0 + k4.0) * dt / 6.0,
+        state.1 + (k1.1 + k2.1 * 2.0 + k3.1 * 2.0 + k4.1) * dt / 6.0,
+    )
+}
+
+/// Propagates this GLONASS [State] by dt (s), by steps of [MAX_STEP_S] at most.
+fn glonass_propagation(mut state: State, acc_ls: &Vector3, dt: f64) -> State {
+    let step = MAX_STEP_S.copysign(dt);
+    let mut remaining = dt;
+
+    while remaining.abs() > 1.0E-9 {
+        let h = if remaining.abs() < MAX_STEP_S {
+            remaining
+        } else {
+            step
+        };
+        state = rk4_step(&state, acc_ls, h);
+        remaining -= h;
+    }
+
+    state
+}
+
+impl Ephemeris {
+    /// Returns broadcasted (position, velocity, acceleration) triplet,
+    /// for GLONASS and SBAS [Ephemeris], in (km, km/s, km/s²).
+    fn broadcast_state(&self) -> Option<(Vector3, Vector3, Vector3)> {
+        Some((
+            Vector3::new(
+                self.get_orbit_f64("satPosX")?,
+                self.get_orbit_f64("satPosY")?,
+                self.get_orbit_f64("satPosZ")?,
+            ),
+            Vector3::new(
+                self.get_orbit_f64("velX")?,
+                self.get_orbit_f64("velY")?,
+                self.get_orbit_f64("velZ")?,
+            ),
+            Vector3::new(
+                self.get_orbit_f64("accelX")?,
+                self.get_orbit_f64("accelY")?,
+                self.get_orbit_f64("accelZ")?,
+            ),
+        ))
+    }
+
+    /// Propagates the GLONASS or SBAS broadcasted state, to obtain the
+    /// ECEF (position, velocity) [Vector3] duplet at desired [Epoch].
+    /// - GLONASS: Runge-Kutta integration of the PZ-90 equations of motion,
+    ///   including the broadcasted luni-solar accelerations, see [Bibliography::GlonassICD].
+    /// - SBAS: constant acceleration extrapolation, see [Bibliography::RtcaDo229].
+    ///   It is only valid within [Ephemeris::validity_duration] of ToC.
+    ///
+    /// ## Input
+    /// - sv: [SV] that must be either GLONASS or SBAS
+    /// - toc: [Epoch] of publication of this frame
+    /// - epoch: desired [Epoch]
+    /// ## Returns
+    /// - (position, velocity): [Vector3] duplet, in (km, km/s)
+    pub fn integrate_position_velocity(
+        &self,
+        sv: SV,
+        toc: Epoch,
+        epoch: Epoch,
+    ) -> Option<(Vector3, Vector3)> {
+        let (pos, vel, acc) = self.broadcast_state()?;
+        let dt = (epoch - toc).to_seconds();
+
+        if sv.constellation == Constellation::Glonass {
+            Some(glonass_propagation((pos, vel), &acc, dt))
+        } else if sv.constellation.is_sbas() {
+            let max_dt = Self::validity_duration(sv.constellation)?.to_seconds();
+            if dt.abs() > max_dt {
+                return None;
+            }
+            Some((pos + vel * dt + acc * dt.powi(2) / 2.0, vel + acc * dt))
+        } else {
+            None
+        }
+    }
+
+    /// Propagates the GLONASS or SBAS broadcasted state to desired [Epoch],
+    /// expressed as ECEF [Orbit]. See [Self::integrate_position_velocity].
+    /// ## Input
+    /// - sv: [SV] that must be either GLONASS or SBAS
+    /// - toc: [Epoch] of publication of this frame
+    /// - epoch: desired [Epoch]
+    pub fn integrate_orbit(&self, sv: SV, toc: Epoch, epoch: Epoch) -> Option<Orbit> {
+        let (pos, vel) = self.integrate_position_velocity(sv, toc, epoch)?;
+        Some(Orbit::from_cartesian_pos_vel(
+            Vector6::new(pos[0], pos[1], pos[2], vel[0], vel[1], vel[2]),
+            epoch,
+            IAU_EARTH_FRAME,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::glonass_propagation;
+    use crate::{
+        navigation::{Ephemeris, OrbitItem},
+        prelude::{Epoch, SV},
+    };
+    use anise::math::Vector3;
+    use std::str::FromStr;
+
+    #[test]
+    fn glonass_icd_example() {
+        // GLONASS ICD (2008) A.3.1.2 example:
+        // from t_e = 11700s to t_i = 12300s (MT)
+        let state = (
+            Vector3::new(7003.008789, -12206.626953, 21280.765625),
+            Vector3::new(0.7835417, 2.8042530, 1.3525150),
+        );
+        let acc_ls = Vector3::new(0.0, 1.7E-9, -5.41E-9);
+
+        let (pos, vel) = glonass_propagation(state, &acc_ls, 600.0);
+
+        for (value, expected) in [
+            (pos[0], 7523.174819),
+            (pos[1], -10506.961965),
+            (pos[2], 21999.239413),
+        ] {
+            assert!(
+                (value - expected).abs() < 1.0E-3,
+                "position error {} km",
+                value - expected
+            );
+        }
+
+        for (value, expected) in [
+            (vel[0], 0.950126007),
+            (vel[1], 2.855687825),
+            (vel[2], 1.040679862),
+        ] {
+            assert!(
+                (value - expected).abs() < 1.0E-5,
+                "velocity error {} km/s",
+                value - expected
+            );
+        }
+
+        // backwards propagation is reciprocal
+        let (pos_back, vel_back) = glonass_propagation((pos, vel), &acc_ls, -600.0);
+        assert!((pos_back - state.0).norm() < 1.0E-6);
+        assert!((vel_back - state.1).norm() < 1.0E-9);
+    }
+
+    #[test]
+    fn glonass_sbas_integration() {
+        let toc = Epoch::from_str("2021-01-01T00:15:00 UTC").unwrap();
+
+        let mut eph = Ephemeris::default();
+        for (key, value) in [
+            ("satPosX", 7003.008789),
+            ("satPosY", -12206.626953),
+            ("satPosZ", 21280.765625),
+            ("velX", 0.7835417),
+            ("velY", 2.8042530),
+            ("velZ", 1.3525150),
+            ("accelX", 0.0),
+            ("accelY", 1.7E-9),
+            ("accelZ", -5.41E-9),
+        ] {
+            eph.orbits.insert(key.to_string(), OrbitItem::F64(value));
+        }
+
+        let r01 = SV::from_str("R01").unwrap();
+
+        // null propagation
+        let (pos, vel) = eph.integrate_position_velocity(r01, toc, toc).unwrap();
+        assert_eq!(pos, Vector3::new(7003.008789, -12206.626953, 21280.765625));
+        assert_eq!(vel, Vector3::new(0.7835417, 2.8042530, 1.3525150));
+
+        let (pos_600s, _) = eph
+            .integrate_position_velocity(r01, toc, toc + hifitime::Unit::Second * 600)
+            .unwrap();
+        assert!(
+            (pos_600s - Vector3::new(7523.174819, -10506.961965, 21999.239413)).norm() < 1.0E-3
+        );
+
+        // SBAS: constant acceleration
+        let s23 = SV::from_str("S23").unwrap();
+        let (pos, vel) = eph
+            .integrate_position_velocity(s23, toc, toc + hifitime::Unit::Second * 10)
+            .unwrap();
+        assert!((pos[0] - (7003.008789 + 7.835417)).abs() < 1.0E-9);
+        assert!((vel[2] - (1.3525150 - 5.41E-8)).abs() < 1.0E-12);
+
+        // SBAS: beyond the GEO navigation message time-out
+        assert!(eph
+            .integrate_position_velocity(s23, toc, toc + hifitime::Unit::Second * 600)
+            .is_none());
+
+        // same propagation, expressed as Orbit
+        let orbit = eph
+            .integrate_orbit(r01, toc, toc + hifitime::Unit::Second * 600)
+            .unwrap();
+        assert_eq!(orbit.epoch, toc + hifitime::Unit::Second * 600);
+        assert!((orbit.radius_km - pos_600s).norm() < 1.0E-9);
+
+        // kepler2position returns the broadcasted state
+        let orbit = eph
+            .kepler2position(r01, toc + hifitime::Unit::Second * 600)
+            .unwrap();
+        assert_eq!(
+            orbit.radius_km,
+            Vector3::new(7003.008789, -12206.626953, 21280.765625)
+        );
+
+        // not applicable to other constellations
+        let g01 = SV::from_str("G01").unwrap();
+        assert!(eph.integrate_position_velocity(g01, toc, toc).is_none());
+    }
+}
@@ -150,6 +150,8 @@ impl Ephemeris {
     /// Returns [SV] [Orbit]al state at t [Epoch].
     /// Self must be correctly selected from navigation record.
     /// See [Bibliography::AsceAppendix3], [Bibliography::JLe19] and [Bibliography::BeiDouICD]
+    /// GLONASS and SBAS: this is the broadcasted state, only valid at ToC.
+    /// Use [Self::integrate_position_velocity] to propagate it.
     /// ## Input
     /// - sv: [SV] satellite identity
     /// - epoch: desired [Epoch]
@@ -160,7 +162,6 @@ impl Ephemeris {
                 self.get_orbit_f64("satPosY")?,
                 self.get_orbit_f64("satPosZ")?,
             );
-            // TODO: velocity + integration
             Some(Orbit::from_position(
                 x_km,
                 y_km,
@@ -188,11 +189,8 @@ impl Ephemeris {
     /// - (position, velocity): [Vector3] duplet, in (km, km/s)
     /// See [Bibliography::AsceAppendix3], [Bibliography::JLe19] and [Bibliography::BeiDouICD]
     pub fn kepler2position_velocity(&self, sv: SV, epoch: Epoch) -> Option<(Vector3, Vector3)> {
-        // In gloass and SBAS scenarios,
-        // we only need to pick up the values from the record.
-        // NB: this is incorrect, it requires an integration process
-        //    that has yet to be understood and implemented.
-        //    SBAS navigation is not supported yet anyway
+        // Broadcasted GLONASS and SBAS state, only valid at ToC.
+        // Refer to [Self::integrate_position_velocity].
         if sv.constellation.is_sbas() || sv.constellation == Constellation::Glonass {
             let (x_km, y_km, z_km) = (
                 self.get_orbit_f64("satPosX")?,
@@ -20,10 +20,16 @@ use log::error;
 #[cfg_attr(docsrs, doc(cfg(feature = "nav")))]
 pub mod kepler;
 
+#[cfg(feature = "nav")]
+mod integration;
+
 #[cfg(feature = "nav")]
 use crate::prelude::nav::Almanac;
 
-use crate::prelude::{Constellation, Duration, Epoch, TimeScale, SV};
+use crate::{
+    navigation::NavMessageType,
+    prelude::{Constellation, Duration, Epoch, TimeScale, Version, SV},
+};
 
 #[cfg(feature = "nav")]
 use anise::{
@@ -307,6 +313,45 @@ impl Ephemeris {
         self.with_orbit("week", OrbitItem::from(week))
     }
 
+    /// Creates new [Ephemeris] from these named orbital values, following the
+    /// NAV standards of this revision and [NavMessageType].
+    /// Values that this revision does not describe are dropped.
+    #[cfg(any(feature = "binex", feature = "rtcm"))]
+    pub(crate) fn with_standard_orbits(
+        &self,
+        constellation: Constellation,
+        version: Version,
+        msgtype: NavMessageType,
+        values: &[(&str, f64)],
+    ) -> Self {
+        let mut s = self.clone();
+
+        let constellation = if constellation.is_sbas() {
+            Constellation::SBAS
+        } else {
+            constellation
+        };
+
+        if let Some(specs) = orbits::closest_nav_standards(constellation, version, msgtype) {
+            for (name, type_str) in specs.items.iter() {
+                let value = values
+                    .iter()
+                    .find_map(|(k, v)| if k == name { Some(*v) } else { None });
+
+                if let Some(value) = value {
+                    // same interpretation as our parser
+                    let value = format!("{:E}", value);
+                    if let Ok(item) =
+                        OrbitItem::new(name, type_str, &value, &msgtype, constellation)
+                    {
+                        s.orbits.insert(name.to_string(), item);
+                    }
+                }
+            }
+        }
+        s
+    }
+
     /// Calculates Clock correction for [SV] at [Epoch] based on [Self]
     /// and ToC [Epoch] of publication of [Self] from the free running clock.
     pub fn clock_correction(
@@ -388,13 +433,10 @@ impl Ephemeris {
             Constellation::Glonass => Some(Duration::from_seconds(1800.0)),
             c => {
                 if c.is_sbas() {
-                    // Tolerate one publication per day.
-                    // Typical RINEX apps will load one set per 24 hr.
-                    // GEO Orbits are very special, with a single entry per day.
-                    // Therefore, in typical RINEX apps, we will have one entry for every day.
-                    // GEO Ephemerides cannot be handled like other Ephemerides anyway, they require
-                    // a complete different logic and calculations
-                    Some(Duration::from_days(1.0))
+                    // GEO navigation message time-out (RTCA DO-229, en route to NPA):
+                    // the broadcasted state is extrapolated with constant acceleration,
+                    // which does not hold for longer periods.
+                    Some(Duration::from_seconds(360.0))
                 } else {
                     None
                 }
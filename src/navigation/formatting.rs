@@ -5,8 +5,8 @@ use std::io::{BufWriter, Write};
 use crate::{
     epoch::epoch_decompose as epoch_decomposition,
     error::FormattingError,
-    navigation::{NavFrame, NavFrameType, NavKey, Record},
-    prelude::{Constellation, Header},
+    navigation::{IonosphereModel, NavFrame, NavFrameType, NavKey, Record},
+    prelude::{Constellation, Epoch, Header},
 };
 
 pub(crate) struct NavFormatter {
@@ -118,54 +118,80 @@ fn format_epoch_v2v3<W: Write>(
     }
 }
 
+/// Formats the epoch that starts the body of ION, STO and EOP V4 frames
+pub(crate) fn format_frame_epoch_v4(t: Epoch) -> String {
+    let (yyyy, m, d, hh, mm, ss, _) = epoch_decomposition(t);
+    format!(
+        "    {:04} {:02} {:02} {:02} {:02} {:02}",
+        yyyy, m, d, hh, mm, ss
+    )
+}
+
 fn format_epoch_v4<W: Write>(w: &mut BufWriter<W>, k: &NavKey) -> std::io::Result<()> {
-    let (yyyy, m, d, hh, mm, ss, _) = epoch_decomposition(k.epoch);
     match k.frmtype {
         NavFrameType::Ephemeris => {
+            let (yyyy, m, d, hh, mm, ss, _) = epoch_decomposition(k.epoch);
             write!(
                 w,
                 "> EPH {:x} {}\n{:x} {:04} {:02} {:02} {:02} {:02} {:02}",
                 k.sv, k.msgtype, k.sv, yyyy, m, d, hh, mm, ss
             )
         },
-        NavFrameType::IonosphereModel => {
-            write!(
-                w,
-                "> ION {:x} {}\n        {:04} {:02} {:02} {:02} {:02} {:02}",
-                k.sv, k.msgtype, yyyy, m, d, hh, mm, ss
-            )
-        },
-        NavFrameType::SystemTimeOffset => {
-            write!(
-                w,
-                "> STO {:x} {}\n        {:04} {:02} {:02} {:02} {:02} {:02}",
-                k.sv, k.msgtype, yyyy, m, d, hh, mm, ss
-            )
+        // epoch is part of the frame body
+        NavFrameType::IonosphereModel => writeln!(w, "> ION {:x} {}", k.sv, k.msgtype),
+        NavFrameType::SystemTimeOffset => writeln!(w, "> STO {:x} {}", k.sv, k.msgtype),
+        NavFrameType::EarthOrientation => writeln!(w, "> EOP {:x} {}", k.sv, k.msgtype),
+    }
+}
+
+/// Latches this V4 system time offset or ionosphere model [NavFrame] into [Header].
+/// This is used when formatting to V2/V3 which only describe them in the header.
+/// Returns false if this [NavFrame] may not be described by the header.
+pub(crate) fn v2v3_latch_frame(header: &mut Header, k: &NavKey, frame: &NavFrame) -> bool {
+    match frame {
+        NavFrame::STO(sto) => {
+            let nav = header.nav.get_or_insert_with(Default::default);
+            if let Some(offset) = nav
+                .time_offsets
+                .iter_mut()
+                .find(|to| to.lhs == sto.lhs && to.rhs == sto.rhs)
+            {
+                // most recent publication prevails
+                if offset.t_ref < sto.t_ref {
+                    *offset = sto.clone();
+                }
+            } else {
+                nav.add_time_offset(sto.clone());
+            }
+            true
         },
-        NavFrameType::EarthOrientation => {
-            write!(
-                w,
-                "> EOP {:x} {}\n        {:04} {:02} {:02} {:02} {:02} {:02}",
-                k.sv, k.msgtype, yyyy, m, d, hh, mm, ss
-            )
+        NavFrame::ION(model) => {
+            // chronological order: most recent publication prevails
+            header.ionod_corrections.insert(k.sv.constellation, *model);
+            true
         },
+        _ => false,
     }
 }
 
+/// Returns a copy of this [Header] where the system time offsets and
+/// ionosphere models, described by V4 frames in the [Record], have been latched.
+/// This is used when formatting to V2/V3 which only describe them in the header.
+pub(crate) fn v2v3_header(header: &Header, rec: &Record) -> Header {
+    let mut header = header.clone();
+
+    for (k, frame) in rec.iter() {
+        v2v3_latch_frame(&mut header, k, frame);
+    }
+
+    header
+}
+
 pub fn format<W: Write>(
     writer: &mut BufWriter<W>,
     rec: &Record,
     header: &Header,
 ) -> Result<(), FormattingError> {
-    let version = header.version;
-
-    let v2 = version.major < 3;
-    let v4 = version.major > 3;
-
-    let file_constell = header
-        .constellation
-        .ok_or(FormattingError::NoConstellationDefinition)?;
-
     // in chronological order
     for epoch in rec.iter().map(|(k, _v)| k.epoch).unique().sorted() {
         // per sorted constellations
@@ -213,18 +239,7 @@ pub fn format<W: Write>(
                         .filter(|(k, _v)| k.epoch == epoch && k.sv == sv && k.frmtype == frmtype)
                         .reduce(|k, _| k)
                     {
-                        // format epoch
-                        if v4 {
-                            format_epoch_v4(writer, k)?;
-                        } else {
-                            format_epoch_v2v3(writer, k, v2, &file_constell)?;
-                        }
-
-                        // format entry
-                        match v {
-                            NavFrame::EPH(eph) => eph.format(writer, k.sv, version, k.msgtype)?,
-                            _ => {},
-                        };
+                        format_frame(writer, k, v, header)?;
                     }
                 }
             }
@@ -233,6 +248,49 @@ pub fn format<W: Write>(
     Ok(())
 }
 
+/// Formats a single [NavFrame] into [BufWriter].
+/// V2/V3: only ephemeris frames exist in the file body, other frames are not formatted.
+pub(crate) fn format_frame<W: Write>(
+    writer: &mut BufWriter<W>,
+    k: &NavKey,
+    v: &NavFrame,
+    header: &Header,
+) -> Result<(), FormattingError> {
+    let version = header.version;
+
+    let v2 = version.major < 3;
+    let v4 = version.major > 3;
+
+    let file_constell = header
+        .constellation
+        .ok_or(FormattingError::NoConstellationDefinition)?;
+
+    if !v4 && k.frmtype != NavFrameType::Ephemeris {
+        return Ok(());
+    }
+
+    // format epoch
+    if v4 {
+        format_epoch_v4(writer, k)?;
+    } else {
+        format_epoch_v2v3(writer, k, v2, &file_constell)?;
+    }
+
+    // format entry
+    match v {
+        NavFrame::EPH(eph) => eph.format(writer, k.sv, version, k.msgtype)?,
+        NavFrame::STO(sto) => sto.format_v4(writer)?,
+        NavFrame::EOP(eop) => eop.format_v4(writer, k.epoch)?,
+        NavFrame::ION(ion) => match ion {
+            IonosphereModel::Klobuchar(kb) => kb.format_v4(writer, k.epoch)?,
+            IonosphereModel::NequickG(ng) => ng.format_v4(writer, k.epoch)?,
+            IonosphereModel::Bdgim(bd) => bd.format_v4(writer, k.epoch)?,
+        },
+    };
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
 
@@ -351,11 +409,7 @@ G01 2023 03 12 00 00 00"
 
         let utf8_ascii = inner.to_ascii_utf8();
 
-        assert_eq!(
-            &utf8_ascii,
-            "> ION G12 LNAV
-        2023 03 12 00 08 54"
-        );
+        assert_eq!(&utf8_ascii, "> ION G12 LNAV\n");
     }
 
     #[test]
@@ -376,11 +430,7 @@ G01 2023 03 12 00 00 00"
 
         let utf8_ascii = inner.to_ascii_utf8();
 
-        assert_eq!(
-            &utf8_ascii,
-            "> STO C21 CNVX
-        2023 03 12 00 20 00"
-        );
+        assert_eq!(&utf8_ascii, "> STO C21 CNVX\n");
     }
 
     #[test]
@@ -401,10 +451,6 @@ G01 2023 03 12 00 00 00"
 
         let utf8_ascii = inner.to_ascii_utf8();
 
-        assert_eq!(
-            &utf8_ascii,
-            "> EOP G27 CNVX
-        2023 03 14 16 51 12"
-        );
+        assert_eq!(&utf8_ascii, "> EOP G27 CNVX\n");
     }
 }
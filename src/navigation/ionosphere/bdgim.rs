@@ -1,9 +1,155 @@
 use crate::{
+    carrier::Carrier,
     epoch::parse_in_timescale as parse_epoch_in_timescale,
+    error::FormattingError,
+    navigation::formatting::{format_frame_epoch_v4, NavFormatter},
     prelude::{Epoch, ParsingError, TimeScale},
 };
 
-use std::str::FromStr;
+use std::{
+    f64::consts::{FRAC_PI_2, PI},
+    io::{BufWriter, Write},
+    str::FromStr,
+};
+
+/// Earth radius [km]
+const EARTH_RADIUS_KM: f64 = 6378.0;
+
+/// Thin shell height [km]
+const SHELL_HEIGHT_KM: f64 = 400.0;
+
+/// Geomagnetic North pole (latitude, longitude) in decimal degrees
+const GEOMAGNETIC_POLE_DDEG: (f64, f64) = (80.27, -72.58);
+
+/// (n, m) degree and order of the broadcast terms
+const BROADCAST_TERMS: [(u8, i8); 9] = [
+    (0, 0),
+    (1, 0),
+    (1, 1),
+    (1, -1),
+    (2, 0),
+    (2, 1),
+    (2, -1),
+    (2, 2),
+    (2, -2),
+];
+
+/// (n, m) degree and order of the non broadcast terms
+const NON_BROADCAST_TERMS: [(u8, i8); 17] = [
+    (3, 0),
+    (3, 1),
+    (3, -1),
+    (3, 2),
+    (3, -2),
+    (3, 3),
+    (3, -3),
+    (4, 0),
+    (4, 1),
+    (4, -1),
+    (4, 2),
+    (4, -2),
+    (5, 0),
+    (5, 1),
+    (5, -1),
+    (5, 2),
+    (5, -2),
+];
+
+/// Periods [days] of the non broadcast coefficients prediction
+/// (the first term is constant).
+const NON_BROADCAST_PERIODS_DAYS: [f64; 12] = [
+    1.0, 0.5, 0.33, 14.6, 27.0, 121.6, 182.62, 365.25, 4028.71, 2014.36, 1342.905, 1007.18,
+];
+
+/// Normalized associated Legendre function of degree n and order m
+fn legendre(n: u8, m: u8, x: f64) -> f64 {
+    let (n, m) = (n as i32, m as i32);
+
+    // P(m, m)
+    let mut pmm = 1.0;
+    for i in 1..=m {
+        pmm *= (2 * i - 1) as f64 * (1.0 - x.powi(2)).sqrt();
+    }
+
+    let p = if n == m {
+        pmm
+    } else {
+        let mut p_2 = pmm;
+        let mut p_1 = x * (2 * m + 1) as f64 * pmm;
+
+        for l in (m + 2)..=n {
+            let p = ((2 * l - 1) as f64 * x * p_1 - (l + m - 1) as f64 * p_2) / (l - m) as f64;
+            p_2 = p_1;
+            p_1 = p;
+        }
+
+        p_1
+    };
+
+    let factorial = |k: i32| (1..=k).map(|i| i as f64).product::<f64>();
+    let delta = if m == 0 { 1.0 } else { 2.0 };
+
+    p * (factorial(n - m) * (2 * n + 1) as f64 * delta / factorial(n + m)).sqrt()
+}
+
+/// Evaluates one spherical harmonic term at this (latitude, longitude) [rad]
+fn harmonic((n, m): (u8, i8), lat_rad: f64, long_rad: f64) -> f64 {
+    let p = legendre(n, m.unsigned_abs(), lat_rad.sin());
+
+    if m >= 0 {
+        p * (m as f64 * long_rad).cos()
+    } else {
+        p * (-m as f64 * long_rad).sin()
+    }
+}
+
+/// Converts geographic (latitude, longitude) [rad] to geomagnetic coordinates
+fn geomagnetic(lat_rad: f64, long_rad: f64) -> (f64, f64) {
+    let (phi_p, lambda_p) = (
+        GEOMAGNETIC_POLE_DDEG.0.to_radians(),
+        GEOMAGNETIC_POLE_DDEG.1.to_radians(),
+    );
+
+    let sin_phi_m =
+        phi_p.sin() * lat_rad.sin() + phi_p.cos() * lat_rad.cos() * (long_rad - lambda_p).cos();
+
+    let lambda_m = (lat_rad.cos() * (long_rad - lambda_p).sin() * phi_p.cos())
+        .atan2(phi_p.sin() * sin_phi_m - lat_rad.sin());
+
+    (sin_phi_m.asin(), lambda_m)
+}
+
+/// BDGIM non broadcast coefficients, as tabulated in the BDS-SIS-ICD (B1C/B2a).
+/// These coefficients are not distributed with this crate: when left to zero,
+/// only the broadcast terms contribute to the evaluation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BdNonBroadcast {
+    /// Cosine coefficients, for each period (the first being constant)
+    /// and each non broadcast term, in TEC unit.
+    pub a: [[f64; 17]; 13],
+    /// Sine coefficients, for each period (the first being unused)
+    /// and each non broadcast term, in TEC unit.
+    pub b: [[f64; 17]; 13],
+}
+
+impl BdNonBroadcast {
+    /// Predicts the 17 non broadcast coefficients (TEC unit) at this [Epoch],
+    /// which should be the publication [Epoch] of the [BdModel].
+    pub fn predict(&self, t_p: Epoch) -> [f64; 17] {
+        let mjd = t_p.to_mjd_utc_days();
+        let mut beta = self.a[0];
+
+        for (k, period) in NON_BROADCAST_PERIODS_DAYS.iter().enumerate() {
+            let (sin, cos) = (2.0 * PI * mjd / period).sin_cos();
+
+            for (j, beta) in beta.iter_mut().enumerate() {
+                *beta += self.a[k + 1][j] * cos + self.b[k + 1][j] * sin;
+            }
+        }
+
+        beta
+    }
+}
 
 /// BDGIM Model payload
 #[derive(Debug, Copy, Clone, Default, PartialEq, PartialOrd)]
@@ -58,4 +204,131 @@ impl BdModel {
 
         Ok((epoch, Self { alpha }))
     }
+
+    /// Ionospheric pierce point (latitude, longitude) [rad]
+    fn pierce_point(
+        rx_lat_long_ddeg: (f64, f64),
+        azimuth_rad: f64,
+        elevation_rad: f64,
+    ) -> (f64, f64) {
+        let phi_u = rx_lat_long_ddeg.0.to_radians();
+        let lambda_u = rx_lat_long_ddeg.1.to_radians();
+
+        let psi = FRAC_PI_2
+            - elevation_rad
+            - (EARTH_RADIUS_KM / (EARTH_RADIUS_KM + SHELL_HEIGHT_KM) * elevation_rad.cos()).asin();
+
+        let phi = (phi_u.sin() * psi.cos() + phi_u.cos() * psi.sin() * azimuth_rad.cos()).asin();
+
+        let lambda = lambda_u
+            + (psi.sin() * azimuth_rad.sin() * phi_u.cos())
+                .atan2(psi.cos() - phi_u.sin() * phi.sin());
+
+        (phi, lambda)
+    }
+
+    /// Evaluates the vertical TEC [TECu] at this location.
+    /// ## Inputs
+    /// - t: [Epoch] of evaluation
+    /// - beta: non broadcast coefficients, see [BdNonBroadcast::predict]
+    /// - lat_long_ddeg: geographic (latitude, longitude) in decimal degrees
+    pub fn vertical_tec(&self, t: Epoch, beta: &[f64; 17], lat_long_ddeg: (f64, f64)) -> f64 {
+        let (phi_m, lambda_m) =
+            geomagnetic(lat_long_ddeg.0.to_radians(), lat_long_ddeg.1.to_radians());
+
+        // mean subsolar point, in the geomagnetic frame
+        let (_, nanos) = t.to_time_scale(TimeScale::BDT).to_time_of_week();
+        let t_s = (nanos as f64 * 1.0E-9).rem_euclid(86400.0);
+        let (_, sun_m) = geomagnetic(0.0, PI * (1.0 - 2.0 * t_s / 86400.0));
+
+        let lambda = lambda_m - sun_m;
+
+        let alpha = [
+            self.alpha.0,
+            self.alpha.1,
+            self.alpha.2,
+            self.alpha.3,
+            self.alpha.4,
+            self.alpha.5,
+            self.alpha.6,
+            self.alpha.7,
+            self.alpha.8,
+        ];
+
+        let a0 = NON_BROADCAST_TERMS
+            .iter()
+            .zip(beta.iter())
+            .map(|(term, beta)| beta * harmonic(*term, phi_m, lambda))
+            .sum::<f64>();
+
+        let vtec = BROADCAST_TERMS
+            .iter()
+            .zip(alpha.iter())
+            .map(|(term, alpha)| alpha * harmonic(*term, phi_m, lambda))
+            .sum::<f64>();
+
+        (a0 + vtec).max(0.0)
+    }
+
+    /// Evaluates the slant ionospheric delay [m] described by this [BdModel].
+    /// ## Inputs
+    /// - t: [Epoch] of evaluation
+    /// - beta: non broadcast coefficients, see [BdNonBroadcast::predict]
+    /// - rx_lat_long_ddeg: receiver geodetic (latitude, longitude) in decimal degrees
+    /// - azimuth_deg: [SV] azimuth in degrees
+    /// - elevation_deg: [SV] elevation in degrees
+    /// - carrier: signal [Carrier]
+    pub fn meters_delay(
+        &self,
+        t: Epoch,
+        beta: &[f64; 17],
+        rx_lat_long_ddeg: (f64, f64),
+        azimuth_deg: f64,
+        elevation_deg: f64,
+        carrier: Carrier,
+    ) -> f64 {
+        let elev = elevation_deg.to_radians();
+        let (phi, lambda) = Self::pierce_point(rx_lat_long_ddeg, azimuth_deg.to_radians(), elev);
+
+        let vtec = self.vertical_tec(t, beta, (phi.to_degrees(), lambda.to_degrees()));
+
+        let ratio = EARTH_RADIUS_KM / (EARTH_RADIUS_KM + SHELL_HEIGHT_KM) * elev.cos();
+        let mapping = 1.0 / (1.0 - ratio.powi(2)).sqrt();
+
+        40.28E16 / carrier.frequency().powi(2) * mapping * vtec
+    }
+
+    /// Format this [BdModel] as V4 frame body, published at [Epoch].
+    pub(crate) fn format_v4<W: Write>(
+        &self,
+        w: &mut BufWriter<W>,
+        t: Epoch,
+    ) -> Result<(), FormattingError> {
+        writeln!(
+            w,
+            "{}{}{}{}",
+            format_frame_epoch_v4(t),
+            NavFormatter::new(self.alpha.0),
+            NavFormatter::new(self.alpha.1),
+            NavFormatter::new(self.alpha.2),
+        )?;
+
+        writeln!(
+            w,
+            "    {}{}{}{}",
+            NavFormatter::new(self.alpha.3),
+            NavFormatter::new(self.alpha.4),
+            NavFormatter::new(self.alpha.5),
+            NavFormatter::new(self.alpha.6),
+        )?;
+
+        writeln!(
+            w,
+            "    {}{}",
+            NavFormatter::new(self.alpha.7),
+            NavFormatter::new(self.alpha.8),
+        )?;
+
+        Ok(())
+    }
 }
@@ -1,16 +1,31 @@
 use crate::{
+    carrier::Carrier,
     epoch::parse_in_timescale as parse_epoch_in_timescale,
     error::FormattingError,
     fmt_rinex,
-    navigation::formatting::NavFormatter,
+    navigation::formatting::{format_frame_epoch_v4, NavFormatter},
     prelude::{Constellation, Epoch, ParsingError, TimeScale},
 };
 
 use std::{
+    f64::consts::{FRAC_PI_2, PI},
     io::{BufWriter, Write},
     str::FromStr,
 };
 
+/// Earth radius [km] used in the thin shell projection
+const EARTH_RADIUS_KM: f64 = 6378.0;
+
+/// BDS thin shell height [km]
+const BDS_SHELL_HEIGHT_KM: f64 = 375.0;
+
+const SPEED_OF_LIGHT_M_S: f64 = 299_792_458.0;
+
+/// Evaluates the Klobuchar cubic polynomial
+fn polynomial(coefs: (f64, f64, f64, f64), x: f64) -> f64 {
+    coefs.0 + coefs.1 * x + coefs.2 * x.powi(2) + coefs.3 * x.powi(3)
+}
+
 /// Klobuchar Parameters region
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
@@ -111,69 +126,155 @@ impl KbModel {
         ))
     }
 
-    // // Converts [KbModel] to meters of delay
-    // pub(crate) fn meters_delay(
-    //     &self,
-    //     t: Epoch,
-    //     e: f64,
-    //     a: f64,
-    //     h_km: f64,
-    //     user_lat_ddeg: f64,
-    //     user_lon_ddeg: f64,
-    //     carrier: Carrier,
-    // ) -> f64 {
-    //     const PHI_P: f64 = 78.3;
-    //     const R_EARTH: f64 = 6378.0;
-    //     const LAMBDA_P: f64 = 291.0;
-    //     const L1_F: f64 = 1575.42E6;
-
-    //     let fract = R_EARTH / (R_EARTH + h_km);
-    //     let phi_u = user_lat_ddeg.to_radians();
-    //     let lambda_u = user_lon_ddeg.to_radians();
-
-    //     let t_gps = t.to_duration_in_time_scale(TimeScale::GPST).to_seconds();
-    //     let psi = PI / 2.0 - e - (fract * e.cos()).asin();
-    //     let phi_i = (phi_u.sin() * psi.cos() + phi_u.cos() * psi.sin() * a.cos()).asin();
-    //     let lambda_i = lambda_u + a.sin() * psi / phi_i.cos();
-    //     let phi_m = (phi_i.sin() * PHI_P.sin()
-    //         + phi_i.cos() * PHI_P.cos() * (lambda_i - LAMBDA_P).cos())
-    //     .asin();
-
-    //     let mut t_s = 43.2E3 * lambda_i / PI + t_gps;
-    //     if t_s > 86.4E3 {
-    //         t_s -= 86.4E3;
-    //     } else if t_s < 0.0 {
-    //         t_s += 86.4E3;
-    //     }
-
-    //     let mut a_i = self.alpha.0 * (phi_m / PI).powi(0)
-    //         + self.alpha.1 * (phi_m / PI).powi(1)
-    //         + self.alpha.2 * (phi_m / PI).powi(2)
-    //         + self.alpha.3 * (phi_m / PI).powi(3);
-    //     if a_i < 0.0 {
-    //         a_i = 0.0_f64;
-    //     }
-    //     let mut p_i = self.beta.0 * (phi_m / PI).powi(0)
-    //         + self.beta.1 * (phi_m / PI).powi(1)
-    //         + self.beta.2 * (phi_m / PI).powi(2)
-    //         + self.beta.3 * (phi_m / PI).powi(3);
-    //     if p_i < 72.0E3 {
-    //         p_i = 72.0E3;
-    //     }
-
-    //     let x_i = 2.0 * PI * (t_s - 50400.0) / p_i;
-    //     let f = 1.0 / ((1.0 - fract * e.cos()).powi(2)).sqrt();
-    //     let i_1 = match x_i < PI / 2.0 {
-    //         true => 5.0 * 10E-9 + a_i * x_i.cos(),
-    //         false => f * 5.0 * 10E-9,
-    //     };
-
-    //     if carrier == Carrier::L1 {
-    //         i_1
-    //     } else {
-    //         i_1 * (L1_F / carrier.frequency()).powi(2)
-    //     }
-    // }
+    /// Evaluates the slant ionospheric delay [m] described by this [KbModel].
+    /// ## Inputs
+    /// - t: [Epoch] of evaluation
+    /// - constellation: [Constellation] that published this model.
+    ///   [Constellation::BeiDou] selects the BDS variant (375 km shell, geographic latitude),
+    ///   any other selects the GPS algorithm (IS-GPS-200, geomagnetic latitude).
+    /// - rx_lat_long_ddeg: receiver geodetic (latitude, longitude) in decimal degrees
+    /// - azimuth_deg: [SV] azimuth in degrees
+    /// - elevation_deg: [SV] elevation in degrees
+    /// - carrier: signal [Carrier], delay is scaled from the model reference
+    ///   frequency (L1 or B1I).
+    pub fn meters_delay(
+        &self,
+        t: Epoch,
+        constellation: Constellation,
+        rx_lat_long_ddeg: (f64, f64),
+        azimuth_deg: f64,
+        elevation_deg: f64,
+        carrier: Carrier,
+    ) -> f64 {
+        let (seconds, reference) = if constellation == Constellation::BeiDou {
+            (
+                self.bds_seconds_delay(t, rx_lat_long_ddeg, azimuth_deg, elevation_deg),
+                Carrier::B1,
+            )
+        } else {
+            (
+                self.gps_seconds_delay(t, rx_lat_long_ddeg, azimuth_deg, elevation_deg),
+                Carrier::L1,
+            )
+        };
+
+        let delay = seconds * SPEED_OF_LIGHT_M_S;
+        delay * (reference.frequency() / carrier.frequency()).powi(2)
+    }
+
+    /// IS-GPS-200 (20.3.3.5.2.5) slant delay [s] on L1,
+    /// where angles are expressed in semi-circles.
+    fn gps_seconds_delay(
+        &self,
+        t: Epoch,
+        rx_lat_long_ddeg: (f64, f64),
+        azimuth_deg: f64,
+        elevation_deg: f64,
+    ) -> f64 {
+        let e = elevation_deg / 180.0;
+        let a = azimuth_deg.to_radians();
+        let phi_u = rx_lat_long_ddeg.0 / 180.0;
+        let lambda_u = rx_lat_long_ddeg.1 / 180.0;
+
+        // earth centered angle
+        let psi = 0.0137 / (e + 0.11) - 0.022;
+
+        // ionospheric pierce point
+        let phi_i = (phi_u + psi * a.cos()).clamp(-0.416, 0.416);
+        let lambda_i = lambda_u + psi * a.sin() / (phi_i * PI).cos();
+
+        // geomagnetic latitude
+        let phi_m = phi_i + 0.064 * ((lambda_i - 1.617) * PI).cos();
+
+        // local time at the pierce point
+        let (_, nanos) = t.to_time_scale(TimeScale::GPST).to_time_of_week();
+        let t_s = (nanos as f64 * 1.0E-9 + 43.2E3 * lambda_i).rem_euclid(86.4E3);
+
+        // obliquity factor
+        let f = 1.0 + 16.0 * (0.53 - e).powi(3);
+
+        let amp = polynomial(self.alpha, phi_m).max(0.0);
+        let per = polynomial(self.beta, phi_m).max(72.0E3);
+        let x = 2.0 * PI * (t_s - 50.4E3) / per;
+
+        if x.abs() < 1.57 {
+            f * (5.0E-9 + amp * (1.0 - x.powi(2) / 2.0 + x.powi(4) / 24.0))
+        } else {
+            f * 5.0E-9
+        }
+    }
+
+    /// BDS-SIS-ICD (B1I) slant delay [s] on B1I,
+    /// using the spherical thin shell geometry.
+    fn bds_seconds_delay(
+        &self,
+        t: Epoch,
+        rx_lat_long_ddeg: (f64, f64),
+        azimuth_deg: f64,
+        elevation_deg: f64,
+    ) -> f64 {
+        let elev = elevation_deg.to_radians();
+        let azim = azimuth_deg.to_radians();
+        let phi_u = rx_lat_long_ddeg.0.to_radians();
+        let lambda_u = rx_lat_long_ddeg.1.to_radians();
+
+        // ionospheric pierce point
+        let ratio = EARTH_RADIUS_KM / (EARTH_RADIUS_KM + BDS_SHELL_HEIGHT_KM) * elev.cos();
+        let psi = FRAC_PI_2 - elev - ratio.asin();
+        let phi_i = (phi_u.sin() * psi.cos() + phi_u.cos() * psi.sin() * azim.cos()).asin();
+        let lambda_i = lambda_u + (psi.sin() * azim.sin() / phi_i.cos()).asin();
+
+        // local time at the pierce point
+        let (_, nanos) = t.to_time_scale(TimeScale::BDT).to_time_of_week();
+        let t_s = (nanos as f64 * 1.0E-9 + 43.2E3 * lambda_i / PI).rem_euclid(86.4E3);
+
+        let phi = (phi_i / PI).abs();
+        let amp = polynomial(self.alpha, phi).max(0.0);
+        let per = polynomial(self.beta, phi).clamp(72.0E3, 172.8E3);
+
+        let vertical = if (t_s - 50.4E3).abs() < per / 4.0 {
+            5.0E-9 + amp * (2.0 * PI * (t_s - 50.4E3) / per).cos()
+        } else {
+            5.0E-9
+        };
+
+        let obliquity = 1.0 / (1.0 - ratio.powi(2)).sqrt();
+        vertical * obliquity
+    }
+
+    /// Format this [KbModel] as V4 frame body, published at [Epoch].
+    pub(crate) fn format_v4<W: Write>(
+        &self,
+        w: &mut BufWriter<W>,
+        t: Epoch,
+    ) -> Result<(), FormattingError> {
+        writeln!(
+            w,
+            "{}{}{}{}",
+            format_frame_epoch_v4(t),
+            NavFormatter::new(self.alpha.0),
+            NavFormatter::new(self.alpha.1),
+            NavFormatter::new(self.alpha.2),
+        )?;
+
+        writeln!(
+            w,
+            "    {}{}{}{}",
+            NavFormatter::new(self.alpha.3),
+            NavFormatter::new(self.beta.0),
+            NavFormatter::new(self.beta.1),
+            NavFormatter::new(self.beta.2),
+        )?;
+
+        writeln!(
+            w,
+            "    {}{}",
+            NavFormatter::new(self.beta.3),
+            NavFormatter::new(self.region as u8 as f64),
+        )?;
+
+        Ok(())
+    }
 
     /// Format this [KbModel] for a V2 header
     pub fn format_v2_header<W: Write>(&self, w: &mut BufWriter<W>) -> Result<(), FormattingError> {
@@ -6,9 +6,9 @@ mod bdgim;
 mod klobuchar;
 mod nequick_g;
 
-pub use bdgim::BdModel;
+pub use bdgim::{BdModel, BdNonBroadcast};
 pub use klobuchar::{KbModel, KbRegionCode};
-pub use nequick_g::{NgModel, NgRegionFlags};
+pub use nequick_g::{NgModel, NgRegionFlags, NgTables};
 
 /// [IonosphereModel] that may be described in modern NAV V4 RINEx
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
@@ -129,28 +129,6 @@ impl IonosphereModel {
         }
     }
 
-    // /* converts self to meters of delay */
-    // pub(crate) fn meters_delay(
-    //     &self,
-    //     t: Epoch,
-    //     e: f64,
-    //     a: f64,
-    //     h_km: f64,
-    //     user_lat_ddeg: f64,
-    //     user_lon_ddeg: f64,
-    //     carrier: Carrier,
-    // ) -> Option<f64> {
-    //     if let Some(kb) = self.as_klobuchar() {
-    //         Some(kb.meters_delay(t, e, a, h_km, user_lat_ddeg, user_lon_ddeg, carrier))
-    //     } else if let Some(ng) = self.as_nequick_g() {
-    //         None
-    //     } else if let Some(bd) = self.as_bdgim() {
-    //         None
-    //     } else {
-    //         None
-    //     }
-    // }
-
     /// Returns reference to Klobuchar [KbModel]
     pub fn as_klobuchar(&self) -> Option<&KbModel> {
         match self {
@@ -1,11 +1,20 @@
 use crate::{
+    carrier::Carrier,
     epoch::parse_in_timescale as parse_epoch_in_timescale,
     error::FormattingError,
     fmt_rinex,
-    navigation::formatting::NavFormatter,
+    navigation::formatting::{format_frame_epoch_v4, NavFormatter},
     prelude::{Constellation, Epoch, ParsingError, TimeScale},
 };
 
+mod profile;
+mod ray;
+mod tables;
+
+pub use tables::NgTables;
+
+use profile::Context;
+
 use bitflags::bitflags;
 
 use std::{
@@ -71,10 +80,75 @@ impl NgModel {
             },
         ))
     }
-    // /* converts self to meters of delay */
-    // pub(crate) fn meters_delay(&self, freq: f64) -> f64 {
-    //     0.0_f64
-    // }
+
+    /// Effective ionisation level Az [sfu], at this MODIP [°].
+    /// A null model defaults to 63.7 sfu.
+    pub fn effective_ionisation_level(&self, modip_ddeg: f64) -> f64 {
+        if self.a.0 == 0.0 && self.a.1 == 0.0 && self.a.2 == 0.0 {
+            63.7
+        } else {
+            (self.a.0 + self.a.1 * modip_ddeg + self.a.2 * modip_ddeg.powi(2)).clamp(0.0, 400.0)
+        }
+    }
+
+    /// Evaluates the slant Total Electron Content [TECu] along the receiver-satellite ray,
+    /// with the NeQuick-G algorithm. Region (storm) flags are not used by the algorithm.
+    /// ## Inputs
+    /// - tables: [NgTables] (CCIR maps and MODIP grid)
+    /// - t: [Epoch] of evaluation
+    /// - rx_lat_long_alt: receiver (latitude [°], longitude [°], altitude [km])
+    /// - sv_lat_long_alt: satellite (latitude [°], longitude [°], altitude [km])
+    pub fn slant_tec(
+        &self,
+        tables: &NgTables,
+        t: Epoch,
+        rx_lat_long_alt: (f64, f64, f64),
+        sv_lat_long_alt: (f64, f64, f64),
+    ) -> f64 {
+        let modip = tables.modip(rx_lat_long_alt.0, rx_lat_long_alt.1);
+        let az = self.effective_ionisation_level(modip);
+
+        let (_, month, _, hours, minutes, seconds, nanos) = t.to_gregorian_utc();
+        let ut_hours = hours as f64
+            + minutes as f64 / 60.0
+            + (seconds as f64 + nanos as f64 * 1.0E-9) / 3600.0;
+
+        let ctx = Context::new(tables, month, ut_hours, az);
+        ray::slant_tec(&ctx, rx_lat_long_alt, sv_lat_long_alt)
+    }
+
+    /// Evaluates the slant ionospheric delay [m] on this [Carrier],
+    /// with the NeQuick-G algorithm. See [Self::slant_tec] for more information.
+    pub fn meters_delay(
+        &self,
+        tables: &NgTables,
+        t: Epoch,
+        rx_lat_long_alt: (f64, f64, f64),
+        sv_lat_long_alt: (f64, f64, f64),
+        carrier: Carrier,
+    ) -> f64 {
+        let stec = self.slant_tec(tables, t, rx_lat_long_alt, sv_lat_long_alt);
+        40.3E16 / carrier.frequency().powi(2) * stec
+    }
+
+    /// Format this [NgModel] as V4 frame body, published at [Epoch].
+    pub(crate) fn format_v4<W: Write>(
+        &self,
+        w: &mut BufWriter<W>,
+        t: Epoch,
+    ) -> Result<(), FormattingError> {
+        writeln!(
+            w,
+            "{}{}{}{}",
+            format_frame_epoch_v4(t),
+            NavFormatter::new(self.a.0),
+            NavFormatter::new(self.a.1),
+            NavFormatter::new(self.a.2),
+        )?;
+
+        writeln!(w, "    {}", NavFormatter::new(self.region.bits() as f64))?;
+        Ok(())
+    }
 
     /// Format this [NgModel] for a V3 [Constellation] header.
     pub fn format_header<W: Write>(
@@ -91,7 +165,7 @@ impl NgModel {
             NavFormatter::new_iono_alpha_beta(self.region.bits() as f64),
         );
 
-        write!(w, "{}\n", fmt_rinex(&formatted, "IONOSPHERIC CORR"))?;
+        writeln!(w, "{}", fmt_rinex(&formatted, "IONOSPHERIC CORR"))?;
 
         Ok(())
     }
@@ -0,0 +1,298 @@
+//! NeQuick-G electron density profile
+use super::tables::NgTables;
+
+use std::f64::consts::PI;
+
+/// Solar zenith angle [°] at day-night transition
+const CHI0_DDEG: f64 = 86.23292796211615;
+
+/// E layer peak height [km]
+const HM_E_KM: f64 = 120.0;
+
+/// Exponential, clipped to avoid overflows
+pub(crate) fn clip_exp(p: f64) -> f64 {
+    if p > 80.0 {
+        5.5406E34
+    } else if p < -80.0 {
+        1.8049E-35
+    } else {
+        p.exp()
+    }
+}
+
+/// Smoothly joins `f1` (x > 0) and `f2` (x < 0)
+fn join(f1: f64, f2: f64, alpha: f64, x: f64) -> f64 {
+    let ee = clip_exp(alpha * x);
+    (f1 * ee + f2) / (ee + 1.0)
+}
+
+/// Epstein layer
+fn epstein(peak: f64, thickness: f64, hm: f64, h: f64) -> f64 {
+    let ee = clip_exp((h - hm) / thickness);
+    peak * ee / (1.0 + ee).powi(2)
+}
+
+/// [Context] gathers all quantities that do not depend on the location
+pub(crate) struct Context<'a> {
+    tables: &'a NgTables,
+    /// Month (1 = January)
+    month: u8,
+    /// Universal time [h]
+    ut_hours: f64,
+    /// Effective ionisation level [sfu]
+    az: f64,
+    /// Effective sunspot number
+    azr: f64,
+    /// Solar declination (sine, cosine)
+    declination: (f64, f64),
+    /// foF2 CCIR coefficients
+    cf2: [f64; 76],
+    /// M(3000)F2 CCIR coefficients
+    cm3: [f64; 49],
+}
+
+impl<'a> Context<'a> {
+    /// Builds a new [Context] for this month (1 = January),
+    /// universal time [h] and effective ionisation level [sfu].
+    pub(crate) fn new(tables: &'a NgTables, month: u8, ut_hours: f64, az: f64) -> Self {
+        let azr = (167273.0 + (az - 63.7) * 1123.6).sqrt() - 408.99;
+
+        let t = 30.5 * month as f64 - 15.0 + (18.0 - ut_hours) / 24.0;
+        let am = (0.9856 * t - 3.289).to_radians();
+        let al = am + (1.916 * am.sin() + 0.020 * (2.0 * am).sin() + 282.634).to_radians();
+
+        let sin_delta = 0.39782 * al.sin();
+        let cos_delta = (1.0 - sin_delta.powi(2)).sqrt();
+
+        let (cf2, cm3) = tables.ccir_time_series(month, ut_hours, azr);
+
+        Self {
+            tables,
+            month,
+            ut_hours,
+            az,
+            azr,
+            declination: (sin_delta, cos_delta),
+            cf2,
+            cm3,
+        }
+    }
+
+    /// Evaluates the [Profile] at this location
+    pub(crate) fn profile(&self, lat_ddeg: f64, long_ddeg: f64) -> Profile {
+        let phi = lat_ddeg.to_radians();
+        let (sin_delta, cos_delta) = self.declination;
+
+        // effective solar zenith angle
+        let lt = self.ut_hours + long_ddeg / 15.0;
+        let cos_chi =
+            phi.sin() * sin_delta + phi.cos() * cos_delta * (PI / 12.0 * (12.0 - lt)).cos();
+        let chi = (1.0 - cos_chi.powi(2)).sqrt().atan2(cos_chi).to_degrees();
+        let chi_eff = join(
+            90.0 - 0.24 * clip_exp(20.0 - 0.2 * chi),
+            chi,
+            12.0,
+            chi - CHI0_DDEG,
+        );
+
+        // E layer
+        let seas = match self.month {
+            1 | 2 | 11 | 12 => -1.0,
+            3 | 4 | 9 | 10 => 0.0,
+            _ => 1.0,
+        };
+
+        let ee = clip_exp(0.3 * lat_ddeg);
+        let seasp = seas * (ee - 1.0) / (ee + 1.0);
+
+        let fo_e = ((1.112 - 0.019 * seasp).powi(2)
+            * self.az.sqrt()
+            * chi_eff.to_radians().cos().powf(0.6)
+            + 0.49)
+            .sqrt();
+
+        // F2 layer
+        let modip = self.tables.modip(lat_ddeg, long_ddeg);
+        let (fo_f2, m3000) =
+            NgTables::ccir_f2_m3000(&self.cf2, &self.cm3, lat_ddeg, long_ddeg, modip);
+
+        // F1 layer: foF1 remains below 85% of foF2
+        let fo_f1 = if fo_e >= 2.0 {
+            (1.4 * fo_e).min(0.85 * fo_f2)
+        } else {
+            0.0
+        };
+
+        let nm_e = 0.124 * fo_e.powi(2);
+        let nm_f1 = 0.124 * fo_f1.powi(2);
+        let nm_f2 = 0.124 * fo_f2.powi(2);
+
+        // peak heights
+        let ratio = fo_f2 / fo_e;
+        let rho = join(ratio, 1.75, 20.0, ratio - 1.75);
+        let dm = 0.253 / (rho - 1.215) - 0.012;
+
+        let m2 = m3000.powi(2);
+        let hm_f2 = 1490.0 * m3000 * ((0.0196 * m2 + 1.0) / (1.2967 * m2 - 1.0)).sqrt()
+            / (m3000 + dm)
+            - 176.0;
+
+        let hm_f1 = (hm_f2 + HM_E_KM) / 2.0;
+
+        // thickness parameters
+        let b2_bot = 0.385 * nm_f2
+            / (0.01 * (-3.467 + 0.857 * fo_f2.powi(2).ln() + 2.02 * m3000.ln()).exp());
+
+        let b1_top = 0.3 * (hm_f2 - hm_f1);
+        let b1_bot = 0.5 * (hm_f1 - HM_E_KM);
+        let be_top = b1_bot.max(7.0);
+        let be_bot = 5.0;
+
+        // layer amplitudes
+        let a1 = 4.0 * nm_f2;
+
+        let (a2, a3) = if fo_f1 < 0.5 {
+            (0.0, 4.0 * (nm_e - epstein(a1, b2_bot, hm_f2, HM_E_KM)))
+        } else {
+            let mut a2a = 0.0;
+            let mut a3a = 4.0 * nm_e;
+
+            for _ in 0..5 {
+                a2a = 4.0
+                    * (nm_f1
+                        - epstein(a1, b2_bot, hm_f2, hm_f1)
+                        - epstein(a3a, be_top, HM_E_KM, hm_f1));
+
+                a2a = join(a2a, 0.8 * nm_f1, 1.0, a2a - 0.8 * nm_f1);
+
+                a3a = 4.0
+                    * (nm_e
+                        - epstein(a2a, b1_bot, hm_f1, HM_E_KM)
+                        - epstein(a1, b2_bot, hm_f2, HM_E_KM));
+            }
+
+            (a2a, join(a3a, 0.05, 60.0, a3a - 0.005))
+        };
+
+        // topside shape parameter
+        let ka = if (4..=9).contains(&self.month) {
+            -7.77 + 0.097 * (hm_f2 / b2_bot).powi(2) + 0.153 * nm_f2
+        } else {
+            6.705 - 0.014 * self.azr - 0.008 * hm_f2
+        };
+
+        let kb = join(ka, 2.0, 1.0, ka - 2.0);
+        let k = join(8.0, kb, 1.0, kb - 8.0);
+
+        Profile {
+            nm_f2,
+            hm_f1,
+            hm_f2,
+            b2_bot,
+            b1_top,
+            b1_bot,
+            be_top,
+            be_bot,
+            amplitudes: [a1, a2, a3],
+            h0: k * b2_bot,
+        }
+    }
+}
+
+/// Vertical electron density [Profile] at one location
+pub(crate) struct Profile {
+    /// F2 peak density [10¹¹ m⁻³]
+    nm_f2: f64,
+    /// F1 peak height [km]
+    hm_f1: f64,
+    /// F2 peak height [km]
+    hm_f2: f64,
+    /// F2 bottom thickness [km]
+    b2_bot: f64,
+    /// F1 top thickness [km]
+    b1_top: f64,
+    /// F1 bottom thickness [km]
+    b1_bot: f64,
+    /// E top thickness [km]
+    be_top: f64,
+    /// E bottom thickness [km]
+    be_bot: f64,
+    /// F2, F1 and E layer amplitudes [10¹¹ m⁻³]
+    amplitudes: [f64; 3],
+    /// Topside thickness [km]
+    h0: f64,
+}
+
+impl Profile {
+    /// Electron density [m⁻³] at this altitude [km]
+    pub(crate) fn density(&self, h_km: f64) -> f64 {
+        let density = if h_km <= self.hm_f2 {
+            self.bottomside(h_km)
+        } else {
+            self.topside(h_km)
+        };
+
+        density * 1.0E11
+    }
+
+    fn bottomside(&self, h_km: f64) -> f64 {
+        // below 100 km: Chapman extrapolation
+        let h = h_km.max(100.0);
+
+        let be = if h > HM_E_KM {
+            self.be_top
+        } else {
+            self.be_bot
+        };
+
+        let bf1 = if h > self.hm_f1 {
+            self.b1_top
+        } else {
+            self.b1_bot
+        };
+
+        let ee = (10.0 / (1.0 + (h - self.hm_f2).abs())).exp();
+
+        let s = [
+            (h - self.hm_f2) / self.b2_bot,
+            (h - self.hm_f1) * ee / bf1,
+            (h - HM_E_KM) * ee / be,
+        ];
+
+        let ds = [1.0 / self.b2_bot, ee / bf1, ee / be];
+
+        let (mut n, mut dn) = (0.0, 0.0);
+
+        for i in 0..3 {
+            if s[i].abs() <= 25.0 {
+                let e = s[i].exp();
+                let term = self.amplitudes[i] * e / (1.0 + e).powi(2);
+                n += term;
+                dn += term * (1.0 - e) / (1.0 + e) * ds[i];
+            }
+        }
+
+        if h_km >= 100.0 {
+            n
+        } else {
+            let bc = 1.0 - 10.0 * dn / n;
+            let z = 0.1 * (h_km - 100.0);
+            n * clip_exp(1.0 - bc * z - clip_exp(-z))
+        }
+    }
+
+    fn topside(&self, h_km: f64) -> f64 {
+        const G: f64 = 0.125;
+        const R: f64 = 100.0;
+
+        let dh = h_km - self.hm_f2;
+        let z = dh / (self.h0 * (1.0 + R * G * dh / (R * self.h0 + G * dh)));
+        let ea = clip_exp(z);
+
+        if ea > 1.0E11 {
+            4.0 * self.nm_f2 / ea
+        } else {
+            4.0 * self.nm_f2 * ea / (1.0 + ea).powi(2)
+        }
+    }
+}
@@ -0,0 +1,184 @@
+//! NeQuick-G ray geometry and electron density integration
+use super::profile::Context;
+
+use std::f64::consts::{FRAC_PI_2, PI};
+
+/// Earth mean radius [km]
+const EARTH_RADIUS_KM: f64 = 6371.2;
+
+/// Maximal Gauss-Kronrod recursion level
+const MAX_RECURSION: u8 = 50;
+
+/// Kronrod (K15) nodes
+const K15_NODES: [f64; 8] = [
+    0.991455371120813,
+    0.949107912342759,
+    0.864864423359769,
+    0.741531185599394,
+    0.586087235467691,
+    0.405845151377397,
+    0.207784955007898,
+    0.0,
+];
+
+/// Kronrod (K15) weights
+const K15_WEIGHTS: [f64; 8] = [
+    0.022935322010529,
+    0.063092092629979,
+    0.104790010322250,
+    0.140653259715525,
+    0.169004726639267,
+    0.190350578064785,
+    0.204432940075298,
+    0.209482141084728,
+];
+
+/// Gauss (G7) weights, applying to odd K15 nodes
+const G7_WEIGHTS: [f64; 4] = [
+    0.129484966168870,
+    0.279705391489277,
+    0.381830050505119,
+    0.417959183673469,
+];
+
+/// Adaptive Gauss-Kronrod integration of `f` between `a` and `b`
+fn integrate<F: Fn(f64) -> f64>(f: &F, a: f64, b: f64, tolerance: f64, level: u8) -> f64 {
+    let center = (a + b) / 2.0;
+    let half = (b - a) / 2.0;
+
+    let (mut k, mut g) = (0.0, 0.0);
+
+    for (i, (node, weight)) in K15_NODES.iter().zip(K15_WEIGHTS.iter()).enumerate() {
+        let value = if *node == 0.0 {
+            f(center)
+        } else {
+            f(center - half * node) + f(center + half * node)
+        };
+
+        k += weight * value;
+
+        if i % 2 == 1 {
+            g += G7_WEIGHTS[i / 2] * value;
+        }
+    }
+
+    let (k, g) = (k * half, g * half);
+
+    if (k - g).abs() <= tolerance * k.abs() || level == MAX_RECURSION {
+        k
+    } else {
+        integrate(f, a, center, tolerance, level + 1)
+            + integrate(f, center, b, tolerance, level + 1)
+    }
+}
+
+/// Integrates `f` between `a` and `b`, splitting the interval at
+/// 1000 km and 2000 km of altitude (`s_1000`, `s_2000`):
+/// the tolerance is relaxed above 1000 km.
+fn integrate_layers<F: Fn(f64) -> f64>(f: &F, a: f64, b: f64, s_1000: f64, s_2000: f64) -> f64 {
+    if b <= a {
+        return 0.0;
+    }
+
+    let mut tec = 0.0;
+    let bounds = [a, s_1000.clamp(a, b), s_2000.clamp(a, b), b];
+
+    for (i, window) in bounds.windows(2).enumerate() {
+        if window[1] > window[0] {
+            let tolerance = if i == 0 { 1.0E-3 } else { 1.0E-2 };
+            tec += integrate(f, window[0], window[1], tolerance, 0);
+        }
+    }
+
+    tec
+}
+
+/// Integrates the electron density [TECu] along the ray, from the receiver to
+/// the satellite, both described as (latitude [°], longitude [°], altitude [km]).
+pub(crate) fn slant_tec(ctx: &Context, rx: (f64, f64, f64), sv: (f64, f64, f64)) -> f64 {
+    let (phi1, lambda1) = (rx.0.to_radians(), rx.1.to_radians());
+    let (phi2, lambda2) = (sv.0.to_radians(), sv.1.to_radians());
+
+    let r1 = EARTH_RADIUS_KM + rx.2;
+    let r2 = EARTH_RADIUS_KM + sv.2;
+
+    let vertical = (phi2 - phi1).abs() < 1.0E-5 && (lambda2 - lambda1).abs() < 1.0E-5;
+
+    // zenith angle
+    let cos_delta = phi1.sin() * phi2.sin() + phi1.cos() * phi2.cos() * (lambda2 - lambda1).cos();
+    let sin_delta = (1.0 - cos_delta.powi(2)).sqrt();
+    let zeta = sin_delta.atan2(cos_delta - r1 / r2);
+
+    // ray perigee
+    let rp = r1 * zeta.sin();
+
+    if vertical || rp < 0.1 {
+        let profile = ctx.profile(rx.0, rx.1);
+        let f = |h: f64| profile.density(h);
+        return integrate_layers(&f, rx.2, sv.2, 1000.0, 2000.0) * 1.0E-13;
+    }
+
+    let (phi_p, lambda_p) = if (phi1.abs() - FRAC_PI_2).abs() < 1.0E-10 {
+        let phi_p = if phi1 > 0.0 { zeta } else { -zeta };
+        let lambda_p = if zeta >= 0.0 { lambda2 + PI } else { lambda2 };
+        (phi_p, lambda_p)
+    } else {
+        let sin_sigma = (lambda2 - lambda1).sin() * phi2.cos() / sin_delta;
+        let cos_sigma = (phi2.sin() - cos_delta * phi1.sin()) / (sin_delta * phi1.cos());
+
+        let delta_p = FRAC_PI_2 - zeta;
+        let sin_phi_p = phi1.sin() * delta_p.cos() - phi1.cos() * delta_p.sin() * cos_sigma;
+        let cos_phi_p = (1.0 - sin_phi_p.powi(2)).sqrt();
+
+        let sin_dlambda = -sin_sigma * delta_p.sin() / cos_phi_p;
+        let cos_dlambda = (delta_p.cos() - phi1.sin() * sin_phi_p) / (phi1.cos() * cos_phi_p);
+
+        (
+            sin_phi_p.atan2(cos_phi_p),
+            sin_dlambda.atan2(cos_dlambda) + lambda1,
+        )
+    };
+
+    // satellite azimuth, seen from the perigee
+    let (sin_sigma_p, cos_sigma_p) = if (phi_p.abs() - FRAC_PI_2).abs() < 1.0E-10 {
+        if phi_p > 0.0 {
+            (0.0, -1.0)
+        } else {
+            (0.0, 1.0)
+        }
+    } else {
+        let cos_psi =
+            phi_p.sin() * phi2.sin() + phi_p.cos() * phi2.cos() * (lambda2 - lambda_p).cos();
+        let sin_psi = (1.0 - cos_psi.powi(2)).sqrt();
+
+        (
+            phi2.cos() * (lambda2 - lambda_p).sin() / sin_psi,
+            (phi2.sin() - phi_p.sin() * cos_psi) / (phi_p.cos() * sin_psi),
+        )
+    };
+
+    // distance to the perigee [km]
+    let s = |r: f64| (r.powi(2) - rp.powi(2)).max(0.0).sqrt();
+
+    let f = |s: f64| {
+        let r = (s.powi(2) + rp.powi(2)).sqrt();
+        let (sin_d, cos_d) = (s / r, rp / r);
+
+        let sin_phi = phi_p.sin() * cos_d + phi_p.cos() * sin_d * cos_sigma_p;
+        let cos_phi = (1.0 - sin_phi.powi(2)).sqrt();
+
+        let lambda =
+            (sin_sigma_p * sin_d * phi_p.cos()).atan2(cos_d - phi_p.sin() * sin_phi) + lambda_p;
+
+        ctx.profile(sin_phi.atan2(cos_phi).to_degrees(), lambda.to_degrees())
+            .density(r - EARTH_RADIUS_KM)
+    };
+
+    integrate_layers(
+        &f,
+        s(r1),
+        s(r2),
+        s(EARTH_RADIUS_KM + 1000.0),
+        s(EARTH_RADIUS_KM + 2000.0),
+    ) * 1.0E-13
+}
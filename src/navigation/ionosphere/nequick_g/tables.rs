@@ -0,0 +1,238 @@
+//! NeQuick-G CCIR maps and MODIP grid
+use crate::prelude::ParsingError;
+
+use std::{fs::read_to_string, path::Path, str::FromStr};
+
+/// MODIP grid dimension (latitudes and longitudes)
+const MODIP_GRID: usize = 39;
+
+/// Number of F2 coefficients in one CCIR file (2 x 76 x 13)
+const F2_COEFS: usize = 1976;
+
+/// Number of M(3000)F2 coefficients in one CCIR file (2 x 49 x 9)
+const FM3_COEFS: usize = 882;
+
+/// Number of (cos, sin) terms per harmonic order, for foF2
+const F2_ORDERS: [usize; 9] = [12, 12, 9, 5, 2, 1, 1, 1, 1];
+
+/// Number of (cos, sin) terms per harmonic order, for M(3000)F2
+const FM3_ORDERS: [usize; 7] = [7, 8, 6, 3, 2, 1, 1];
+
+/// [NgTables] gathers the CCIR maps and MODIP grid the NeQuick-G model relies on,
+/// as distributed with the European GNSS (Galileo) ionospheric correction algorithm:
+/// `modipNeQG_wrapped.asc` and `ccir11.asc` (January) to `ccir22.asc` (December).
+/// These tables are not distributed with this crate and must be loaded,
+/// see [Self::from_files].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NgTables {
+    /// MODIP [°] grid: 39 latitudes (-95° to 95°) by 39 longitudes (-180° to 200°)
+    modip: Vec<f64>,
+    /// CCIR coefficients, for each month
+    ccir: Vec<Vec<f64>>,
+}
+
+/// Parses a whitespace separated table
+fn parse_table(content: &str, size: usize, err: ParsingError) -> Result<Vec<f64>, ParsingError> {
+    let values = content
+        .split_ascii_whitespace()
+        .map(|item| f64::from_str(&item.replace('D', "E")))
+        .collect::<Result<Vec<_>, _>>();
+
+    match values {
+        Ok(values) if values.len() == size => Ok(values),
+        _ => Err(err),
+    }
+}
+
+/// Third order interpolation of 4 equally spaced samples, at `x` in [0, 1]
+/// between the second and third sample.
+fn interpolate(z: [f64; 4], x: f64) -> f64 {
+    if x.abs() < 1.0E-10 {
+        return z[1];
+    }
+
+    let delta = 2.0 * x - 1.0;
+    let g1 = z[2] + z[1];
+    let g2 = z[2] - z[1];
+    let g3 = z[3] + z[0];
+    let g4 = (z[3] - z[0]) / 3.0;
+
+    let a0 = 9.0 * g1 - g3;
+    let a1 = 9.0 * g2 - g4;
+    let a2 = g3 - g1;
+    let a3 = g4 - g2;
+
+    (a0 + a1 * delta + a2 * delta.powi(2) + a3 * delta.powi(3)) / 16.0
+}
+
+/// Evaluates a CCIR map (Legendre like expansion)
+fn ccir_map(coefs: &[f64], orders: &[usize], sin_modip: f64, lat_deg: f64, long_deg: f64) -> f64 {
+    let cos_phi = lat_deg.to_radians().cos();
+    let lambda = long_deg.to_radians();
+
+    let mut m = [1.0; 12];
+    for k in 1..m.len() {
+        m[k] = m[k - 1] * sin_modip;
+    }
+
+    let mut value = (0..orders[0]).map(|k| coefs[k] * m[k]).sum::<f64>();
+
+    let mut offset = orders[0];
+    let mut p = 1.0;
+
+    for (n, order) in orders.iter().enumerate().skip(1) {
+        let (sin, cos) = (n as f64 * lambda).sin_cos();
+        p *= cos_phi;
+
+        for k in 0..*order {
+            value += (coefs[offset + 2 * k] * cos + coefs[offset + 2 * k + 1] * sin) * m[k] * p;
+        }
+
+        offset += 2 * order;
+    }
+
+    value
+}
+
+impl NgTables {
+    /// Builds [NgTables] from the MODIP grid and the 12 monthly CCIR files content,
+    /// January first.
+    pub fn new(modip: &str, ccir: [&str; 12]) -> Result<Self, ParsingError> {
+        let modip = parse_table(
+            modip,
+            MODIP_GRID * MODIP_GRID,
+            ParsingError::NequickGModipTable,
+        )?;
+
+        let ccir = ccir
+            .iter()
+            .map(|content| {
+                parse_table(
+                    content,
+                    F2_COEFS + FM3_COEFS,
+                    ParsingError::NequickGCcirTable,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { modip, ccir })
+    }
+
+    /// Builds [NgTables] from the MODIP grid file and the directory
+    /// that contains the `ccir11.asc` to `ccir22.asc` files.
+    pub fn from_files<P: AsRef<Path>, Q: AsRef<Path>>(
+        modip: P,
+        ccir_dir: Q,
+    ) -> Result<Self, ParsingError> {
+        let modip = read_to_string(modip)?;
+
+        let mut ccir = Vec::with_capacity(12);
+        for month in 1..=12 {
+            let path = ccir_dir.as_ref().join(format!("ccir{}.asc", month + 10));
+            ccir.push(read_to_string(path)?);
+        }
+
+        let ccir: [&str; 12] = std::array::from_fn(|i| ccir[i].as_str());
+        Self::new(&modip, ccir)
+    }
+
+    /// Modified dip latitude (MODIP) [°] at this location
+    pub fn modip(&self, lat_ddeg: f64, long_ddeg: f64) -> f64 {
+        if lat_ddeg <= -90.0 {
+            return -90.0;
+        } else if lat_ddeg >= 90.0 {
+            return 90.0;
+        }
+
+        let long_ddeg = (long_ddeg + 180.0).rem_euclid(360.0) - 180.0;
+
+        let mut l = ((long_ddeg + 180.0) / 10.0).floor() as i32 - 2;
+        if l < 0 {
+            l += 36;
+        } else if l > 33 {
+            l -= 36;
+        }
+
+        let a = (lat_ddeg + 90.0) / 5.0 + 1.0;
+        let x = a - a.floor();
+        let i = a.floor() as i32 - 2;
+
+        let mut zk = [0.0; 4];
+
+        for (k, zk) in zk.iter_mut().enumerate() {
+            let mut zj = [0.0; 4];
+
+            for (j, zj) in zj.iter_mut().enumerate() {
+                let row = (i + j as i32 + 1) as usize;
+                let col = (l + k as i32 + 1) as usize;
+                *zj = self.modip[row * MODIP_GRID + col];
+            }
+
+            *zk = interpolate(zj, x);
+        }
+
+        let b = (long_ddeg + 180.0) / 10.0;
+        interpolate(zk, b - b.floor())
+    }
+
+    /// Interpolates the CCIR coefficients of this month (1 = January)
+    /// to this effective sunspot number, and evaluates their Fourier time series
+    /// at this universal time [h]. Returns (foF2, M(3000)F2) coefficients.
+    pub(crate) fn ccir_time_series(
+        &self,
+        month: u8,
+        ut_hours: f64,
+        azr: f64,
+    ) -> ([f64; 76], [f64; 49]) {
+        let ccir = &self.ccir[(month as usize).clamp(1, 12) - 1];
+        let t = (15.0 * ut_hours - 180.0).to_radians();
+        let w = azr / 100.0;
+
+        let mut cf2 = [0.0; 76];
+        for (j, cf2) in cf2.iter_mut().enumerate() {
+            let af2 = |k: usize| ccir[k + 13 * j] * (1.0 - w) + ccir[k + 13 * (j + 76)] * w;
+
+            *cf2 = af2(0)
+                + (1..=6)
+                    .map(|k| {
+                        let (sin, cos) = (k as f64 * t).sin_cos();
+                        af2(2 * k - 1) * sin + af2(2 * k) * cos
+                    })
+                    .sum::<f64>();
+        }
+
+        let fm3 = &ccir[F2_COEFS..];
+
+        let mut cm3 = [0.0; 49];
+        for (j, cm3) in cm3.iter_mut().enumerate() {
+            let am3 = |k: usize| fm3[k + 9 * j] * (1.0 - w) + fm3[k + 9 * (j + 49)] * w;
+
+            *cm3 = am3(0)
+                + (1..=4)
+                    .map(|k| {
+                        let (sin, cos) = (k as f64 * t).sin_cos();
+                        am3(2 * k - 1) * sin + am3(2 * k) * cos
+                    })
+                    .sum::<f64>();
+        }
+
+        (cf2, cm3)
+    }
+
+    /// Evaluates (foF2 [MHz], M(3000)F2) at this location, from the
+    /// coefficients obtained with [Self::ccir_time_series].
+    pub(crate) fn ccir_f2_m3000(
+        cf2: &[f64; 76],
+        cm3: &[f64; 49],
+        lat_ddeg: f64,
+        long_ddeg: f64,
+        modip_ddeg: f64,
+    ) -> (f64, f64) {
+        let sin_modip = modip_ddeg.to_radians().sin();
+
+        (
+            ccir_map(cf2, &F2_ORDERS, sin_modip, lat_ddeg, long_ddeg),
+            ccir_map(cm3, &FM3_ORDERS, sin_modip, lat_ddeg, long_ddeg),
+        )
+    }
+}
@@ -12,15 +12,18 @@ pub mod rinex;
 
 pub(crate) mod formatting;
 
-pub(crate) use formatting::format;
+pub(crate) use formatting::{format, format_frame, v2v3_header, v2v3_latch_frame};
 pub(crate) use parsing::{is_new_epoch, parse_epoch};
 
 pub use crate::navigation::{
-    earth_orientation::EarthOrientation,
+    earth_orientation::{EarthOrientation, EopProvider},
     ephemeris::{flags::*, orbits::OrbitItem, Ephemeris},
     frame::{NavFrame, NavFrameType},
     header::HeaderFields,
-    ionosphere::{BdModel, IonosphereModel, KbModel, KbRegionCode, NgModel, NgRegionFlags},
+    ionosphere::{
+        BdModel, BdNonBroadcast, IonosphereModel, KbModel, KbRegionCode, NgModel, NgRegionFlags,
+        NgTables,
+    },
     message::NavMessageType,
     time::TimeOffset,
 };
@@ -1,11 +1,16 @@
 use crate::{
-    navigation::{BdModel, Ephemeris, IonosphereModel, KbModel, NavKey, NgModel},
+    carrier::Carrier,
+    navigation::{
+        BdModel, BdNonBroadcast, Ephemeris, IonosphereModel, KbModel, NavKey, NgModel, NgTables,
+    },
     prelude::{
         nav::{Almanac, AzElRange, Orbit},
-        Epoch, Rinex, SV,
+        Constellation, Epoch, Rinex, TimeScale, SV,
     },
 };
 
+use hifitime::Unit;
+
 impl Rinex {
     /// [SV] orbital state vector determination attempt, that only applies
     /// to Navigation [Rinex].
@@ -16,7 +21,19 @@ impl Rinex {
     /// - orbital state: expressed as ECEF [Orbit]
     pub fn sv_orbit(&self, sv: SV, t: Epoch) -> Option<Orbit> {
         let (toc, _, eph) = self.nav_ephemeris_selection(sv, t)?;
-        eph.kepler2position(sv, t)
+        if sv.constellation == Constellation::Glonass || sv.constellation.is_sbas() {
+            eph.integrate_orbit(sv, toc, t)
+        } else {
+            eph.kepler2position(sv, t)
+        }
+    }
+
+    /// [SV] orbital state vector determination attempt, expressed in the inertial
+    /// EME2000 frame, using the Earth Orientation Parameters broadcast in this NAV V4 [Rinex].
+    /// For repeated calls, prefer [Self::nav_eop_provider] and [Self::sv_orbit].
+    pub fn sv_inertial_orbit(&self, sv: SV, t: Epoch) -> Option<Orbit> {
+        let orbit = self.sv_orbit(sv, t)?;
+        self.nav_eop_provider().itrf_to_eme2000(orbit)
     }
 
     /// [SV] (azimuth, elevation, slant range) triplet determination,
@@ -49,21 +66,22 @@ impl Rinex {
     /// ## Returns
     /// - (toc, toe, [Ephemeris]) triplet if an [Ephemeris] message
     /// was decoded in the correct time frame.
-    /// Note that `ToE` does not exist for GEO/SBAS [SV], so `ToC` is simply
+    /// Note that `ToE` does not exist for GLONASS and GEO/SBAS [SV], so `ToC` is simply
     /// copied in this case, to maintain the API.
     pub fn nav_ephemeris_selection(&self, sv: SV, t: Epoch) -> Option<(Epoch, Epoch, &Ephemeris)> {
         let sv_ts = sv.constellation.timescale()?;
 
-        if sv.constellation.is_sbas() {
+        if sv.constellation.is_sbas() || sv.constellation == Constellation::Glonass {
+            let max_dtoc = Ephemeris::validity_duration(sv.constellation)?;
             self.nav_ephemeris_frames_iter()
                 .filter_map(|(k, eph)| {
-                    if k.sv == sv {
+                    if k.sv == sv && (t - k.epoch).abs() < max_dtoc {
                         Some((k.epoch, k.epoch, eph))
                     } else {
                         None
                     }
                 })
-                .min_by_key(|(toc, _, _)| t - *toc)
+                .min_by_key(|(toc, _, _)| (t - *toc).abs())
         } else {
             self.nav_ephemeris_frames_iter()
                 .filter_map(|(k, eph)| {
@@ -100,6 +118,77 @@ impl Rinex {
         )
     }
 
+    /// Klobuchar [KbModel] selection, that only applies to Navigation [Rinex].
+    /// ## Inputs
+    /// - constellation: [Constellation] that published the model
+    /// - t: desired [Epoch]
+    /// ## Returns
+    /// - (publication [Epoch], [KbModel]) of the latest model published
+    ///   within 24 hours prior `t`. RINEX2/3 header models are considered
+    ///   published at midnight (GPST) of the first [Epoch], and applying
+    ///   for the entire day course: expect discontinuities at publication
+    ///   of a new model.
+    pub fn nav_klobuchar_selection(
+        &self,
+        constellation: Constellation,
+        t: Epoch,
+    ) -> Option<(Epoch, &KbModel)> {
+        let header = self.first_epoch().and_then(|t0| {
+            let model = self
+                .header
+                .ionod_corrections
+                .get(&constellation)?
+                .as_klobuchar()?;
+
+            let t0 = t0.to_time_scale(TimeScale::GPST).floor(1.0 * Unit::Day);
+            Some((t0, model))
+        });
+
+        self.nav_klobuchar_models_iter()
+            .filter_map(|(k, model)| {
+                if k.sv.constellation == constellation {
+                    Some((k.epoch, model))
+                } else {
+                    None
+                }
+            })
+            .chain(header)
+            .filter(|(t_i, _)| *t_i <= t && t - *t_i < 1.0 * Unit::Day)
+            .min_by_key(|(t_i, _)| t - *t_i)
+    }
+
+    /// Klobuchar slant ionospheric delay evaluation,
+    /// that only applies to Navigation [Rinex].
+    /// ## Inputs
+    /// - sv: target [SV]. BeiDou vehicles use the BDS model variant,
+    ///   any other [SV] uses the model published by its own [Constellation].
+    /// - t: target [Epoch]
+    /// - rx_lat_long_ddeg: receiver geodetic (latitude, longitude) in decimal degrees
+    /// - azelrange: [AzElRange] of this [SV], for example obtained with
+    ///   [Self::nav_azimuth_elevation_range]
+    /// - carrier: signal [Carrier]
+    /// ## Returns
+    /// - slant delay in meters, when a [KbModel] was selected (see [Self::nav_klobuchar_selection])
+    pub fn nav_klobuchar_ionosphere_delay(
+        &self,
+        sv: SV,
+        t: Epoch,
+        rx_lat_long_ddeg: (f64, f64),
+        azelrange: &AzElRange,
+        carrier: Carrier,
+    ) -> Option<f64> {
+        let (_, model) = self.nav_klobuchar_selection(sv.constellation, t)?;
+
+        Some(model.meters_delay(
+            t,
+            sv.constellation,
+            rx_lat_long_ddeg,
+            azelrange.azimuth_deg,
+            azelrange.elevation_deg,
+            carrier,
+        ))
+    }
+
     /// BDGIM [BdModel] Ionosphere model [Iterator].
     /// Refer to [Self::nav_klobuchar_models_iter] for similar examples.
     pub fn nav_bdgim_models_iter(&self) -> Box<dyn Iterator<Item = (&NavKey, &BdModel)> + '_> {
@@ -112,6 +201,56 @@ impl Rinex {
         )
     }
 
+    /// BDGIM [BdModel] selection, that only applies to Navigation [Rinex].
+    /// ## Inputs
+    /// - t: desired [Epoch]
+    /// ## Returns
+    /// - (publication [Epoch], [BdModel]) of the latest model published
+    ///   within 24 hours prior `t`.
+    pub fn nav_bdgim_selection(&self, t: Epoch) -> Option<(Epoch, &BdModel)> {
+        self.nav_bdgim_models_iter()
+            .filter(|(k, _)| k.epoch <= t && t - k.epoch < 1.0 * Unit::Day)
+            .min_by_key(|(k, _)| t - k.epoch)
+            .map(|(k, model)| (k.epoch, model))
+    }
+
+    /// BDGIM slant ionospheric delay evaluation,
+    /// that only applies to Navigation [Rinex].
+    /// ## Inputs
+    /// - t: target [Epoch]
+    /// - rx_lat_long_ddeg: receiver geodetic (latitude, longitude) in decimal degrees
+    /// - azelrange: [AzElRange] of the BeiDou [SV], for example obtained with
+    ///   [Self::nav_azimuth_elevation_range]
+    /// - carrier: signal [Carrier]
+    /// - non_broadcast: optional [BdNonBroadcast] coefficients, predicted at publication time.
+    ///   [BdNonBroadcast::default] applies when `None`.
+    /// ## Returns
+    /// - slant delay in meters, when a [BdModel] was selected (see [Self::nav_bdgim_selection])
+    pub fn nav_bdgim_ionosphere_delay(
+        &self,
+        t: Epoch,
+        rx_lat_long_ddeg: (f64, f64),
+        azelrange: &AzElRange,
+        carrier: Carrier,
+        non_broadcast: Option<&BdNonBroadcast>,
+    ) -> Option<f64> {
+        let (t_p, model) = self.nav_bdgim_selection(t)?;
+
+        let beta = match non_broadcast {
+            Some(non_broadcast) => non_broadcast.predict(t_p),
+            None => BdNonBroadcast::default().predict(t_p),
+        };
+
+        Some(model.meters_delay(
+            t,
+            &beta,
+            rx_lat_long_ddeg,
+            azelrange.azimuth_deg,
+            azelrange.elevation_deg,
+            carrier,
+        ))
+    }
+
     /// Nequick-G [NgModel] Ionosphere model [Iterator].
     /// Refer to [Self::nav_klobuchar_models_iter] for similar examples.
     pub fn nav_nequickg_models_iter(&self) -> Box<dyn Iterator<Item = (&NavKey, &NgModel)> + '_> {
@@ -123,4 +262,53 @@ impl Rinex {
                 }),
         )
     }
+
+    /// Nequick-G [NgModel] selection, that only applies to Navigation [Rinex].
+    /// ## Inputs
+    /// - t: desired [Epoch]
+    /// ## Returns
+    /// - (publication [Epoch], [NgModel]) of the latest Galileo model published
+    ///   within 24 hours prior `t`. RINEX3 header models are considered published
+    ///   at midnight (GST) of the first [Epoch], see [Self::nav_klobuchar_selection].
+    pub fn nav_nequickg_selection(&self, t: Epoch) -> Option<(Epoch, &NgModel)> {
+        let header = self.first_epoch().and_then(|t0| {
+            let model = self
+                .header
+                .ionod_corrections
+                .get(&Constellation::Galileo)?
+                .as_nequick_g()?;
+
+            let t0 = t0.to_time_scale(TimeScale::GST).floor(1.0 * Unit::Day);
+            Some((t0, model))
+        });
+
+        self.nav_nequickg_models_iter()
+            .map(|(k, model)| (k.epoch, model))
+            .chain(header)
+            .filter(|(t_i, _)| *t_i <= t && t - *t_i < 1.0 * Unit::Day)
+            .min_by_key(|(t_i, _)| t - *t_i)
+    }
+
+    /// Nequick-G slant ionospheric delay evaluation,
+    /// that only applies to Navigation [Rinex].
+    /// ## Inputs
+    /// - tables: [NgTables] (CCIR maps and MODIP grid)
+    /// - t: target [Epoch]
+    /// - rx_lat_long_alt: receiver (latitude [°], longitude [°], altitude [km])
+    /// - sv_lat_long_alt: satellite (latitude [°], longitude [°], altitude [km])
+    /// - carrier: signal [Carrier]
+    /// ## Returns
+    /// - slant delay in meters, when a [NgModel] was selected (see [Self::nav_nequickg_selection])
+    pub fn nav_nequickg_ionosphere_delay(
+        &self,
+        tables: &NgTables,
+        t: Epoch,
+        rx_lat_long_alt: (f64, f64, f64),
+        sv_lat_long_alt: (f64, f64, f64),
+        carrier: Carrier,
+    ) -> Option<f64> {
+        let (_, model) = self.nav_nequickg_selection(t)?;
+
+        Some(model.meters_delay(tables, t, rx_lat_long_alt, sv_lat_long_alt, carrier))
+    }
 }
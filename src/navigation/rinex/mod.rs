@@ -12,7 +12,8 @@ pub mod ut1; // feature dependent high level methods
 
 use crate::{
     navigation::{
-        EarthOrientation, Ephemeris, NavFrame, NavFrameType, NavKey, NavMessageType, TimeOffset,
+        EarthOrientation, EopProvider, Ephemeris, NavFrame, NavFrameType, NavKey, NavMessageType,
+        TimeOffset,
     },
     prelude::{Epoch, Rinex, RinexType, SV},
 };
@@ -84,6 +85,18 @@ impl Rinex {
         }
     }
 
+    /// Gathers all [EarthOrientation] frames of this NAV V4 [Rinex] in an [EopProvider].
+    /// Identical publications broadcast by several [SV] are only retained once.
+    pub fn nav_eop_provider(&self) -> EopProvider {
+        let mut provider = EopProvider::default();
+
+        for (k, eop) in self.nav_earth_orientation_frames_iter() {
+            provider.add_parameters(k.epoch, eop.clone());
+        }
+
+        provider
+    }
+
     /// [IonosphereModel] frames [Iterator].
     /// This type of frames exists in NAV V4 only.
     pub fn nav_ionosphere_models_iter(
@@ -133,81 +146,4 @@ impl Rinex {
                 .map(|(k, eph)| (*k, eph.sv_clock())),
         )
     }
-
-    // /*
-    //  * [IonMessage] Iterator
-    //  */
-    // fn ionod_correction_models(
-    //     &self,
-    // ) -> Box<dyn Iterator<Item = (Epoch, (NavMsgType, SV, IonMessage))> + '_> {
-    //     /*
-    //      * Answers both OLD and MODERN RINEX requirements
-    //      * In RINEX2/3, midnight UTC is the publication datetime
-    //      */
-    //     let t0 = self.first_epoch().unwrap(); // will fail on invalid RINEX
-    //     let t0 = Epoch::from_utc_days(t0.to_utc_days().round());
-    //     Box::new(
-    //         self.header
-    //             .ionod_corrections
-    //             .iter()
-    //             .map(move |(c, ion)| (t0, (NavMsgType::LNAV, SV::new(*c, 1), *ion)))
-    //             .chain(self.navigation().flat_map(|(t, frames)| {
-    //                 frames.iter().filter_map(move |fr| {
-    //                     let (msg, sv, ion) = fr.as_ion()?;
-    //                     Some((*t, (msg, sv, *ion)))
-    //                 })
-    //             })),
-    //     )
-    // }
-
-    // /// Returns Ionospheric delay compensation, to apply at "t" desired Epoch
-    // /// and desired location. NB: we only support Klobuchar models at the moment,
-    // /// as we don't know how to convert other models (feel free to contribute).
-    // /// "t" must be within a 24 hour time frame of the oldest model.
-    // /// When working with RINEX2/3, the model is published at midnight
-    // /// and you should expect discontinuities when a new model is being published.
-    // pub fn ionod_correction(
-    //     &self,
-    //     t: Epoch,
-    //     sv_elevation: f64,
-    //     sv_azimuth: f64,
-    //     user_lat_ddeg: f64,
-    //     user_lon_ddeg: f64,
-    //     carrier: Carrier,
-    // ) -> Option<f64> {
-    //     // determine nearest in time
-    //     let (_, (model_sv, model)) = self
-    //         .ionod_correction_models()
-    //         .filter_map(|(t_i, (_, sv_i, msg_i))| {
-    //             // TODO
-    //             // calculations currently limited to KB model: implement others
-    //             let _ = msg_i.as_klobuchar()?;
-    //             // At most 1 day from publication time
-    //             if t_i <= t && (t - t_i) < 24.0 * Unit::Hour {
-    //                 Some((t_i, (sv_i, msg_i)))
-    //             } else {
-    //                 None
-    //             }
-    //         })
-    //         .min_by_key(|(t_i, _)| (t - *t_i))?;
-
-    //     // TODO
-    //     // calculations currently limited to KB model: implement others
-    //     let kb = model.as_klobuchar().unwrap();
-    //     let h_km = match model_sv.constellation {
-    //         Constellation::BeiDou => 375.0,
-    //         // we only expect BDS or GPS here,
-    //         // wrongly formed RINEX will cause innacurate results
-    //         Constellation::GPS | _ => 350.0,
-    //     };
-    //     Some(kb.meters_delay(
-    //         t,
-    //         sv_elevation,
-    //         sv_azimuth,
-    //         h_km,
-    //         user_lat_ddeg,
-    //         user_lon_ddeg,
-    //         carrier,
-    //     ))
-    // }
 }
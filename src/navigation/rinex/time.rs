@@ -1,11 +1,122 @@
 use qc_traits::{TimeCorrection, TimeCorrectionsDB};
 
-use crate::prelude::{Duration, Epoch, Rinex};
+use crate::{
+    navigation::TimeOffset,
+    prelude::{Duration, Epoch, Rinex, TimeScale},
+};
+
+use hifitime::Polynomial;
+
+/// Converts [TimeOffset] to [TimeCorrection], with its polynomial
+/// expressed at this reference [Epoch] (in left hand side [hifitime::TimeScale]).
+/// [Polynomial] terms are [Duration]s (1 ns resolution): typical drifts vanish,
+/// but they are accounted for in the constant term, at this reference.
+fn time_correction(
+    offset: &TimeOffset,
+    ref_epoch: Epoch,
+    validity_period: Duration,
+) -> TimeCorrection {
+    let (a1, a2) = (offset.polynomial.1, offset.polynomial.2);
+    let dt = (ref_epoch - offset.reference_epoch()).to_seconds();
+
+    TimeCorrection {
+        lhs_timescale: offset.lhs,
+        rhs_timescale: offset.rhs,
+        ref_epoch,
+        validity_period,
+        polynomial: Polynomial {
+            constant: Duration::from_seconds(offset.evaluate(ref_epoch)),
+            rate: Duration::from_seconds(a1 + 2.0 * a2 * dt),
+            accel: Duration::from_seconds(a2),
+        },
+    }
+}
+
+/// Transmission [Epoch] of this [TimeOffset], defaulting to its reference.
+fn publication_epoch(offset: &TimeOffset) -> Epoch {
+    offset
+        .transmission_epoch()
+        .unwrap_or(offset.reference_epoch())
+}
 
 impl Rinex {
+    /// [TimeOffset] selection, that only applies to Navigation [Rinex].
+    /// ## Inputs
+    /// - lhs, rhs: [TimeScale]s of interest, in any order
+    /// - t: desired [Epoch]
+    /// ## Returns
+    /// - the most recent System Time Offset (STO) frame transmitted prior `t`, in NAV V4.
+    /// - the header [TimeOffset] whose reference is the closest to `t`, in NAV V2/V3.
+    pub fn nav_time_offset_selection(
+        &self,
+        lhs: TimeScale,
+        rhs: TimeScale,
+        t: Epoch,
+    ) -> Option<&TimeOffset> {
+        let matches = |offset: &TimeOffset| {
+            (offset.lhs == lhs && offset.rhs == rhs) || (offset.lhs == rhs && offset.rhs == lhs)
+        };
+
+        let frame = self
+            .nav_system_time_frames_iter()
+            .map(|(_, offset)| offset)
+            .filter(|offset| matches(offset) && publication_epoch(offset) <= t)
+            .max_by_key(|offset| publication_epoch(offset));
+
+        frame.or_else(|| {
+            self.header
+                .nav
+                .iter()
+                .flat_map(|header| header.time_offsets.iter())
+                .filter(|offset| matches(offset))
+                .min_by_key(|offset| (t - offset.reference_epoch()).abs())
+        })
+    }
+
+    /// Converts [Epoch] to desired [TimeScale], using the [TimeOffset] selected with
+    /// [Self::nav_time_offset_selection]. Unlike [TimeCorrectionsDB], the polynomial
+    /// is evaluated in f64: drift terms are not quantized.
+    /// Only applies to Navigation [Rinex].
+    /// ```
+    /// use std::str::FromStr;
+    /// use rinex::prelude::{Rinex, Epoch, TimeScale};
+    ///
+    /// let rinex = Rinex::from_gzip_file("data/NAV/V3/ESBC00DNK_R_20201770000_01D_MN.rnx.gz")
+    ///     .unwrap();
+    ///
+    /// let t = Epoch::from_str("2020-06-25T12:00:00 GPST").unwrap();
+    ///
+    /// let utc = rinex.nav_precise_epoch_correction(t, TimeScale::UTC)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(utc.time_scale, TimeScale::UTC);
+    ///
+    /// // GPST/BDT is not available
+    /// assert!(rinex.nav_precise_epoch_correction(t, TimeScale::BDT).is_none());
+    /// ```
+    pub fn nav_precise_epoch_correction(&self, t: Epoch, target: TimeScale) -> Option<Epoch> {
+        if t.time_scale == target {
+            return Some(t);
+        }
+
+        let offset = self.nav_time_offset_selection(t.time_scale, target, t)?;
+        // rounded to Epoch resolution
+        let correction = Duration::from_nanoseconds((offset.evaluate(t) * 1.0E9).round());
+
+        if offset.lhs == t.time_scale {
+            Some(t.to_time_scale(target) - correction)
+        } else {
+            Some(t.to_time_scale(target) + correction)
+        }
+    }
+
     /// Collect a [TimeCorrectionsDB] from this Navigation [Rinex],
     /// which you can then use for internal or external precise correction.
     /// Does not apply to any other format.
+    /// In NAV V4, each System Time Offset (STO) publication is referenced at its
+    /// transmission: the database selects the closest reference, with 1 ns
+    /// resolution. Prefer [Self::nav_precise_epoch_correction] to always apply
+    /// the most recent publication.
     /// ```
     /// use std::str::FromStr;
     /// use rinex::prelude::{Rinex, Epoch, TimeScale};
@@ -106,12 +217,16 @@ impl Rinex {
 
         let mut db = TimeCorrectionsDB::default();
 
-        // collect from possible V3 header
-        let header = self.header.nav.as_ref()?;
-
         let one_day = Duration::from_days(1.0);
 
-        for value in header.time_offsets.iter() {
+        // collect from possible V3 header
+        let header_offsets = self
+            .header
+            .nav
+            .iter()
+            .flat_map(|header| header.time_offsets.iter());
+
+        for value in header_offsets {
             let ref_epoch = Epoch::from_time_of_week(value.t_ref.0, value.t_ref.1, value.lhs);
 
             let correction = TimeCorrection {
@@ -125,9 +240,38 @@ impl Rinex {
             db.add(correction);
         }
 
-        // collect from possible V4 frames
-        // TODO: don't know how to interprate the ref time
-        // let record = self.record.as_nav()?;
+        // collect from possible V4 frames: each publication is referenced
+        // at its transmission, and valid until the next one is transmitted.
+        // Identical publications are broadcast by several SV.
+        let mut publications = Vec::<(Epoch, &TimeOffset)>::new();
+
+        for (_, offset) in self.nav_system_time_frames_iter() {
+            let t_tm = publication_epoch(offset);
+
+            if let Some((t, _)) = publications.iter_mut().find(|(_, pub_offset)| {
+                pub_offset.lhs == offset.lhs
+                    && pub_offset.rhs == offset.rhs
+                    && pub_offset.t_ref == offset.t_ref
+                    && pub_offset.polynomial == offset.polynomial
+            }) {
+                *t = (*t).min(t_tm);
+            } else {
+                publications.push((t_tm, offset));
+            }
+        }
+
+        publications.sort_by_key(|(t, _)| *t);
+
+        for (i, (t_tm, offset)) in publications.iter().enumerate() {
+            let validity_period = publications
+                .iter()
+                .skip(i + 1)
+                .find(|(t, next)| *t > *t_tm && next.lhs == offset.lhs && next.rhs == offset.rhs)
+                .map(|(t, _)| *t - *t_tm)
+                .unwrap_or(one_day);
+
+            db.add(time_correction(offset, *t_tm, validity_period));
+        }
 
         Some(db)
     }
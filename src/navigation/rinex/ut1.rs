@@ -1,4 +1,9 @@
-use crate::prelude::{ut1::DeltaTaiUt1, Duration, Rinex};
+use crate::prelude::{
+    ut1::{DeltaTaiUt1, Ut1Provider},
+    Duration, Rinex,
+};
+
+use hifitime::HifitimeError;
 
 impl Rinex {
     /// Forms a [DeltaTaiUt1] [Iterator] from all Earth Orientation parameters contained in this NAV V4 RINEX.
@@ -6,10 +11,21 @@ impl Rinex {
     pub fn nav_delta_tai_ut1_iter(&self) -> Box<dyn Iterator<Item = DeltaTaiUt1> + '_> {
         Box::new(
             self.nav_earth_orientation_frames_iter()
-                .map(|(k, eop)| DeltaTaiUt1 {
-                    epoch: k.epoch,
-                    delta_tai_minus_ut1: Duration::from_seconds(eop.delta_ut1.0),
+                .filter_map(|(k, eop)| {
+                    // broadcast parameters are UT1-UTC
+                    let tai_utc = k.epoch.leap_seconds(true)?;
+                    Some(DeltaTaiUt1 {
+                        epoch: k.epoch,
+                        delta_tai_minus_ut1: Duration::from_seconds(tai_utc - eop.delta_ut1.0),
+                    })
                 }),
         )
     }
+
+    /// Builds a [Ut1Provider] from all Earth Orientation parameters contained in this NAV V4 RINEX,
+    /// sampled hourly. See [crate::navigation::EopProvider::to_ut1_provider].
+    pub fn nav_ut1_provider(&self) -> Result<Ut1Provider, HifitimeError> {
+        self.nav_eop_provider()
+            .to_ut1_provider(Duration::from_hours(1.0))
+    }
 }
@@ -2,7 +2,10 @@ use crate::{
     epoch::epoch_decompose,
     error::FormattingError,
     fmt_rinex,
-    navigation::{formatting::NavFormatter, time::TimeOffset},
+    navigation::{
+        formatting::{format_frame_epoch_v4, NavFormatter},
+        time::TimeOffset,
+    },
     prelude::{Epoch, TimeScale},
 };
 
@@ -99,26 +102,25 @@ impl TimeOffset {
         Ok(())
     }
 
+    /// Format [TimeOffset] according to RINEXv4 standard
     pub(crate) fn format_v4<W: Write>(&self, w: &mut BufWriter<W>) -> Result<(), FormattingError> {
         let t = Epoch::from_time_of_week(self.t_ref.0, self.t_ref.1, self.lhs);
-        let (y, m, d, hh, mm, ss, _) = epoch_decompose(t);
 
-        writeln!(
-            w,
-            "    {:04} {:02} {:02} {:02} {:02} {:02} {}",
-            y,
-            m,
-            d,
-            hh,
-            mm,
-            ss,
+        let formatted = format!(
+            "{} {}",
+            format_frame_epoch_v4(t),
             self.to_lhs_rhs_timescales(),
-        )?;
+        );
+
+        match &self.utc {
+            Some(utc) => writeln!(w, "{:<62}{}", formatted, utc)?,
+            None => writeln!(w, "{}", formatted)?,
+        }
 
         writeln!(
             w,
             "    {}{}{}{}",
-            NavFormatter::new((self.t_ref.1 / 1_000_000_000) as f64),
+            NavFormatter::new(self.t_tm.unwrap_or(self.t_ref.1) as f64 / 1.0E9),
             NavFormatter::new(self.polynomial.0),
             NavFormatter::new(self.polynomial.1),
             NavFormatter::new(self.polynomial.2),
@@ -25,6 +25,10 @@ pub struct TimeOffset {
     /// Possible UTC ID# in case this came from RINEXv4
     pub utc: Option<String>,
 
+    /// Possible transmission time, expressed as nanoseconds of week,
+    /// in case this came from RINEXv4
+    pub t_tm: Option<u64>,
+
     /// Interpolation polynomial
     pub polynomial: (f64, f64, f64),
 }
@@ -43,6 +47,7 @@ impl TimeOffset {
             rhs,
             t_ref,
             utc: None,
+            t_tm: None,
             polynomial,
         }
     }
@@ -59,11 +64,53 @@ impl TimeOffset {
             lhs,
             rhs,
             utc: None,
+            t_tm: None,
             polynomial,
             t_ref: (t_week, t_nanos),
         }
     }
 
+    /// Copies and returns [TimeOffset] with transmission time,
+    /// expressed as nanoseconds of week.
+    pub fn with_transmission_time(&self, t_tm: u64) -> Self {
+        let mut s = self.clone();
+        s.t_tm = Some(t_tm);
+        s
+    }
+
+    /// Returns reference [Epoch] of this [TimeOffset], in left hand side [TimeScale].
+    pub fn reference_epoch(&self) -> Epoch {
+        Epoch::from_time_of_week(self.t_ref.0, self.t_ref.1, self.lhs)
+    }
+
+    /// Resolves the transmission [Epoch] of this [TimeOffset], if known.
+    /// The transmission time is only given within the week, we resolve it
+    /// as the one closest to the reference time, which may
+    /// belong to the previous or following week.
+    pub fn transmission_epoch(&self) -> Option<Epoch> {
+        let t_tm = self.t_tm?;
+        let t_ref = self.reference_epoch();
+        let half_week = Duration::from_days(3.5);
+
+        let t = Epoch::from_time_of_week(self.t_ref.0, t_tm, self.lhs);
+
+        if t - t_ref > half_week {
+            Some(t - Duration::from_days(7.0))
+        } else if t_ref - t > half_week {
+            Some(t + Duration::from_days(7.0))
+        } else {
+            Some(t)
+        }
+    }
+
+    /// Evaluates this [TimeOffset] polynomial at [Epoch], in seconds.
+    /// Evaluation is performed in f64: drift terms are not quantized.
+    pub fn evaluate(&self, t: Epoch) -> f64 {
+        let (a0, a1, a2) = self.polynomial;
+        let dt = (t - self.reference_epoch()).to_seconds();
+        a0 + a1 * dt + a2 * dt.powi(2)
+    }
+
     /// Converts this [TimeOffset] to Hifitime [Polynomial].
     pub(crate) fn to_hifitime_polynomial(&self) -> Polynomial {
         Polynomial {
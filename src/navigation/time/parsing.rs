@@ -148,11 +148,11 @@ impl TimeOffset {
     /// Parse [TimeOffset] from RINEXv4 standard
     pub fn parse_v4(line_1: &str, line_2: &str) -> Result<Self, ParsingError> {
         let (epoch, rem) = line_1.split_at(24);
-        let (timescales, _) = rem.split_at(4);
+        let (timescales, rem) = rem.split_at(4);
 
         let (lhs, rhs) = Self::parse_lhs_rhs_timescales(timescales)?;
 
-        // let utc = rem.trim().to_string();
+        let utc = rem.trim();
         let t_ref = parse_epoch_in_timescale(epoch.trim(), lhs)?;
         let (t_week, t_nanos) = t_ref.to_time_of_week();
 
@@ -161,11 +161,11 @@ impl TimeOffset {
         let (a1, rem) = rem.split_at(19);
         let (a2, _) = rem.split_at(19);
 
-        // let t_tm = t_tm
-        //     .trim()
-        //     .replace('D', "e")
-        //     .parse::<f64>()
-        //     .map_err(|_| ParsingError::NavTimeOffsetParinsg)?;
+        let t_tm = t_tm
+            .trim()
+            .replace('D', "e")
+            .parse::<f64>()
+            .map_err(|_| ParsingError::NavTimeOffsetParinsg)?;
 
         let (a0, a1, a2) = (
             a0.trim()
@@ -182,7 +182,12 @@ impl TimeOffset {
                 .map_err(|_| ParsingError::NavTimeOffsetParinsg)?,
         );
 
-        let time_offset = Self::from_time_of_week(t_week, t_nanos, lhs, rhs, (a0, a1, a2));
+        let mut time_offset = Self::from_time_of_week(t_week, t_nanos, lhs, rhs, (a0, a1, a2))
+            .with_transmission_time((t_tm * 1.0E9).round() as u64);
+
+        if !utc.is_empty() {
+            time_offset.utc = Some(utc.to_string());
+        }
 
         Ok(time_offset)
     }
@@ -339,7 +344,7 @@ mod test {
 
     #[test]
     fn parsing_v4() {
-        for (line_1, line_2, lhs, rhs, t_ref, t_sec, a0, a1, a2) in [
+        for (line_1, line_2, lhs, rhs, t_ref, t_sec, t_tm, a0, a1, a2) in [
             (
                 "    2022 06 08 00 00 00 GAUT                                  UTCGAL",
                 "     2.952070000000E+05-1.862645149231E-09 8.881784197001E-16 0.000000000000E+00",
@@ -347,6 +352,7 @@ mod test {
                 TimeScale::UTC,
                 "2022-06-08T00:00:00 GST",
                 295207,
+                "2022-06-08T10:00:07 GST",
                 -1.862645149231E-09,
                 8.881784197001E-16,
                 0.0,
@@ -358,6 +364,7 @@ mod test {
                 TimeScale::UTC,
                 "2022-06-10T19:56:48 GPST",
                 295284,
+                "2022-06-08T10:01:24 GPST",
                 9.313225746155E-10,
                 2.664535259100E-15,
                 0.0,
@@ -371,7 +378,11 @@ mod test {
             assert_eq!(time_offset.lhs, lhs);
             assert_eq!(time_offset.rhs, rhs);
             assert_eq!(time_offset.t_ref.0, t_ref_week);
-            //assert_eq!(time_offset.t_ref.1, t_sec * 1_000_000_000);
+            assert_eq!(time_offset.reference_epoch(), t_ref);
+            assert_eq!(time_offset.t_tm, Some(t_sec * 1_000_000_000));
+
+            let t_tm = Epoch::from_str(t_tm).unwrap();
+            assert_eq!(time_offset.transmission_epoch(), Some(t_tm));
 
             assert_eq!(time_offset.polynomial, (a0, a1, a2),);
 
@@ -383,13 +394,18 @@ mod test {
 
             for (index, line) in formatted.split('\n').enumerate() {
                 if index == 0 {
-                    // assert_eq!(line, line_1);
+                    assert_eq!(line, line_1);
                 } else if index == 1 {
-                    // assert_eq!(line, line_2);
+                    assert_eq!(line, line_2);
                 } else if index == 3 {
                     panic!("two lines expected (only)!");
                 }
             }
+
+            let mut lines = formatted.lines();
+            let reparsed =
+                TimeOffset::parse_v4(lines.next().unwrap(), lines.next().unwrap()).unwrap();
+            assert_eq!(reparsed, time_offset);
         }
     }
 }
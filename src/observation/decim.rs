@@ -1,10 +1,64 @@
 //! Observation RINEX decimation
-use crate::{observation::Record, prelude::Epoch};
-use qc_traits::{DecimationFilter, DecimationFilterType};
+use crate::{
+    observation::{Record, SignalObservation},
+    prelude::{Constellation, Epoch, Observable},
+    processing::{unsupported_target, TargetDecimator},
+};
+
+use qc_traits::{DecimationFilter, DecimationFilterType, FilterItem};
+
+use std::str::FromStr;
+
+/// Returns true if this [SignalObservation] is targeted by [FilterItem]
+fn targeted(item: &FilterItem, signal: &SignalObservation) -> bool {
+    match item {
+        FilterItem::SvItem(svs) => svs.contains(&signal.sv),
+        FilterItem::ConstellationItem(constellations) => {
+            constellations.contains(&signal.sv.constellation)
+                || (signal.sv.constellation.is_sbas()
+                    && constellations.contains(&Constellation::SBAS))
+        },
+        FilterItem::ComplexItem(items) => items
+            .iter()
+            .filter_map(|item| Observable::from_str(item).ok())
+            .any(|observable| observable == signal.observable),
+        _ => false,
+    }
+}
+
+/// Decimates targeted signals (or clock) only
+fn targeted_decim_mut(rec: &mut Record, item: &FilterItem, filter: &DecimationFilterType) {
+    let mut signals = TargetDecimator::new(filter);
+    let mut clock = TargetDecimator::new(filter);
+
+    rec.retain(|k, obs| {
+        if *item == FilterItem::ClockItem {
+            if obs.clock.is_some() && !clock.retain((), k.epoch) {
+                obs.clock = None;
+            }
+        } else {
+            obs.signals.retain(|sig| {
+                if targeted(item, sig) {
+                    signals.retain((sig.sv, sig.observable.clone()), k.epoch)
+                } else {
+                    true
+                }
+            });
+        }
+        obs.clock.is_some() || !obs.signals.is_empty()
+    });
+}
 
 pub(crate) fn decim_mut(rec: &mut Record, decim: &DecimationFilter) {
-    if decim.item.is_some() {
-        todo!("targetted decimation not supported yet");
+    if let Some(item) = &decim.item {
+        match item {
+            FilterItem::SvItem(_)
+            | FilterItem::ConstellationItem(_)
+            | FilterItem::ComplexItem(_)
+            | FilterItem::ClockItem => targeted_decim_mut(rec, item, &decim.filter),
+            _ => unsupported_target("Observation", item),
+        }
+        return;
     }
     match decim.filter {
         DecimationFilterType::Modulo(r) => {
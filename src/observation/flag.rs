@@ -36,6 +36,18 @@ impl EpochFlag {
     pub fn is_ok(self) -> bool {
         self == Self::Ok
     }
+
+    /// Returns true if this flag describes a special event (flag 2-5),
+    /// that is followed by special records (Header lines) instead of observations.
+    pub fn is_event(self) -> bool {
+        matches!(
+            self,
+            Self::AntennaBeingMoved
+                | Self::NewSiteOccupation
+                | Self::HeaderInformationFollows
+                | Self::ExternalEvent
+        )
+    }
 }
 
 impl FromStr for EpochFlag {
@@ -105,4 +117,13 @@ mod test {
         assert_eq!(format!("{}", EpochFlag::ExternalEvent), "5");
         assert_eq!(format!("{}", EpochFlag::CycleSlip), "6");
     }
+    #[test]
+    fn events() {
+        for flag in ["0", "1", "6"] {
+            assert!(!EpochFlag::from_str(flag).unwrap().is_event());
+        }
+        for flag in ["2", "3", "4", "5"] {
+            assert!(EpochFlag::from_str(flag).unwrap().is_event());
+        }
+    }
 }
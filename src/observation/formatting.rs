@@ -29,6 +29,10 @@ impl Observations {
 
         let numsat = sv_list.len();
 
+        if key.flag.is_event() {
+            return self.format_event(v2, key, w);
+        }
+
         if v2 {
             self.format_v2(w, key, &header, &sv_list, numsat)
         } else {
@@ -36,6 +40,39 @@ impl Observations {
         }
     }
 
+    /// Formats special event: epoch descriptor (without clock) followed by
+    /// the special records, written as is.
+    fn format_event<W: Write>(
+        &self,
+        v2: bool,
+        key: &ObsKey,
+        w: &mut BufWriter<W>,
+    ) -> Result<(), FormattingError> {
+        if v2 {
+            writeln!(
+                w,
+                " {}  {}{:3}",
+                epoch_format(key.epoch, RinexType::ObservationData, 2),
+                key.flag,
+                self.special_records.len(),
+            )?;
+        } else {
+            writeln!(
+                w,
+                "> {}  {}{:3}",
+                epoch_format(key.epoch, RinexType::ObservationData, 3),
+                key.flag,
+                self.special_records.len(),
+            )?;
+        }
+
+        for record in self.special_records.iter() {
+            writeln!(w, "{}", record)?;
+        }
+
+        Ok(())
+    }
+
     /// Formats [Observations] according to RINEXv2 standards.
     fn format_v2<W: Write>(
         &self,
@@ -248,9 +285,10 @@ impl Observations {
         numsat: usize,
     ) -> Result<(), FormattingError> {
         if let Some(clock) = self.clock {
+            // receiver clock offset: 6X,F15.12 (s), with picosecond resolution
             writeln!(
                 w,
-                "> {}  {} {:2}  {:13.4}",
+                "> {}  {} {:2}      {:15.12}",
                 epoch_format(key.epoch, RinexType::ObservationData, 3),
                 key.flag,
                 numsat,
@@ -297,6 +335,7 @@ mod test {
 
         let obs = Observations {
             clock: None,
+            special_records: Vec::new(),
             signals: vec![
                 SignalObservation {
                     value: 1.0,
@@ -390,6 +429,7 @@ mod test {
 
         let obs = Observations {
             clock: None,
+            special_records: Vec::new(),
             signals: vec![
                 SignalObservation {
                     value: 1.0,
@@ -555,6 +595,7 @@ mod test {
 
         let obs = Observations {
             clock: None,
+            special_records: Vec::new(),
             signals: vec![
                 SignalObservation {
                     value: 1.0,
@@ -671,6 +712,7 @@ mod test {
 
         let mut obs = Observations {
             clock: None,
+            special_records: Vec::new(),
             signals: Vec::new(),
         };
 
@@ -756,6 +798,7 @@ mod test {
 
         let mut obs = Observations {
             clock: None,
+            special_records: Vec::new(),
             signals: Vec::new(),
         };
 
@@ -31,7 +31,10 @@ pub use lli::LliFlags;
 pub use signal::SignalObservation;
 pub use snr::SNR;
 
-pub(crate) use parsing::{is_new_epoch, parse_epoch};
+pub(crate) use parsing::{is_new_epoch, num_event_records, parse_epoch};
+
+#[cfg(feature = "parallel")]
+pub(crate) use parsing::is_event_epoch;
 
 #[cfg(docsrs)]
 use crate::Bibliography;
@@ -52,6 +55,10 @@ pub struct Observations {
     pub clock: Option<ClockObservation>,
     /// List of [SignalObservation]s.
     pub signals: Vec<SignalObservation>,
+    /// Special records, that only exist in special events (see [EpochFlag::is_event]).
+    /// These are Header lines (antenna or site changes, comments..) stored as is,
+    /// that describe the new context. For example, kinematic to static transitions.
+    pub special_records: Vec<String>,
 }
 
 impl Default for Observations {
@@ -59,6 +66,7 @@ impl Default for Observations {
         Self {
             clock: None,
             signals: Vec::with_capacity(16),
+            special_records: Vec::new(),
         }
     }
 }
@@ -80,6 +88,13 @@ impl Observations {
         s.clock = Some(clock);
         s
     }
+    /// Define [Observations] with one more special record (Header line),
+    /// only relevant to special events.
+    pub fn with_special_record(&self, record: &str) -> Self {
+        let mut s = self.clone();
+        s.special_records.push(record.to_string());
+        s
+    }
 }
 
 /// [ObsKey] is used to Index [Observations] in [Record] type definition.
@@ -4,7 +4,7 @@ use crate::{
     observation::{
         ClockObservation, EpochFlag, LliFlags, ObsKey, Observations, SignalObservation, SNR,
     },
-    prelude::{Constellation, Header, Observable, ParsingError, TimeScale, Version, SV},
+    prelude::{Constellation, Epoch, Header, Observable, ParsingError, TimeScale, Version, SV},
 };
 
 use std::{
@@ -54,11 +54,48 @@ pub fn is_new_epoch(line: &str, v: Version) -> bool {
     }
 }
 
+/// Returns the number of special records following this epoch descriptor,
+/// when it describes a special event (flag 2-5). Returns 0 otherwise.
+pub fn num_event_records(line: &str, v: Version) -> usize {
+    let offset = flag_offset(v);
+
+    match epoch_flag(line, v) {
+        Some(flag) if flag.is_event() => line
+            .get(offset + 3..offset + 6)
+            .and_then(|numsat| numsat.trim().parse::<usize>().ok())
+            .unwrap_or(0),
+        _ => 0,
+    }
+}
+
+/// Returns true if this epoch descriptor describes a special event (flag 2-5).
+#[cfg(feature = "parallel")]
+pub fn is_event_epoch(line: &str, v: Version) -> bool {
+    epoch_flag(line, v).is_some_and(|flag| flag.is_event())
+}
+
+/// Offset of [EpochFlag] in epoch descriptor
+fn flag_offset(v: Version) -> usize {
+    if v.major < 3 {
+        26
+    } else {
+        29
+    }
+}
+
+/// Returns [EpochFlag] of this epoch descriptor
+fn epoch_flag(line: &str, v: Version) -> Option<EpochFlag> {
+    let offset = flag_offset(v);
+    line.get(offset..offset + 3)
+        .and_then(|flag| EpochFlag::from_str(flag.trim()).ok())
+}
+
 /// Parses record entries from readable content
 /// ## Input
 ///   - header: reference to previously parsed [Header]
 ///   - content: readable content
 ///   - ts: [TimeScale] defined by [Header]
+///   - prev: previous [Epoch], special events may omit the timestamp
 ///   - observations: preallocated [Observations] for performance issue.
 /// ## Output
 ///   - [ObsKey] record indexer
@@ -66,6 +103,7 @@ pub fn parse_epoch(
     header: &Header,
     content: &str,
     ts: TimeScale,
+    prev: Option<Epoch>,
     observations: &mut Observations,
 ) -> Result<ObsKey, ParsingError> {
     let mut lines = content.lines();
@@ -94,11 +132,16 @@ pub fn parse_epoch(
     }
 
     let (date, rem) = line.split_at(offset);
-    let epoch = parse_epoch_in_timescale(date, ts)?;
 
     let (flag, rem) = rem.split_at(3);
     let flag = EpochFlag::from_str(flag.trim())?;
 
+    let epoch = if flag.is_event() && date.trim().is_empty() {
+        prev.ok_or(ParsingError::EmptyEpoch)?
+    } else {
+        parse_epoch_in_timescale(date, ts)?
+    };
+
     let key = ObsKey { epoch, flag };
 
     let (num_sat, rem) = rem.split_at(3);
@@ -139,19 +182,22 @@ pub fn parse_epoch(
 
     if let Some(offset) = offs {
         if let Ok(offset_s) = offset.parse::<f64>() {
-            observations
-                .with_clock_observation(ClockObservation::default().with_offset_s(epoch, offset_s));
+            observations.clock = Some(ClockObservation::default().with_offset_s(epoch, offset_s));
         }
     }
 
-    match flag {
-        EpochFlag::Ok | EpochFlag::PowerFailure | EpochFlag::CycleSlip => {
-            parse_observations(header, num_sat, rem, lines, &mut observations.signals)?;
-        },
-        _ => {
-            // Hardware events are not supported yet (coming soon)
-            return Err(ParsingError::ObsHardwareEvent);
-        },
+    if flag.is_event() {
+        // special records are stored as is
+        for _ in 0..num_sat {
+            match lines.next() {
+                Some(line) => observations
+                    .special_records
+                    .push(line.trim_end().to_string()),
+                None => return Err(ParsingError::ObsEventMissingData),
+            }
+        }
+    } else {
+        parse_observations(header, num_sat, rem, lines, &mut observations.signals)?;
     }
 
     Ok(key)
@@ -541,8 +587,8 @@ fn parse_signals_v3(
 mod test {
     use super::is_new_epoch;
     use crate::{
-        observation::{EpochFlag, SignalObservation, SNR},
-        prelude::{Constellation, Observable, Version, SV},
+        observation::{ClockObservation, EpochFlag, SignalObservation, SNR},
+        prelude::{Constellation, Epoch, Observable, Version, SV},
         tests::toolkit::generic_observation_epoch_decoding_test,
     };
     use std::str::FromStr;
@@ -611,7 +657,10 @@ G09  25493930.890   133971510.403 6        41.250                    25493926.95
             30,
             "2022-03-04T00:00:00 GPST",
             EpochFlag::Ok,
-            None,
+            Some(ClockObservation::default().with_offset_s(
+                Epoch::from_str("2022-03-04T00:00:00 GPST").unwrap(),
+                0.0,
+            )),
             vec![
                 SignalObservation {
                     sv: SV::from_str("G01").unwrap(),
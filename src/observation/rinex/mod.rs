@@ -100,6 +100,26 @@ impl Rinex {
         }))
     }
 
+    /// Returns special events Iterator: [ObsKey] (see [crate::observation::EpochFlag::is_event])
+    /// and the special records (Header lines) that describe the new context.
+    /// ```
+    /// use rinex::prelude::*;
+    /// let rinex = Rinex::from_file("data/OBS/V3/DUTH0630.22O")
+    ///     .unwrap();
+    /// for (key, records) in rinex.observation_events_iter() {
+    ///     println!("{} (flag={}): {:?}", key.epoch, key.flag, records);
+    /// }
+    /// ```
+    pub fn observation_events_iter(&self) -> Box<dyn Iterator<Item = (ObsKey, &[String])> + '_> {
+        Box::new(self.observations_iter().filter_map(|(k, v)| {
+            if k.flag.is_event() {
+                Some((*k, v.special_records.as_slice()))
+            } else {
+                None
+            }
+        }))
+    }
+
     /// Pseudo Range observation Iterator
     pub fn pseudo_range_observations_iter(
         &self,
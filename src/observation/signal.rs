@@ -49,7 +49,7 @@ impl SignalObservation {
     ///  - NB: when both are missing, we still return OK.
     /// This allows method that Iterate over OK Epoch Data to consider
     /// data when SNR or LLI are missing.
-    pub fn is_ok(self) -> bool {
+    pub fn is_ok(&self) -> bool {
         let lli_ok = self.lli.unwrap_or(LliFlags::OK_OR_UNKNOWN) == LliFlags::OK_OR_UNKNOWN;
         let snr_ok = self.snr.unwrap_or_default().strong();
         lli_ok && snr_ok
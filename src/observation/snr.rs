@@ -153,6 +153,23 @@ impl From<u8> for SNR {
     }
 }
 
+impl From<SNR> for u8 {
+    fn from(snr: SNR) -> Self {
+        match snr {
+            SNR::DbHz0 => 0,
+            SNR::DbHz12 => 1,
+            SNR::DbHz12_17 => 2,
+            SNR::DbHz18_23 => 3,
+            SNR::DbHz24_29 => 4,
+            SNR::DbHz30_35 => 5,
+            SNR::DbHz36_41 => 6,
+            SNR::DbHz42_47 => 7,
+            SNR::DbHz48_53 => 8,
+            SNR::DbHz54 => 9,
+        }
+    }
+}
+
 impl SNR {
     /// Returns true if self describes a bad signal level
     pub fn bad(self) -> bool {
@@ -196,6 +213,7 @@ mod test {
         assert!(snr.excellent());
         assert_eq!(format!("{:x}", snr), "8");
         assert_eq!(format!("{:e}", snr), "[48, 53[ dB");
+        assert_eq!(u8::from(snr), 8);
 
         let snr: SNR = SNR::from(31.3);
         assert_eq!(snr, SNR::DbHz30_35);
@@ -1,11 +1,65 @@
 use crate::{
-    clock::record::clock_decim_mut, doris::decim::decim_mut as doris_decim_mut,
-    ionex::decim_mut as ionex_decim_mut, meteo::decim::decim_mut as meteo_decim_mut,
+    clock::record::clock_decim_mut,
+    doris::decim::decim_mut as doris_decim_mut,
+    ionex::decim_mut as ionex_decim_mut,
+    meteo::decim::decim_mut as meteo_decim_mut,
     navigation::decim::decim_mut as navigation_decim_mut,
-    observation::decim::decim_mut as observation_decim_mut, prelude::Rinex,
+    observation::decim::decim_mut as observation_decim_mut,
+    prelude::{Epoch, Rinex},
 };
 
-use qc_traits::{Decimate, DecimationFilter};
+use qc_traits::{Decimate, DecimationFilter, DecimationFilterType, FilterItem};
+
+#[cfg(feature = "log")]
+use log::error;
+
+use std::{collections::HashMap, hash::Hash};
+
+/// Decimation state of each targeted item, used in targeted decimation.
+pub(crate) struct TargetDecimator<K> {
+    filter: DecimationFilterType,
+    states: HashMap<K, (u32, Option<Epoch>)>,
+}
+
+impl<K: Hash + Eq> TargetDecimator<K> {
+    pub fn new(filter: &DecimationFilterType) -> Self {
+        Self {
+            filter: filter.clone(),
+            states: Default::default(),
+        }
+    }
+
+    /// Returns true if this target should be retained at this [Epoch].
+    /// Targets must be presented in chronological order.
+    pub fn retain(&mut self, target: K, epoch: Epoch) -> bool {
+        let (count, last_retained) = self.states.entry(target).or_default();
+
+        match self.filter {
+            DecimationFilterType::Modulo(r) => {
+                let retained = (*count % r) == 0;
+                *count += 1;
+                retained
+            },
+            DecimationFilterType::Duration(interval) => match last_retained {
+                Some(last) if epoch - *last < interval => false,
+                _ => {
+                    *last_retained = Some(epoch);
+                    true // always retain 1st epoch
+                },
+            },
+        }
+    }
+}
+
+/// Reports a [FilterItem] that may not be targeted in this type of record.
+/// Targeted decimation then leaves the record untouched.
+pub(crate) fn unsupported_target(record: &str, item: &FilterItem) {
+    #[cfg(feature = "log")]
+    error!("{} decimation: {:?} may not be targeted", record, item);
+
+    #[cfg(not(feature = "log"))]
+    let _ = (record, item);
+}
 
 impl Decimate for Rinex {
     fn decimate(&self, f: &DecimationFilter) -> Self {
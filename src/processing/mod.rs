@@ -3,6 +3,8 @@ use qc_traits::Preprocessing;
 use crate::prelude::Rinex;
 
 mod decim;
+pub(crate) use decim::{unsupported_target, TargetDecimator};
+
 mod repair;
 mod split;
 mod timeshift;
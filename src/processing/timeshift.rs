@@ -1,34 +1,92 @@
-use crate::{
-    observation::{ObsKey, Observations},
-    prelude::{Duration, Header, Rinex, TimeScale},
-};
+use crate::prelude::{Duration, Epoch, Header, Rinex, TimeScale};
 
 use qc_traits::{TimeCorrectionError, TimeCorrectionsDB, Timeshift};
 
 use std::collections::BTreeMap;
 
-impl Timeshift for Header {
-    fn timeshift(&self, timescale: TimeScale) -> Self
-    where
-        Self: Sized,
-    {
-        let mut s = self.clone();
-        s.timeshift_mut(timescale);
-        s
+/// Rebuilds this record, with all keys transposed by the provided method.
+fn transpose_record_mut<K: Ord + Clone, V: Clone, F: Fn(&K) -> Result<K, TimeCorrectionError>>(
+    rec: &mut BTreeMap<K, V>,
+    transpose: F,
+) -> Result<(), TimeCorrectionError> {
+    let mut new_rec = BTreeMap::<K, V>::new();
+
+    for (k, v) in rec.iter() {
+        new_rec.insert(transpose(k)?, v.clone());
     }
 
-    fn timeshift_mut(&mut self, timescale: TimeScale) {
+    *rec = new_rec;
+    Ok(())
+}
+
+/// Transposes [Epoch] using [TimeCorrectionsDB].
+fn precise_epoch_correction(
+    db: &TimeCorrectionsDB,
+    t: Epoch,
+    timescale: TimeScale,
+) -> Result<Epoch, TimeCorrectionError> {
+    db.precise_epoch_correction(t, timescale)
+        .ok_or(TimeCorrectionError::NoCorrectionAvailable(
+            t.time_scale,
+            timescale,
+        ))
+}
+
+impl Header {
+    /// Transposes all [Header] epochs to desired [TimeScale], using provided method.
+    fn transpose_mut<F: Fn(Epoch) -> Result<Epoch, TimeCorrectionError>>(
+        &mut self,
+        timescale: TimeScale,
+        transpose: F,
+    ) -> Result<(), TimeCorrectionError> {
         let one_us = Duration::from_microseconds(1.0);
 
         if let Some(obs) = &mut self.obs {
             if let Some(epoch) = &mut obs.timeof_first_obs {
-                *epoch = epoch.to_time_scale(timescale).round(one_us);
+                *epoch = transpose(*epoch)?.round(one_us);
             }
 
             if let Some(epoch) = &mut obs.timeof_last_obs {
-                *epoch = epoch.to_time_scale(timescale).round(one_us);
+                *epoch = transpose(*epoch)?.round(one_us);
             }
         }
+
+        if let Some(doris) = &mut self.doris {
+            if let Some(epoch) = &mut doris.timeof_first_obs {
+                *epoch = transpose(*epoch)?.round(one_us);
+            }
+
+            if let Some(epoch) = &mut doris.timeof_last_obs {
+                *epoch = transpose(*epoch)?.round(one_us);
+            }
+        }
+
+        if let Some(ionex) = &mut self.ionex {
+            ionex.epoch_of_first_map = transpose(ionex.epoch_of_first_map)?.round(one_us);
+            ionex.epoch_of_last_map = transpose(ionex.epoch_of_last_map)?.round(one_us);
+        }
+
+        if let Some(clock) = &mut self.clock {
+            clock.timescale = Some(timescale);
+        }
+
+        Ok(())
+    }
+}
+
+impl Timeshift for Header {
+    fn timeshift(&self, timescale: TimeScale) -> Self
+    where
+        Self: Sized,
+    {
+        let mut s = self.clone();
+        s.timeshift_mut(timescale);
+        s
+    }
+
+    fn timeshift_mut(&mut self, timescale: TimeScale) {
+        // infallible
+        let _ = self.transpose_mut(timescale, |t| Ok(t.to_time_scale(timescale)));
     }
 
     fn precise_correction(
@@ -49,30 +107,94 @@ impl Timeshift for Header {
         db: &TimeCorrectionsDB,
         timescale: TimeScale,
     ) -> Result<(), TimeCorrectionError> {
-        let one_us = Duration::from_microseconds(1.0);
-
-        if let Some(obs) = &mut self.obs {
-            if let Some(epoch) = &mut obs.timeof_first_obs {
-                *epoch = db
-                    .precise_epoch_correction(*epoch, timescale)
-                    .ok_or(TimeCorrectionError::NoCorrectionAvailable(
-                        epoch.time_scale,
-                        timescale,
-                    ))?
-                    .round(one_us);
-            }
+        // self is only modified on success
+        let mut s = self.clone();
+        s.transpose_mut(timescale, |t| precise_epoch_correction(db, t, timescale))?;
+        *self = s;
+        Ok(())
+    }
+}
 
-            if let Some(epoch) = &mut obs.timeof_last_obs {
-                *epoch = db
-                    .precise_epoch_correction(*epoch, timescale)
-                    .ok_or(TimeCorrectionError::NoCorrectionAvailable(
-                        epoch.time_scale,
-                        timescale,
-                    ))?
-                    .round(one_us);
-            }
+impl Rinex {
+    /// Transposes all record epochs using provided method.
+    /// The record is only modified on success.
+    fn transpose_record_mut<F: Fn(Epoch) -> Result<Epoch, TimeCorrectionError>>(
+        &mut self,
+        transpose: F,
+    ) -> Result<(), TimeCorrectionError> {
+        if let Some(rec) = self.record.as_mut_obs() {
+            transpose_record_mut(rec, |k| {
+                let mut k = *k;
+                k.epoch = transpose(k.epoch)?;
+                Ok(k)
+            })
+        } else if let Some(rec) = self.record.as_mut_nav() {
+            // NAV keys remain expressed in the SV timescale, as RINEX formatting requires
+            transpose_record_mut(rec, |k| {
+                let mut k = *k;
+                k.epoch = transpose(k.epoch)?.to_time_scale(k.epoch.time_scale);
+                Ok(k)
+            })
+        } else if let Some(rec) = self.record.as_mut_clock() {
+            transpose_record_mut(rec, |t| transpose(*t))
+        } else if let Some(rec) = self.record.as_mut_meteo() {
+            transpose_record_mut(rec, |k| {
+                let mut k = k.clone();
+                k.epoch = transpose(k.epoch)?;
+                Ok(k)
+            })
+        } else if let Some(rec) = self.record.as_mut_doris() {
+            transpose_record_mut(rec, |k| {
+                let mut k = k.clone();
+                k.epoch = transpose(k.epoch)?;
+                Ok(k)
+            })
+        } else if let Some(rec) = self.record.as_mut_ionex() {
+            transpose_record_mut(rec, |k| {
+                let mut k = *k;
+                k.epoch = transpose(k.epoch)?;
+                Ok(k)
+            })
+        } else {
+            Ok(())
         }
+    }
 
+    /// [Timeshift::precise_correction] using the System Time Offsets of this
+    /// Navigation [Rinex] (see [Rinex::nav_precise_epoch_correction]), rather than a [TimeCorrectionsDB]:
+    /// the most recent publication always applies and drift terms are not quantized.
+    #[cfg(feature = "nav")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "nav")))]
+    pub fn precise_nav_correction(
+        &self,
+        nav: &Rinex,
+        timescale: TimeScale,
+    ) -> Result<Self, TimeCorrectionError> {
+        let mut s = self.clone();
+        s.precise_nav_correction_mut(nav, timescale)?;
+        Ok(s)
+    }
+
+    /// [Self::precise_nav_correction] mutable implementation.
+    /// Self is only modified on success.
+    #[cfg(feature = "nav")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "nav")))]
+    pub fn precise_nav_correction_mut(
+        &mut self,
+        nav: &Rinex,
+        timescale: TimeScale,
+    ) -> Result<(), TimeCorrectionError> {
+        let transpose = |t: Epoch| {
+            nav.nav_precise_epoch_correction(t, timescale).ok_or(
+                TimeCorrectionError::NoCorrectionAvailable(t.time_scale, timescale),
+            )
+        };
+
+        let mut header = self.header.clone();
+        header.transpose_mut(timescale, transpose)?;
+
+        self.transpose_record_mut(transpose)?;
+        self.header = header;
         Ok(())
     }
 }
@@ -90,18 +212,8 @@ impl Timeshift for Rinex {
     fn timeshift_mut(&mut self, timescale: TimeScale) {
         self.header.timeshift_mut(timescale);
 
-        if let Some(obs_rec) = self.record.as_mut_obs() {
-            let mut new_rec = BTreeMap::<ObsKey, Observations>::new();
-
-            for (k, values) in obs_rec.iter() {
-                let transposed = k.epoch.to_time_scale(timescale);
-                let mut key = k.clone();
-                key.epoch = transposed;
-                new_rec.insert(key, values.clone());
-            }
-
-            *obs_rec = new_rec;
-        }
+        // infallible
+        let _ = self.transpose_record_mut(|t| Ok(t.to_time_scale(timescale)));
     }
 
     fn precise_correction(
@@ -122,24 +234,10 @@ impl Timeshift for Rinex {
         db: &TimeCorrectionsDB,
         timescale: TimeScale,
     ) -> Result<(), TimeCorrectionError> {
-        self.header.precise_correction_mut(db, timescale)?;
-
-        if let Some(obs_rec) = self.record.as_mut_obs() {
-            let mut new_rec = BTreeMap::<ObsKey, Observations>::new();
-
-            for (k, values) in obs_rec.iter() {
-                let transposed = db.precise_epoch_correction(k.epoch, timescale).ok_or(
-                    TimeCorrectionError::NoCorrectionAvailable(k.epoch.time_scale, timescale),
-                )?;
-
-                let mut key = k.clone();
-                key.epoch = transposed;
-                new_rec.insert(key, values.clone());
-            }
-
-            *obs_rec = new_rec;
-        }
-
+        // header is only committed once the record has been corrected
+        let header = self.header.precise_correction(db, timescale)?;
+        self.transpose_record_mut(|t| precise_epoch_correction(db, t, timescale))?;
+        self.header = header;
         Ok(())
     }
 }
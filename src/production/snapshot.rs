@@ -1,6 +1,12 @@
 //! RINEX collection option
 use crate::prelude::Duration;
 
+#[cfg(any(feature = "binex", feature = "rtcm"))]
+use crate::prelude::Epoch;
+
+#[cfg(any(feature = "binex", feature = "rtcm"))]
+use hifitime::Unit;
+
 /// [SnapshotMode] is used by RINEX collection methods,
 /// like [BIN2RNX] for example, that needs to collect a RINEX
 /// (usually of fixed duration)
@@ -27,4 +33,50 @@ impl SnapshotMode {
     pub fn periodic(dt: Duration) -> Self {
         Self::Periodic(dt)
     }
+
+    /// Returns true if [Epoch] "t" no longer belongs to the
+    /// snapshot that started at "t0", and a new snapshot should be published.
+    /// Boundaries are expressed in the timescale of "t0".
+    #[cfg(any(feature = "binex", feature = "rtcm"))]
+    pub(crate) fn is_new_snapshot(&self, t0: Epoch, t: Epoch) -> bool {
+        let t = t.to_time_scale(t0.time_scale);
+        match self {
+            Self::DailyMidnight => t.floor(1.0 * Unit::Day) != t0.floor(1.0 * Unit::Day),
+            Self::DailyMidnightNoon => t.floor(12.0 * Unit::Hour) != t0.floor(12.0 * Unit::Hour),
+            Self::Hourly => t.floor(1.0 * Unit::Hour) != t0.floor(1.0 * Unit::Hour),
+            Self::Periodic(dt) => t - t0 >= *dt,
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(any(feature = "binex", feature = "rtcm"))]
+mod test {
+    use super::SnapshotMode;
+    use crate::prelude::{Duration, Epoch};
+    use std::str::FromStr;
+
+    #[test]
+    fn snapshot_boundaries() {
+        let t0 = Epoch::from_str("2020-01-01T23:00:00 GPST").unwrap();
+        let t1 = Epoch::from_str("2020-01-01T23:59:30 GPST").unwrap();
+        let t2 = Epoch::from_str("2020-01-02T00:00:00 GPST").unwrap();
+
+        assert!(!SnapshotMode::DailyMidnight.is_new_snapshot(t0, t1));
+        assert!(SnapshotMode::DailyMidnight.is_new_snapshot(t0, t2));
+        assert!(!SnapshotMode::Hourly.is_new_snapshot(t0, t1));
+        assert!(SnapshotMode::Hourly.is_new_snapshot(t1, t2));
+
+        let t3 = Epoch::from_str("2020-01-02T12:00:00 GPST").unwrap();
+        assert!(!SnapshotMode::DailyMidnightNoon.is_new_snapshot(t2, t2));
+        assert!(SnapshotMode::DailyMidnightNoon.is_new_snapshot(t2, t3));
+
+        let periodic = SnapshotMode::periodic(Duration::from_seconds(60.0));
+        assert!(!periodic.is_new_snapshot(t0, t0));
+        assert!(periodic.is_new_snapshot(t1, t2 + Duration::from_seconds(30.0)));
+
+        let t4 = Epoch::from_str("2020-01-01T23:30:00 UTC").unwrap();
+        assert!(!SnapshotMode::DailyMidnight.is_new_snapshot(t0, t4));
+        assert!(!SnapshotMode::Hourly.is_new_snapshot(t0, t4));
+    }
 }
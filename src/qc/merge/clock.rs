@@ -1,12 +1,33 @@
-use crate::{clock::Record, prelude::qc::MergeError};
+use crate::{
+    clock::Record,
+    prelude::qc::{MergeConflict, MergeError, MergePolicy},
+};
 
 use super::merge_mut_option;
 
-pub fn merge_mut(rec: &mut Record, rhs: &Record) -> Result<(), MergeError> {
+pub fn merge_mut(
+    rec: &mut Record,
+    rhs: &Record,
+    policy: MergePolicy,
+    conflicts: &mut Vec<MergeConflict>,
+) -> Result<(), MergeError> {
     for (rhs_epoch, rhs_content) in rhs.iter() {
         if let Some(lhs_content) = rec.get_mut(rhs_epoch) {
             for (rhs_key, rhs_prof) in rhs_content.iter() {
                 if let Some(lhs_prof) = lhs_content.get_mut(rhs_key) {
+                    if lhs_prof.bias != rhs_prof.bias {
+                        conflicts.push(MergeConflict::Clock {
+                            epoch: *rhs_epoch,
+                            key: rhs_key.clone(),
+                            lhs: lhs_prof.bias,
+                            rhs: rhs_prof.bias,
+                        });
+
+                        if policy == MergePolicy::KeepRight {
+                            *lhs_prof = rhs_prof.clone();
+                        }
+                    }
+
                     // enhance only, if possible
                     merge_mut_option(&mut lhs_prof.drift, &rhs_prof.drift);
                     merge_mut_option(&mut lhs_prof.drift_dev, &rhs_prof.drift_dev);
@@ -1,11 +1,11 @@
 use crate::prelude::{
-    qc::{Merge, MergeError},
+    qc::{Merge, MergeError, RinexMergeError},
     Constellation, Epoch, Header,
 };
 
 use super::{
-    merge_mut_option, merge_mut_unique_vec, merge_mut_vec, merge_obsrinex_observables,
-    merge_time_of_first_obs, merge_time_of_last_obs,
+    merge_glo_channels, merge_mut_option, merge_mut_unique_vec, merge_mut_vec, merge_obs_scaling,
+    merge_obsrinex_observables, merge_time_of_first_obs, merge_time_of_last_obs,
 };
 
 impl Merge for Header {
@@ -18,8 +18,17 @@ impl Merge for Header {
         Ok(lhs)
     }
     fn merge_mut(&mut self, rhs: &Self) -> Result<(), MergeError> {
+        self.merge_header_mut(rhs)?;
+        Ok(())
+    }
+}
+
+impl Header {
+    /// [Merge::merge_mut] implementation, that describes
+    /// the RINEX specific failures.
+    pub(crate) fn merge_header_mut(&mut self, rhs: &Self) -> Result<(), RinexMergeError> {
         if self.rinex_type != rhs.rinex_type {
-            return Err(MergeError::FileTypeMismatch);
+            return Err(MergeError::FileTypeMismatch.into());
         }
 
         let (a_cst, b_cst) = (self.constellation, rhs.constellation);
@@ -89,7 +98,7 @@ impl Merge for Header {
                 .count();
         }
 
-        // TODO: merge::merge_mut(&mut self.glo_channels, &rhs.glo_channels);
+        merge_glo_channels(&mut self.glo_channels, &rhs.glo_channels)?;
 
         // RINEX specific operation
         if let Some(lhs) = &mut self.antex {
@@ -99,7 +108,7 @@ impl Merge for Header {
                 let mut mixed_antex = lhs.pcv_type.is_relative() && !rhs.pcv_type.is_relative();
                 mixed_antex |= !lhs.pcv_type.is_relative() && rhs.pcv_type.is_relative();
                 if mixed_antex {
-                    return Err(MergeError::FileTypeMismatch);
+                    return Err(MergeError::FileTypeMismatch.into());
                 }
                 //TODO: merge_mut_option(&mut lhs.reference_sn, &rhs.reference_sn);
             }
@@ -122,7 +131,13 @@ impl Merge for Header {
                 merge_time_of_first_obs(&mut lhs.timeof_first_obs, &rhs.timeof_first_obs);
                 merge_time_of_last_obs(&mut lhs.timeof_last_obs, &rhs.timeof_last_obs);
 
-                // TODO: lhs.clock_offset_applied |= rhs.clock_offset_applied;
+                // Record values are rescaled prior to this point
+                merge_obs_scaling(&mut lhs.scaling, &rhs.scaling);
+
+                // mixing compensated and uncompensated receiver clocks is not feasible
+                if lhs.clock_offset_applied != rhs.clock_offset_applied {
+                    return Err(RinexMergeError::ClockOffsetAppliedMismatch);
+                }
             }
         }
         if let Some(lhs) = &mut self.meteo {
@@ -145,19 +160,19 @@ impl Merge for Header {
         if let Some(lhs) = &mut self.ionex {
             if let Some(rhs) = &rhs.ionex {
                 if lhs.reference != rhs.reference {
-                    return Err(MergeError::ReferenceFrameMismatch);
+                    return Err(MergeError::ReferenceFrameMismatch.into());
                 }
                 if lhs.grid != rhs.grid {
-                    return Err(MergeError::DimensionMismatch);
+                    return Err(MergeError::DimensionMismatch.into());
                 }
                 if lhs.map_dimension != rhs.map_dimension {
-                    return Err(MergeError::DimensionMismatch);
+                    return Err(MergeError::DimensionMismatch.into());
                 }
                 if lhs.base_radius != rhs.base_radius {
-                    return Err(MergeError::DimensionMismatch);
+                    return Err(MergeError::DimensionMismatch.into());
                 }
 
-                //TODO: this is not enough, need to take into account and rescale..
+                // TEC values are stored in TECu: finest exponent is enough
                 lhs.exponent = std::cmp::min(lhs.exponent, rhs.exponent);
 
                 merge_mut_option(&mut lhs.description, &rhs.description);
@@ -1,8 +1,28 @@
-use crate::{meteo::Record, prelude::qc::MergeError};
+use crate::{
+    meteo::Record,
+    prelude::qc::{MergeConflict, MergeError, MergePolicy},
+};
 
-pub fn merge_mut(rec: &mut Record, rhs: &Record) -> Result<(), MergeError> {
+pub fn merge_mut(
+    rec: &mut Record,
+    rhs: &Record,
+    policy: MergePolicy,
+    conflicts: &mut Vec<MergeConflict>,
+) -> Result<(), MergeError> {
     for (k, v) in rhs.iter() {
-        if rec.get(&k).is_none() {
+        if let Some(lhs) = rec.get_mut(k) {
+            if *lhs != *v {
+                conflicts.push(MergeConflict::Meteo {
+                    key: k.clone(),
+                    lhs: *lhs,
+                    rhs: *v,
+                });
+
+                if policy == MergePolicy::KeepRight {
+                    *lhs = *v;
+                }
+            }
+        } else {
             rec.insert(k.clone(), *v);
         }
     }
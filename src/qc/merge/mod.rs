@@ -1,7 +1,7 @@
 //! RINEX File merging (combination)
 use crate::prelude::{
     qc::{Merge, MergeError},
-    Constellation, Epoch, Observable, Rinex,
+    Constellation, Epoch, Observable, Rinex, SV,
 };
 
 mod antex;
@@ -12,16 +12,19 @@ mod ionex;
 mod meteo;
 mod nav;
 mod obs;
+mod policy;
 mod prod;
 // mod io; // I/O is work in progress
 
+pub use policy::{MergeConflict, MergePolicy, MergeReport, RinexMergeError};
+
 use antex::merge_mut as merge_mut_antex;
 use clock::merge_mut as merge_mut_clock;
 use doris::merge_mut as merge_mut_doris;
 use ionex::merge_mut as merge_mut_ionex;
 use meteo::merge_mut as merge_mut_meteo;
 use nav::merge_mut as merge_mut_nav;
-use obs::merge_mut as merge_mut_obs;
+use obs::{merge_mut as merge_mut_obs, rescale_mut as rescale_mut_obs};
 
 use std::cmp::PartialEq;
 use std::collections::HashMap;
@@ -87,6 +90,35 @@ pub(crate) fn merge_obsrinex_observables(
     }
 }
 
+/// Merges Observation scaling factors, retaining the finest scaling.
+pub(crate) fn merge_obs_scaling(
+    lhs: &mut HashMap<(Constellation, Observable), u16>,
+    rhs: &HashMap<(Constellation, Observable), u16>,
+) {
+    for (k, scaling) in rhs.iter() {
+        let lhs = lhs.entry(k.clone()).or_insert(*scaling);
+        *lhs = std::cmp::max(*lhs, *scaling);
+    }
+}
+
+/// Merges GLONASS channels. Conflicting assignments are rejected.
+pub(crate) fn merge_glo_channels(
+    lhs: &mut HashMap<SV, i8>,
+    rhs: &HashMap<SV, i8>,
+) -> Result<(), RinexMergeError> {
+    for (sv, channel) in rhs.iter() {
+        let lhs = *lhs.entry(*sv).or_insert(*channel);
+        if lhs != *channel {
+            return Err(RinexMergeError::GloChannelConflict {
+                sv: *sv,
+                lhs,
+                rhs: *channel,
+            });
+        }
+    }
+    Ok(())
+}
+
 /// Merges "TIME OF FIRST" special OBSERVATION header field
 pub(crate) fn merge_time_of_first_obs(lhs: &mut Option<Epoch>, rhs: &Option<Epoch>) {
     if lhs.is_none() {
@@ -119,51 +151,131 @@ impl Merge for Rinex {
     }
 
     fn merge_mut(&mut self, rhs: &Self) -> Result<(), MergeError> {
-        self.header.merge_mut(&rhs.header)?;
+        self.merge_with_policy_mut(rhs, MergePolicy::KeepLeft)?;
+        Ok(())
+    }
+}
+
+impl Rinex {
+    /// [Merge::merge] with explicit [MergePolicy], that applies to colliding
+    /// Observation, Navigation, Clock and Meteo data.
+    /// Other formats are merged the [Merge] way. The returned [MergeReport]
+    /// describes all the conflicts that were found.
+    pub fn merge_with_policy(
+        &self,
+        rhs: &Self,
+        policy: MergePolicy,
+    ) -> Result<(Self, MergeReport), RinexMergeError> {
+        let mut lhs = self.clone();
+        let report = lhs.merge_with_policy_mut(rhs, policy)?;
+        Ok((lhs, report))
+    }
+
+    /// [Self::merge_with_policy] mutable implementation.
+    /// When using [MergePolicy::RejectOnConflict], self is not modified
+    /// if conflicts were found, and the [MergeReport] is marked as rejected.
+    pub fn merge_with_policy_mut(
+        &mut self,
+        rhs: &Self,
+        policy: MergePolicy,
+    ) -> Result<MergeReport, RinexMergeError> {
+        let mut report = MergeReport::new(policy);
+
+        if policy == MergePolicy::RejectOnConflict {
+            let mut lhs = self.clone();
+            lhs.merge_records_mut(rhs, policy, &mut report.conflicts)?;
+
+            if report.is_clean() {
+                *self = lhs;
+            } else {
+                report.rejected = true;
+            }
+        } else {
+            self.merge_records_mut(rhs, policy, &mut report.conflicts)?;
+        }
+
+        Ok(report)
+    }
+
+    fn merge_records_mut(
+        &mut self,
+        rhs: &Self,
+        policy: MergePolicy,
+        conflicts: &mut Vec<MergeConflict>,
+    ) -> Result<(), RinexMergeError> {
+        // Observations are expressed with the finest scaling
+        if let (Some(lhs_specs), Some(rhs_specs)) = (&self.header.obs, &rhs.header.obs) {
+            if lhs_specs.scaling != rhs_specs.scaling {
+                let mut scaling = lhs_specs.scaling.clone();
+                merge_obs_scaling(&mut scaling, &rhs_specs.scaling);
+
+                if let Some(rec) = self.record.as_mut_obs() {
+                    rescale_mut_obs(rec, &lhs_specs.scaling, &scaling);
+                }
+
+                let mut rhs = rhs.clone();
+
+                if let Some(rec) = rhs.record.as_mut_obs() {
+                    rescale_mut_obs(rec, &rhs_specs.scaling, &scaling);
+                }
+
+                if let Some(specs) = &mut rhs.header.obs {
+                    specs.scaling = scaling.clone();
+                }
+
+                if let Some(specs) = &mut self.header.obs {
+                    specs.scaling = scaling;
+                }
+
+                return self.merge_records_mut(&rhs, policy, conflicts);
+            }
+        }
+
+        self.header.merge_header_mut(&rhs.header)?;
         self.production.merge_mut(&rhs.production)?;
 
         if let Some(lhs) = self.record.as_mut_nav() {
             if let Some(rhs) = rhs.record.as_nav() {
-                return merge_mut_nav(lhs, rhs);
+                return Ok(merge_mut_nav(lhs, rhs, policy, conflicts)?);
             } else {
-                return Err(MergeError::FileTypeMismatch);
+                return Err(MergeError::FileTypeMismatch.into());
             }
         } else if let Some(lhs) = self.record.as_mut_obs() {
             if let Some(rhs) = rhs.record.as_obs() {
-                return merge_mut_obs(lhs, rhs);
+                return Ok(merge_mut_obs(lhs, rhs, policy, conflicts)?);
             } else {
-                return Err(MergeError::FileTypeMismatch);
+                return Err(MergeError::FileTypeMismatch.into());
             }
         } else if let Some(lhs) = self.record.as_mut_meteo() {
             if let Some(rhs) = rhs.record.as_meteo() {
-                return merge_mut_meteo(lhs, rhs);
+                return Ok(merge_mut_meteo(lhs, rhs, policy, conflicts)?);
             } else {
-                return Err(MergeError::FileTypeMismatch);
+                return Err(MergeError::FileTypeMismatch.into());
             }
         } else if let Some(lhs) = self.record.as_mut_ionex() {
             if let Some(rhs) = rhs.record.as_ionex() {
-                return merge_mut_ionex(lhs, rhs);
+                return Ok(merge_mut_ionex(lhs, rhs)?);
             } else {
-                return Err(MergeError::FileTypeMismatch);
+                return Err(MergeError::FileTypeMismatch.into());
             }
         } else if let Some(lhs) = self.record.as_mut_antex() {
             if let Some(rhs) = rhs.record.as_antex() {
-                return merge_mut_antex(lhs, rhs);
+                return Ok(merge_mut_antex(lhs, rhs)?);
             } else {
-                return Err(MergeError::FileTypeMismatch);
+                return Err(MergeError::FileTypeMismatch.into());
             }
         } else if let Some(lhs) = self.record.as_mut_clock() {
             if let Some(rhs) = rhs.record.as_clock() {
-                return merge_mut_clock(lhs, rhs);
+                return Ok(merge_mut_clock(lhs, rhs, policy, conflicts)?);
             } else {
-                return Err(MergeError::FileTypeMismatch);
+                return Err(MergeError::FileTypeMismatch.into());
             }
         } else {
             let doris = self.record.as_mut_doris().unwrap();
             if let Some(rhs) = rhs.record.as_doris() {
-                return merge_mut_doris(doris, rhs);
+                return Ok(merge_mut_doris(doris, rhs)?);
             } else {
-                return Err(MergeError::FileTypeMismatch);
+                return Err(MergeError::FileTypeMismatch.into());
             }
         }
     }
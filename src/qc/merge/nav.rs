@@ -1,9 +1,55 @@
-use crate::{navigation::Record, prelude::qc::MergeError};
+use crate::{
+    navigation::{Ephemeris, NavFrame, Record},
+    prelude::qc::{MergeConflict, MergeError, MergePolicy},
+};
 
-pub fn merge_mut(rec: &mut Record, rhs: &Record) -> Result<(), MergeError> {
+/// Returns issue of data, for [Ephemeris] frames that define one
+fn issue_of_data(eph: &Ephemeris) -> Option<u32> {
+    ["iode", "iodnav", "aode", "iodn"]
+        .iter()
+        .find_map(|field| eph.get_orbit_f64(field))
+        .map(|iod| iod as u32)
+}
+
+/// Returns true if rhs should replace lhs, according to [MergePolicy::PreferHealthy]
+fn prefer_rhs(lhs: &NavFrame, rhs: &NavFrame) -> bool {
+    match (lhs.as_ephemeris(), rhs.as_ephemeris()) {
+        (Some(lhs), Some(rhs)) => match (lhs.sv_healthy(), rhs.sv_healthy()) {
+            (false, true) => true,
+            (true, false) => false,
+            _ => issue_of_data(rhs) > issue_of_data(lhs),
+        },
+        _ => false,
+    }
+}
+
+pub fn merge_mut(
+    rec: &mut Record,
+    rhs: &Record,
+    policy: MergePolicy,
+    conflicts: &mut Vec<MergeConflict>,
+) -> Result<(), MergeError> {
     for (k, v) in rhs.iter() {
-        if rec.get(&k).is_none() {
-            rec.insert(k.clone(), v.clone());
+        if let Some(lhs) = rec.get_mut(k) {
+            if lhs != v {
+                conflicts.push(MergeConflict::Navigation {
+                    key: *k,
+                    lhs_iod: lhs.as_ephemeris().and_then(issue_of_data),
+                    rhs_iod: v.as_ephemeris().and_then(issue_of_data),
+                });
+
+                let overwrite = match policy {
+                    MergePolicy::KeepRight => true,
+                    MergePolicy::PreferHealthy => prefer_rhs(lhs, v),
+                    _ => false,
+                };
+
+                if overwrite {
+                    *lhs = v.clone();
+                }
+            }
+        } else {
+            rec.insert(*k, v.clone());
         }
     }
     Ok(())
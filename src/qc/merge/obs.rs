@@ -1,16 +1,88 @@
-use crate::{observation::Record, prelude::qc::MergeError};
+use crate::{
+    observation::Record,
+    prelude::{
+        qc::{MergeConflict, MergeError, MergePolicy},
+        Constellation, Observable,
+    },
+};
 
-pub fn merge_mut(rec: &mut Record, rhs: &Record) -> Result<(), MergeError> {
+use std::collections::HashMap;
+
+/// Rescales all observations from current scaling to new scaling
+pub fn rescale_mut(
+    rec: &mut Record,
+    scaling: &HashMap<(Constellation, Observable), u16>,
+    target: &HashMap<(Constellation, Observable), u16>,
+) {
+    for (_, v) in rec.iter_mut() {
+        for sig in v.signals.iter_mut() {
+            let constellation = if sig.sv.constellation.is_sbas() {
+                Constellation::SBAS
+            } else {
+                sig.sv.constellation
+            };
+
+            let key = (constellation, sig.observable.clone());
+            let from = scaling.get(&key).copied().unwrap_or(1);
+            let to = target.get(&key).copied().unwrap_or(1);
+
+            if from != to {
+                sig.value *= to as f64 / from as f64;
+            }
+        }
+    }
+}
+
+pub fn merge_mut(
+    rec: &mut Record,
+    rhs: &Record,
+    policy: MergePolicy,
+    conflicts: &mut Vec<MergeConflict>,
+) -> Result<(), MergeError> {
     for (k, rhs) in rhs.iter() {
         if let Some(lhs) = rec.get_mut(k) {
             // TODO: could merge clock field
             //  but only if receivers do match exactly !
+            if let (Some(lhs_clk), Some(rhs_clk)) = (&mut lhs.clock, rhs.clock) {
+                if lhs_clk.offset_s != rhs_clk.offset_s {
+                    conflicts.push(MergeConflict::ReceiverClock {
+                        key: *k,
+                        lhs: lhs_clk.offset_s,
+                        rhs: rhs_clk.offset_s,
+                    });
+
+                    if policy == MergePolicy::KeepRight {
+                        *lhs_clk = rhs_clk;
+                    }
+                }
+            }
+
             for rhs in rhs.signals.iter() {
                 if let Some(lhs) = lhs
                     .signals
                     .iter_mut()
                     .find(|sig| sig.sv == rhs.sv && sig.observable == rhs.observable)
                 {
+                    if lhs.value != rhs.value {
+                        conflicts.push(MergeConflict::Signal {
+                            key: *k,
+                            sv: rhs.sv,
+                            observable: rhs.observable.clone(),
+                            lhs: lhs.value,
+                            rhs: rhs.value,
+                        });
+
+                        let overwrite = match policy {
+                            MergePolicy::KeepRight => true,
+                            MergePolicy::PreferHealthy => !lhs.is_ok() && rhs.is_ok(),
+                            _ => false,
+                        };
+
+                        if overwrite {
+                            *lhs = rhs.clone();
+                        }
+                    }
+
                     if let Some(lli) = rhs.lli {
                         if lhs.lli.is_none() {
                             lhs.lli = Some(lli);
@@ -0,0 +1,143 @@
+use crate::{
+    clock::ClockKey,
+    navigation::NavKey,
+    observation::ObsKey,
+    prelude::{qc::MergeError, Epoch, MeteoKey, Observable, SV},
+};
+
+use thiserror::Error;
+
+/// [RinexMergeError] is returned by [Rinex::merge_with_policy](crate::prelude::Rinex::merge_with_policy).
+/// It extends [MergeError] with the RINEX specific failures,
+/// that [Merge](crate::prelude::qc::Merge) reports as [MergeError::Other].
+#[derive(Debug, Error)]
+pub enum RinexMergeError {
+    /// Generic [MergeError]
+    #[error("{0}")]
+    Merge(#[from] MergeError),
+    /// Both headers assign a different channel to this GLONASS [SV]
+    #[error("conflicting glonass channels for {sv}: {lhs} and {rhs}")]
+    GloChannelConflict {
+        /// GLONASS [SV]
+        sv: SV,
+        /// Left hand side channel
+        lhs: i8,
+        /// Right hand side channel
+        rhs: i8,
+    },
+    /// Receiver clock compensated and uncompensated Observations cannot be mixed
+    #[error("receiver clock compensation mismatch")]
+    ClockOffsetAppliedMismatch,
+}
+
+impl From<RinexMergeError> for MergeError {
+    fn from(e: RinexMergeError) -> Self {
+        match e {
+            RinexMergeError::Merge(e) => e,
+            _ => MergeError::Other,
+        }
+    }
+}
+
+/// [MergePolicy] defines how colliding data are resolved
+/// when merging two [Rinex](crate::prelude::Rinex) together.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Left hand side is always retained (this is how [Merge](crate::prelude::qc::Merge) behaves).
+    #[default]
+    KeepLeft,
+    /// Right hand side overwrites the left hand side.
+    KeepRight,
+    /// Healthy data are retained. For Navigation frames, the healthy
+    /// [Ephemeris](crate::navigation::Ephemeris) is preferred, then the latest issue of data.
+    /// For Observations, sane signals (see [SignalObservation::is_ok](crate::prelude::obs::SignalObservation::is_ok))
+    /// are preferred. Left hand side is retained in any other case.
+    PreferHealthy,
+    /// Merging is rejected as soon as one conflict is found.
+    /// Left hand side is not modified in that case.
+    RejectOnConflict,
+}
+
+/// [MergeConflict] describes two colliding but differing data points
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeConflict {
+    /// Two signal observations differ
+    Signal {
+        /// [ObsKey] of this epoch
+        key: ObsKey,
+        /// [SV]: signal source
+        sv: SV,
+        /// [Observable]
+        observable: Observable,
+        /// Left hand side value
+        lhs: f64,
+        /// Right hand side value
+        rhs: f64,
+    },
+    /// Two receiver clock offsets [s] differ
+    ReceiverClock {
+        /// [ObsKey] of this epoch
+        key: ObsKey,
+        /// Left hand side offset [s]
+        lhs: f64,
+        /// Right hand side offset [s]
+        rhs: f64,
+    },
+    /// Two Navigation frames differ. Issue of data is
+    /// only described for Ephemeris frames that define one.
+    Navigation {
+        /// [NavKey] of this frame
+        key: NavKey,
+        /// Left hand side issue of data
+        lhs_iod: Option<u32>,
+        /// Right hand side issue of data
+        rhs_iod: Option<u32>,
+    },
+    /// Two clock profiles differ
+    Clock {
+        /// [Epoch] of this profile
+        epoch: Epoch,
+        /// [ClockKey] of this profile
+        key: ClockKey,
+        /// Left hand side bias [s]
+        lhs: f64,
+        /// Right hand side bias [s]
+        rhs: f64,
+    },
+    /// Two meteo observations differ
+    Meteo {
+        /// [MeteoKey] of this observation
+        key: MeteoKey,
+        /// Left hand side value
+        lhs: f64,
+        /// Right hand side value
+        rhs: f64,
+    },
+}
+
+/// [MergeReport] describes the outcome of a merge operation
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeReport {
+    /// [MergePolicy] that was applied
+    pub policy: MergePolicy,
+    /// All [MergeConflict]s that were found
+    pub conflicts: Vec<MergeConflict>,
+    /// True when the operation was rejected (see [MergePolicy::RejectOnConflict])
+    pub rejected: bool,
+}
+
+impl MergeReport {
+    /// Creates a new empty [MergeReport]
+    pub(crate) fn new(policy: MergePolicy) -> Self {
+        Self {
+            policy,
+            conflicts: Default::default(),
+            rejected: false,
+        }
+    }
+
+    /// Returns true if no conflicts were found
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
@@ -1 +1,3 @@
 mod merge;
+
+pub use merge::{MergeConflict, MergePolicy, MergeReport, RinexMergeError};
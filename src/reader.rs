@@ -1,154 +1,137 @@
-//! Buffered Reader wrapper, for efficient data reading.
-use std::io::{BufRead, BufReader, Error as IoError, Read};
+//! Streamed RINEX parsing, that does not load the whole record in memory.
+use crate::{
+    prelude::{Comments, Header, ParsingError},
+    record::{RecordEntry, RecordParser},
+};
 
-// Modify this value to update the internal buffer depth.
-// RINEX is \n termination based, and always made of rather short lines (about 512 is reasonnable).
-// So the proposed value should be more than enough.
-const BUF_SIZE: usize = 2048;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::Path,
+};
 
-/// [Reader] is dedicated to efficient RINEX input parsing.
-/// Whether they are local files or not. It implements efficient
-/// internal buffering that is specifically scaled for RINEX content.
-/// It is solely here to provide [BufRead] implementation on [Read]able interfaces.
+#[cfg(feature = "flate2")]
+use flate2::read::GzDecoder;
+
+/// [Reader] parses the [Header] section, then streams the record
+/// one [RecordEntry] at a time, without ever storing the complete record.
+/// This is the prefered solution to process very large files.
+/// CRINEX is decompressed on the fly, and Gzip compressed files are
+/// supported with [Reader::from_gzip_file].
+///
+/// ```
+/// use rinex::prelude::*;
+///
+/// let reader = Reader::from_file("data/OBS/V3/DUTH0630.22O")
+///     .unwrap();
+///
+/// assert!(reader.header().obs.is_some());
+///
+/// for entry in reader {
+///     if let RecordEntry::Obs(key, observations) = entry.unwrap() {
+///         for signal in observations.signals.iter() {
+///             println!("{} {} {}", key.epoch, signal.sv, signal.value);
+///         }
+///     }
+/// }
+/// ```
+///
+/// IONEX special case: TEC maps are streamed as soon as they are parsed,
+/// RMS maps are therefore not applied. Use [crate::Rinex::parse] if you need them.
 pub struct Reader<R: Read> {
-    /// Internal buffer, which needs to store up to two complete lines.
-    /// Since the objective here is to always provide a complete Line (without \n termination),
-    /// and be consistent with std::Lines iterator.
-    /// RINEX lines are short, and this will be more than enough.
-    buf: [u8; BUF_SIZE],
-    rd_ptr: usize,
-    wr_ptr: usize,
-    reader: R,
+    /// [Header] that was parsed
+    header: Header,
+    /// [RecordParser]
+    parser: RecordParser,
+    /// [BufReader]
+    reader: BufReader<R>,
+    /// current line storage
+    line_buf: String,
+    /// End of Stream reached, or parsing aborted.
+    done: bool,
 }
 
 impl<R: Read> Reader<R> {
-    pub fn new(reader: R) -> Self {
-        Self {
+    /// Builds a new [Reader] by consuming the [Header] section
+    /// of this readable I/O interface.
+    pub fn new(reader: R) -> Result<Self, ParsingError> {
+        let mut reader = BufReader::new(reader);
+        let header = Header::parse(&mut reader)?;
+        let parser = RecordParser::new(&header)?;
+        Ok(Self {
+            header,
+            parser,
             reader,
-            wr_ptr: 0,
-            rd_ptr: 0,
-            buf: [0; BUF_SIZE],
-        }
+            done: false,
+            line_buf: String::with_capacity(128),
+        })
     }
-}
-
-impl<R: Read> Read for Reader<R> {
-    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        // always try to pull new content, if we can
-        let avail = BUF_SIZE - self.wr_ptr;
-        if avail > 0 {
-            let size = self.reader.read(&mut self.buf[self.wr_ptr..])?;
-            self.wr_ptr += size;
-        }
 
-        // try to provide new content if possible
-        let buf_len = buf.len();
+    /// Returns [Header] that was parsed.
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
 
-        let avail = BUF_SIZE - self.rd_ptr;
-        if avail < buf_len {
-            // we have less than buffer can accept:
-            // return everything we have
-            buf[..avail].copy_from_slice(&self.buf[self.rd_ptr..]);
-            Ok(avail)
-        } else {
-            // we have more than buffer can accept
-            // return only what it can accept, avoiding overflow
-            buf.copy_from_slice(&self.buf[self.rd_ptr..self.rd_ptr + buf_len]);
-            Ok(buf_len)
-        }
+    /// Returns [Comments] collected so far.
+    pub fn comments(&self) -> &Comments {
+        &self.parser.comments
     }
 }
 
-impl<R: Read> BufRead for Reader<R> {
-    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
-        let avail = BUF_SIZE - self.wr_ptr;
-        if avail > 0 {
-            let size = self.reader.read(&mut self.buf[self.wr_ptr..])?;
-            self.wr_ptr += size;
-        }
-        Ok(&self.buf[self.rd_ptr..])
-    }
-    fn consume(&mut self, s: usize) {
-        let avail = 8192 - self.rd_ptr;
-        if avail >= s {
-            self.rd_ptr += s;
-        } else {
-            self.rd_ptr = 8192;
-        }
+impl Reader<File> {
+    /// Builds a new [Reader] from local readable file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ParsingError> {
+        let fd = File::open(path)?;
+        Self::new(fd)
     }
 }
 
-#[cfg(test)]
-mod test {
-    use super::Reader;
-    use std::fs::File;
-    use std::io::BufRead;
-    use std::io::BufReader;
-
-    #[test]
-    fn v3_lines_iter() {
-        let mut last_passed = false;
+#[cfg(feature = "flate2")]
+#[cfg_attr(docsrs, doc(cfg(feature = "flate2")))]
+impl Reader<GzDecoder<File>> {
+    /// Builds a new [Reader] from local gzip compressed file.
+    pub fn from_gzip_file<P: AsRef<Path>>(path: P) -> Result<Self, ParsingError> {
+        let fd = File::open(path)?;
+        Self::new(GzDecoder::new(fd))
+    }
+}
 
-        let fd = File::open("../data/OBS/V3/DUTH0630.22O").unwrap();
-        let reader = BufReader::new(fd);
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<RecordEntry, ParsingError>;
 
-        for (nth, line) in reader.lines().enumerate() {
-            if let Ok(line) = line {
-                println!("LINE \"{}\"", line);
-                if nth == 0 {
-                    assert_eq!(line, "     3.02           OBSERVATION DATA    M: MIXED            RINEX VERSION / TYPE");
-                } else if nth == 1 {
-                    assert_eq!(
-                        line,
-                        "HEADER CHANGED BY EPN CB ON 2022-03-11                      COMMENT"
-                    );
-                } else if nth == 34 {
-                    assert_eq!(
-                        line,
-                        "                                                            END OF HEADER"
-                    );
-                } else if nth == 89 {
-                    assert_eq!(line, "R24  20147683.700   107738728.87108     -2188.113          51.000    20147688.700    83796794.50808     -1701.871          48.500");
-                    last_passed = true;
-                } else {
-                    if nth > 89 {
-                        panic!("producing more than expected!");
-                    }
-                }
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(entry) = self.parser.entries.pop_front() {
+                return Some(Ok(entry));
             }
-        }
-        assert!(last_passed);
-    }
 
+            if self.done {
+                return None;
+            }
 
-    #[test]
-    fn v3_lengthy_lines_iter() {
-        let mut last_passed = false;
+            self.line_buf.clear();
 
-        let fd = File::open("../data/CRNX/V3/ESBC00DNK_R_20201770000_01D_30S_MO.crx.gz").unwrap();
-        let reader = Reader::new(fd);
+            let eos = match self.reader.read_line(&mut self.line_buf) {
+                Ok(size) => size == 0,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e.into()));
+                },
+            };
 
-        for (nth, line) in reader.lines().enumerate() {
-            if let Ok(line) = line {
-                if nth == 0 {
-assert_eq!(line, "3.0                 COMPACT RINEX FORMAT                    CRINEX VERS   / TYPE");
-                } else if nth == 1 {
-assert_eq!(line, "RNX2CRX ver.4.0.8                       06-Jul-22 13:22     CRINEX PROG / DATE");
-                } else if nth == 56 {
-                    assert_eq!(
-                        line,
-                        "                                                            END OF HEADER"
-                    );
-                } else if nth == 136615 {
-                    assert_eq!(line, "-14156  -3697  936  250");
-                    last_passed = true;
-                } else {
-                    if nth > 89 {
-                        panic!("producing more than expected!");
-                    }
-                }
+            match self.parser.consume(&self.header, &mut self.line_buf, eos) {
+                Ok(stop) => self.done = stop,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                },
             }
+
+            // IONEX: stream TEC maps as soon as they are parsed
+            let ionex = std::mem::take(&mut self.parser.ionex_rec);
+            self.parser
+                .entries
+                .extend(ionex.into_iter().map(|(k, v)| RecordEntry::Ionex(k, v)));
         }
-        assert!(last_passed);
     }
 }
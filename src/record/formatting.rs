@@ -1,6 +1,9 @@
 use crate::{
+    antex::format as format_antex,
+    clock::format as format_clock,
     doris::format as format_doris_observations,
     hatanaka::Compressor,
+    ionex::format as format_ionex,
     meteo::format as format_meteo_observations,
     navigation::format as format_navigation,
     prelude::{FormattingError, Header},
@@ -42,6 +45,12 @@ impl Record {
             format_doris_observations(w, rec, header)
         } else if let Some(rec) = self.as_nav() {
             format_navigation(w, rec, header)
+        } else if let Some(rec) = self.as_clock() {
+            format_clock(w, rec, header)
+        } else if let Some(rec) = self.as_ionex() {
+            format_ionex(w, rec, header)
+        } else if let Some(rec) = self.as_antex() {
+            format_antex(w, rec, header)
         } else {
             Ok(())
         }
@@ -12,6 +12,9 @@ use serde::Serialize;
 mod formatting;
 mod parsing;
 
+pub use parsing::RecordEntry;
+pub(crate) use parsing::RecordParser;
+
 /// RINEX [Record] type, inner content is RINEX type dependent.
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
@@ -1,38 +1,33 @@
 use crate::{
     antex::{
         record::{is_new_epoch as is_new_antex_epoch, parse_antenna as parse_antex_antenna},
-        Record as AntexRecord,
+        Antenna, FrequencyDependentData,
     },
+    carrier::Carrier,
     clock::{
         record::{is_new_epoch as is_new_clock_epoch, parse_epoch as parse_clock_epoch},
-        ClockKey, ClockProfile, Record as ClockRecord,
+        ClockKey, ClockProfile,
     },
     doris::{
-        is_new_epoch as is_new_doris_epoch, parse_epoch as parse_doris_epoch, Record as DorisRecord,
+        is_new_epoch as is_new_doris_epoch, parse_epoch as parse_doris_epoch, DorisKey,
+        Observations as DorisObservations,
     },
     epoch::parse_ionex_utc as parse_ionex_utc_epoch,
     hatanaka::DecompressorExpert,
     ionex::{
-        is_new_height_map,
-        is_new_rms_map,
-        is_new_tec_map,
-        //parse_height_map as parse_ionex_height_map,
-        parse_rms_map as parse_ionex_rms_map,
-        parse_tec_map as parse_ionex_tec_map,
-        Quantized as IonexQuantized,
-        Record as IonexRecord,
+        is_new_height_map, is_new_rms_map, is_new_tec_map,
+        parse_height_map as parse_ionex_height_map, parse_rms_map as parse_ionex_rms_map,
+        parse_tec_map as parse_ionex_tec_map, IonexKey, Quantized as IonexQuantized,
+        Record as IonexRecord, TEC,
     },
     is_rinex_comment,
-    meteo::{
-        is_new_epoch as is_new_meteo_epoch, parse_epoch as parse_meteo_epoch, Record as MeteoRecord,
-    },
+    meteo::{is_new_epoch as is_new_meteo_epoch, parse_epoch as parse_meteo_epoch, MeteoKey},
     navigation::{
-        is_new_epoch as is_new_nav_epoch, parse_epoch as parse_nav_epoch, Record as NavRecord,
+        is_new_epoch as is_new_nav_epoch, parse_epoch as parse_nav_epoch, NavFrame, NavKey,
     },
-    observation::Observations,
     observation::{
-        is_new_epoch as is_new_observation_epoch, parse_epoch as parse_observation_epoch,
-        Record as ObservationRecord,
+        is_new_epoch as is_new_observation_epoch, num_event_records,
+        parse_epoch as parse_observation_epoch, ObsKey, Observations,
     },
     prelude::{Epoch, Header, ParsingError, TimeScale},
     record::{Comments, Record},
@@ -40,7 +35,7 @@ use crate::{
 };
 
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashMap, VecDeque},
     io::{BufRead, BufReader, Read},
     str::from_utf8,
 };
@@ -48,46 +43,71 @@ use std::{
 #[cfg(feature = "log")]
 use log::error;
 
-impl Record {
-    /// Parses [Record] section by consuming [Reader] entirely.
-    /// This requires reference to [Header] that was just parsed by consuming [Reader] until this point.
-    pub fn parse<R: Read>(
-        header: &mut Header,
-        reader: &mut BufReader<R>,
-    ) -> Result<(Self, Comments), ParsingError> {
-        // eos reached: process pending buffer & exit
-        let mut eos = false;
-
-        // crinex decompression in failure: process pending buffer & exit
-        let mut crinex_error = false;
-
-        // current line storage
-        let mut line_buf = String::with_capacity(128);
-
-        // epoch storage
-        let mut epoch_buf = String::with_capacity(1024);
-
-        // comments management
-        let mut comments: Comments = Comments::new();
-        let mut comment_ts = Epoch::default();
-        let mut comment_content = Vec::<String>::with_capacity(4);
-
-        // ANTEX
-        let mut atx_rec = AntexRecord::new();
-
-        // NAV
-        let mut nav_rec = NavRecord::new();
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+#[cfg(feature = "parallel")]
+use crate::observation::is_event_epoch as is_observation_event_epoch;
+
+/// [RecordEntry] is a single [Record] entry, as produced by the parser.
+/// This is what [crate::reader::Reader] streams, one at a time.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordEntry {
+    /// ANTEX: one antenna and its calibration profiles
+    Antex(Antenna, HashMap<Carrier, FrequencyDependentData>),
+    /// Clock RINEX: one clock profile, at this [Epoch]
+    Clock(Epoch, ClockKey, ClockProfile),
+    /// DORIS: all [DorisObservations] of this epoch
+    Doris(DorisKey, DorisObservations),
+    /// IONEX: one TEC point
+    Ionex(IonexKey, TEC),
+    /// Meteo RINEX: one observation
+    Meteo(MeteoKey, f64),
+    /// NAV RINEX: one [NavFrame]
+    Nav(NavKey, NavFrame),
+    /// Observation RINEX: all [Observations] of this epoch
+    Obs(ObsKey, Observations),
+}
 
-        // OBS
-        let mut obs_rec = ObservationRecord::new();
-        let mut observations = Observations::default();
+/// [RecordParser] stacks lines until a complete epoch is identified,
+/// which is then parsed into [RecordEntry]s.
+pub(crate) struct RecordParser {
+    /// epoch storage
+    epoch_buf: String,
+    /// CRINEX decompression
+    is_crinex: bool,
+    crinex_buf: [u8; CRINEX_BUF_SIZE],
+    decompressor: DecompressorExpert<5>,
+    /// OBS
+    observations: Observations,
+    obs_ts: TimeScale,
+    obs_prev: Option<Epoch>,
+    /// OBS: special event records, pending
+    obs_event_records: usize,
+    /// CLK
+    clk_ts: TimeScale,
+    /// IONEX: maps are parsed and augmented (RMS) in this record
+    pub ionex_rec: IonexRecord,
+    ionex_t: Epoch,
+    ionex_tec_exponent: i8,
+    ionex_lat_exponent: i8,
+    ionex_long_exponent: i8,
+    ionex_alt_exponent: i8,
+    /// comments management
+    pub comments: Comments,
+    comment_ts: Epoch,
+    /// parsed entries, waiting to be consumed
+    pub entries: VecDeque<RecordEntry>,
+}
 
-        // CRINEX case
-        const CRINEX_BUF_SIZE: usize = 1024;
-        let mut buf = [0; CRINEX_BUF_SIZE];
+const CRINEX_BUF_SIZE: usize = 1024;
 
-        let mut is_crinex = false;
+impl RecordParser {
+    /// Builds a new [RecordParser], ready to parse the record
+    /// described by this [Header].
+    pub fn new(header: &Header) -> Result<Self, ParsingError> {
         let mut crinex_v3 = false;
+        let mut is_crinex = false;
         let mut gnss_observables = Default::default();
 
         if let Some(obs) = &header.obs {
@@ -100,21 +120,12 @@ impl Record {
 
         // Build a decompressor, that we deployed if needed.
         // These parameters are compatible with historical RNX2CRX tool.
-        let mut decompressor = DecompressorExpert::<5>::new(
+        let decompressor = DecompressorExpert::<5>::new(
             crinex_v3,
             header.constellation.unwrap_or_default(),
             gnss_observables,
         );
 
-        // MET
-        let mut met_rec = MeteoRecord::new();
-
-        // CLK
-        let mut clk_rec = ClockRecord::new();
-
-        // DORIS
-        let mut dor_rec = DorisRecord::new();
-
         // OBSERVATION case: timescale is either defined by
         // [+] TIME OF FIRST header field
         // [+] TIME OF LAST header field (flexibility, actually invalid according to specs)
@@ -142,11 +153,9 @@ impl Record {
         if let Some(clk) = &header.clock {
             if let Some(ts) = clk.timescale {
                 clk_ts = ts;
-            } else {
-                if let Some(constellation) = &header.constellation {
-                    if let Some(ts) = constellation.timescale() {
-                        clk_ts = ts;
-                    }
+            } else if let Some(constellation) = &header.constellation {
+                if let Some(ts) = constellation.timescale() {
+                    clk_ts = ts;
                 }
             }
         }
@@ -156,8 +165,6 @@ impl Record {
         //  but others may exist:
         //  in this case we use the previously identified Epoch
         //  and attach other kinds of maps
-        let mut ionex_rec = IonexRecord::new();
-        let mut ionex_t = Epoch::default();
         let mut ionex_tec_exponent = Default::default();
         let mut ionex_lat_exponent = Default::default();
         let mut ionex_long_exponent = Default::default();
@@ -170,247 +177,478 @@ impl Record {
             ionex_alt_exponent = IonexQuantized::find_exponent(ionex.grid.height.spacing);
         }
 
-        // Iterate and consume, one line at a time
-        while let Ok(size) = reader.read_line(&mut line_buf) {
-            if size == 0 {
-                // reached EOS
-                // we might still have something to process prior exiting
-                eos |= true;
-            }
+        Ok(Self {
+            is_crinex,
+            decompressor,
+            obs_ts,
+            clk_ts,
+            ionex_tec_exponent,
+            ionex_lat_exponent,
+            ionex_long_exponent,
+            ionex_alt_exponent,
+            ionex_t: Epoch::default(),
+            ionex_rec: IonexRecord::new(),
+            crinex_buf: [0; CRINEX_BUF_SIZE],
+            epoch_buf: String::with_capacity(1024),
+            observations: Observations::default(),
+            obs_prev: None,
+            obs_event_records: 0,
+            comments: Comments::new(),
+            comment_ts: Epoch::default(),
+            entries: VecDeque::with_capacity(8),
+        })
+    }
 
-            // (special case) COMMENTS: store as is
-            if is_rinex_comment(&line_buf) {
-                let comment = line_buf.split_at(60).0.trim_end();
-                comment_content.push(comment.to_string());
+    /// Consumes a new line (including its termination), that was just read.
+    /// Set `eos` when End of Stream was reached, to process pending content.
+    /// Returns true when parsing should stop.
+    pub fn consume(
+        &mut self,
+        header: &Header,
+        line_buf: &mut String,
+        eos: bool,
+    ) -> Result<bool, ParsingError> {
+        // crinex decompression in failure: process pending buffer & exit
+        let mut crinex_error = false;
 
-                // skip parsing
-                line_buf.clear();
-                continue;
+        // (special case) OBS special events: following header records
+        // (comments included) are stacked as is
+        let event_record = self.obs_event_records > 0;
+
+        // (special case) COMMENTS: skip parsing
+        if !event_record && is_rinex_comment(line_buf) {
+            return Ok(false);
+        }
+
+        // (special case) IONEX exponent scaling:
+        // keep up to date, so data interpretation remains correct
+        if line_buf.contains("EXPONENT") {
+            // we don't tolerate invalid scaling specs.
+            // This assures that data interpretation is always correct.
+            self.ionex_tec_exponent = line_buf
+                .split_at(60)
+                .0
+                .trim()
+                .parse::<i8>()
+                .map_err(|_| ParsingError::IonexScalingExponent)?;
+
+            return Ok(false);
+        }
+
+        // (special case) IONEX temporal classification
+        if line_buf.contains("EPOCH OF CURRENT MAP") {
+            // we do not tolerate invalid temporal specs
+            // This assures that data is always correctly sorted
+            self.ionex_t = parse_ionex_utc_epoch(line_buf.split_at(60).0)?;
+            return Ok(false);
+        }
+
+        // (special case) COMMENTS: store as is
+        if !event_record && line_buf.contains("COMMENT") {
+            let content = line_buf.split_at(60).0.trim();
+            if let Some(comments) = self.comments.get_mut(&self.comment_ts) {
+                comments.push(content.to_string());
+            } else {
+                self.comments
+                    .insert(self.comment_ts, vec![content.to_string()]);
             }
+        }
 
-            // (special case) IONEX exponent scaling:
-            // keep up to date, so data interpretation remains correct
-            if line_buf.contains("EXPONENT") {
-                // we don't tolerate invalid scaling specs.
-                // This assures that data interpretation is always correct.
-                ionex_tec_exponent = line_buf
-                    .split_at(60)
-                    .0
-                    .trim()
-                    .parse::<i8>()
-                    .map_err(|_| ParsingError::IonexScalingExponent)?;
-
-                // skip parsing
-                line_buf.clear();
-                continue;
+        // CRINEX special case:
+        // - apply decompression algorithm prior moving forward
+        // - decompress new pending line, which may recover several lines (in old V1 format)
+        if self.is_crinex {
+            let line_len = line_buf.len();
+
+            // catch errors nicely, simply log them
+            // it is normal to abort on final line for example
+            match self.decompressor.decompress(
+                line_buf,
+                line_len,
+                &mut self.crinex_buf,
+                CRINEX_BUF_SIZE,
+            ) {
+                Ok(size) => {
+                    if size > 0 {
+                        // clear and overwrite pending content with recovered content
+                        // we should have valid ASCII UTF-8 at all times, at this point
+                        let recovered = from_utf8(&self.crinex_buf[..size])
+                            .map_err(|_| ParsingError::BadUtf8Crinex)?;
+
+                        line_buf.clear();
+                        line_buf.push_str(recovered);
+                        line_buf.push('\n');
+                    } else if !eos {
+                        // nothing recovered yet (buffered by decompressor):
+                        // compressed content should never be stacked
+                        return Ok(false);
+                    }
+                },
+                Err(_) => {
+                    crinex_error = true;
+                },
             }
+        }
 
-            // (special case) IONEX temporal classification
-            if line_buf.contains("EPOCH OF CURRENT MAP") {
-                // we do not tolerate invalid temporal specs
-                // This assures that data is always correctly sorted
-                ionex_t = parse_ionex_utc_epoch(line_buf.split_at(60).0)?;
+        if event_record {
+            self.obs_event_records -= 1;
+            self.epoch_buf.push_str(line_buf);
+            return Ok(eos || crinex_error);
+        }
 
-                // skip parsing
-                line_buf.clear();
-                continue;
-            }
+        // we're trying to stack a complete epoch
+        // that we process once a new one appears
+        if !self.epoch_buf.is_empty() {
+            // trick to force attempt on last iteration
+            let new_epoch = eos || Record::is_new_epoch(line_buf, header);
 
-            // (special case) COMMENTS: store as is
-            if line_buf.contains("COMMENT") {
-                let content = line_buf.split_at(60).0.trim();
-                if let Some(comments) = comments.get_mut(&comment_ts) {
-                    comments.push(content.to_string());
-                } else {
-                    comments.insert(comment_ts, vec![content.to_string()]);
-                }
+            if new_epoch {
+                // new epoch appearing: process what we have buffered
+                self.parse_epoch(header);
+                self.epoch_buf.clear();
             }
+        }
+
+        // OBS special events: special records follow
+        if header.rinex_type == Type::ObservationData
+            && is_new_observation_epoch(line_buf, header.version)
+        {
+            self.obs_event_records = num_event_records(line_buf, header.version);
+        }
+
+        // always stack new content
+        self.epoch_buf.push_str(line_buf);
 
-            // CRINEX special case:
-            // - apply decompression algorithm prior moving forward
-            // - decompress new pending line, which may recover several lines (in old V1 format)
-            if is_crinex {
-                let line_len = line_buf.len();
-
-                // catch errors nicely, simply log them
-                // it is normal to abort on final line for example
-                match decompressor.decompress(&line_buf, line_len, &mut buf, CRINEX_BUF_SIZE) {
-                    Ok(size) => {
-                        if size > 0 {
-                            // clear and overwrite pending content with recovered content
-                            // we should have valid ASCII UTF-8 at all times, at this point
-                            let recovered =
-                                from_utf8(&buf[..size]).map_err(|_| ParsingError::BadUtf8Crinex)?;
-
-                            line_buf.clear();
-                            line_buf = recovered.to_string();
-                            line_buf.push('\n');
-                        }
+        Ok(eos || crinex_error)
+    }
+
+    /// Parses pending epoch, parsing method is format dependent.
+    fn parse_epoch(&mut self, header: &Header) {
+        match &header.rinex_type {
+            Type::NavigationData => {
+                if let Ok((k, v)) = parse_nav_epoch(header, &self.epoch_buf) {
+                    self.comment_ts = k.epoch; // for comments storage
+                    self.entries.push_back(RecordEntry::Nav(k, v));
+                }
+            },
+            Type::ObservationData => {
+                match parse_observation_epoch(
+                    header,
+                    &self.epoch_buf,
+                    self.obs_ts,
+                    self.obs_prev,
+                    &mut self.observations,
+                ) {
+                    Ok(key) => {
+                        self.obs_prev = Some(key.epoch);
+                        self.comment_ts = key.epoch; // for comments storage
+                        self.entries
+                            .push_back(RecordEntry::Obs(key, self.observations.clone()));
                     },
-                    Err(_) => {
-                        crinex_error = true;
+                    #[cfg(feature = "log")]
+                    Err(e) => {
+                        error!("parsing: {}", e);
                     },
+                    #[cfg(not(feature = "log"))]
+                    Err(_) => {},
                 }
-            }
 
-            let mut new_epoch = false;
-
-            // we're trying to stack a complete epoch
-            // that we process once a new one appears
-            if epoch_buf.len() > 0 {
-                new_epoch = Self::is_new_epoch(&line_buf, &header);
-
-                // trick to force attempt on last iteration
-                new_epoch |= eos;
-
-                if new_epoch {
-                    // new epoch appearing: process what we have buffered
-                    // parsing method is format dependent
-                    //println!("***MATCH***");
-
-                    match &header.rinex_type {
-                        Type::NavigationData => {
-                            if let Ok((k, v)) = parse_nav_epoch(&header, &epoch_buf) {
-                                nav_rec.insert(k, v);
-                                // println!("nav_epoch={:?}", k); // DEBUG
-                                comment_ts = k.epoch; // for comments storage
-                            }
-                        },
-                        Type::ObservationData => {
-                            match parse_observation_epoch(
-                                header,
-                                &epoch_buf,
-                                obs_ts,
-                                &mut observations,
-                            ) {
-                                Ok(key) => {
-                                    //println!("key={:?}", key);
-                                    obs_rec.insert(key, observations.clone());
-                                    comment_ts = key.epoch; // for comments storage
-                                },
-                                #[cfg(feature = "log")]
-                                Err(e) => {
-                                    error!("parsing: {}", e);
-                                },
-                                #[cfg(not(feature = "log"))]
-                                Err(_) => {},
-                            }
-
-                            observations.signals.clear(); // reset for next parsing (single alloc)
-                        },
-
-                        Type::DORIS => {
-                            if let Ok((k, observations)) = parse_doris_epoch(header, &epoch_buf) {
-                                comment_ts = k.epoch; // for comments storage
-                                dor_rec.insert(k, observations);
-                            }
-                        },
-
-                        Type::MeteoData => {
-                            if let Ok(items) = parse_meteo_epoch(header, &epoch_buf) {
-                                for (k, v) in items.iter() {
-                                    met_rec.insert(k.clone(), *v);
-                                    comment_ts = k.epoch; // for comments storage
-                                }
-                            }
-                        },
-
-                        Type::ClockData => {
-                            if let Ok((epoch, key, profile)) =
-                                parse_clock_epoch(header.version, &epoch_buf, clk_ts)
-                            {
-                                if let Some(e) = clk_rec.get_mut(&epoch) {
-                                    e.insert(key, profile);
-                                } else {
-                                    let mut inner: BTreeMap<ClockKey, ClockProfile> =
-                                        BTreeMap::new();
-                                    inner.insert(key, profile);
-                                    clk_rec.insert(epoch, inner);
-                                }
-                                comment_ts = epoch; // for comments storage
-                            }
-                        },
-
-                        Type::AntennaData => {
-                            let (antenna, content) = parse_antex_antenna(&epoch_buf).unwrap();
-                            atx_rec.push((antenna, content));
-                        },
-
-                        Type::IonosphereMaps => {
-                            if is_new_tec_map(&line_buf) {
-                                match parse_ionex_tec_map(
-                                    &epoch_buf,
-                                    ionex_lat_exponent,
-                                    ionex_long_exponent,
-                                    ionex_alt_exponent,
-                                    ionex_tec_exponent,
-                                    ionex_t,
-                                    &mut ionex_rec,
-                                ) {
-                                    Ok(()) => {},
-                                    Err(_) => {},
-                                }
-                            } else if is_new_rms_map(&line_buf) {
-                                match parse_ionex_rms_map(
-                                    &epoch_buf,
-                                    ionex_lat_exponent,
-                                    ionex_long_exponent,
-                                    ionex_alt_exponent,
-                                    ionex_tec_exponent,
-                                    ionex_t,
-                                    &mut ionex_rec,
-                                ) {
-                                    Ok(()) => {},
-                                    Err(_) => {},
-                                }
-                            } else {
-                                // match parse_ionex_height_map(
-                                //     &epoch_buf,
-                                //     ionex_lat_exponent,
-                                //     ionex_long_exponent,
-                                //     ionex_alt_exponent,
-                                //     ionex_tec_exponent,
-                                //     ionex_t,
-                                //     &mut ionex_rec,
-                                // ) {
-                                //     Ok(()) => {},
-                                //     Err(_) => {},
-                                // }
-                            }
-                        },
+                // reset for next parsing (single alloc)
+                self.observations.clock = None;
+                self.observations.signals.clear();
+                self.observations.special_records.clear();
+            },
+            Type::DORIS => {
+                if let Ok((k, observations)) = parse_doris_epoch(header, &self.epoch_buf) {
+                    self.comment_ts = k.epoch; // for comments storage
+                    self.entries.push_back(RecordEntry::Doris(k, observations));
+                }
+            },
+            Type::MeteoData => {
+                if let Ok(items) = parse_meteo_epoch(header, &self.epoch_buf) {
+                    for (k, v) in items {
+                        self.comment_ts = k.epoch; // for comments storage
+                        self.entries.push_back(RecordEntry::Meteo(k, v));
                     }
                 }
-            }
+            },
+            Type::ClockData => {
+                if let Ok((epoch, key, profile)) =
+                    parse_clock_epoch(header.version, &self.epoch_buf, self.clk_ts)
+                {
+                    self.comment_ts = epoch; // for comments storage
+                    self.entries
+                        .push_back(RecordEntry::Clock(epoch, key, profile));
+                }
+            },
+            Type::AntennaData => {
+                if let Ok((antenna, content)) = parse_antex_antenna(&self.epoch_buf) {
+                    self.entries.push_back(RecordEntry::Antex(antenna, content));
+                }
+            },
+            Type::IonosphereMaps => {
+                if is_new_tec_map(&self.epoch_buf) {
+                    let _ = parse_ionex_tec_map(
+                        &self.epoch_buf,
+                        self.ionex_lat_exponent,
+                        self.ionex_long_exponent,
+                        self.ionex_alt_exponent,
+                        self.ionex_tec_exponent,
+                        self.ionex_t,
+                        &mut self.ionex_rec,
+                    );
+                } else if is_new_rms_map(&self.epoch_buf) {
+                    let _ = parse_ionex_rms_map(
+                        &self.epoch_buf,
+                        self.ionex_lat_exponent,
+                        self.ionex_long_exponent,
+                        self.ionex_alt_exponent,
+                        self.ionex_tec_exponent,
+                        self.ionex_t,
+                        &mut self.ionex_rec,
+                    );
+                } else if is_new_height_map(&self.epoch_buf) {
+                    let _ = parse_ionex_height_map(
+                        &self.epoch_buf,
+                        self.ionex_lat_exponent,
+                        self.ionex_long_exponent,
+                        self.ionex_alt_exponent,
+                        self.ionex_tec_exponent,
+                        self.ionex_t,
+                        &mut self.ionex_rec,
+                    );
+                }
+            },
+        }
+    }
+}
 
-            // clear on new epoch detection
-            if new_epoch {
-                epoch_buf.clear();
-            }
+impl Record {
+    /// Parses [Record] section by consuming [Reader] entirely.
+    /// This requires reference to [Header] that was just parsed by consuming [Reader] until this point.
+    pub fn parse<R: Read>(
+        header: &mut Header,
+        reader: &mut BufReader<R>,
+    ) -> Result<(Self, Comments), ParsingError> {
+        #[cfg(feature = "parallel")]
+        if Self::parallel_parsing(header) {
+            let mut content = Vec::new();
+            reader.read_to_end(&mut content)?;
+
+            // invalid content: stop on last valid line
+            let content = match String::from_utf8(content) {
+                Ok(content) => content,
+                Err(e) => {
+                    let valid = e.utf8_error().valid_up_to();
+                    let mut content = e.into_bytes();
+                    let end = content[..valid]
+                        .iter()
+                        .rposition(|b| *b == b'\n')
+                        .map(|pos| pos + 1)
+                        .unwrap_or(0);
+                    content.truncate(end);
+                    String::from_utf8(content).unwrap_or_default()
+                },
+            };
+
+            return Self::parse_chunks(header, &content, rayon::current_num_threads());
+        }
+
+        Self::parse_sequential(header, reader)
+    }
+
+    /// Parses [Record] section one line at a time, by consuming [Reader] entirely.
+    pub(crate) fn parse_sequential<R: Read>(
+        header: &mut Header,
+        reader: &mut BufReader<R>,
+    ) -> Result<(Self, Comments), ParsingError> {
+        let mut parser = RecordParser::new(header)?;
+
+        // current line storage
+        let mut line_buf = String::with_capacity(128);
+
+        let mut record = match &header.rinex_type {
+            Type::AntennaData => Record::AntexRecord(Default::default()),
+            Type::ClockData => Record::ClockRecord(Default::default()),
+            Type::IonosphereMaps => Record::IonexRecord(Default::default()),
+            Type::MeteoData => Record::MeteoRecord(Default::default()),
+            Type::NavigationData => Record::NavRecord(Default::default()),
+            Type::ObservationData => Record::ObsRecord(Default::default()),
+            Type::DORIS => Record::DorisRecord(Default::default()),
+        };
 
-            // always stack new content
-            epoch_buf.push_str(&line_buf);
+        // Iterate and consume, one line at a time
+        while let Ok(size) = reader.read_line(&mut line_buf) {
+            // reached EOS: we might still have something to process prior exiting
+            let eos = size == 0;
+
+            let stop = parser.consume(header, &mut line_buf, eos)?;
+
+            for entry in parser.entries.drain(..) {
+                record.insert_entry(entry);
+            }
 
-            if eos || crinex_error {
+            if stop {
                 break;
             }
 
             line_buf.clear(); // always clear newline buf
-        } //loop
-
-        // wrap content and exit
-        let record = match &header.rinex_type {
-            Type::AntennaData => Record::AntexRecord(atx_rec),
-            Type::ClockData => Record::ClockRecord(clk_rec),
-            Type::IonosphereMaps => Record::IonexRecord(ionex_rec),
-            Type::MeteoData => Record::MeteoRecord(met_rec),
-            Type::NavigationData => Record::NavRecord(nav_rec),
-            Type::ObservationData => Record::ObsRecord(obs_rec),
-            Type::DORIS => Record::DorisRecord(dor_rec),
+        }
+
+        // IONEX: maps are augmented in place, wrap them now
+        if let Some(rec) = record.as_mut_ionex() {
+            *rec = std::mem::take(&mut parser.ionex_rec);
+        }
+
+        Ok((record, parser.comments))
+    }
+
+    /// Returns true if this [Record] may be parsed in parallel:
+    /// Observation (not compressed), Navigation and Clock RINEX.
+    #[cfg(feature = "parallel")]
+    fn parallel_parsing(header: &Header) -> bool {
+        match header.rinex_type {
+            Type::ObservationData => !header.is_crinex(),
+            Type::NavigationData | Type::ClockData => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the record may be split at this line.
+    /// Observation RINEX is never split at special events,
+    /// that may omit the timestamp and rely on previous epoch.
+    #[cfg(feature = "parallel")]
+    fn is_chunk_boundary(line: &str, header: &Header) -> bool {
+        if !Self::is_new_epoch(line, header) {
+            return false;
+        }
+        match header.rinex_type {
+            Type::ObservationData => !is_observation_event_epoch(line, header.version),
+            _ => true,
+        }
+    }
+
+    /// Splits the record content at epoch boundaries, in about `num_chunks` chunks
+    /// which are parsed concurrently. [Record] is identical to sequential parsing.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn parse_chunks(
+        header: &Header,
+        content: &str,
+        num_chunks: usize,
+    ) -> Result<(Self, Comments), ParsingError> {
+        let chunk_size = content.len() / num_chunks.max(1);
+
+        let mut chunks = Vec::with_capacity(num_chunks);
+        let (mut start, mut offset) = (0, 0);
+
+        for line in content.split_inclusive('\n') {
+            if offset - start >= chunk_size && Self::is_chunk_boundary(line, header) {
+                chunks.push(&content[start..offset]);
+                start = offset;
+            }
+            offset += line.len();
+        }
+
+        chunks.push(&content[start..]);
+
+        let parsed = chunks
+            .par_iter()
+            .map(|chunk| {
+                let mut parser = RecordParser::new(header)?;
+                let mut line_buf = String::with_capacity(128);
+                let mut entries = Vec::new();
+
+                for line in chunk.split_inclusive('\n') {
+                    line_buf.clear();
+                    line_buf.push_str(line);
+
+                    parser.consume(header, &mut line_buf, false)?;
+                    entries.extend(parser.entries.drain(..));
+                }
+
+                line_buf.clear();
+                parser.consume(header, &mut line_buf, true)?;
+                entries.extend(parser.entries.drain(..));
+
+                Ok((entries, parser.comments, parser.comment_ts))
+            })
+            .collect::<Result<Vec<_>, ParsingError>>()?;
+
+        let mut record = match &header.rinex_type {
+            Type::ClockData => Record::ClockRecord(Default::default()),
+            Type::NavigationData => Record::NavRecord(Default::default()),
+            _ => Record::ObsRecord(Default::default()),
         };
+
+        let mut comments = Comments::new();
+        let mut comment_ts = Epoch::default();
+
+        for (nth, (entries, chunk_comments, chunk_ts)) in parsed.into_iter().enumerate() {
+            for entry in entries {
+                record.insert_entry(entry);
+            }
+
+            // comments that preceded the first parsed epoch of this chunk
+            // are attached to the last epoch of previous chunk
+            for (mut t, content) in chunk_comments {
+                if nth > 0 && t == Epoch::default() {
+                    t = comment_ts;
+                }
+                comments.entry(t).or_default().extend(content);
+            }
+
+            if chunk_ts != Epoch::default() {
+                comment_ts = chunk_ts;
+            }
+        }
+
         Ok((record, comments))
     }
 
-    fn is_new_epoch(line: &str, header: &Header) -> bool {
+    /// Inserts a new [RecordEntry] into this [Record].
+    fn insert_entry(&mut self, entry: RecordEntry) {
+        match (self, entry) {
+            (Record::AntexRecord(rec), RecordEntry::Antex(antenna, content)) => {
+                rec.push((antenna, content));
+            },
+            (Record::ClockRecord(rec), RecordEntry::Clock(epoch, key, profile)) => {
+                if let Some(e) = rec.get_mut(&epoch) {
+                    e.insert(key, profile);
+                } else {
+                    let mut inner: BTreeMap<ClockKey, ClockProfile> = BTreeMap::new();
+                    inner.insert(key, profile);
+                    rec.insert(epoch, inner);
+                }
+            },
+            (Record::DorisRecord(rec), RecordEntry::Doris(k, v)) => {
+                rec.insert(k, v);
+            },
+            (Record::IonexRecord(rec), RecordEntry::Ionex(k, v)) => {
+                rec.insert(k, v);
+            },
+            (Record::MeteoRecord(rec), RecordEntry::Meteo(k, v)) => {
+                rec.insert(k, v);
+            },
+            (Record::NavRecord(rec), RecordEntry::Nav(k, v)) => {
+                rec.insert(k, v);
+            },
+            (Record::ObsRecord(rec), RecordEntry::Obs(k, v)) => {
+                // consecutive events may share the same timestamp (omitted):
+                // special records are then gathered, not replaced
+                match rec.get_mut(&k) {
+                    Some(event) if k.flag.is_event() => {
+                        event.special_records.extend(v.special_records);
+                    },
+                    _ => {
+                        rec.insert(k, v);
+                    },
+                }
+            },
+            _ => {},
+        }
+    }
+
+    pub(crate) fn is_new_epoch(line: &str, header: &Header) -> bool {
         if is_rinex_comment(line) {
             return false;
         }
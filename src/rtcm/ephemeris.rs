@@ -0,0 +1,730 @@
+//! RTCM broadcast ephemeris messages interpretation
+use std::f64::consts::PI;
+
+use crate::{
+    navigation::{Ephemeris, NavFrameType, NavKey, NavMessageType},
+    prelude::{Constellation, Epoch, TimeScale, Version, SV},
+};
+
+use hifitime::Unit;
+
+use rtcm_rs::{
+    msg::{Msg1019T, Msg1020T, Msg1042T, Msg1044T, Msg1045T, Msg1046T},
+    Message,
+};
+
+/// GPS, QZSS and BeiDou User Range Accuracy, in meters, indexed by URA index
+const URA_M: [f64; 16] = [
+    2.4, 3.4, 4.85, 6.85, 9.65, 13.65, 24.0, 48.0, 96.0, 192.0, 384.0, 768.0, 1536.0, 3072.0,
+    6144.0, 6144.0,
+];
+
+/// Galileo data source when interpreting F/NAV (E5a-I with E1,E5a clock)
+const GAL_FNAV_SOURCE: f64 = 0x102 as f64;
+
+/// Galileo data source when interpreting I/NAV (E1-B, E5b-I with E1,E5b clock)
+const GAL_INAV_SOURCE: f64 = 0x205 as f64;
+
+/// Converts GPS, QZSS and BeiDou URA index to meters
+fn ura_meters(index: u8) -> f64 {
+    URA_M[(index as usize).min(15)]
+}
+
+/// Converts Galileo SISA index to meters.
+/// "No Accuracy Prediction Available" is described as -1.
+fn sisa_meters(index: u8) -> f64 {
+    let index = index as f64;
+    match index as u8 {
+        0..=49 => index * 0.01,
+        50..=74 => 0.5 + (index - 50.0) * 0.02,
+        75..=99 => 1.0 + (index - 75.0) * 0.04,
+        100..=125 => 2.0 + (index - 100.0) * 0.16,
+        _ => -1.0,
+    }
+}
+
+/// Converts an accuracy (in meters) to GPS, QZSS and BeiDou URA index
+fn ura_index(meters: f64) -> u8 {
+    URA_M.iter().position(|ura| meters <= *ura).unwrap_or(15) as u8
+}
+
+/// Converts an accuracy (in meters) to Galileo SISA index
+fn sisa_index(meters: f64) -> u8 {
+    if meters < 0.0 {
+        255
+    } else if meters < 0.5 {
+        (meters / 0.01).round() as u8
+    } else if meters < 1.0 {
+        50 + ((meters - 0.5) / 0.02).round() as u8
+    } else if meters < 2.0 {
+        75 + ((meters - 1.0) / 0.04).round() as u8
+    } else if meters <= 6.0 {
+        100 + ((meters - 2.0) / 0.16).round() as u8
+    } else {
+        255
+    }
+}
+
+/// Resolves a truncated week counter (modulo 2^bits),
+/// using the week counter of the reference [Epoch].
+fn resolve_week(week: u16, bits: u32, ref_week: u32) -> u32 {
+    let modulo = 1_u32 << bits;
+    let week = week as u32 % modulo;
+    let rollovers = ((ref_week as f64 - week as f64) / modulo as f64).round();
+    week + rollovers.max(0.0) as u32 * modulo
+}
+
+/// Resolves the Moscow day (expressed in [TimeScale::UTC], +3h) closest to this reference.
+fn moscow_day(t_ref: Epoch, time_of_day: f64) -> Epoch {
+    let t_ref = t_ref.to_time_scale(TimeScale::UTC) + 3.0 * Unit::Hour;
+    let day = t_ref.floor(1.0 * Unit::Day);
+    let t = day + time_of_day * Unit::Second;
+    if t - t_ref > 12.0 * Unit::Hour {
+        day - 1.0 * Unit::Day
+    } else if t_ref - t > 12.0 * Unit::Hour {
+        day + 1.0 * Unit::Day
+    } else {
+        day
+    }
+}
+
+/// Seconds of UTC week (starting on sunday) of this [Epoch]
+fn seconds_of_utc_week(t: Epoch) -> f64 {
+    let t = t.to_time_scale(TimeScale::UTC);
+    let days = (t.weekday_utc() as u8 + 1) % 7;
+    days as f64 * 86_400.0 + (t - t.floor(1.0 * Unit::Day)).to_seconds()
+}
+
+/// [RtcmEphemeris] is the interpretation of any RTCM broadcast ephemeris message.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RtcmEphemeris {
+    /// [SV] broadcasting this ephemeris
+    pub sv: SV,
+    /// [NavMessageType] of this ephemeris, in RINEX4 terminology
+    pub msgtype: NavMessageType,
+    /// Time of Clock, expressed in the [SV] timescale
+    pub toc: Epoch,
+    /// Clock bias (s)
+    pub clock_bias: f64,
+    /// Clock drift (s.s⁻¹)
+    pub clock_drift: f64,
+    /// Clock drift rate (s.s⁻²), or message frame time for GLONASS
+    pub clock_drift_rate: f64,
+    /// Orbital parameters, named as in our NAV database
+    pub orbits: Vec<(&'static str, f64)>,
+}
+
+impl RtcmEphemeris {
+    /// Interprets this RTCM [Message] as [RtcmEphemeris], if it is
+    /// a supported broadcast ephemeris (1019, 1020, 1042, 1044, 1045 or 1046).
+    /// RTCM truncates the week counters (and GLONASS only describes the time of day),
+    /// we pick the solution closest to this reference [Epoch].
+    pub fn from_message(msg: &Message, t_ref: Epoch) -> Option<Self> {
+        match msg {
+            Message::Msg1019(eph) => {
+                let t_ref = t_ref.to_time_scale(TimeScale::GPST);
+                let (ref_week, ref_nanos) = t_ref.to_time_of_week();
+                let week = resolve_week(eph.gps_week_number, 10, ref_week);
+                let toc = Epoch::from_time_of_week(
+                    week,
+                    (eph.toc_s as f64 * 1.0E9).round() as u64,
+                    TimeScale::GPST,
+                );
+
+                Some(Self {
+                    sv: SV::new(Constellation::GPS, eph.gps_satellite_id),
+                    msgtype: NavMessageType::LNAV,
+                    toc,
+                    clock_bias: eph.af0_s,
+                    clock_drift: eph.af1_s_s as f64,
+                    clock_drift_rate: eph.af2_s_s2 as f64,
+                    orbits: vec![
+                        ("iode", eph.iode as f64),
+                        ("crs", eph.crs_m as f64),
+                        ("deltaN", eph.delta_n_sc_s as f64 * PI),
+                        ("m0", eph.m0_sc * PI),
+                        ("cuc", eph.cuc_rad as f64),
+                        ("e", eph.eccentricity),
+                        ("cus", eph.cus_rad as f64),
+                        ("sqrta", eph.sqrt_a_sqrt_m),
+                        ("toe", eph.toe_s as f64),
+                        ("cic", eph.cic_rad as f64),
+                        ("omega0", eph.omega0_sc * PI),
+                        ("cis", eph.cis_rad as f64),
+                        ("i0", eph.i0_sc * PI),
+                        ("crc", eph.crc_m as f64),
+                        ("omega", eph.omega_sc * PI),
+                        ("omegaDot", eph.omegadot_sc_s * PI),
+                        ("idot", eph.idot_sc_s * PI),
+                        ("l2Codes", eph.code_on_l2_ind as f64),
+                        ("week", week as f64),
+                        ("l2p", eph.l2_p_data_flag as f64),
+                        ("accuracy", ura_meters(eph.ura_index)),
+                        ("health", eph.sv_health_ind as f64),
+                        ("tgd", eph.tgd_s as f64),
+                        ("iodc", eph.iodc as f64),
+                        ("t_tm", ref_nanos as f64 * 1.0E-9),
+                        // fit interval (hours)
+                        ("fitInt", if eph.fit_interval_ind == 0 { 4.0 } else { 6.0 }),
+                    ],
+                })
+            },
+            Message::Msg1044(eph) => {
+                let t_ref = t_ref.to_time_scale(TimeScale::QZSST);
+                let (ref_week, ref_nanos) = t_ref.to_time_of_week();
+                let week = resolve_week(eph.qzss_week_number, 10, ref_week);
+                let toc = Epoch::from_time_of_week(
+                    week,
+                    (eph.toc_s as f64 * 1.0E9).round() as u64,
+                    TimeScale::QZSST,
+                );
+
+                Some(Self {
+                    sv: SV::new(Constellation::QZSS, eph.qzss_satellite_id),
+                    msgtype: NavMessageType::LNAV,
+                    toc,
+                    clock_bias: eph.af0_s,
+                    clock_drift: eph.af1_s_s as f64,
+                    clock_drift_rate: eph.af2_s_s2 as f64,
+                    orbits: vec![
+                        ("iode", eph.iode as f64),
+                        ("crs", eph.crs_m as f64),
+                        ("deltaN", eph.delta_n_sc_s as f64 * PI),
+                        ("m0", eph.m0_sc * PI),
+                        ("cuc", eph.cuc_rad as f64),
+                        ("e", eph.eccentricity),
+                        ("cus", eph.cus_rad as f64),
+                        ("sqrta", eph.sqrt_a_sqrt_m),
+                        ("toe", eph.toe_s as f64),
+                        ("cic", eph.cic_rad as f64),
+                        ("omega0", eph.omega0_sc * PI),
+                        ("cis", eph.cis_rad as f64),
+                        ("i0", eph.i0_sc * PI),
+                        ("crc", eph.crc_m as f64),
+                        ("omega", eph.omega_sc * PI),
+                        ("omegaDot", eph.omegadot_sc_s * PI),
+                        ("idot", eph.idot_sc_s * PI),
+                        ("l2Codes", eph.code_on_l2_ind as f64),
+                        ("week", week as f64),
+                        ("accuracy", ura_meters(eph.ura_index)),
+                        ("health", eph.sv_health_ind as f64),
+                        ("tgd", eph.tgd_s as f64),
+                        ("iodc", eph.iodc as f64),
+                        ("t_tm", ref_nanos as f64 * 1.0E-9),
+                        // QZSS: fit interval flag
+                        ("fitInt", eph.fit_interval_ind as f64),
+                    ],
+                })
+            },
+            Message::Msg1042(eph) => {
+                let t_ref = t_ref.to_time_scale(TimeScale::BDT);
+                let (ref_week, ref_nanos) = t_ref.to_time_of_week();
+                let week = resolve_week(eph.bds_week_number, 13, ref_week);
+                let toc = Epoch::from_time_of_week(
+                    week,
+                    (eph.toc_s as f64 * 1.0E9).round() as u64,
+                    TimeScale::BDT,
+                );
+
+                // GEO satellites broadcast D2 messages
+                let msgtype = match eph.bds_satellite_id {
+                    1..=5 | 59..=63 => NavMessageType::D2,
+                    _ => NavMessageType::D1,
+                };
+
+                let tgd1 = eph.tgd1_s as f64;
+                let tgd2 = eph.tgd2_s as f64;
+
+                Some(Self {
+                    sv: SV::new(Constellation::BeiDou, eph.bds_satellite_id),
+                    msgtype,
+                    toc,
+                    clock_bias: eph.a0_s,
+                    clock_drift: eph.a1_s_s,
+                    clock_drift_rate: eph.a2_s_s2 as f64,
+                    orbits: vec![
+                        ("aode", eph.aode as f64),
+                        ("crs", eph.crs_m as f64),
+                        ("deltaN", eph.delta_n_sc_s as f64 * PI),
+                        ("m0", eph.m0_sc * PI),
+                        ("cuc", eph.cuc_rad as f64),
+                        ("e", eph.eccentricity),
+                        ("cus", eph.cus_rad as f64),
+                        ("sqrta", eph.sqrt_a_sqrt_m),
+                        ("toe", eph.toe_s as f64),
+                        ("cic", eph.cic_rad as f64),
+                        ("omega0", eph.omega0_sc * PI),
+                        ("cis", eph.cis_rad as f64),
+                        ("i0", eph.i0_sc * PI),
+                        ("crc", eph.crc_m as f64),
+                        ("omega", eph.omega_sc * PI),
+                        ("omegaDot", eph.omegadot_sc_s * PI),
+                        ("idot", eph.idot_sc_s * PI),
+                        ("week", week as f64),
+                        ("accuracy", ura_meters(eph.ura_index)),
+                        ("health", eph.sv_health_flag as f64),
+                        // RINEX3 naming
+                        ("tgd1b1b3", tgd1),
+                        ("tgd2b2b3", tgd2),
+                        // RINEX4 naming
+                        ("tgdb1b3", tgd1),
+                        ("tgdb2b3", tgd2),
+                        ("t_tm", ref_nanos as f64 * 1.0E-9),
+                        ("aodc", eph.aodc as f64),
+                    ],
+                })
+            },
+            Message::Msg1045(eph) => {
+                let t_ref = t_ref.to_time_scale(TimeScale::GST);
+                let (ref_week, ref_nanos) = t_ref.to_time_of_week();
+                let week = resolve_week(eph.gal_week_number, 12, ref_week);
+                let toc = Epoch::from_time_of_week(
+                    week,
+                    (eph.toc_s as f64 * 1.0E9).round() as u64,
+                    TimeScale::GST,
+                );
+
+                let health =
+                    (eph.e5a_data_validity_flag as u32) << 3 | (eph.e5a_sig_health_ind as u32) << 4;
+
+                Some(Self {
+                    sv: SV::new(Constellation::Galileo, eph.gal_satellite_id),
+                    msgtype: NavMessageType::FNAV,
+                    toc,
+                    clock_bias: eph.af0_s,
+                    clock_drift: eph.af1_s_s,
+                    clock_drift_rate: eph.af2_s_s2 as f64,
+                    orbits: vec![
+                        ("iodnav", eph.iodnav as f64),
+                        ("crs", eph.crs_m as f64),
+                        ("deltaN", eph.delta_n_sc_s as f64 * PI),
+                        ("m0", eph.m0_sc * PI),
+                        ("cuc", eph.cuc_rad as f64),
+                        ("e", eph.eccentricity),
+                        ("cus", eph.cus_rad as f64),
+                        ("sqrta", eph.sqrt_a_sqrt_m),
+                        ("toe", eph.toe_s as f64),
+                        ("cic", eph.cic_rad as f64),
+                        ("omega0", eph.omega0_sc * PI),
+                        ("cis", eph.cis_rad as f64),
+                        ("i0", eph.i0_sc * PI),
+                        ("crc", eph.crc_m as f64),
+                        ("omega", eph.omega_sc * PI),
+                        ("omegaDot", eph.omegadot_sc_s * PI),
+                        ("idot", eph.idot_sc_s as f64 * PI),
+                        ("source", GAL_FNAV_SOURCE),
+                        // RINEX: continuous GPS week counter
+                        ("week", (week + 1024) as f64),
+                        ("sisa", sisa_meters(eph.sisa_e1_e5a_index)),
+                        ("health", health as f64),
+                        ("bgdE5aE1", eph.bgd_e1_e5a_s as f64),
+                        ("t_tm", ref_nanos as f64 * 1.0E-9),
+                    ],
+                })
+            },
+            Message::Msg1046(eph) => {
+                let t_ref = t_ref.to_time_scale(TimeScale::GST);
+                let (ref_week, ref_nanos) = t_ref.to_time_of_week();
+                let week = resolve_week(eph.gal_week_number, 12, ref_week);
+                let toc = Epoch::from_time_of_week(
+                    week,
+                    (eph.toc_s as f64 * 1.0E9).round() as u64,
+                    TimeScale::GST,
+                );
+
+                let health = eph.e1_b_data_validity_flag as u32
+                    | (eph.e1_b_sig_health_ind as u32) << 1
+                    | (eph.e5b_data_validity_flag as u32) << 6
+                    | (eph.e5b_sig_health_ind as u32) << 7;
+
+                Some(Self {
+                    sv: SV::new(Constellation::Galileo, eph.gal_satellite_id),
+                    msgtype: NavMessageType::INAV,
+                    toc,
+                    clock_bias: eph.af0_s,
+                    clock_drift: eph.af1_s_s,
+                    clock_drift_rate: eph.af2_s_s2 as f64,
+                    orbits: vec![
+                        ("iodnav", eph.iodnav as f64),
+                        ("crs", eph.crs_m as f64),
+                        ("deltaN", eph.delta_n_sc_s as f64 * PI),
+                        ("m0", eph.m0_sc * PI),
+                        ("cuc", eph.cuc_rad as f64),
+                        ("e", eph.eccentricity),
+                        ("cus", eph.cus_rad as f64),
+                        ("sqrta", eph.sqrt_a_sqrt_m),
+                        ("toe", eph.toe_s as f64),
+                        ("cic", eph.cic_rad as f64),
+                        ("omega0", eph.omega0_sc * PI),
+                        ("cis", eph.cis_rad as f64),
+                        ("i0", eph.i0_sc * PI),
+                        ("crc", eph.crc_m as f64),
+                        ("omega", eph.omega_sc * PI),
+                        ("omegaDot", eph.omegadot_sc_s * PI),
+                        ("idot", eph.idot_sc_s as f64 * PI),
+                        ("source", GAL_INAV_SOURCE),
+                        // RINEX: continuous GPS week counter
+                        ("week", (week + 1024) as f64),
+                        ("sisa", sisa_meters(eph.sisa_e1_e5b_index)),
+                        ("health", health as f64),
+                        ("bgdE5aE1", eph.bgd_e1_e5a_s as f64),
+                        ("bgdE5bE1", eph.bgd_e1_e5b_s as f64),
+                        ("t_tm", ref_nanos as f64 * 1.0E-9),
+                    ],
+                })
+            },
+            Message::Msg1020(eph) => {
+                // tb and tk are expressed in Moscow time (UTC +3h)
+                let tb_s = eph.tb_min as f64 * 60.0;
+                let day = moscow_day(t_ref, tb_s);
+                let toc = day + tb_s * Unit::Second - 3.0 * Unit::Hour;
+
+                let tk_s = eph.tk_h as f64 * 3600.0 + eph.tk_min as f64 * 60.0 + eph.tk_s as f64;
+                let mut tk = day + tk_s * Unit::Second - 3.0 * Unit::Hour;
+                if tk - toc > 12.0 * Unit::Hour {
+                    tk -= 1.0 * Unit::Day;
+                }
+
+                Some(Self {
+                    sv: SV::new(Constellation::Glonass, eph.glo_satellite_id),
+                    msgtype: NavMessageType::FDMA,
+                    toc,
+                    clock_bias: -eph.tau_n_s,
+                    clock_drift: eph.gamma_n as f64,
+                    clock_drift_rate: seconds_of_utc_week(tk),
+                    orbits: vec![
+                        ("satPosX", eph.xn_km),
+                        ("velX", eph.xn_first_deriv_km_s),
+                        ("accelX", eph.xn_second_deriv_km_s2 as f64),
+                        ("health", eph.glo_eph_health_flag as f64),
+                        ("satPosY", eph.yn_km),
+                        ("velY", eph.yn_first_deriv_km_s),
+                        ("accelY", eph.yn_second_deriv_km_s2 as f64),
+                        ("channel", eph.glo_satellite_freq_chan_number as f64),
+                        ("satPosZ", eph.zn_km),
+                        ("velZ", eph.zn_first_deriv_km_s),
+                        ("accelZ", eph.zn_second_deriv_km_s2 as f64),
+                        ("ageOp", eph.en_d as f64),
+                        ("L1L2GrpDelay", eph.glo_m_delta_tau_n_s as f64),
+                        ("urai", eph.glo_m_ft_ind as f64),
+                    ],
+                })
+            },
+            _ => None,
+        }
+    }
+
+    /// Converts this [RtcmEphemeris] to [NavKey] and [Ephemeris],
+    /// following the NAV standards of this revision.
+    /// Orbital parameters that do not exist in this revision are dropped.
+    pub fn to_ephemeris(&self, version: Version) -> (NavKey, Ephemeris) {
+        // V2/V3 only describe legacy frames
+        let msgtype = if version.major < 4 {
+            NavMessageType::LNAV
+        } else {
+            self.msgtype
+        };
+
+        let ephemeris = Ephemeris {
+            clock_bias: self.clock_bias,
+            clock_drift: self.clock_drift,
+            clock_drift_rate: self.clock_drift_rate,
+            orbits: Default::default(),
+        }
+        .with_standard_orbits(self.sv.constellation, version, msgtype, &self.orbits);
+
+        let key = NavKey {
+            epoch: self.toc,
+            sv: self.sv,
+            msgtype,
+            frmtype: NavFrameType::Ephemeris,
+        };
+
+        (key, ephemeris)
+    }
+}
+
+impl RtcmEphemeris {
+    /// Encodes this [Ephemeris] frame as RTCM [Message], for the constellations
+    /// and frames that RTCM supports (GPS and QZSS LNAV, Glonass FDMA,
+    /// Galileo F/NAV and I/NAV, BeiDou D1/D2).
+    pub fn to_message(key: &NavKey, eph: &Ephemeris) -> Option<Message> {
+        let get = |name: &str| eph.get_orbit_f64(name).unwrap_or(0.0);
+        let sv = key.sv;
+
+        match (sv.constellation, key.msgtype) {
+            (Constellation::GPS, NavMessageType::LNAV) => {
+                let (_, nanos) = key.epoch.to_time_scale(TimeScale::GPST).to_time_of_week();
+                Some(Message::Msg1019(Msg1019T {
+                    gps_satellite_id: sv.prn,
+                    gps_week_number: (get("week") as u32 % 1024) as u16,
+                    ura_index: ura_index(get("accuracy")),
+                    code_on_l2_ind: get("l2Codes") as u8,
+                    idot_sc_s: get("idot") / PI,
+                    iode: get("iode") as u8,
+                    toc_s: (nanos as f64 * 1.0E-9) as f32,
+                    af2_s_s2: eph.clock_drift_rate as f32,
+                    af1_s_s: eph.clock_drift as f32,
+                    af0_s: eph.clock_bias,
+                    iodc: get("iodc") as u16,
+                    crs_m: get("crs") as f32,
+                    delta_n_sc_s: (get("deltaN") / PI) as f32,
+                    m0_sc: get("m0") / PI,
+                    cuc_rad: get("cuc") as f32,
+                    eccentricity: get("e"),
+                    cus_rad: get("cus") as f32,
+                    sqrt_a_sqrt_m: get("sqrta"),
+                    toe_s: get("toe") as f32,
+                    cic_rad: get("cic") as f32,
+                    omega0_sc: get("omega0") / PI,
+                    cis_rad: get("cis") as f32,
+                    i0_sc: get("i0") / PI,
+                    crc_m: get("crc") as f32,
+                    omega_sc: get("omega") / PI,
+                    omegadot_sc_s: get("omegaDot") / PI,
+                    tgd_s: get("tgd") as f32,
+                    sv_health_ind: get("health") as u8,
+                    l2_p_data_flag: get("l2p") as u8,
+                    fit_interval_ind: (get("fitInt") > 4.0) as u8,
+                }))
+            },
+            (Constellation::QZSS, NavMessageType::LNAV) => {
+                let (_, nanos) = key.epoch.to_time_scale(TimeScale::QZSST).to_time_of_week();
+                Some(Message::Msg1044(Msg1044T {
+                    qzss_satellite_id: sv.prn,
+                    toc_s: (nanos as f64 * 1.0E-9) as f32,
+                    af2_s_s2: eph.clock_drift_rate as f32,
+                    af1_s_s: eph.clock_drift as f32,
+                    af0_s: eph.clock_bias,
+                    iode: get("iode") as u8,
+                    crs_m: get("crs") as f32,
+                    delta_n_sc_s: (get("deltaN") / PI) as f32,
+                    m0_sc: get("m0") / PI,
+                    cuc_rad: get("cuc") as f32,
+                    eccentricity: get("e"),
+                    cus_rad: get("cus") as f32,
+                    sqrt_a_sqrt_m: get("sqrta"),
+                    toe_s: get("toe") as f32,
+                    cic_rad: get("cic") as f32,
+                    omega0_sc: get("omega0") / PI,
+                    cis_rad: get("cis") as f32,
+                    i0_sc: get("i0") / PI,
+                    crc_m: get("crc") as f32,
+                    omega_sc: get("omega") / PI,
+                    omegadot_sc_s: get("omegaDot") / PI,
+                    idot_sc_s: get("idot") / PI,
+                    code_on_l2_ind: get("l2Codes") as u8,
+                    qzss_week_number: (get("week") as u32 % 1024) as u16,
+                    ura_index: ura_index(get("accuracy")),
+                    sv_health_ind: get("health") as u8,
+                    tgd_s: get("tgd") as f32,
+                    iodc: get("iodc") as u16,
+                    fit_interval_ind: (get("fitInt") > 0.0) as u8,
+                }))
+            },
+            (
+                Constellation::BeiDou,
+                NavMessageType::LNAV | NavMessageType::D1 | NavMessageType::D2,
+            ) => {
+                let (_, nanos) = key.epoch.to_time_scale(TimeScale::BDT).to_time_of_week();
+                let tgd1 = eph
+                    .get_orbit_f64("tgdb1b3")
+                    .or(eph.get_orbit_f64("tgd1b1b3"))
+                    .unwrap_or(0.0);
+                let tgd2 = eph
+                    .get_orbit_f64("tgdb2b3")
+                    .or(eph.get_orbit_f64("tgd2b2b3"))
+                    .unwrap_or(0.0);
+
+                Some(Message::Msg1042(Msg1042T {
+                    bds_satellite_id: sv.prn,
+                    bds_week_number: (get("week") as u32 % 8192) as u16,
+                    ura_index: ura_index(get("accuracy")),
+                    idot_sc_s: get("idot") / PI,
+                    aode: get("aode") as u8,
+                    toc_s: (nanos as f64 * 1.0E-9) as f32,
+                    a2_s_s2: eph.clock_drift_rate as f32,
+                    a1_s_s: eph.clock_drift,
+                    a0_s: eph.clock_bias,
+                    aodc: get("aodc") as u8,
+                    crs_m: get("crs") as f32,
+                    delta_n_sc_s: (get("deltaN") / PI) as f32,
+                    m0_sc: get("m0") / PI,
+                    cuc_rad: get("cuc") as f32,
+                    eccentricity: get("e"),
+                    cus_rad: get("cus") as f32,
+                    sqrt_a_sqrt_m: get("sqrta"),
+                    toe_s: get("toe") as f32,
+                    cic_rad: get("cic") as f32,
+                    omega0_sc: get("omega0") / PI,
+                    cis_rad: get("cis") as f32,
+                    i0_sc: get("i0") / PI,
+                    crc_m: get("crc") as f32,
+                    omega_sc: get("omega") / PI,
+                    omegadot_sc_s: get("omegaDot") / PI,
+                    tgd1_s: tgd1 as f32,
+                    tgd2_s: tgd2 as f32,
+                    sv_health_flag: get("health") as u8,
+                }))
+            },
+            (
+                Constellation::Galileo,
+                NavMessageType::LNAV | NavMessageType::FNAV | NavMessageType::INAV,
+            ) => {
+                let (_, nanos) = key.epoch.to_time_scale(TimeScale::GST).to_time_of_week();
+                let toc_s = (nanos as f64 * 1.0E-9) as f32;
+
+                // RINEX: continuous GPS week counter
+                let week = ((get("week") as u32).saturating_sub(1024) % 4096) as u16;
+                let health = get("health") as u32;
+
+                let fnav = match key.msgtype {
+                    NavMessageType::FNAV => true,
+                    NavMessageType::INAV => false,
+                    _ => get("source") as u32 & 0x02 > 0,
+                };
+
+                if fnav {
+                    Some(Message::Msg1045(Msg1045T {
+                        gal_satellite_id: sv.prn,
+                        gal_week_number: week,
+                        iodnav: get("iodnav") as u16,
+                        sisa_e1_e5a_index: sisa_index(get("sisa")),
+                        idot_sc_s: (get("idot") / PI) as f32,
+                        toc_s,
+                        af2_s_s2: eph.clock_drift_rate as f32,
+                        af1_s_s: eph.clock_drift,
+                        af0_s: eph.clock_bias,
+                        crs_m: get("crs") as f32,
+                        delta_n_sc_s: (get("deltaN") / PI) as f32,
+                        m0_sc: get("m0") / PI,
+                        cuc_rad: get("cuc") as f32,
+                        eccentricity: get("e"),
+                        cus_rad: get("cus") as f32,
+                        sqrt_a_sqrt_m: get("sqrta"),
+                        toe_s: get("toe") as f32,
+                        cic_rad: get("cic") as f32,
+                        omega0_sc: get("omega0") / PI,
+                        cis_rad: get("cis") as f32,
+                        i0_sc: get("i0") / PI,
+                        crc_m: get("crc") as f32,
+                        omega_sc: get("omega") / PI,
+                        omegadot_sc_s: get("omegaDot") / PI,
+                        bgd_e1_e5a_s: get("bgdE5aE1") as f32,
+                        e5a_sig_health_ind: ((health >> 4) & 0x03) as u8,
+                        e5a_data_validity_flag: ((health >> 3) & 0x01) as u8,
+                        ..Default::default()
+                    }))
+                } else {
+                    Some(Message::Msg1046(Msg1046T {
+                        gal_satellite_id: sv.prn,
+                        gal_week_number: week,
+                        iodnav: get("iodnav") as u16,
+                        sisa_e1_e5b_index: sisa_index(get("sisa")),
+                        idot_sc_s: (get("idot") / PI) as f32,
+                        toc_s,
+                        af2_s_s2: eph.clock_drift_rate as f32,
+                        af1_s_s: eph.clock_drift,
+                        af0_s: eph.clock_bias,
+                        crs_m: get("crs") as f32,
+                        delta_n_sc_s: (get("deltaN") / PI) as f32,
+                        m0_sc: get("m0") / PI,
+                        cuc_rad: get("cuc") as f32,
+                        eccentricity: get("e"),
+                        cus_rad: get("cus") as f32,
+                        sqrt_a_sqrt_m: get("sqrta"),
+                        toe_s: get("toe") as f32,
+                        cic_rad: get("cic") as f32,
+                        omega0_sc: get("omega0") / PI,
+                        cis_rad: get("cis") as f32,
+                        i0_sc: get("i0") / PI,
+                        crc_m: get("crc") as f32,
+                        omega_sc: get("omega") / PI,
+                        omegadot_sc_s: get("omegaDot") / PI,
+                        bgd_e1_e5a_s: get("bgdE5aE1") as f32,
+                        bgd_e1_e5b_s: get("bgdE5bE1") as f32,
+                        e5b_sig_health_ind: ((health >> 7) & 0x03) as u8,
+                        e5b_data_validity_flag: ((health >> 6) & 0x01) as u8,
+                        e1_b_sig_health_ind: ((health >> 1) & 0x03) as u8,
+                        e1_b_data_validity_flag: (health & 0x01) as u8,
+                        ..Default::default()
+                    }))
+                }
+            },
+            (Constellation::Glonass, NavMessageType::LNAV | NavMessageType::FDMA) => {
+                // tb and tk are expressed in Moscow time (UTC +3h)
+                let toc = key.epoch.to_time_scale(TimeScale::UTC) + 3.0 * Unit::Hour;
+                let tb = (toc - toc.floor(1.0 * Unit::Day)).to_seconds();
+
+                let tk = (eph.clock_drift_rate + 3.0 * 3600.0).rem_euclid(86_400.0);
+                let tk_h = (tk / 3600.0).floor();
+                let tk_min = ((tk - tk_h * 3600.0) / 60.0).floor();
+                let tk_s = tk - tk_h * 3600.0 - tk_min * 60.0;
+
+                // four year interval, and day number within it
+                let (year, _, _, _, _, _, _) = toc.to_gregorian_utc();
+                let n4_year = year - (year - 1996).rem_euclid(4);
+                let n4_start = Epoch::from_gregorian_utc_at_midnight(n4_year, 1, 1);
+                let nt = (toc - n4_start).to_unit(Unit::Day).floor() as u16 + 1;
+
+                Some(Message::Msg1020(Msg1020T {
+                    glo_satellite_id: sv.prn,
+                    glo_satellite_freq_chan_number: eph.glonass_freq_channel().unwrap_or(0),
+                    tk_h: tk_h as u8,
+                    tk_min: tk_min as u8,
+                    tk_s: if tk_s >= 30.0 { 30 } else { 0 },
+                    glo_eph_health_flag: (get("health") as u32 & 0x01) as u8,
+                    tb_min: ((tb / 900.0).round() * 15.0) as u16,
+                    xn_first_deriv_km_s: get("velX"),
+                    xn_km: get("satPosX"),
+                    xn_second_deriv_km_s2: get("accelX") as f32,
+                    yn_first_deriv_km_s: get("velY"),
+                    yn_km: get("satPosY"),
+                    yn_second_deriv_km_s2: get("accelY") as f32,
+                    zn_first_deriv_km_s: get("velZ"),
+                    zn_km: get("satPosZ"),
+                    zn_second_deriv_km_s2: get("accelZ") as f32,
+                    gamma_n: eph.clock_drift as f32,
+                    tau_n_s: -eph.clock_bias,
+                    glo_m_delta_tau_n_s: get("L1L2GrpDelay") as f32,
+                    en_d: (get("ageOp") as u8).min(31),
+                    glo_m_ft_ind: (get("urai") as u8).min(15),
+                    glo_m_nt_d: nt,
+                    na_d: nt,
+                    glo_m_n4_year: n4_year as u16,
+                    ..Default::default()
+                }))
+            },
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn week_resolution() {
+        assert_eq!(resolve_week(294, 10, 2318), 2342);
+        assert_eq!(resolve_week(294, 10, 2342), 2342);
+        assert_eq!(resolve_week(1023, 10, 2049), 2047);
+        assert_eq!(resolve_week(1294, 12, 1294), 1294);
+    }
+
+    #[test]
+    fn accuracy() {
+        assert_eq!(ura_meters(0), 2.4);
+        assert_eq!(ura_meters(15), 6144.0);
+        assert_eq!(sisa_meters(10), 0.1);
+        assert_eq!(sisa_meters(50), 0.5);
+        assert_eq!(sisa_meters(255), -1.0);
+
+        for index in [0, 7, 14] {
+            assert_eq!(ura_index(ura_meters(index)), index);
+        }
+        for index in [0, 10, 49, 50, 74, 75, 99, 107, 125, 255] {
+            assert_eq!(sisa_index(sisa_meters(index)), index);
+        }
+    }
+}
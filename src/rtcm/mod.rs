@@ -1,5 +1,9 @@
 //! RTCM serdes oprations
 
+mod ephemeris;
+mod msm;
+mod rnx2rtcm;
 mod rtcm2rnx;
 
+pub use rnx2rtcm::RNX2RTCM;
 pub use rtcm2rnx::RTCM2RNX;
@@ -0,0 +1,928 @@
+//! RTCM Multiple Signal Messages (MSM) interpretation
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    observation::{LliFlags, SignalObservation, SNR},
+    prelude::{Carrier, Constellation, Epoch, Observable, TimeScale, SV},
+};
+
+use hifitime::Unit;
+
+use rtcm_rs::{
+    msg::{
+        BdsSigId, GalSigId, GloSigId, GpsSigId, Msg1077Sig, Msg1077T, Msg1087Sig, Msg1087T,
+        Msg1097Sig, Msg1097T, Msg1117Sig, Msg1117T, Msg1127Sig, Msg1127T, Msm57GloSat, Msm57Sat,
+        QzssSigId,
+    },
+    Message,
+};
+
+/// Range travelled by light in 1 millisecond, in meters
+const LIGHT_MS_M: f64 = 299_792.458;
+
+/// Fine pseudo range dynamics (extended resolution), in milliseconds
+const FINE_PSEUDO_RANGE_MAX_MS: f64 = 1.0 / 1024.0;
+
+/// Fine phase range dynamics (extended resolution), in milliseconds
+const FINE_PHASE_RANGE_MAX_MS: f64 = 1.0 / 256.0;
+
+/// Fine phase range rate dynamics, in m/s
+const FINE_RANGE_RATE_MAX_M_S: f64 = 1.6383;
+
+/// Maximal number of cells (satellites x signals) in one MSM
+const MAX_CELLS: usize = 64;
+
+/// Converts a lock time (in milliseconds) to the extended lock time indicator (DF407)
+fn lock_time_indicator(lock_ms: u64) -> u16 {
+    if lock_ms < 64 {
+        lock_ms as u16
+    } else if lock_ms >= 67_108_864 {
+        704
+    } else {
+        let k = (63 - (lock_ms / 32).leading_zeros()) as u64;
+        ((lock_ms >> k) + 32 * k) as u16
+    }
+}
+
+/// Returns true if this signal (band, attribute) may be described by MSM
+fn is_msm_signal(constellation: Constellation, band: u8, attribute: char) -> bool {
+    match constellation {
+        Constellation::GPS => GpsSigId::new(band, attribute).is_valid(),
+        Constellation::Glonass => GloSigId::new(band, attribute).is_valid(),
+        Constellation::Galileo => GalSigId::new(band, attribute).is_valid(),
+        Constellation::QZSS => QzssSigId::new(band, attribute).is_valid(),
+        Constellation::BeiDou => BdsSigId::new(band, attribute).is_valid(),
+        _ => false,
+    }
+}
+
+/// Satellite data, as described by one MSM.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MsmSatellite {
+    /// Satellite number
+    pub prn: u8,
+    /// Rough range, in milliseconds (None when not valid)
+    pub rough_range_ms: Option<f64>,
+    /// Rough phase range rate in m/s (MSM5 & MSM7 only)
+    pub rough_range_rate_m_s: Option<f64>,
+    /// GLONASS frequency channel number (MSM5 & MSM7 only)
+    pub glo_channel: Option<i8>,
+}
+
+/// Signal data, as described by one MSM.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MsmSignal {
+    /// Satellite number
+    pub prn: u8,
+    /// RINEX frequency band
+    pub band: u8,
+    /// RINEX tracking mode (attribute)
+    pub attribute: char,
+    /// Fine pseudo range, in milliseconds
+    pub fine_pseudo_range_ms: Option<f64>,
+    /// Fine phase range, in milliseconds
+    pub fine_phase_range_ms: Option<f64>,
+    /// Fine phase range rate, in m/s (MSM5 & MSM7 only)
+    pub fine_range_rate_m_s: Option<f64>,
+    /// Lock time indicator
+    pub lock_time: u16,
+    /// Half cycle ambiguity
+    pub half_cycle: bool,
+    /// Carrier to noise ratio, in dB.Hz
+    pub cnr_dbhz: Option<f64>,
+}
+
+/// [Msm] is the interpretation of any MSM4, MSM5 or MSM7 message,
+/// whatever the constellation.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Msm {
+    /// [Constellation] of all satellites
+    pub constellation: Constellation,
+    /// Time of week in milliseconds, or time of day (Moscow time) for GLONASS
+    pub time_ms: u32,
+    /// GLONASS day of week (Moscow time, 0 being sunday), when known
+    pub glo_day_of_week: Option<u8>,
+    /// Satellites data
+    pub satellites: Vec<MsmSatellite>,
+    /// Signals data
+    pub signals: Vec<MsmSignal>,
+}
+
+/// Returns the wavelength (in meters) of this signal (for example "1C"),
+/// GLONASS FDMA signals requiring the frequency channel to be known.
+fn wavelength(sv: SV, code: &str, glo_channels: &HashMap<SV, i8>) -> Option<f64> {
+    let carrier = Carrier::from_observable(
+        sv.constellation,
+        &Observable::PhaseRange(format!("L{}", code)),
+    )
+    .ok()?;
+
+    let carrier = match carrier {
+        Carrier::G1(_) => Carrier::G1(Some(*glo_channels.get(&sv)?)),
+        Carrier::G2(_) => Carrier::G2(Some(*glo_channels.get(&sv)?)),
+        carrier => carrier,
+    };
+
+    Some(carrier.wavelength())
+}
+
+macro_rules! msm46_satellites {
+    ($data:expr) => {
+        $data
+            .satellite_data
+            .iter()
+            .map(|sat| MsmSatellite {
+                prn: sat.satellite_id,
+                rough_range_ms: sat
+                    .gnss_satellite_rough_range_integer_ms
+                    .map(|ms| ms as f64 + sat.gnss_satellite_rough_range_mod1ms_ms),
+                rough_range_rate_m_s: None,
+                glo_channel: None,
+            })
+            .collect()
+    };
+}
+
+macro_rules! msm57_satellites {
+    ($data:expr, $glo_channel:expr) => {
+        $data
+            .satellite_data
+            .iter()
+            .map(|sat| MsmSatellite {
+                prn: sat.satellite_id,
+                rough_range_ms: sat
+                    .gnss_satellite_rough_range_integer_ms
+                    .map(|ms| ms as f64 + sat.gnss_satellite_rough_range_mod1ms_ms),
+                rough_range_rate_m_s: sat
+                    .gnss_satellite_rough_phaserange_rates_m_s
+                    .map(|rate| rate as f64),
+                glo_channel: $glo_channel(sat),
+            })
+            .collect()
+    };
+}
+
+macro_rules! msm4_signals {
+    ($data:expr) => {
+        $data
+            .signal_data
+            .iter()
+            .map(|sig| MsmSignal {
+                prn: sig.satellite_id,
+                band: sig.signal_id.band(),
+                attribute: sig.signal_id.attribute(),
+                fine_pseudo_range_ms: sig.gnss_signal_fine_pseudorange_ms,
+                fine_phase_range_ms: sig.gnss_signal_fine_phaserange_ms,
+                fine_range_rate_m_s: None,
+                lock_time: sig.gnss_phaserange_lock_time_ind as u16,
+                half_cycle: sig.half_cycle_ambiguity_ind > 0,
+                cnr_dbhz: sig.gnss_signal_cnr_dbhz.map(|cnr| cnr as f64),
+            })
+            .collect()
+    };
+}
+
+macro_rules! msm5_signals {
+    ($data:expr) => {
+        $data
+            .signal_data
+            .iter()
+            .map(|sig| MsmSignal {
+                prn: sig.satellite_id,
+                band: sig.signal_id.band(),
+                attribute: sig.signal_id.attribute(),
+                fine_pseudo_range_ms: sig.gnss_signal_fine_pseudorange_ms,
+                fine_phase_range_ms: sig.gnss_signal_fine_phaserange_ms,
+                fine_range_rate_m_s: sig.gnss_signal_fine_phaserange_rate_m_s,
+                lock_time: sig.gnss_phaserange_lock_time_ind as u16,
+                half_cycle: sig.half_cycle_ambiguity_ind > 0,
+                cnr_dbhz: sig.gnss_signal_cnr_dbhz.map(|cnr| cnr as f64),
+            })
+            .collect()
+    };
+}
+
+macro_rules! msm7_signals {
+    ($data:expr) => {
+        $data
+            .signal_data
+            .iter()
+            .map(|sig| MsmSignal {
+                prn: sig.satellite_id,
+                band: sig.signal_id.band(),
+                attribute: sig.signal_id.attribute(),
+                fine_pseudo_range_ms: sig.gnss_signal_fine_pseudorange_ext_ms,
+                fine_phase_range_ms: sig.gnss_signal_fine_phaserange_ext_ms,
+                fine_range_rate_m_s: sig.gnss_signal_fine_phaserange_rate_m_s,
+                lock_time: sig.gnss_phaserange_lock_time_ext_ind,
+                half_cycle: sig.half_cycle_ambiguity_ind > 0,
+                cnr_dbhz: sig.gnss_signal_cnr_ext_dbhz,
+            })
+            .collect()
+    };
+}
+
+macro_rules! msm7_message {
+    ($msm:expr, $msg:ident, $sig:ident, $sig_id:ident, $time_ms:ident, $station_id:expr, $multiple:expr, $sat:expr) => {{
+        let mut msg = $msg {
+            reference_station_id: $station_id,
+            $time_ms: $msm.time_ms,
+            msm_multiple_message_flag: $multiple as u8,
+            ..Default::default()
+        };
+
+        let mut satellites = $msm.satellites.iter().collect::<Vec<_>>();
+        satellites.sort_by_key(|sat| sat.prn);
+
+        for sat in satellites {
+            msg.data_segment.satellite_data.push($sat(sat));
+        }
+
+        let mut signals = $msm
+            .signals
+            .iter()
+            .map(|sig| $sig {
+                satellite_id: sig.prn,
+                signal_id: $sig_id::new(sig.band, sig.attribute),
+                gnss_signal_fine_pseudorange_ext_ms: sig.fine_pseudo_range_ms,
+                gnss_signal_fine_phaserange_ext_ms: sig.fine_phase_range_ms,
+                gnss_phaserange_lock_time_ext_ind: sig.lock_time,
+                half_cycle_ambiguity_ind: sig.half_cycle as u8,
+                gnss_signal_cnr_ext_dbhz: sig.cnr_dbhz,
+                gnss_signal_fine_phaserange_rate_m_s: sig.fine_range_rate_m_s,
+            })
+            .collect::<Vec<_>>();
+
+        // cells are described in mask order
+        signals.sort_by(|a, b| {
+            a.satellite_id.cmp(&b.satellite_id).then(
+                a.signal_id
+                    .partial_cmp(&b.signal_id)
+                    .unwrap_or(std::cmp::Ordering::Equal),
+            )
+        });
+
+        for sig in signals {
+            msg.data_segment.signal_data.push(sig);
+        }
+
+        msg
+    }};
+}
+
+macro_rules! msm {
+    (glonass: $msg:expr, $time_ms:ident, $day:ident, $satellites:expr, $signals:expr) => {
+        Some(Msm {
+            constellation: Constellation::Glonass,
+            time_ms: $msg.$time_ms,
+            glo_day_of_week: $msg.$day,
+            satellites: $satellites,
+            signals: $signals,
+        })
+    };
+    ($msg:expr, $constellation:expr, $time_ms:ident, $satellites:expr, $signals:expr) => {
+        Some(Msm {
+            constellation: $constellation,
+            time_ms: $msg.$time_ms,
+            glo_day_of_week: None,
+            satellites: $satellites,
+            signals: $signals,
+        })
+    };
+}
+
+impl Msm {
+    /// Interprets this RTCM [Message], if it is a supported MSM.
+    pub fn from_message(msg: &Message) -> Option<Self> {
+        let no_channel = |_: &_| None;
+        let glo_channel =
+            |sat: &rtcm_rs::msg::Msm57GloSat| sat.glonass_satellite_frequency_channel_number;
+
+        match msg {
+            // GPS
+            Message::Msg1074(m) => msm!(
+                m,
+                Constellation::GPS,
+                gps_epoch_time_ms,
+                msm46_satellites!(m.data_segment),
+                msm4_signals!(m.data_segment)
+            ),
+            Message::Msg1075(m) => msm!(
+                m,
+                Constellation::GPS,
+                gps_epoch_time_ms,
+                msm57_satellites!(m.data_segment, no_channel),
+                msm5_signals!(m.data_segment)
+            ),
+            Message::Msg1077(m) => msm!(
+                m,
+                Constellation::GPS,
+                gps_epoch_time_ms,
+                msm57_satellites!(m.data_segment, no_channel),
+                msm7_signals!(m.data_segment)
+            ),
+            // Glonass
+            Message::Msg1084(m) => msm!(
+                glonass: m,
+                glo_epoch_time_ms,
+                glo_day_of_week,
+                msm46_satellites!(m.data_segment),
+                msm4_signals!(m.data_segment)
+            ),
+            Message::Msg1085(m) => msm!(
+                glonass: m,
+                glo_epoch_time_ms,
+                glo_day_of_week,
+                msm57_satellites!(m.data_segment, glo_channel),
+                msm5_signals!(m.data_segment)
+            ),
+            Message::Msg1087(m) => msm!(
+                glonass: m,
+                glo_epoch_time_ms,
+                glo_day_of_week,
+                msm57_satellites!(m.data_segment, glo_channel),
+                msm7_signals!(m.data_segment)
+            ),
+            // Galileo
+            Message::Msg1094(m) => msm!(
+                m,
+                Constellation::Galileo,
+                gal_epoch_time_ms,
+                msm46_satellites!(m.data_segment),
+                msm4_signals!(m.data_segment)
+            ),
+            Message::Msg1095(m) => msm!(
+                m,
+                Constellation::Galileo,
+                gal_epoch_time_ms,
+                msm57_satellites!(m.data_segment, no_channel),
+                msm5_signals!(m.data_segment)
+            ),
+            Message::Msg1097(m) => msm!(
+                m,
+                Constellation::Galileo,
+                gal_epoch_time_ms,
+                msm57_satellites!(m.data_segment, no_channel),
+                msm7_signals!(m.data_segment)
+            ),
+            // QZSS
+            Message::Msg1114(m) => msm!(
+                m,
+                Constellation::QZSS,
+                qzss_epoch_time_ms,
+                msm46_satellites!(m.data_segment),
+                msm4_signals!(m.data_segment)
+            ),
+            Message::Msg1115(m) => msm!(
+                m,
+                Constellation::QZSS,
+                qzss_epoch_time_ms,
+                msm57_satellites!(m.data_segment, no_channel),
+                msm5_signals!(m.data_segment)
+            ),
+            Message::Msg1117(m) => msm!(
+                m,
+                Constellation::QZSS,
+                qzss_epoch_time_ms,
+                msm57_satellites!(m.data_segment, no_channel),
+                msm7_signals!(m.data_segment)
+            ),
+            // BeiDou
+            Message::Msg1124(m) => msm!(
+                m,
+                Constellation::BeiDou,
+                bds_epoch_time_ms,
+                msm46_satellites!(m.data_segment),
+                msm4_signals!(m.data_segment)
+            ),
+            Message::Msg1125(m) => msm!(
+                m,
+                Constellation::BeiDou,
+                bds_epoch_time_ms,
+                msm57_satellites!(m.data_segment, no_channel),
+                msm5_signals!(m.data_segment)
+            ),
+            Message::Msg1127(m) => msm!(
+                m,
+                Constellation::BeiDou,
+                bds_epoch_time_ms,
+                msm57_satellites!(m.data_segment, no_channel),
+                msm7_signals!(m.data_segment)
+            ),
+            _ => None,
+        }
+    }
+
+    /// Resolves the sampling [Epoch] (expressed in [TimeScale::GPST]),
+    /// MSM only describing the time of week (or time of day):
+    /// we pick the solution closest to this reference [Epoch].
+    pub fn epoch(&self, t_ref: Epoch) -> Epoch {
+        let t = match self.constellation {
+            Constellation::Glonass => {
+                // GLONASS time of day is expressed in Moscow time (UTC +3h)
+                let t_ref = t_ref.to_time_scale(TimeScale::UTC) + 3.0 * Unit::Hour;
+                let t = t_ref.floor(1.0 * Unit::Day) + (self.time_ms as f64) * Unit::Millisecond;
+                let t = if t - t_ref > 12.0 * Unit::Hour {
+                    t - 1.0 * Unit::Day
+                } else if t_ref - t > 12.0 * Unit::Hour {
+                    t + 1.0 * Unit::Day
+                } else {
+                    t
+                };
+                t - 3.0 * Unit::Hour
+            },
+            constellation => {
+                let ts = constellation.timescale().unwrap_or(TimeScale::GPST);
+                let t_ref = t_ref.to_time_scale(ts);
+                let (week, _) = t_ref.to_time_of_week();
+                let t = Epoch::from_time_of_week(week, self.time_ms as u64 * 1_000_000, ts);
+                if t - t_ref > 3.5 * Unit::Day {
+                    t - 1.0 * Unit::Week
+                } else if t_ref - t > 3.5 * Unit::Day {
+                    t + 1.0 * Unit::Week
+                } else {
+                    t
+                }
+            },
+        };
+        t.to_time_scale(TimeScale::GPST)
+    }
+
+    /// Converts this [Msm] to [SignalObservation]s.
+    /// ## Inputs
+    /// - glo_channels: GLONASS frequency channels, required to
+    ///   express GLONASS phase and doppler observations.
+    /// - lock_times: lock time indicators, updated by this method
+    ///   and used to emit [LliFlags::LOCK_LOSS].
+    pub fn signal_observations(
+        &self,
+        glo_channels: &HashMap<SV, i8>,
+        lock_times: &mut HashMap<(SV, String), u16>,
+    ) -> Vec<SignalObservation> {
+        let mut observations = Vec::with_capacity(self.signals.len() * 4);
+
+        for signal in self.signals.iter() {
+            let sat = match self.satellites.iter().find(|sat| sat.prn == signal.prn) {
+                Some(sat) => sat,
+                None => continue,
+            };
+
+            let rough_range_ms = match sat.rough_range_ms {
+                Some(rough) => rough,
+                None => continue,
+            };
+
+            let sv = SV::new(self.constellation, signal.prn);
+            let code = format!("{}{}", signal.band, signal.attribute);
+            let snr = signal.cnr_dbhz.map(SNR::from);
+
+            let lambda = wavelength(sv, &code, glo_channels);
+
+            if let Some(fine) = signal.fine_pseudo_range_ms {
+                let pr = (rough_range_ms + fine) * LIGHT_MS_M;
+                let mut obs =
+                    SignalObservation::new(sv, Observable::PseudoRange(format!("C{}", code)), pr);
+                obs.snr = snr;
+                observations.push(obs);
+            }
+
+            if let Some(lambda) = lambda {
+                if let Some(fine) = signal.fine_phase_range_ms {
+                    let cycles = (rough_range_ms + fine) * LIGHT_MS_M / lambda;
+
+                    let mut lli = LliFlags::OK_OR_UNKNOWN;
+                    if signal.half_cycle {
+                        lli |= LliFlags::HALF_CYCLE_SLIP;
+                    }
+
+                    // lock time decrease (or lock reacquired twice): loss of lock
+                    match lock_times.insert((sv, code.clone()), signal.lock_time) {
+                        Some(prev) if signal.lock_time < prev => lli |= LliFlags::LOCK_LOSS,
+                        Some(0) | None if signal.lock_time == 0 => lli |= LliFlags::LOCK_LOSS,
+                        _ => {},
+                    }
+
+                    let mut obs = SignalObservation::new(
+                        sv,
+                        Observable::PhaseRange(format!("L{}", code)),
+                        cycles,
+                    );
+                    obs.snr = snr;
+                    obs.lli = Some(lli);
+                    observations.push(obs);
+                }
+
+                if let (Some(rough), Some(fine)) =
+                    (sat.rough_range_rate_m_s, signal.fine_range_rate_m_s)
+                {
+                    let doppler = -(rough + fine) / lambda;
+                    observations.push(SignalObservation::new(
+                        sv,
+                        Observable::Doppler(format!("D{}", code)),
+                        doppler,
+                    ));
+                }
+            }
+
+            if let Some(cnr) = signal.cnr_dbhz {
+                observations.push(SignalObservation::new(
+                    sv,
+                    Observable::SSI(format!("S{}", code)),
+                    cnr,
+                ));
+            }
+        }
+
+        observations
+    }
+}
+
+/// Signal measurements, gathered per (band, attribute) prior MSM encoding
+#[derive(Debug, Default)]
+struct Cell<'a> {
+    pseudo_range: Option<&'a SignalObservation>,
+    phase_range: Option<&'a SignalObservation>,
+    doppler: Option<&'a SignalObservation>,
+    ssi: Option<&'a SignalObservation>,
+}
+
+/// Carrier phase tracking state, required to encode MSM continuously.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct PhaseTracking {
+    /// Start of continuous tracking, per signal
+    lock_start: HashMap<(SV, String), Epoch>,
+    /// Integer number of cycles removed from the phase range (per signal),
+    /// for it to fit within the MSM dynamics
+    offsets: HashMap<(SV, String), f64>,
+}
+
+impl Msm {
+    /// Builds the [Msm] describing these [SignalObservation]s of this [Constellation],
+    /// sampled at this [Epoch]. Only signals that MSM can describe are retained.
+    /// ## Inputs
+    /// - glo_channels: GLONASS frequency channels, required to
+    ///   express GLONASS phase and doppler observations.
+    /// - tracking: [PhaseTracking] state, updated by this method.
+    pub fn from_observations(
+        constellation: Constellation,
+        epoch: Epoch,
+        observations: &[SignalObservation],
+        glo_channels: &HashMap<SV, i8>,
+        tracking: &mut PhaseTracking,
+    ) -> Option<Self> {
+        let (time_ms, glo_day_of_week) = match constellation {
+            Constellation::Glonass => {
+                // GLONASS time of day is expressed in Moscow time (UTC +3h)
+                let t = epoch.to_time_scale(TimeScale::UTC) + 3.0 * Unit::Hour;
+                let tod = t - t.floor(1.0 * Unit::Day);
+                let day_of_week = (t.weekday_utc() as u8 + 1) % 7;
+                (
+                    tod.to_unit(Unit::Millisecond).round() as u32,
+                    Some(day_of_week),
+                )
+            },
+            constellation => {
+                let ts = constellation.timescale()?;
+                let (_, nanos) = epoch.to_time_scale(ts).to_time_of_week();
+                ((nanos as f64 / 1.0E6).round() as u32, None)
+            },
+        };
+
+        // gather signals per satellite and per (band, attribute)
+        let mut cells = BTreeMap::<SV, BTreeMap<String, Cell>>::new();
+
+        for obs in observations.iter() {
+            if obs.sv.constellation != constellation {
+                continue;
+            }
+
+            let code = match obs.observable.code() {
+                Some(code) => code,
+                None => continue,
+            };
+
+            let mut chars = code.chars();
+            let band = chars.next().and_then(|c| c.to_digit(10)).unwrap_or(0) as u8;
+            let attribute = chars.next().unwrap_or(' ');
+
+            if !is_msm_signal(constellation, band, attribute) {
+                continue;
+            }
+
+            let cell = cells.entry(obs.sv).or_default().entry(code).or_default();
+
+            match obs.observable {
+                Observable::PseudoRange(_) => cell.pseudo_range = Some(obs),
+                Observable::PhaseRange(_) => cell.phase_range = Some(obs),
+                Observable::Doppler(_) => cell.doppler = Some(obs),
+                Observable::SSI(_) => cell.ssi = Some(obs),
+                _ => {},
+            }
+        }
+
+        let mut satellites = Vec::with_capacity(cells.len());
+        let mut signals = Vec::with_capacity(cells.len() * 4);
+
+        for (sv, cells) in cells.iter() {
+            let lambdas = cells
+                .keys()
+                .map(|code| (code, wavelength(*sv, code, glo_channels)))
+                .collect::<HashMap<_, _>>();
+
+            // rough range, from first pseudo range (or phase range)
+            let rough_range_ms = cells
+                .values()
+                .find_map(|cell| cell.pseudo_range.map(|pr| pr.value / LIGHT_MS_M))
+                .or_else(|| {
+                    cells.iter().find_map(|(code, cell)| {
+                        let lambda = lambdas[code]?;
+                        cell.phase_range.map(|ph| ph.value * lambda / LIGHT_MS_M)
+                    })
+                })
+                .map(|range_ms| (range_ms * 1024.0).round() / 1024.0)
+                .filter(|range_ms| *range_ms >= 0.0 && *range_ms < 255.0);
+
+            let rough_range_ms = match rough_range_ms {
+                Some(rough) => rough,
+                None => continue,
+            };
+
+            // rough range rate, from first doppler
+            let rough_range_rate_m_s = cells
+                .iter()
+                .find_map(|(code, cell)| {
+                    let lambda = lambdas[code]?;
+                    cell.doppler.map(|dop| (-dop.value * lambda).round())
+                })
+                .filter(|rate| rate.abs() < 8192.0);
+
+            satellites.push(MsmSatellite {
+                prn: sv.prn,
+                rough_range_ms: Some(rough_range_ms),
+                rough_range_rate_m_s,
+                glo_channel: glo_channels.get(sv).copied(),
+            });
+
+            for (code, cell) in cells.iter() {
+                let mut chars = code.chars();
+                let band = chars.next().and_then(|c| c.to_digit(10)).unwrap_or(0) as u8;
+                let attribute = chars.next().unwrap_or(' ');
+                let lambda = lambdas[code];
+
+                let fine_pseudo_range_ms = cell
+                    .pseudo_range
+                    .map(|pr| pr.value / LIGHT_MS_M - rough_range_ms)
+                    .filter(|fine| fine.abs() < FINE_PSEUDO_RANGE_MAX_MS);
+
+                let mut fine_phase_range_ms = None;
+                let mut lock_time = 0;
+                let mut half_cycle = false;
+
+                if let (Some(phase), Some(lambda)) = (cell.phase_range, lambda) {
+                    let key = (*sv, code.clone());
+                    let lli = phase.lli.unwrap_or(LliFlags::OK_OR_UNKNOWN);
+                    let mut loss_of_lock = lli.intersects(LliFlags::LOCK_LOSS);
+                    half_cycle = lli.intersects(LliFlags::HALF_CYCLE_SLIP);
+
+                    let offset = tracking.offsets.get(&key).copied().unwrap_or(0.0);
+                    let mut fine = (phase.value - offset) * lambda / LIGHT_MS_M - rough_range_ms;
+
+                    if fine.abs() >= FINE_PHASE_RANGE_MAX_MS {
+                        // remove integer number of cycles: this is a discontinuity
+                        let offset = ((phase.value * lambda / LIGHT_MS_M - rough_range_ms)
+                            * LIGHT_MS_M
+                            / lambda)
+                            .round();
+                        tracking.offsets.insert(key.clone(), offset);
+                        fine = (phase.value - offset) * lambda / LIGHT_MS_M - rough_range_ms;
+                        loss_of_lock = true;
+                    }
+
+                    if fine.abs() < FINE_PHASE_RANGE_MAX_MS {
+                        let start = tracking.lock_start.entry(key).or_insert(epoch);
+                        if loss_of_lock {
+                            *start = epoch;
+                        }
+
+                        let lock_ms = (epoch - *start).to_unit(Unit::Millisecond).max(0.0);
+                        lock_time = lock_time_indicator(lock_ms as u64);
+                        fine_phase_range_ms = Some(fine);
+                    }
+                }
+
+                let fine_range_rate_m_s = match (rough_range_rate_m_s, cell.doppler, lambda) {
+                    (Some(rough), Some(doppler), Some(lambda)) => {
+                        let fine = -doppler.value * lambda - rough;
+                        if fine.abs() < FINE_RANGE_RATE_MAX_M_S {
+                            Some(fine)
+                        } else {
+                            None
+                        }
+                    },
+                    _ => None,
+                };
+
+                let cnr_dbhz = cell
+                    .ssi
+                    .map(|ssi| ssi.value)
+                    .or_else(|| {
+                        [cell.pseudo_range, cell.phase_range]
+                            .iter()
+                            .flatten()
+                            .find_map(|obs| obs.snr.map(f64::from))
+                    })
+                    .filter(|cnr| *cnr > 0.0)
+                    .map(|cnr| cnr.min(63.9375));
+
+                signals.push(MsmSignal {
+                    prn: sv.prn,
+                    band,
+                    attribute,
+                    fine_pseudo_range_ms,
+                    fine_phase_range_ms,
+                    fine_range_rate_m_s,
+                    lock_time,
+                    half_cycle,
+                    cnr_dbhz,
+                });
+            }
+        }
+
+        if satellites.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            constellation,
+            time_ms,
+            glo_day_of_week,
+            satellites,
+            signals,
+        })
+    }
+
+    /// Splits this [Msm] so each one respects the MSM cell limitation.
+    pub fn split(self) -> Vec<Self> {
+        let mut split = Vec::new();
+        let mut codes = Vec::<(u8, char)>::new();
+
+        let mut current = Self {
+            constellation: self.constellation,
+            time_ms: self.time_ms,
+            glo_day_of_week: self.glo_day_of_week,
+            satellites: Vec::new(),
+            signals: Vec::new(),
+        };
+
+        for sat in self.satellites.into_iter() {
+            let sat_signals = self
+                .signals
+                .iter()
+                .filter(|sig| sig.prn == sat.prn)
+                .cloned()
+                .collect::<Vec<_>>();
+
+            let mut union = codes.clone();
+            for sig in sat_signals.iter() {
+                if !union.contains(&(sig.band, sig.attribute)) {
+                    union.push((sig.band, sig.attribute));
+                }
+            }
+
+            if !current.satellites.is_empty()
+                && (current.satellites.len() + 1) * union.len() > MAX_CELLS
+            {
+                let next = Self {
+                    satellites: Vec::new(),
+                    signals: Vec::new(),
+                    ..current.clone()
+                };
+
+                split.push(std::mem::replace(&mut current, next));
+
+                union = sat_signals
+                    .iter()
+                    .map(|sig| (sig.band, sig.attribute))
+                    .collect();
+                union.dedup();
+            }
+
+            codes = union;
+            current.satellites.push(sat);
+            current.signals.extend(sat_signals);
+        }
+
+        if !current.satellites.is_empty() {
+            split.push(current);
+        }
+
+        split
+    }
+
+    /// Encodes this [Msm] as MSM7 [Message].
+    /// ## Inputs
+    /// - station_id: reference station ID
+    /// - multiple_message: true when other MSM of the same epoch follow
+    pub fn to_msm7(&self, station_id: u16, multiple_message: bool) -> Option<Message> {
+        let sat = |sat: &MsmSatellite| Msm57Sat {
+            satellite_id: sat.prn,
+            gnss_satellite_rough_range_integer_ms: sat.rough_range_ms.map(|ms| ms.floor() as u8),
+            gnss_satellite_rough_range_mod1ms_ms: sat
+                .rough_range_ms
+                .map(|ms| ms - ms.floor())
+                .unwrap_or_default(),
+            gnss_satellite_rough_phaserange_rates_m_s: sat
+                .rough_range_rate_m_s
+                .map(|rate| rate as i16),
+            ..Default::default()
+        };
+
+        let glo_sat = |sat: &MsmSatellite| Msm57GloSat {
+            satellite_id: sat.prn,
+            gnss_satellite_rough_range_integer_ms: sat.rough_range_ms.map(|ms| ms.floor() as u8),
+            glonass_satellite_frequency_channel_number: sat.glo_channel,
+            gnss_satellite_rough_range_mod1ms_ms: sat
+                .rough_range_ms
+                .map(|ms| ms - ms.floor())
+                .unwrap_or_default(),
+            gnss_satellite_rough_phaserange_rates_m_s: sat
+                .rough_range_rate_m_s
+                .map(|rate| rate as i16),
+        };
+
+        match self.constellation {
+            Constellation::GPS => Some(Message::Msg1077(msm7_message!(
+                self,
+                Msg1077T,
+                Msg1077Sig,
+                GpsSigId,
+                gps_epoch_time_ms,
+                station_id,
+                multiple_message,
+                sat
+            ))),
+            Constellation::Glonass => {
+                let mut msg = msm7_message!(
+                    self,
+                    Msg1087T,
+                    Msg1087Sig,
+                    GloSigId,
+                    glo_epoch_time_ms,
+                    station_id,
+                    multiple_message,
+                    glo_sat
+                );
+                msg.glo_day_of_week = self.glo_day_of_week;
+                Some(Message::Msg1087(msg))
+            },
+            Constellation::Galileo => Some(Message::Msg1097(msm7_message!(
+                self,
+                Msg1097T,
+                Msg1097Sig,
+                GalSigId,
+                gal_epoch_time_ms,
+                station_id,
+                multiple_message,
+                sat
+            ))),
+            Constellation::QZSS => Some(Message::Msg1117(msm7_message!(
+                self,
+                Msg1117T,
+                Msg1117Sig,
+                QzssSigId,
+                qzss_epoch_time_ms,
+                station_id,
+                multiple_message,
+                sat
+            ))),
+            Constellation::BeiDou => Some(Message::Msg1127(msm7_message!(
+                self,
+                Msg1127T,
+                Msg1127Sig,
+                BdsSigId,
+                bds_epoch_time_ms,
+                station_id,
+                multiple_message,
+                sat
+            ))),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lock_time_indicators() {
+        assert_eq!(lock_time_indicator(0), 0);
+        assert_eq!(lock_time_indicator(63), 63);
+        assert_eq!(lock_time_indicator(64), 64);
+        assert_eq!(lock_time_indicator(127), 95);
+        assert_eq!(lock_time_indicator(128), 96);
+        assert_eq!(lock_time_indicator(300), 133);
+        assert_eq!(lock_time_indicator(67_108_863), 703);
+        assert_eq!(lock_time_indicator(100_000_000), 704);
+    }
+}
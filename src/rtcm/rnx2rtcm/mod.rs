@@ -0,0 +1,302 @@
+//! RINEX to RTCM serialization
+use std::io::Write;
+
+use crate::prelude::{Constellation, Epoch, Header, Rinex};
+
+use rtcm_rs::{
+    msg::{Msg1005T, Msg1006T, Msg1033T},
+    Message, MessageBuilder,
+};
+
+#[cfg(feature = "log")]
+use log::error;
+
+mod nav;
+mod obs;
+
+use nav::Streamer as NavStreamer;
+use obs::Streamer as ObsStreamer;
+
+/// RINEX Type dependant record streamer
+enum TypeDependentStreamer<'a> {
+    /// OBS Record streamer
+    Obs(ObsStreamer<'a>),
+    /// NAV Record streamer
+    Nav(NavStreamer<'a>),
+}
+
+impl TypeDependentStreamer<'_> {
+    fn epoch(&self) -> Option<Epoch> {
+        match self {
+            Self::Obs(streamer) => streamer.epoch(),
+            Self::Nav(streamer) => streamer.epoch(),
+        }
+    }
+}
+
+impl Iterator for TypeDependentStreamer<'_> {
+    type Item = Message;
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Obs(streamer) => streamer.next(),
+            Self::Nav(streamer) => streamer.next(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum State {
+    /// Station coordinates (1005 or 1006)
+    #[default]
+    StationCoordinates,
+    /// Antenna and receiver descriptors (1033)
+    StationDescriptors,
+    RecordStream,
+}
+
+/// RNX2RTCM can serialize a [Rinex] into a stream of RTCM [Message]s.
+/// Use the [Iterator] implementation to obtain the [Message]s, or [Self::write_all]
+/// to encode the stream into any [Write]able interface (file, TCP socket..).
+pub struct RNX2RTCM<'a> {
+    /// Reference station ID
+    station_id: u16,
+    /// Header consumption State machine
+    state: State,
+    /// RINEX [Header] snapshot
+    header: &'a Header,
+    /// RINEX [TypeDependentStreamer]
+    streamer: TypeDependentStreamer<'a>,
+    /// Assert (before deployment) whether the station messages should not be streamed (no default!)
+    pub skip_header: bool,
+}
+
+impl Iterator for RNX2RTCM<'_> {
+    type Item = Message;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let msg = match self.state {
+                State::StationCoordinates => {
+                    self.state = State::StationDescriptors;
+                    if self.skip_header {
+                        None
+                    } else {
+                        self.forge_station_coordinates()
+                    }
+                },
+                State::StationDescriptors => {
+                    self.state = State::RecordStream;
+                    if self.skip_header {
+                        None
+                    } else {
+                        self.forge_station_descriptors()
+                    }
+                },
+                State::RecordStream => {
+                    return self.streamer.next();
+                },
+            };
+
+            if msg.is_some() {
+                return msg;
+            }
+        }
+    }
+}
+
+impl RNX2RTCM<'_> {
+    /// Returns [Epoch] of the latest streamed record entry,
+    /// which allows pacing a replay at the original rate.
+    pub fn epoch(&self) -> Option<Epoch> {
+        self.streamer.epoch()
+    }
+
+    /// Encodes the next [Message] of this stream.
+    /// Messages that cannot be encoded are dropped.
+    pub fn next_frame(&mut self) -> Option<Vec<u8>> {
+        let mut builder = MessageBuilder::new();
+        loop {
+            let msg = self.next()?;
+            match builder.build_message(&msg) {
+                Ok(bytes) => return Some(bytes.to_vec()),
+                Err(e) => {
+                    #[cfg(feature = "log")]
+                    error!("rnx2rtcm: failed to encode message: {:?}", e);
+                    #[cfg(not(feature = "log"))]
+                    let _ = e;
+                },
+            }
+        }
+    }
+
+    /// Encodes the remaining stream into this [Write]able interface.
+    /// Returns the total number of bytes written.
+    pub fn write_all<W: Write>(&mut self, w: &mut W) -> std::io::Result<usize> {
+        let mut size = 0;
+        while let Some(frame) = self.next_frame() {
+            w.write_all(&frame)?;
+            size += frame.len();
+        }
+        Ok(size)
+    }
+
+    /// Forms 1006 (or 1005 when antenna height is unknown) from [Header].
+    /// [Header::rx_position] is the marker position, while RTCM describes the
+    /// antenna reference point (ARP) and its height above the marker.
+    /// The ARP is therefore the marker, offset by the antenna eccentricities.
+    fn forge_station_coordinates(&self) -> Option<Message> {
+        let marker = self.header.rx_position?;
+
+        let (gps, glonass, galileo) = match self.header.constellation {
+            Some(Constellation::Mixed) | None => (1, 1, 1),
+            Some(constellation) => (
+                (constellation == Constellation::GPS) as u8,
+                (constellation == Constellation::Glonass) as u8,
+                (constellation == Constellation::Galileo) as u8,
+            ),
+        };
+
+        let antenna = self.header.rcvr_antenna.as_ref();
+
+        let height = antenna.and_then(|antenna| antenna.height);
+
+        let enu = (
+            antenna.and_then(|antenna| antenna.eastern).unwrap_or(0.0),
+            antenna.and_then(|antenna| antenna.northern).unwrap_or(0.0),
+            height.unwrap_or(0.0),
+        );
+
+        let (x, y, z) = enu_to_ecef(marker, enu);
+
+        match height {
+            Some(height) => Some(Message::Msg1006(Msg1006T {
+                reference_station_id: self.station_id,
+                gps_flag: gps,
+                glonass_flag: glonass,
+                galileo_flag: galileo,
+                antenna_ref_point_ecef_x_m: x,
+                antenna_ref_point_ecef_y_m: y,
+                antenna_ref_point_ecef_z_m: z,
+                antenna_height_m: height,
+                ..Default::default()
+            })),
+            None => Some(Message::Msg1005(Msg1005T {
+                reference_station_id: self.station_id,
+                gps_flag: gps,
+                glonass_flag: glonass,
+                galileo_flag: galileo,
+                antenna_ref_point_ecef_x_m: x,
+                antenna_ref_point_ecef_y_m: y,
+                antenna_ref_point_ecef_z_m: z,
+                ..Default::default()
+            })),
+        }
+    }
+
+    /// Forms 1033 from [Header]
+    fn forge_station_descriptors(&self) -> Option<Message> {
+        let antenna = self.header.rcvr_antenna.as_ref();
+        let rcvr = self.header.rcvr.as_ref();
+
+        if antenna.is_none() && rcvr.is_none() {
+            return None;
+        }
+
+        Some(Message::Msg1033(Msg1033T {
+            reference_station_id: self.station_id,
+            antenna_descriptor_str: antenna
+                .map(|antenna| antenna.model.as_str())
+                .unwrap_or_default()
+                .into(),
+            antenna_setup_id: 0,
+            antenna_serial_number_str: antenna
+                .map(|antenna| antenna.sn.as_str())
+                .unwrap_or_default()
+                .into(),
+            receiver_type_descriptor_str: rcvr
+                .map(|rcvr| rcvr.model.as_str())
+                .unwrap_or_default()
+                .into(),
+            receiver_firmware_version_str: rcvr
+                .map(|rcvr| rcvr.firmware.as_str())
+                .unwrap_or_default()
+                .into(),
+            receiver_serial_number_str: rcvr
+                .map(|rcvr| rcvr.sn.as_str())
+                .unwrap_or_default()
+                .into(),
+        }))
+    }
+}
+
+/// Converts local (east, north, up) offsets, expressed in meters,
+/// at this ECEF position (WGS84), to ECEF coordinates.
+fn enu_to_ecef(ecef: (f64, f64, f64), enu: (f64, f64, f64)) -> (f64, f64, f64) {
+    const A: f64 = 6378137.0;
+    const F: f64 = 1.0 / 298.257223563;
+
+    let (x, y, z) = ecef;
+    let (e, n, u) = enu;
+
+    let b = A * (1.0 - F);
+    let e2 = F * (2.0 - F);
+    let ep2 = e2 / (1.0 - e2);
+
+    // geodetic coordinates (Bowring)
+    let p = (x * x + y * y).sqrt();
+    let theta = (z * A).atan2(p * b);
+    let lat = (z + ep2 * b * theta.sin().powi(3)).atan2(p - e2 * A * theta.cos().powi(3));
+    let long = y.atan2(x);
+
+    let (sin_lat, cos_lat) = lat.sin_cos();
+    let (sin_long, cos_long) = long.sin_cos();
+
+    (
+        x - sin_long * e - sin_lat * cos_long * n + cos_lat * cos_long * u,
+        y + cos_long * e - sin_lat * sin_long * n + cos_lat * sin_long * u,
+        z + cos_lat * n + sin_lat * u,
+    )
+}
+
+impl Rinex {
+    /// Create a [RNX2RTCM] streamer to convert this [Rinex]
+    /// into a stream of RTCM [Message]s. The stream will be made of
+    /// - one 1006 message describing the antenna reference point (from [Header::rx_position]
+    ///   and the antenna eccentricities) and the antenna height above the marker,
+    ///   or 1005 when the antenna height is unknown
+    /// - one 1033 message describing the antenna and receiver (from [Header::rcvr_antenna]
+    ///   and [Header::rcvr])
+    /// - the record stream:
+    ///   - Observation RINEX: MSM7 messages of GPS, Glonass, Galileo, QZSS
+    ///     and BeiDou, one set per epoch
+    ///   - Navigation RINEX: GPS (1019), Glonass (1020), BeiDou (1042), QZSS (1044)
+    ///     and Galileo (1045, 1046) ephemeris messages
+    ///
+    /// Header messages are omitted when the [Header] does not describe them.
+    ///
+    /// ## Inputs
+    /// - station_id: reference station ID
+    /// ## Output
+    /// - [RNX2RTCM]: a RTCM [Message] Iterator, or None if
+    ///   this RINEX format is not supported.
+    pub fn rnx2rtcm(&self, station_id: u16) -> Option<RNX2RTCM<'_>> {
+        let streamer = if let Some(rec) = self.record.as_obs() {
+            TypeDependentStreamer::Obs(ObsStreamer::new(
+                station_id,
+                &self.header.glo_channels,
+                rec.iter(),
+            ))
+        } else if self.record.as_nav().is_some() {
+            TypeDependentStreamer::Nav(NavStreamer::new(self))
+        } else {
+            return None;
+        };
+
+        Some(RNX2RTCM {
+            station_id,
+            streamer,
+            header: &self.header,
+            state: State::default(),
+            skip_header: false,
+        })
+    }
+}
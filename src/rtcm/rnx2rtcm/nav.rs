@@ -0,0 +1,41 @@
+use crate::{
+    navigation::{Ephemeris, NavKey},
+    prelude::{Epoch, Rinex},
+    rtcm::ephemeris::RtcmEphemeris,
+};
+
+use rtcm_rs::Message;
+
+/// NAV Record Streamer
+pub struct Streamer<'a> {
+    /// [Epoch] of the latest streamed ephemeris
+    epoch: Option<Epoch>,
+    ephemeris_iter: Box<dyn Iterator<Item = (&'a NavKey, &'a Ephemeris)> + 'a>,
+}
+
+impl<'a> Streamer<'a> {
+    pub fn new(rinex: &'a Rinex) -> Self {
+        Self {
+            epoch: None,
+            ephemeris_iter: rinex.nav_ephemeris_frames_iter(),
+        }
+    }
+
+    pub fn epoch(&self) -> Option<Epoch> {
+        self.epoch
+    }
+}
+
+impl Iterator for Streamer<'_> {
+    type Item = Message;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (key, eph) = self.ephemeris_iter.next()?;
+
+            if let Some(msg) = RtcmEphemeris::to_message(key, eph) {
+                self.epoch = Some(key.epoch);
+                return Some(msg);
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::{btree_map::Iter, HashMap, VecDeque};
+
+use crate::{
+    observation::{ObsKey, Observations},
+    prelude::{Constellation, Epoch, SV},
+    rtcm::msm::{Msm, PhaseTracking},
+};
+
+use rtcm_rs::Message;
+
+/// Constellations that MSM can describe, in order of publication
+const CONSTELLATIONS: [Constellation; 5] = [
+    Constellation::GPS,
+    Constellation::Glonass,
+    Constellation::Galileo,
+    Constellation::QZSS,
+    Constellation::BeiDou,
+];
+
+/// OBS Record Streamer
+pub struct Streamer<'a> {
+    /// Reference station ID
+    station_id: u16,
+    /// GLONASS frequency channels
+    glo_channels: &'a HashMap<SV, i8>,
+    /// Carrier phase tracking state
+    tracking: PhaseTracking,
+    /// [Epoch] of the latest streamed observations
+    epoch: Option<Epoch>,
+    /// Pending messages of current epoch
+    pending: VecDeque<Message>,
+    /// Record iterator
+    iter: Iter<'a, ObsKey, Observations>,
+}
+
+impl<'a> Streamer<'a> {
+    pub fn new(
+        station_id: u16,
+        glo_channels: &'a HashMap<SV, i8>,
+        iter: Iter<'a, ObsKey, Observations>,
+    ) -> Self {
+        Self {
+            station_id,
+            glo_channels,
+            iter,
+            epoch: None,
+            tracking: Default::default(),
+            pending: Default::default(),
+        }
+    }
+
+    pub fn epoch(&self) -> Option<Epoch> {
+        self.epoch
+    }
+
+    /// Forms all MSM of this epoch. The multiple message flag
+    /// is asserted on all of them but the last one.
+    fn forge_messages(&mut self, key: &ObsKey, observations: &Observations) {
+        let mut msm = Vec::new();
+
+        for constellation in CONSTELLATIONS {
+            if let Some(content) = Msm::from_observations(
+                constellation,
+                key.epoch,
+                &observations.signals,
+                self.glo_channels,
+                &mut self.tracking,
+            ) {
+                msm.extend(content.split());
+            }
+        }
+
+        let total = msm.len();
+
+        for (nth, msm) in msm.iter().enumerate() {
+            if let Some(msg) = msm.to_msm7(self.station_id, nth + 1 < total) {
+                self.pending.push_back(msg);
+            }
+        }
+    }
+}
+
+impl Iterator for Streamer<'_> {
+    type Item = Message;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(msg) = self.pending.pop_front() {
+                return Some(msg);
+            }
+
+            let (key, observations) = self.iter.next()?;
+
+            if key.flag.is_event() {
+                continue;
+            }
+
+            self.epoch = Some(key.epoch);
+            self.forge_messages(key, observations);
+        }
+    }
+}
@@ -1,54 +1,399 @@
 //! RTCM to RINEX deserialization
-use std::io::Read;
+use std::{
+    collections::{HashMap, VecDeque},
+    io::Read,
+};
 
-use rtcm_rs::next_msg_frame as next_rtcm_msg_frame;
+use crate::{
+    navigation::NavFrame,
+    observation::{EpochFlag, ObsKey},
+    prelude::{Duration, Epoch, Rinex, Version, SV},
+    production::{Postponing, SnapshotMode},
+    rtcm::{ephemeris::RtcmEphemeris, msm::Msm},
+};
 
-/// RTCM2RNX can deserialize a RTCM stream to RINEX Tokens.
+use rtcm_rs::{next_msg_frame as next_rtcm_msg_frame, Message};
+
+#[cfg(feature = "log")]
+use log::{debug, error, info};
+
+/// Internal buffer size. Largest RTCM frame is 1029 bytes.
+const BUF_SIZE: usize = 4096;
+
+/// RTCM2RNX is a RINEX producer from a RTCM stream.
+/// It interprets the streamed messages and collects them as RINEX.
+/// The production behavior is defined by [SnapshotMode]: each [Iterator::next]
+/// call returns a [Rinex] once the snapshot is complete. The pending snapshots
+/// are published when reaching end of stream. Observation and Navigation
+/// snapshots are published separately, one after the other.
+///
+/// Currently supported messages:
+/// - MSM4, MSM5 and MSM7 of GPS, Glonass, Galileo, QZSS and BeiDou,
+///   collected as Observation [Rinex].
+/// - GPS (1019), Glonass (1020), BeiDou (1042), QZSS (1044) and
+///   Galileo F/NAV (1045) and I/NAV (1046) ephemerides,
+///   collected as Navigation [Rinex] (V4 by default, refer to [Self::with_nav_version]).
+///   Snapshot boundaries are defined by the observations.
 pub struct RTCM2RNX<R: Read> {
+    /// True when collecting is feasible
+    pub active: bool,
+    /// Collected size, for postponing mechanism
+    size: usize,
+    /// Snapshot mode
+    pub snapshot_mode: SnapshotMode,
+    /// Postponing option
+    pub postponing: Postponing,
+    /// Reference [Epoch] used to resolve the RTCM time of week.
+    /// Initialized by "now", then follows the stream.
+    t_ref: Epoch,
+    /// Starting point of current snapshot
+    snapshot_t0: Option<Epoch>,
+    /// True when collecting CRINEX
+    crinex: bool,
+    /// Lock time indicators, to detect loss of lock
+    lock_times: HashMap<(SV, String), u16>,
+    /// Pending OBS [Rinex]
+    obs_rinex: Rinex,
+    /// Pending NAV [Rinex]
+    nav_rinex: Rinex,
+    /// Published [Rinex] snapshots
+    published: VecDeque<Rinex>,
     /// internal buffer
     buf: Vec<u8>,
+    /// Number of available bytes in buffer
+    avail: usize,
     /// True when EOS has been reached
     eos: bool,
-    /// pointer
-    ptr: usize,
     /// [Read]able interface
     reader: R,
 }
 
 impl<R: Read> Iterator for RTCM2RNX<R> {
-    type Item = Option<()>;
+    type Item = Rinex;
     fn next(&mut self) -> Option<Self::Item> {
-        if !self.eos {
-            if self.ptr < self.buf.len() {
-                // try filling with new bytes
-                let size = self.reader.read(&mut self.buf).ok()?;
-                if size == 0 {
-                    self.eos = true;
-                }
+        loop {
+            if let Some(rinex) = self.published.pop_front() {
+                return Some(rinex);
+            }
+
+            match self.next_message() {
+                Some((msg, size)) => {
+                    if self.active {
+                        self.collect(msg);
+                    } else {
+                        self.postponed(size);
+                    }
+                },
+                None => {
+                    // end of stream: publish pending snapshot
+                    self.publish();
+                    return self.published.pop_front();
+                },
             }
+        }
+    }
+}
+
+impl<R: Read> RTCM2RNX<R> {
+    /// Creates a new [RTCM2RNX] working from [Read]able interface.
+    /// It will stream [Rinex] as long as the interface is alive.
+    ///
+    /// NB:
+    /// - [RTCM2RNX] needs the system time to be determined, because RTCM
+    ///   only describes the time of week. If determination fails, this method will panic.
+    ///   We propose [Self::new_system_time] if you want to manually define "now",
+    ///   which is also required when replaying RTCM recordings.
+    ///
+    /// ## Inputs
+    /// - crinex: set to true if you want to use the CRINEX compression
+    ///   algorithm when collecting Observation RINEX.
+    /// - production rate control as [SnapshotMode]
+    /// - [Postponing] option
+    /// - read: [Read]able interface
+    pub fn new(crinex: bool, snapshot_mode: SnapshotMode, postponing: Postponing, read: R) -> Self {
+        Self::new_system_time(
+            crinex,
+            Epoch::now().unwrap_or_else(|e| panic!("system time determination failed with {}", e)),
+            snapshot_mode,
+            postponing,
+            read,
+        )
+    }
+
+    /// Infaillible [RTCM2RNX] creation, use this if you have no means to access system time,
+    /// or to replay RTCM recordings. "now" should be within a few days of the recording.
+    /// Refer to [Self::new] for more information.
+    pub fn new_system_time(
+        crinex: bool,
+        now: Epoch,
+        snapshot_mode: SnapshotMode,
+        postponing: Postponing,
+        read: R,
+    ) -> Self {
+        Self {
+            size: 0,
+            crinex,
+            postponing,
+            snapshot_mode,
+            t_ref: now,
+            snapshot_t0: None,
+            lock_times: HashMap::new(),
+            obs_rinex: Self::basic_obs(crinex),
+            nav_rinex: Rinex::basic_nav(),
+            published: VecDeque::new(),
+            buf: vec![0; BUF_SIZE],
+            avail: 0,
+            eos: false,
+            reader: read,
+            active: postponing == Postponing::None,
+        }
+    }
+
+    /// Creates a new [RTCM2RNX] that will collect a [Rinex] once a day at midnight,
+    /// with deployment possibly postponed.
+    pub fn new_daily(crinex: bool, postponing: Postponing, read: R) -> Self {
+        Self::new(crinex, SnapshotMode::DailyMidnight, postponing, read)
+    }
+
+    /// Creates a new [RTCM2RNX] that will collect a [Rinex] twice a day at midnight and noon,
+    /// with deployment possibly postponed.
+    pub fn new_midnight_noon(crinex: bool, postponing: Postponing, read: R) -> Self {
+        Self::new(crinex, SnapshotMode::DailyMidnightNoon, postponing, read)
+    }
+
+    /// Creates a new [RTCM2RNX] that will collect a [Rinex] hourly
+    /// with deployment possibly postponed.
+    pub fn new_hourly(crinex: bool, postponing: Postponing, read: R) -> Self {
+        Self::new(crinex, SnapshotMode::Hourly, postponing, read)
+    }
+
+    /// Creates a new [RTCM2RNX] that will collect a [Rinex] periodically,
+    /// with deployment possibly postponed.
+    pub fn new_periodic(crinex: bool, period: Duration, postponing: Postponing, read: R) -> Self {
+        Self::new(crinex, SnapshotMode::Periodic(period), postponing, read)
+    }
+
+    /// Obtain reference to pending Observation RINEX
+    pub fn obs_rinex(&self) -> &Rinex {
+        &self.obs_rinex
+    }
+
+    /// Obtain reference to pending Navigation RINEX
+    pub fn nav_rinex(&self) -> &Rinex {
+        &self.nav_rinex
+    }
+
+    /// Defines the Navigation RINEX revision to be produced.
+    /// Only V3 and V4 are supported, V4 being the default.
+    pub fn with_nav_version(mut self, version: Version) -> Self {
+        self.nav_rinex.header.version = version;
+        self
+    }
+
+    fn basic_obs(crinex: bool) -> Rinex {
+        if crinex {
+            Rinex::basic_crinex()
         } else {
-            if self.ptr == 0 {
-                // done consuming the last bytes
+            Rinex::basic_obs()
+        }
+    }
+
+    /// Grabs next valid RTCM [Message] and its encoding size.
+    /// Returns None once the stream has been fully consumed.
+    fn next_message(&mut self) -> Option<(Message, usize)> {
+        loop {
+            let (consumed, msg) = match next_rtcm_msg_frame(&self.buf[..self.avail]) {
+                (consumed, Some(frame)) => {
+                    (consumed, Some((frame.get_message(), frame.frame_len())))
+                },
+                (consumed, None) => (consumed, None),
+            };
+
+            // discard consumed (or invalid) bytes
+            self.buf.copy_within(consumed..self.avail, 0);
+            self.avail -= consumed;
+
+            if msg.is_some() {
+                return msg;
+            }
+
+            if self.eos {
                 return None;
             }
+
+            match self.reader.read(&mut self.buf[self.avail..]) {
+                Ok(0) => self.eos = true,
+                Ok(size) => self.avail += size,
+                Err(_e) => {
+                    #[cfg(feature = "log")]
+                    error!("rtcm2rnx: i/o error: {}", _e);
+                    self.eos = true;
+                },
+            }
+        }
+    }
+
+    /// Collects this [Message]
+    fn collect(&mut self, msg: Message) {
+        match msg {
+            Message::Corrupt => {
+                #[cfg(feature = "log")]
+                error!("rtcm2rnx: corrupt message");
+            },
+            msg => {
+                if let Some(msm) = Msm::from_message(&msg) {
+                    self.collect_msm(msm);
+                } else if let Some(eph) = RtcmEphemeris::from_message(&msg, self.t_ref) {
+                    self.collect_ephemeris(eph);
+                } else {
+                    #[cfg(feature = "log")]
+                    debug!("rtcm2rnx: unhandled message {:?}", msg.number());
+                }
+            },
+        }
+    }
+
+    /// Collects this [Msm] as Observations
+    fn collect_msm(&mut self, msm: Msm) {
+        let epoch = msm.epoch(self.t_ref);
+        self.t_ref = epoch;
+        self.new_snapshot(epoch);
+
+        let header = &mut self.obs_rinex.header;
+
+        for sat in msm.satellites.iter() {
+            if let Some(channel) = sat.glo_channel {
+                header
+                    .glo_channels
+                    .insert(SV::new(msm.constellation, sat.prn), channel);
+            }
         }
 
-        match next_rtcm_msg_frame(&self.buf[self.ptr..]) {
-            (_, Some(_)) => {},
-            (_, None) => {},
+        let signals = msm.signal_observations(&header.glo_channels, &mut self.lock_times);
+
+        let obs_header = header.obs.get_or_insert_with(Default::default);
+        let codes = obs_header.codes.entry(msm.constellation).or_default();
+
+        for signal in signals.iter() {
+            if !codes.contains(&signal.observable) {
+                codes.push(signal.observable.clone());
+            }
         }
 
-        None
+        let record = self
+            .obs_rinex
+            .record
+            .as_mut_obs()
+            .expect("internal error: invalid obs record");
+
+        let key = ObsKey {
+            epoch,
+            flag: EpochFlag::Ok,
+        };
+
+        record.entry(key).or_default().signals.extend(signals);
     }
-}
 
-impl<R: Read> RTCM2RNX<R> {
-    pub fn new(r: R) -> Self {
-        Self {
-            ptr: 0,
-            reader: r,
-            eos: false,
-            buf: Vec::with_capacity(1024),
+    /// Collects this [RtcmEphemeris] as Navigation
+    fn collect_ephemeris(&mut self, eph: RtcmEphemeris) {
+        let (key, ephemeris) = eph.to_ephemeris(self.nav_rinex.header.version);
+
+        let record = self
+            .nav_rinex
+            .record
+            .as_mut_nav()
+            .expect("internal error: invalid nav record");
+
+        // ephemerides are periodically repeated: latest copy prevails
+        record.insert(key, NavFrame::EPH(ephemeris));
+    }
+
+    /// Publishes current snapshot, when this [Epoch] no longer belongs to it.
+    fn new_snapshot(&mut self, epoch: Epoch) {
+        match self.snapshot_t0 {
+            Some(t0) => {
+                if self.snapshot_mode.is_new_snapshot(t0, epoch) {
+                    self.publish();
+                    self.snapshot_t0 = Some(epoch);
+                }
+            },
+            None => self.snapshot_t0 = Some(epoch),
+        }
+    }
+
+    /// Publishes pending [Rinex] (if any)
+    fn publish(&mut self) {
+        self.publish_obs();
+        self.publish_nav();
+    }
+
+    /// Publishes pending Observation [Rinex] (if any)
+    fn publish_obs(&mut self) {
+        let mut obs_rinex = Self::basic_obs(self.crinex);
+
+        // knowledge of GLONASS channels is preserved
+        obs_rinex.header.glo_channels = self.obs_rinex.header.glo_channels.clone();
+
+        let mut obs_rinex = std::mem::replace(&mut self.obs_rinex, obs_rinex);
+
+        let (first, last) = match obs_rinex.record.as_obs() {
+            Some(record) => match (record.keys().next(), record.keys().last()) {
+                (Some(first), Some(last)) => (first.epoch, last.epoch),
+                _ => return,
+            },
+            None => return,
+        };
+
+        if let Some(obs) = &mut obs_rinex.header.obs {
+            obs.timeof_first_obs = Some(first);
+            obs.timeof_last_obs = Some(last);
+        }
+
+        #[cfg(feature = "log")]
+        info!("rtcm2rnx: publishing {} - {} snapshot", first, last);
+
+        self.published.push_back(obs_rinex);
+    }
+
+    /// Publishes pending Navigation [Rinex] (if any)
+    fn publish_nav(&mut self) {
+        let mut nav_rinex = Rinex::basic_nav();
+        nav_rinex.header.version = self.nav_rinex.header.version;
+
+        let nav_rinex = std::mem::replace(&mut self.nav_rinex, nav_rinex);
+
+        if nav_rinex.record.as_nav().is_none_or(|rec| rec.is_empty()) {
+            return;
+        }
+
+        #[cfg(feature = "log")]
+        info!("rtcm2rnx: publishing navigation snapshot");
+
+        self.published.push_back(nav_rinex);
+    }
+
+    /// Postponing mechanism
+    fn postponed(&mut self, msg_size: usize) {
+        match self.postponing {
+            Postponing::SystemTime(t) => {
+                let now = Epoch::now()
+                    .unwrap_or_else(|e| panic!("system time determination failure: {}", e));
+                self.active = now > t;
+            },
+            Postponing::Size(size) => {
+                self.size += msg_size;
+                self.active = self.size >= size;
+            },
+            Postponing::Messages(size) => {
+                self.size += 1;
+                self.active = self.size >= size;
+            },
+            Postponing::None => self.active = true,
+        }
+
+        #[cfg(feature = "log")]
+        if self.active {
+            info!("rtcm2rnx now deployed: production is pending");
         }
     }
 }
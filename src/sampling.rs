@@ -1,6 +1,17 @@
 use crate::prelude::{Duration, Epoch, Rinex, TimeSeries};
 
 impl Rinex {
+    /// [Epoch] Iterator used in sampling analysis.
+    /// Observation special events (see [crate::observation::EpochFlag::is_event])
+    /// are not actual samples and are therefore not considered.
+    fn sampling_epoch_iter(&self) -> Box<dyn Iterator<Item = Epoch> + '_> {
+        if let Some(r) = self.record.as_obs() {
+            Box::new(r.keys().filter(|k| !k.flag.is_event()).map(|k| k.epoch))
+        } else {
+            self.epoch_iter()
+        }
+    }
+
     /// Returns first [Epoch] encountered in time
     pub fn first_epoch(&self) -> Option<Epoch> {
         self.epoch_iter().next()
@@ -57,6 +68,7 @@ impl Rinex {
     /// Histogram analysis on Epoch interval. Although
     /// it is feasible on all types indexed by [Epoch],
     /// this operation only makes truly sense on Observation Data.
+    /// Observation special events are not considered.
     /// ```
     /// use rinex::prelude::*;
     /// use itertools::Itertools;
@@ -74,8 +86,8 @@ impl Rinex {
         // compute dt = |e_k+1 - e_k| : instantaneous epoch delta
         //              then compute an histogram on these intervals
         Box::new(
-            self.epoch_iter()
-                .zip(self.epoch_iter().skip(1))
+            self.sampling_epoch_iter()
+                .zip(self.sampling_epoch_iter().skip(1))
                 .map(|(ek, ekp1)| ekp1 - ek) // following step computes the histogram
                 // and at the same time performs a .unique() like filter
                 .fold(vec![], |mut list, dt| {
@@ -163,8 +175,8 @@ impl Rinex {
             },
         };
         Box::new(
-            self.epoch_iter()
-                .zip(self.epoch_iter().skip(1))
+            self.sampling_epoch_iter()
+                .zip(self.sampling_epoch_iter().skip(1))
                 .filter_map(move |(ek, ekp1)| {
                     let dt = ekp1 - ek; // gap
                     if dt > sample_rate {
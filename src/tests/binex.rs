@@ -0,0 +1,513 @@
+//! BINEX streams interpretation
+use crate::{
+    navigation::NavMessageType,
+    observation::{ClockObservation, EpochFlag, LliFlags, ObsKey, Observations, SNR},
+    prelude::{
+        binex::{ObservationRecord, PrototypeMessage, PrototypeRecord},
+        *,
+    },
+    production::{Postponing, SnapshotMode},
+    tests::reader::{obs_rinex, to_bytes},
+};
+
+use ::binex::prelude::{
+    EphemerisFrame, GALEphemeris, GLOEphemeris, GPSEphemeris, GPSRaw, Message, Meta,
+    MonumentGeoMetadata, MonumentGeoRecord, Record, SBASEphemeris, Solutions, SolutionsFrame,
+};
+
+use std::{io::BufReader, str::FromStr};
+
+fn encode(record: Record) -> Vec<u8> {
+    let meta = Meta {
+        reversed: false,
+        enhanced_crc: false,
+        big_endian: true,
+    };
+
+    let msg = Message::new(meta, record);
+    let size = msg.encoding_size();
+    let mut buf = vec![0; size];
+    msg.encode(&mut buf, size).unwrap();
+    buf
+}
+
+fn gps_ephemeris(prn: u8, tow: i32, toc: i32) -> Vec<u8> {
+    encode(Record::new_ephemeris_frame(EphemerisFrame::GPS(
+        GPSEphemeris {
+            sv_prn: prn,
+            toe: 2318,
+            tow,
+            toc,
+            iode: 10,
+            iodc: 10,
+            clock_offset: 1.0E-4,
+            m0_rad: 0.5,
+            e: 0.01,
+            sqrt_a: 5153.5,
+            ura_m: 2.0,
+            ..Default::default()
+        },
+    )))
+}
+
+/// Site description, ephemerides of G05, E11, R07 and S23, transmitted on 2024-06-10,
+/// and raw G09 subframes (that cannot be interpreted)
+fn stream() -> Vec<u8> {
+    let t = Epoch::from_str("2024-06-10T11:00:00 GPST").unwrap();
+
+    let geo = MonumentGeoRecord::new(
+        t,
+        MonumentGeoMetadata::ReceiverFirmware,
+        "SEPT POLARX5",
+        "TRM59800.00     NONE",
+        "ESBC00DNK",
+        "10118M001",
+    )
+    .with_receiver_serial_number("3001376")
+    .with_receiver_firmware_version("5.3.2")
+    .with_antenna_serial_number("5000118436")
+    .with_observer("OPERATOR")
+    .with_agency("AGENCY")
+    .with_comment("Converted from BINEX");
+
+    let mut stream = encode(Record::new_monument_geo(geo));
+
+    let mut pvt = Solutions::new(t);
+    pvt.frames
+        .push(SolutionsFrame::new_antenna_wgs84_ecef_position(
+            3582105.291,
+            532589.7313,
+            5232754.8054,
+        ));
+
+    stream.extend(encode(Record::new_solutions(pvt)));
+
+    // transmitted 11:00, toc 12:00
+    stream.extend(gps_ephemeris(5, 126_000, 129_600));
+
+    stream.extend(encode(Record::new_ephemeris_frame(EphemerisFrame::GAL(
+        GALEphemeris {
+            sv_prn: 11,
+            toe_week: 2318 - 1024,
+            tow: 126_000,
+            toe_s: 129_600,
+            iodnav: 20,
+            clock_offset: -2.0E-4,
+            sqrt_a: 5440.6,
+            sisa: 3.12,
+            source: 0x205,
+            ..Default::default()
+        },
+    ))));
+
+    stream.extend(encode(Record::new_ephemeris_frame(EphemerisFrame::GLO(
+        GLOEphemeris {
+            slot: 7,
+            // 2024-06-10 (UTC)
+            day: 16_227,
+            tod_s: 43_200,
+            t_k_sec: 41_400,
+            clock_offset_s: 1.0E-5,
+            x_km: 10_000.0,
+            z_km: -15_000.0,
+            freq_channel: -2,
+            ..Default::default()
+        },
+    ))));
+
+    stream.extend(encode(Record::new_ephemeris_frame(EphemerisFrame::SBAS(
+        SBASEphemeris {
+            sbas_prn: 123,
+            toe: 2318,
+            tow: 126_000,
+            clock_offset: 1.0E-8,
+            x_km: 40_000.0,
+            iodn: 3,
+            ..Default::default()
+        },
+    ))));
+
+    let mut raw = GPSRaw::new();
+    raw.svid1 = 9;
+
+    stream.extend(encode(Record::new_ephemeris_frame(EphemerisFrame::GPSRaw(
+        raw,
+    ))));
+
+    stream
+}
+
+#[test]
+fn bin2rnx_ephemeris() {
+    let now = Epoch::from_str("2024-06-10T12:00:00 GPST").unwrap();
+    let stream = stream();
+
+    let bin2rnx = BIN2RNX::new_system_time(
+        false,
+        now,
+        SnapshotMode::DailyMidnight,
+        Postponing::None,
+        stream.as_slice(),
+    );
+
+    let snapshots = bin2rnx.collect::<Vec<_>>();
+    assert_eq!(snapshots.len(), 1);
+
+    let rinex = &snapshots[0];
+    assert!(rinex.is_navigation_rinex());
+    assert_eq!(rinex.header.version, Version::new(4, 0));
+    assert_eq!(rinex.nav_ephemeris_frames_iter().count(), 4);
+
+    // raw subframes are not interpreted
+    let g09 = SV::from_str("G09").unwrap();
+    assert!(rinex.nav_ephemeris_frames_iter().all(|(k, _)| k.sv != g09));
+
+    // site description
+    let header = &rinex.header;
+    assert_eq!(header.observer.as_deref(), Some("OPERATOR"));
+    assert_eq!(header.agency.as_deref(), Some("AGENCY"));
+
+    let marker = header.geodetic_marker.as_ref().unwrap();
+    assert_eq!(marker.name, "ESBC00DNK");
+    assert_eq!(marker.number(), Some("10118M001".to_string()));
+
+    let rcvr = header.rcvr.as_ref().unwrap();
+    assert_eq!(rcvr.model, "SEPT POLARX5");
+    assert_eq!(rcvr.sn, "3001376");
+    assert_eq!(rcvr.firmware, "5.3.2");
+
+    let antenna = header.rcvr_antenna.as_ref().unwrap();
+    assert_eq!(antenna.model, "TRM59800.00     NONE");
+    assert_eq!(antenna.sn, "5000118436");
+
+    assert_eq!(
+        header.rx_position,
+        Some((3582105.291, 532589.7313, 5232754.8054))
+    );
+    assert!(header
+        .comments
+        .contains(&"Converted from BINEX".to_string()));
+
+    // ephemerides
+    let g05 = SV::from_str("G05").unwrap();
+    let e11 = SV::from_str("E11").unwrap();
+    let r07 = SV::from_str("R07").unwrap();
+    let s23 = SV::from_str("S23").unwrap();
+
+    for (k, eph) in rinex.nav_ephemeris_frames_iter() {
+        if k.sv == g05 {
+            assert_eq!(k.epoch, now);
+            assert_eq!(k.msgtype, NavMessageType::LNAV);
+            assert!((eph.clock_bias - 1.0E-4).abs() < 1.0E-9);
+            assert_eq!(eph.get_orbit_f64("iode"), Some(10.0));
+            assert_eq!(eph.get_orbit_f64("m0"), Some(0.5));
+            assert_eq!(eph.get_orbit_f64("accuracy"), Some(2.0));
+            assert_eq!(eph.get_week(), Some(2318));
+            assert_eq!(eph.toe(g05), Some(now));
+        } else if k.sv == e11 {
+            assert_eq!(k.epoch, Epoch::from_str("2024-06-10T12:00:00 GST").unwrap());
+            assert_eq!(k.msgtype, NavMessageType::INAV);
+            assert_eq!(eph.get_orbit_f64("week"), Some(2318.0));
+            assert_eq!(eph.get_orbit_f64("iodnav"), Some(20.0));
+            assert_eq!(eph.get_orbit_f64("sisa"), Some(3.12f32 as f64));
+        } else if k.sv == r07 {
+            assert_eq!(k.epoch, Epoch::from_str("2024-06-10T12:00:00 UTC").unwrap());
+            assert_eq!(k.msgtype, NavMessageType::FDMA);
+            assert_eq!(eph.clock_bias, 1.0E-5);
+            assert_eq!(eph.glonass_freq_channel(), Some(-2));
+            assert_eq!(eph.get_orbit_f64("satPosZ"), Some(-15_000.0));
+            // message frame time: monday, 11:30 UTC
+            assert_eq!(eph.clock_drift_rate, 86_400.0 + 41_400.0);
+        } else {
+            assert_eq!(k.sv, s23);
+            assert_eq!(k.msgtype, NavMessageType::SBAS);
+            assert_eq!(
+                k.epoch,
+                Epoch::from_str("2024-06-10T11:00:00 GPST").unwrap()
+            );
+            assert_eq!(eph.get_orbit_f64("satPosX"), Some(40_000.0));
+            assert_eq!(eph.get_orbit_f64("iodn"), Some(3.0));
+        }
+    }
+
+    // formatted and parsed back
+    let bytes = to_bytes(rinex);
+    let mut reader = BufReader::new(bytes.as_slice());
+    let parsed = Rinex::parse(&mut reader).unwrap();
+    assert_eq!(parsed.nav_ephemeris_frames_iter().count(), 4);
+    assert_eq!(parsed.header.observer.as_deref(), Some("OPERATOR"));
+
+    // RINEX3 production
+    let bin2rnx = BIN2RNX::new_system_time(
+        false,
+        now,
+        SnapshotMode::DailyMidnight,
+        Postponing::None,
+        stream.as_slice(),
+    )
+    .with_nav_version(Version::new(3, 5));
+
+    let snapshots = bin2rnx.collect::<Vec<_>>();
+    let rinex = &snapshots[0];
+    assert_eq!(rinex.header.version, Version::new(3, 5));
+    assert!(rinex
+        .nav_ephemeris_frames_iter()
+        .all(|(k, _)| k.msgtype == NavMessageType::LNAV));
+
+    let bytes = to_bytes(rinex);
+    let mut reader = BufReader::new(bytes.as_slice());
+    let parsed = Rinex::parse(&mut reader).unwrap();
+    assert_eq!(parsed.nav_ephemeris_frames_iter().count(), 4);
+}
+
+#[test]
+fn bin2rnx_snapshots() {
+    let now = Epoch::from_str("2024-06-10T12:00:00 GPST").unwrap();
+
+    // transmitted 11:00, 11:30 and 12:00
+    let mut stream = gps_ephemeris(5, 126_000, 129_600);
+    stream.extend(gps_ephemeris(12, 127_800, 129_600));
+    stream.extend(gps_ephemeris(5, 129_600, 136_800));
+
+    let bin2rnx = BIN2RNX::new_system_time(
+        false,
+        now,
+        SnapshotMode::Hourly,
+        Postponing::None,
+        stream.as_slice(),
+    );
+
+    let snapshots = bin2rnx.collect::<Vec<_>>();
+    assert_eq!(snapshots.len(), 2);
+    assert_eq!(snapshots[0].nav_ephemeris_frames_iter().count(), 2);
+    assert_eq!(snapshots[1].nav_ephemeris_frames_iter().count(), 1);
+
+    // postponed deployment: first message is dropped
+    let bin2rnx = BIN2RNX::new_system_time(
+        false,
+        now,
+        SnapshotMode::DailyMidnight,
+        Postponing::Messages(1),
+        stream.as_slice(),
+    );
+
+    let snapshots = bin2rnx.collect::<Vec<_>>();
+    assert_eq!(snapshots.len(), 1);
+    assert_eq!(snapshots[0].nav_ephemeris_frames_iter().count(), 2);
+}
+
+#[test]
+fn rnx2bin_round_trip() {
+    let now = Epoch::from_str("2024-06-10T12:00:00 GPST").unwrap();
+    let stream = stream();
+
+    let mut bin2rnx = BIN2RNX::new_system_time(
+        false,
+        now,
+        SnapshotMode::DailyMidnight,
+        Postponing::None,
+        stream.as_slice(),
+    );
+
+    let rinex = bin2rnx.next().unwrap();
+
+    let meta = Meta {
+        reversed: false,
+        enhanced_crc: false,
+        big_endian: true,
+    };
+
+    let mut rnx2bin = rinex.rnx2bin(meta).unwrap();
+    rnx2bin.custom_announce = Some("Field unit #1".to_string());
+
+    let mut stream = Vec::new();
+    for msg in rnx2bin {
+        let size = msg.encoding_size();
+        let mut buf = vec![0; size];
+        msg.encode(&mut buf, size).unwrap();
+        stream.extend(buf);
+    }
+
+    let mut bin2rnx = BIN2RNX::new_system_time(
+        false,
+        now,
+        SnapshotMode::DailyMidnight,
+        Postponing::None,
+        stream.as_slice(),
+    );
+
+    let parsed = bin2rnx.next().unwrap();
+    assert!(bin2rnx.next().is_none());
+
+    let (header, parsed_header) = (&rinex.header, &parsed.header);
+    assert_eq!(parsed_header.observer, header.observer);
+    assert_eq!(parsed_header.agency, header.agency);
+    assert_eq!(parsed_header.geodetic_marker, header.geodetic_marker);
+    assert_eq!(parsed_header.rcvr, header.rcvr);
+    assert_eq!(parsed_header.rcvr_antenna, header.rcvr_antenna);
+    assert_eq!(parsed_header.rx_position, header.rx_position);
+
+    // stream announcements are not preserved
+    let mut comments = header.comments.clone();
+    comments.insert(0, "Field unit #1".to_string());
+    assert_eq!(parsed_header.comments, comments);
+
+    assert_eq!(parsed.nav_ephemeris_frames_iter().count(), 4);
+
+    for ((k, eph), (parsed_k, parsed_eph)) in rinex
+        .nav_ephemeris_frames_iter()
+        .zip(parsed.nav_ephemeris_frames_iter())
+    {
+        assert_eq!(parsed_k, k);
+        assert_eq!(parsed_eph, eph, "{} ephemeris mismatch", k.sv);
+    }
+}
+
+/// Serializes this [Rinex] as a BINEX stream
+fn rnx2bin(rinex: &Rinex, meta: Meta) -> Vec<u8> {
+    let mut stream = Vec::new();
+    for msg in rinex.rnx2bin(meta).unwrap() {
+        let size = msg.encoding_size();
+        let mut buf = vec![0; size];
+        assert_eq!(msg.encode(&mut buf, size).unwrap(), size);
+        stream.extend(buf);
+    }
+    stream
+}
+
+#[test]
+fn rnx2bin_obs_round_trip() {
+    let meta = Meta {
+        reversed: false,
+        enhanced_crc: false,
+        big_endian: true,
+    };
+
+    let mut rinex = obs_rinex();
+    let t0 = rinex.first_epoch().unwrap();
+
+    for (nth, (key, observations)) in rinex.observations_iter_mut().enumerate() {
+        observations.clock =
+            Some(ClockObservation::default().with_offset_s(key.epoch, nth as f64 * 1.0E-7));
+        observations.signals[0].lli = Some(LliFlags::LOCK_LOSS);
+        observations.signals[1].snr = Some(SNR::DbHz42_47);
+    }
+
+    let record = rinex.record.as_mut_obs().unwrap();
+
+    record.insert(
+        ObsKey {
+            epoch: t0 + Duration::from_seconds(15.0),
+            flag: EpochFlag::HeaderInformationFollows,
+        },
+        Observations {
+            special_records: vec!["Antenna moved to the roof".to_string()],
+            ..Default::default()
+        },
+    );
+
+    let stream = rnx2bin(&rinex, meta);
+
+    let now = Epoch::from_str("2020-06-25T12:00:00 GPST").unwrap();
+
+    let bin2rnx = BIN2RNX::new_system_time(
+        false,
+        now,
+        SnapshotMode::DailyMidnight,
+        Postponing::None,
+        stream.as_slice(),
+    );
+
+    let snapshots = bin2rnx.collect::<Vec<_>>();
+    assert_eq!(snapshots.len(), 1);
+
+    let parsed = &snapshots[0];
+    assert!(parsed.is_observation_rinex());
+    assert_eq!(parsed.record.as_obs(), rinex.record.as_obs());
+
+    let obs = parsed.header.obs.as_ref().unwrap();
+    assert_eq!(obs.timeof_first_obs, Some(t0));
+    assert_eq!(obs.codes, rinex.header.obs.as_ref().unwrap().codes);
+}
+
+#[test]
+#[cfg(feature = "meteo")]
+fn rnx2bin_meteo_round_trip() {
+    let content = "     3.01           METEOROLOGICAL DATA                     RINEX VERSION / TYPE
+     2    PR    TD                                          # / TYPES OF OBSERV
+                                                            END OF HEADER
+ 2020  6 25  0  0  0 1013.2   20.5
+ 2020  6 25  0  1  0 1014.2   21.5
+ 2020  6 25  0  2  0 1015.2   22.5
+";
+
+    let meta = Meta {
+        reversed: false,
+        enhanced_crc: false,
+        big_endian: true,
+    };
+
+    let mut reader = BufReader::new(content.as_bytes());
+    let rinex = Rinex::parse(&mut reader).unwrap();
+
+    let stream = rnx2bin(&rinex, meta);
+
+    let now = Epoch::from_str("2020-06-25T12:00:00 GPST").unwrap();
+
+    let bin2rnx = BIN2RNX::new_system_time(
+        false,
+        now,
+        SnapshotMode::DailyMidnight,
+        Postponing::None,
+        stream.as_slice(),
+    );
+
+    let snapshots = bin2rnx.collect::<Vec<_>>();
+    assert_eq!(snapshots.len(), 1);
+
+    let parsed = &snapshots[0];
+    assert!(parsed.is_meteo_rinex());
+    assert_eq!(parsed.record.as_meteo().unwrap().len(), 6);
+    assert_eq!(parsed.record.as_meteo(), rinex.record.as_meteo());
+    assert_eq!(
+        parsed.header.meteo.as_ref().unwrap().codes,
+        rinex.header.meteo.as_ref().unwrap().codes
+    );
+}
+
+#[test]
+fn prototype_messages() {
+    let rinex = obs_rinex();
+    let (key, observations) = rinex.observations_iter().next().unwrap();
+
+    // large epoch: CRC32 protection
+    let mut large = observations.clone();
+    for _ in 0..100 {
+        large.signals.extend(observations.signals.clone());
+    }
+
+    for big_endian in [true, false] {
+        let meta = Meta {
+            big_endian,
+            reversed: false,
+            enhanced_crc: false,
+        };
+
+        for observations in [observations, &large] {
+            let record = ObservationRecord::new(*key, observations.clone());
+            let msg = PrototypeMessage::new(meta, PrototypeRecord::Observation(record));
+
+            let size = msg.encoding_size();
+            let mut buf = vec![0; size];
+            assert_eq!(msg.encode(&mut buf, size).unwrap(), size);
+            assert_eq!(buf[1], 0x7f);
+
+            let decoded = PrototypeMessage::decode(&buf).unwrap();
+            assert_eq!(decoded, msg);
+
+            // corrupt payload
+            buf[size / 2] ^= 0x01;
+            assert!(PrototypeMessage::decode(&buf).is_err());
+        }
+    }
+}
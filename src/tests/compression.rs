@@ -1,8 +1,61 @@
 #[cfg(test)]
 mod test {
     use crate::prelude::*;
-    use crate::tests::toolkit::generic_observation_comparison;
-    use std::{fs::remove_file as fs_remove_file, path::PathBuf};
+    use crate::{
+        hatanaka::Decompressor,
+        observation::{ClockObservation, EpochFlag, ObsKey, Observations},
+        tests::{
+            reader::{obs_rinex, to_bytes},
+            toolkit::generic_observation_comparison,
+        },
+    };
+    use std::{
+        io::BufReader,
+        path::PathBuf,
+        str::{from_utf8, FromStr},
+    };
+
+    /// Returns the record section of this RINEX content, without trailing whitespaces
+    fn body(content: &[u8]) -> Vec<String> {
+        let content = from_utf8(content).unwrap();
+
+        let body = match content.find("END OF HEADER") {
+            Some(offset) => &content[offset..],
+            None => content,
+        };
+
+        body.lines()
+            .skip_while(|line| line.contains("END OF HEADER"))
+            .map(|line| line.trim_end().to_string())
+            .collect()
+    }
+
+    /// Decompresses the record section of this CRINEX content
+    fn decompress(header: &Header, content: &[u8]) -> Vec<String> {
+        let v3 = header.version.major > 2;
+        let constellation = header.constellation.unwrap_or(Constellation::Mixed);
+        let codes = header.obs.as_ref().unwrap().codes.clone();
+
+        let mut decompressor = Decompressor::new(v3, constellation, codes);
+
+        let mut buf = [0; 4096];
+        let mut decompressed = Vec::new();
+
+        for line in body(content).iter() {
+            let size = decompressor
+                .decompress(line, line.len(), &mut buf, 4096)
+                .unwrap();
+
+            decompressed.extend_from_slice(&buf[..size]);
+
+            // produced content is not always terminated
+            if size > 0 && buf[size - 1] != b'\n' {
+                decompressed.push(b'\n');
+            }
+        }
+
+        body(&decompressed)
+    }
 
     #[test]
     #[ignore]
@@ -37,11 +90,9 @@ mod test {
             let rnx = Rinex::from_file(&fullpath).unwrap();
             let dut = rnx.rnx2crnx();
 
-            // dump
-            dut.to_file("v1_compressed.txt").unwrap();
-
             // parse back
-            let parsed_back = Rinex::from_file("v1_compressed.txt").unwrap();
+            let compressed = to_bytes(&dut);
+            let parsed_back = Rinex::parse(&mut BufReader::new(compressed.as_slice())).unwrap();
 
             // parse model
             let model_path = crnx_path.to_string_lossy().to_string();
@@ -49,9 +100,6 @@ mod test {
 
             // run testbench
             generic_observation_comparison(&parsed_back, &model);
-
-            // destroy
-            let _ = fs_remove_file("v1_compressed.txt");
         }
     }
 
@@ -92,11 +140,9 @@ mod test {
             println!("compressing \"{}\"..", rnx_path.to_string_lossy());
             let dut = rnx.rnx2crnx();
 
-            // dump
-            dut.to_file("v3_compressed.txt").unwrap();
-
             // parse back
-            let parsed_back = Rinex::from_file("v3_compressed.txt").unwrap();
+            let compressed = to_bytes(&dut);
+            let parsed_back = Rinex::parse(&mut BufReader::new(compressed.as_slice())).unwrap();
 
             // parse model
             let model_path = crnx_path.to_string_lossy().to_string();
@@ -105,8 +151,145 @@ mod test {
             // run testbench
             generic_observation_comparison(&parsed_back, &model);
 
-            // destroy
-            let _ = fs_remove_file("v3_compressed.txt");
+            // decompressed content is identical to readable content
+            assert_eq!(
+                decompress(&dut.header, &compressed),
+                body(&to_bytes(&rnx)),
+                "{}: decompressed content differs",
+                rnx_name
+            );
+        }
+    }
+
+    #[test]
+    fn crinex3_clock_and_events() {
+        let mut rinex = obs_rinex();
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let e05 = SV::from_str("E05").unwrap();
+
+        let record = rinex.record.as_mut_obs().unwrap();
+
+        for (nth, (k, v)) in record.iter_mut().enumerate() {
+            // clock offset is interrupted once
+            if nth != 5 {
+                let offset_s = 1.23456789E-4 + nth as f64 * 1.0E-9;
+                v.clock = Some(ClockObservation::default().with_offset_s(k.epoch, offset_s));
+            }
+            // E05 is lost for two epochs
+            if nth == 3 || nth == 4 {
+                v.signals.retain(|sig| sig.sv != e05);
+            }
+        }
+
+        // external event
+        let event = ObsKey {
+            epoch: t0 + Duration::from_seconds(195.0),
+            flag: EpochFlag::ExternalEvent,
+        };
+
+        record.insert(event, Observations::default());
+
+        let plain = to_bytes(&rinex);
+        let compressed = to_bytes(&rinex.rnx2crnx());
+
+        let content = from_utf8(&compressed).unwrap();
+        let lines = content.lines().collect::<Vec<_>>();
+        let end_of_header = lines
+            .iter()
+            .position(|line| line.contains("END OF HEADER"))
+            .unwrap();
+
+        // epoch descriptor (without clock), compressed clock
+        assert_eq!(
+            lines[end_of_header + 1],
+            "> 2020 06 25 00 00  0.0000000  0  3      G01G08E05"
+        );
+        assert_eq!(lines[end_of_header + 2], "3&123456789");
+        assert_eq!(lines[end_of_header + 6], "                   3");
+        assert_eq!(lines[end_of_header + 7], "1000");
+
+        // event is not compressed, next epoch is re-initialized
+        let event = lines
+            .iter()
+            .position(|line| line.starts_with("> 2020 06 25 00 03 15.0000000  5  0"))
+            .unwrap();
+
+        assert_eq!(
+            lines[event + 1],
+            "> 2020 06 25 00 03 30.0000000  0  3      G01G08E05"
+        );
+        assert_eq!(lines[event + 2], "3&123463789");
+
+        // decompressed content is identical to readable content
+        assert_eq!(decompress(&rinex.header, &compressed), body(&plain));
+
+        // parse back
+        let model = Rinex::parse(&mut BufReader::new(plain.as_slice())).unwrap();
+        let dut = Rinex::parse(&mut BufReader::new(compressed.as_slice())).unwrap();
+
+        let model = model.record.as_obs().unwrap();
+        let record = dut.record.as_obs().unwrap();
+
+        // special event is preserved
+        assert_eq!(record.len(), 11);
+        assert_eq!(dut.observation_events_iter().count(), 1);
+
+        for ((k, v), (model_k, model_v)) in record.iter().zip(model.iter()) {
+            assert_eq!(k, model_k);
+            assert_eq!(v.clock, model_v.clock);
+            assert_eq!(v.signals.len(), model_v.signals.len());
+            for signal in v.signals.iter() {
+                assert!(model_v.signals.contains(signal));
+            }
         }
+
+        for (nth, (_, v)) in record
+            .iter()
+            .filter(|(k, _)| !k.flag.is_event())
+            .enumerate()
+        {
+            if nth == 5 {
+                assert!(v.clock.is_none());
+            } else {
+                let clock = v.clock.unwrap();
+                let expected = 1.23456789E-4 + nth as f64 * 1.0E-9;
+                assert!((clock.offset_s - expected).abs() < 1.0E-12);
+            }
+        }
+    }
+
+    #[test]
+    fn crinex3_event_records() {
+        let mut rinex = obs_rinex();
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+
+        let event = ObsKey {
+            epoch: t0 + Duration::from_seconds(195.0),
+            flag: EpochFlag::ExternalEvent,
+        };
+
+        let trigger = format!("{:<60}COMMENT", "EXTERNAL TRIGGER");
+
+        rinex
+            .record
+            .as_mut_obs()
+            .unwrap()
+            .insert(event, Observations::default().with_special_record(&trigger));
+
+        let compressed = to_bytes(&rinex.rnx2crnx());
+        let content = from_utf8(&compressed).unwrap();
+        let lines = content.lines().collect::<Vec<_>>();
+
+        // special records are written as is, next epoch is re-initialized
+        let nth = lines
+            .iter()
+            .position(|line| line.starts_with("> 2020 06 25 00 03 15.0000000  5  1"))
+            .unwrap();
+
+        assert_eq!(lines[nth + 1], trigger);
+        assert_eq!(
+            lines[nth + 2],
+            "> 2020 06 25 00 03 30.0000000  0  3      G01G08E05"
+        );
     }
 }
@@ -0,0 +1,121 @@
+use std::{
+    collections::HashMap,
+    io::{BufReader, BufWriter},
+    str::{from_utf8, FromStr},
+};
+
+use crate::{
+    hatanaka::Compressor,
+    observation::HeaderFields,
+    prelude::{Constellation, Epoch, Header, Observable, Rinex, Version},
+    tests::crinex::decompression::{pdel0010_21, vlns0630},
+};
+
+/// This method is used by all "raw" compression tests,
+/// to compare the compressed content to what historical RNX2CRX produced.
+/// Inputs:
+/// - constellation (from supposedly parsed header)
+/// - specs (from supposedly parsed header)
+/// - crinex: expected compressed content
+/// - rinex: readable V3 content
+pub fn run_raw_compression_test(
+    constellation_specs: &[&str],
+    observable_specs: &[&str],
+    crinex: &str,
+    rinex: &str,
+) {
+    let mut codes = HashMap::<Constellation, Vec<Observable>>::new();
+
+    for (constell, observables) in constellation_specs.iter().zip(observable_specs.iter()) {
+        let constell = Constellation::from_str(constell.trim()).unwrap();
+        let observables = observables
+            .split(',')
+            .map(|ob| Observable::from_str(ob.trim()).unwrap())
+            .collect::<Vec<_>>();
+        codes.insert(constell, observables);
+    }
+
+    // Mixed content: timescale is defined by first epoch
+    let items = rinex[1..].split_ascii_whitespace().collect::<Vec<_>>();
+    let t0 = Epoch::from_str(&format!(
+        "{}-{}-{}T{}:{}:{} GPST",
+        items[0],
+        items[1],
+        items[2],
+        items[3],
+        items[4],
+        items[5].split('.').next().unwrap()
+    ))
+    .unwrap();
+
+    let fields = HeaderFields {
+        codes,
+        timeof_first_obs: Some(t0),
+        ..Default::default()
+    };
+
+    let header = Header::basic_obs()
+        .with_version(Version::new(3, 4))
+        .with_observation_fields(fields);
+
+    let mut w = BufWriter::new(Vec::new());
+    header.format(&mut w).unwrap();
+
+    let mut content = w.into_inner().unwrap();
+    content.extend_from_slice(rinex.as_bytes());
+    content.push(b'\n');
+
+    let parsed = Rinex::parse(&mut BufReader::new(content.as_slice())).unwrap();
+    let obs = parsed.header.obs.as_ref().unwrap();
+
+    let mut compressor = Compressor::default();
+    let mut w = BufWriter::new(Vec::new());
+
+    for (k, v) in parsed.record.as_obs().unwrap().iter() {
+        compressor.format_epoch(&mut w, k, v, obs).unwrap();
+    }
+
+    let compressed = w.into_inner().unwrap();
+    let compressed = from_utf8(&compressed).unwrap();
+
+    let mut model = crinex.lines();
+
+    for (nth, line) in compressed.lines().enumerate() {
+        let expected = model.next().expect("too many compressed lines").trim_end();
+        assert_eq!(line.trim_end(), expected, "failed on line={}", nth);
+    }
+
+    // one clock line per epoch
+    let epochs = rinex.lines().filter(|line| line.starts_with('>')).count();
+    assert_eq!(compressed.lines().count(), rinex.lines().count() + epochs);
+}
+
+#[test]
+fn v3_pdel0010_21() {
+    run_raw_compression_test(
+        &["GPS", "GLO"],
+        &[
+            "C1C, L1C, D1C, S1C, C2W, L2W, D2W, S2W",
+            "C1C, L1C, D1C, S1C, C2P, L2P, D2P, S2P",
+        ],
+        pdel0010_21::INPUT,
+        pdel0010_21::OUTPUT,
+    );
+}
+
+#[test]
+fn v3_vlns0630() {
+    // hours and minutes were not zero padded by the producer (I2.2 in RINEX3)
+    let crinex = vlns0630::INPUT.replacen("> 2022 03 04  0  0", "> 2022 03 04 00 00", 1);
+    let rinex = vlns0630::OUTPUT.replacen("> 2022 03 04  0  0", "> 2022 03 04 00 00", 1);
+
+    run_raw_compression_test(
+        &["GPS", "GLO"],
+        &[
+            "C1C, L1C, S1C, C2P, C2W, C2S, C2L, C2X, L2P, L2W, L2S, L2L, L2X, S2P, S2W, S2S, S2L, S2X",
+            "C1C, L1C, S1C, C2C, C2P, L2C, L2P, S2C, S2P",
+        ],
+        &crinex,
+        &rinex,
+    );
+}
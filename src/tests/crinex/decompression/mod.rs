@@ -5,9 +5,9 @@ mod kosg0010;
 // V3 test
 mod esbcdnk;
 // v3 test
-mod pdel0010_21;
+pub mod pdel0010_21;
 // v1 test with clock (not very meaningful though..)
-mod vlns0630;
+pub mod vlns0630;
 // v1
 mod zegv0010;
 
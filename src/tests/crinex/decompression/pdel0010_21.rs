@@ -2,7 +2,7 @@
 // RNX2CRX and decompressed with CRX2RNX historical tools
 use crate::tests::crinex::decompression::run_raw_decompression_test;
 
-const INPUT : &str = "> 2021 01 01 00 00  0.0000000  0 18      G01G07G08G10G16G20G21G23G26G27G30R02R09R15R16R17R18R19
+pub const INPUT: &str = "> 2021 01 01 00 00  0.0000000  0 18      G01G07G08G10G16G20G21G23G26G27G30R02R09R15R16R17R18R19
 
 3&23304001080 3&122463355107 3&3646410 3&43250 3&23304002300 3&95426008500 3&2841359 3&39250 &&07&&&&&&06&&&&
 3&22810555860 3&119870275483 3&935180 3&47250 3&22810553240 3&93405390868 3&728711 3&41750 &&07&&&&&&06&&&&
@@ -429,7 +429,7 @@ const INPUT : &str = "> 2021 01 01 00 00  0.0000000  0 18      G01G07G08G10G16G2
                 10 &
 ";
 
-const OUTPUT :&str = "> 2021 01 01 00 00  0.0000000  0 18
+pub const OUTPUT: &str = "> 2021 01 01 00 00  0.0000000  0 18
 G01  23304001.080   122463355.10707      3646.410          43.250    23304002.300    95426008.50006      2841.359          39.250  
 G07  22810555.860   119870275.48307       935.180          47.250    22810553.240    93405390.86806       728.711          41.750  
 G08  20971862.720   110207902.78308       989.156          50.250    20971862.920    85876301.69509       770.770          54.750  
@@ -2,7 +2,7 @@
 // RNX2CRX and decompressed with CRX2RNX historical tools
 use crate::tests::crinex::decompression::run_raw_decompression_test;
 
-const INPUT : &str = "> 2022 03 04  0  0  0.0000000  0 22      G01G03G04G06G09G12G17G19G21G22G25G31G32R01R02R07R08R09R10R17R23R24
+pub const INPUT: &str = "> 2022 03 04  0  0  0.0000000  0 22      G01G03G04G06G09G12G17G19G21G22G25G31G32R01R02R07R08R09R10R17R23R24
 3&0
 3&20832393682 3&109474991854 3&49500  3&20832389822     3&85305196437     3&49500    &&&8&&&&&&&&&&&&&&&8&&&&&&&&&&&&&&&&
 3&20342516786 3&106900663487 3&50000  3&20342512006     3&83299201382     3&50000    &&&8&&&&&&&&&&&&&&&8&&&&&&&&&&&&&&&&
@@ -51,7 +51,7 @@ const INPUT : &str = "> 2022 03 04  0  0  0.0000000  0 22      G01G03G04G06G09G1
 21437600 114676771 500
 2797000 14956818 250  2797020  11633067  -250";
 
-const OUTPUT:  &str = "> 2022 03 04  0  0  0.0000000  0 22       0.000000000000
+pub const OUTPUT: &str = "> 2022 03 04  0  0  0.0000000  0 22       0.000000000000
 G01  20832393.682   109474991.854 8        49.500                    20832389.822                                                                    85305196.437 8                                                                        49.500
 G03  20342516.786   106900663.487 8        50.000                    20342512.006                                                                    83299201.382 8                                                                        50.000
 G04  22448754.952   117969025.322 8        48.250                    22448749.312                                                                    91923884.833 7                                                                        43.750
@@ -1 +1,2 @@
+mod compression;
 mod decompression;
@@ -0,0 +1,159 @@
+//! Cycle slip detection
+use crate::{
+    observation::{LliFlags, ObsKey, Observations, SignalObservation},
+    prelude::{
+        obs::{CsDetector, CsSelectionMethod, CsStrategy, EpochFlag},
+        *,
+    },
+};
+
+use hifitime::Unit;
+use std::str::FromStr;
+
+/// G01 tracked on L1, L2 and L5 every 30s, with a gap between the
+/// 15th and 18th epochs. L1 slips by 50 cycles on the 10th epoch.
+fn slipping_rinex(observables: &[&str]) -> Rinex {
+    let g01 = SV::from_str("G01").unwrap();
+    let t0 = Epoch::from_str("2024-06-10T00:00:00 GPST").unwrap();
+
+    let mut rinex = Rinex::basic_obs();
+    let rec = rinex.record.as_mut_obs().unwrap();
+
+    for i in (0..30).filter(|i| !(15..18).contains(i)) {
+        let dt = i as f64 * 30.0;
+        let range = 22.0E6 + 500.0 * dt;
+        let iono = 3.0 + 1.0E-4 * dt;
+
+        let mut observations = Observations::default();
+
+        for observable in observables {
+            let observable = Observable::from_str(observable).unwrap();
+            let carrier = observable.to_carrier(Constellation::GPS).unwrap();
+
+            let lambda = carrier.wavelength();
+            let iono = iono * (Carrier::L1.frequency() / carrier.frequency()).powi(2);
+            let slip = if carrier == Carrier::L1 && i >= 10 {
+                50.0
+            } else {
+                0.0
+            };
+
+            let value = match observable {
+                Observable::PseudoRange(_) => range + iono,
+                Observable::PhaseRange(_) => (range - iono) / lambda + 1000.0 + slip,
+                _ => -500.0 / lambda,
+            };
+
+            observations
+                .signals
+                .push(SignalObservation::new(g01, observable, value));
+        }
+
+        let key = ObsKey {
+            epoch: t0 + dt * Unit::Second,
+            flag: EpochFlag::Ok,
+        };
+
+        rec.insert(key, observations);
+    }
+
+    rinex
+}
+
+fn slip(carrier: Carrier) -> (Epoch, SV, Carrier) {
+    (
+        Epoch::from_str("2024-06-10T00:05:00 GPST").unwrap(),
+        SV::from_str("G01").unwrap(),
+        carrier,
+    )
+}
+
+#[test]
+fn cs_triple_frequency() {
+    let rinex = slipping_rinex(&["C1C", "L1C", "D1C", "C2W", "L2W", "C5Q", "L5Q"]);
+
+    for strategy in [
+        CsStrategy::GeometryFree,
+        CsStrategy::MelbourneWubbena,
+        CsStrategy::PhaseDoppler,
+        CsStrategy::SingleFrequency,
+    ] {
+        let detector =
+            CsDetector::default().with_selection_method(CsSelectionMethod::Manual(strategy));
+
+        assert_eq!(
+            detector.cs_detection(&rinex),
+            vec![slip(Carrier::L1)],
+            "{:?}: wrong detection",
+            strategy
+        );
+    }
+
+    assert_eq!(
+        CsDetector::default().cs_detection(&rinex),
+        vec![slip(Carrier::L1)]
+    );
+
+    // below detection threshold
+    let detector = CsDetector::default()
+        .with_selection_method(CsSelectionMethod::Manual(CsStrategy::SingleFrequency))
+        .with_cmc_threshold(10.0);
+
+    assert!(detector.cs_detection(&rinex).is_empty());
+}
+
+#[test]
+fn cs_dual_frequency() {
+    let rinex = slipping_rinex(&["C1C", "L1C", "C2W", "L2W"]);
+
+    // ambiguous: both carriers are reported
+    assert_eq!(
+        CsDetector::default().cs_detection(&rinex),
+        vec![slip(Carrier::L1), slip(Carrier::L2)]
+    );
+
+    // not feasible
+    let detector = CsDetector::default()
+        .with_selection_method(CsSelectionMethod::Manual(CsStrategy::PhaseDoppler));
+
+    assert!(detector.cs_detection(&rinex).is_empty());
+
+    // fallback
+    let detector = CsDetector::default()
+        .with_selection_method(CsSelectionMethod::Prefered(CsStrategy::PhaseDoppler));
+
+    assert_eq!(
+        detector.cs_detection(&rinex),
+        vec![slip(Carrier::L1), slip(Carrier::L2)]
+    );
+}
+
+#[test]
+fn cs_single_frequency() {
+    let mut rinex = slipping_rinex(&["C1C", "L1C", "D1C"]);
+
+    let detector = CsDetector::default();
+    assert_eq!(
+        detector.cs_detection_mut(&mut rinex),
+        vec![slip(Carrier::L1)]
+    );
+
+    let flagged = rinex
+        .phase_cycle_slip_events()
+        .map(|(k, sig)| (k.epoch, sig.sv, sig.observable.clone()))
+        .collect::<Vec<_>>();
+
+    let (t, g01, _) = slip(Carrier::L1);
+    assert_eq!(
+        flagged,
+        vec![(t, g01, Observable::from_str("L1C").unwrap())]
+    );
+
+    for (k, sig) in rinex.phase_range_observations_iter() {
+        if k.epoch != t {
+            assert!(sig.lli.is_none());
+        } else {
+            assert_eq!(sig.lli, Some(LliFlags::LOCK_LOSS));
+        }
+    }
+}
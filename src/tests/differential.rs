@@ -0,0 +1,216 @@
+//! Base / Rover differential context
+use crate::{
+    differential::Error,
+    observation::{ObsKey, Observations, SignalObservation},
+    prelude::{
+        obs::{DiffContext, DoubleDiffKey, EpochFlag, SingleDiffKey},
+        *,
+    },
+};
+
+use hifitime::Unit;
+use std::str::FromStr;
+
+fn t(i: usize) -> Epoch {
+    Epoch::from_str("2024-06-10T00:00:00 GPST").unwrap() + (i as f64 * 30.0) * Unit::Second
+}
+
+fn sv(sv: &str) -> SV {
+    SV::from_str(sv).unwrap()
+}
+
+/// Synthetic geometric range, per [SV]
+fn range(sv: SV, i: usize) -> f64 {
+    20.0E6 + sv.prn as f64 * 1.0E3 + i as f64 * 10.0
+}
+
+/// Builds one receiver, sampling all [SV]s at all epochs unless
+/// they are declared as missing. Rover ranges are offset by
+/// (PRN x 2 m) and impacted by a 100 m receiver clock offset.
+fn receiver(rover: bool, epochs: usize, svs: &[&str], missing: &[(usize, &str)]) -> Rinex {
+    let mut rinex = Rinex::basic_obs();
+    let rec = rinex.record.as_mut_obs().unwrap();
+
+    for i in 0..epochs {
+        let mut observations = Observations::default();
+
+        for sat in svs {
+            if missing.contains(&(i, sat)) {
+                continue;
+            }
+
+            let sat = sv(sat);
+
+            let mut value = range(sat, i);
+            if rover {
+                value += 100.0 + sat.prn as f64 * 2.0;
+            }
+
+            for (observable, value) in [("C1C", value), ("L1C", value), ("S1C", 45.0)] {
+                let observable = Observable::from_str(observable).unwrap();
+                observations
+                    .signals
+                    .push(SignalObservation::new(sat, observable, value));
+            }
+        }
+
+        let key = ObsKey {
+            epoch: t(i),
+            flag: EpochFlag::Ok,
+        };
+
+        rec.insert(key, observations);
+    }
+
+    rinex
+}
+
+#[test]
+fn diff_context_alignment() {
+    let base = receiver(false, 10, &["G01", "G02", "E05"], &[(3, "G02")]);
+    let rover = receiver(true, 12, &["G01", "G02", "G03", "E05"], &[]);
+
+    let ctx = DiffContext::new(&base, &rover).unwrap();
+
+    for rinex in [&ctx.base, &ctx.rover] {
+        let rec = rinex.record.as_obs().unwrap();
+        assert_eq!(rec.len(), 10);
+
+        for (k, v) in rec.iter() {
+            assert!(v.signals.iter().all(|sig| sig.sv != sv("G03")));
+            assert!(v.signals.iter().all(|sig| {
+                sig.observable.is_phase_range_observable()
+                    || sig.observable.is_pseudo_range_observable()
+            }));
+
+            if k.epoch == t(3) {
+                assert_eq!(v.signals.len(), 4);
+            } else {
+                assert_eq!(v.signals.len(), 6);
+            }
+        }
+    }
+
+    assert!(matches!(
+        DiffContext::new(&Rinex::basic_nav(), &rover),
+        Err(Error::NotObservationBase)
+    ));
+
+    assert!(matches!(
+        DiffContext::new(&base, &Rinex::basic_nav()),
+        Err(Error::NotObservationRover)
+    ));
+}
+
+#[test]
+fn diff_context_single_differences() {
+    let base = receiver(false, 10, &["G01", "G02", "E05"], &[(3, "G02")]);
+    let rover = receiver(true, 10, &["G01", "G02", "E05"], &[]);
+
+    let ctx = DiffContext::new(&base, &rover).unwrap();
+    let sd = ctx.single_differences();
+
+    assert_eq!(sd.len(), 10 * 3 * 2 - 2);
+
+    for (k, value) in sd.iter() {
+        assert_eq!(*value, 100.0 + k.sv.prn as f64 * 2.0, "{:?}", k);
+    }
+
+    let key = SingleDiffKey {
+        epoch: t(3),
+        sv: sv("G02"),
+        observable: Observable::from_str("L1C").unwrap(),
+    };
+
+    assert!(!sd.contains_key(&key));
+}
+
+#[test]
+fn diff_context_double_differences() {
+    let base = receiver(
+        false,
+        10,
+        &["G01", "G02", "G03", "E05"],
+        &[(4, "G01"), (5, "G01")],
+    );
+
+    let rover = receiver(true, 10, &["G01", "G02", "G03", "E05"], &[]);
+
+    let ctx = DiffContext::new(&base, &rover).unwrap();
+    let dd = ctx.double_differences();
+
+    // E05 is the only Galileo vehicle: no DD
+    assert!(dd.keys().all(|k| k.sv.constellation == Constellation::GPS));
+
+    // G01 is the reference until it disappears, then G02 remains the reference
+    for k in dd.keys() {
+        if k.epoch < t(4) {
+            assert_eq!(k.reference, sv("G01"));
+        } else {
+            assert_eq!(k.reference, sv("G02"));
+        }
+    }
+
+    assert_eq!(dd.len(), (4 * 2 + 2 + 4 * 2) * 2);
+
+    // receiver clock cancels out
+    for (k, value) in dd.iter() {
+        let expected = (k.sv.prn as f64 - k.reference.prn as f64) * 2.0;
+        assert_eq!(*value, expected, "{:?}", k);
+    }
+
+    let key = DoubleDiffKey {
+        epoch: t(9),
+        sv: sv("G01"),
+        reference: sv("G02"),
+        observable: Observable::from_str("C1C").unwrap(),
+    };
+
+    assert_eq!(dd.get(&key), Some(&-2.0));
+}
+
+#[test]
+fn diff_context_glonass_double_differences() {
+    let base = receiver(false, 10, &["G01", "G02", "R01", "R02"], &[]);
+    let rover = receiver(true, 10, &["G01", "G02", "R01", "R02"], &[]);
+
+    let ctx = DiffContext::new(&base, &rover).unwrap();
+    let dd = ctx.double_differences();
+
+    // Glonass phase is not double differentiated (FDMA)
+    assert!(dd.keys().all(|k| {
+        k.sv.constellation != Constellation::Glonass || !k.observable.is_phase_range_observable()
+    }));
+
+    // one GPS (phase and range) and one Glonass (range) DD per epoch
+    assert_eq!(dd.len(), 10 * 3);
+
+    let key = DoubleDiffKey {
+        epoch: t(0),
+        sv: sv("R02"),
+        reference: sv("R01"),
+        observable: Observable::from_str("C1C").unwrap(),
+    };
+
+    assert_eq!(dd.get(&key), Some(&2.0));
+}
+
+#[test]
+fn diff_context_exact_epochs() {
+    let base = receiver(false, 10, &["G01", "G02"], &[]);
+    let mut rover = receiver(true, 10, &["G01", "G02"], &[]);
+
+    // rover sampling is slightly offset: epochs are not matched
+    let rec = rover.record.as_mut_obs().unwrap();
+    *rec = std::mem::take(rec)
+        .into_iter()
+        .map(|(mut k, v)| {
+            k.epoch += 1.0 * Unit::Millisecond;
+            (k, v)
+        })
+        .collect();
+
+    let ctx = DiffContext::new(&base, &rover).unwrap();
+    assert!(ctx.single_differences().is_empty());
+    assert!(ctx.double_differences().is_empty());
+}
@@ -0,0 +1,258 @@
+use crate::{
+    navigation::{EarthOrientation, EopProvider, NavFrame, NavFrameType, NavKey, NavMessageType},
+    prelude::{
+        nav::{Frame, Orbit},
+        Duration, Epoch, Rinex, TimeScale, SV,
+    },
+};
+
+use anise::{
+    constants::frames::{EARTH_ITRF93, EARTH_J2000},
+    math::{Matrix3, Vector3, Vector6},
+};
+
+use std::str::FromStr;
+
+/// Arc-second to radians
+const ARCSEC_TO_RAD: f64 = std::f64::consts::PI / 180.0 / 3600.0;
+
+/// Seconds of GPST week
+fn seconds_of_week(t: Epoch) -> u32 {
+    let (_, nanos) = t.to_time_scale(TimeScale::GPST).to_time_of_week();
+    (nanos / 1_000_000_000) as u32
+}
+
+/// Parameters transmitted at their reference [Epoch]
+fn eop(t_ref: Epoch, x: f64, y: f64, dut1: f64) -> EarthOrientation {
+    EarthOrientation {
+        x: (x, 0.0, 0.0),
+        y: (y, 0.0, 0.0),
+        t_tm: seconds_of_week(t_ref),
+        delta_ut1: (dut1, 0.0, 0.0),
+    }
+}
+
+fn orbit(t: Epoch, pos_vel: (f64, f64, f64, f64, f64, f64), frame: Frame) -> Orbit {
+    let (x, y, z, vx, vy, vz) = pos_vel;
+    Orbit::from_cartesian_pos_vel(Vector6::new(x, y, z, vx, vy, vz), t, frame)
+}
+
+#[test]
+fn eop_provider() {
+    let t0 = Epoch::from_str("2023-03-14T16:51:12 UTC").unwrap();
+    let t = |days: f64| t0 + Duration::from_days(days);
+
+    let published = EarthOrientation {
+        x: (0.1, 0.01, 0.001),
+        y: (0.3, -0.02, 0.0),
+        t_tm: 4392,
+        delta_ut1: (-0.0194, -1.4E-4, 0.0),
+    };
+
+    let (x, y, dut1) = published.evaluate(t0, t(2.0));
+    assert!((x - 0.124).abs() < 1.0E-12);
+    assert!((y - 0.26).abs() < 1.0E-12);
+    assert!((dut1 + 0.01968).abs() < 1.0E-12);
+
+    // 4392s of GPST week: 2023-03-12T01:13:12 GPST
+    let t_tm = Epoch::from_str("2023-03-12T01:12:54 UTC").unwrap();
+    assert_eq!(published.transmission_epoch(t0), t_tm);
+
+    // next publication is referenced one day later, but transmitted half a day later
+    let mut next = eop(t(1.0), 0.2, 0.4, 0.1);
+    next.t_tm = seconds_of_week(t(0.5));
+
+    let provider = EopProvider::default()
+        .with_parameters(t0, published)
+        .with_parameters(t(1.0), next);
+
+    assert!(provider
+        .selection(t_tm - Duration::from_seconds(1.0))
+        .is_none());
+    assert!(provider
+        .parameters(t_tm - Duration::from_seconds(1.0))
+        .is_none());
+
+    let (t_ref, _) = provider.selection(t_tm).unwrap();
+    assert_eq!(t_ref, t0);
+
+    let (t_ref, _) = provider.selection(t(0.25)).unwrap();
+    assert_eq!(t_ref, t0);
+
+    // selected from transmission, evaluated with respect to reference time
+    let (t_ref, _) = provider.selection(t(0.5)).unwrap();
+    assert_eq!(t_ref, t(1.0));
+    assert_eq!(provider.polar_motion_arcsec(t(0.75)), Some((0.2, 0.4)));
+
+    let (t_ref, _) = provider.selection(t(3.0)).unwrap();
+    assert_eq!(t_ref, t(1.0));
+
+    assert_eq!(provider.polar_motion_arcsec(t(3.0)), Some((0.2, 0.4)));
+    assert_eq!(provider.ut1_utc(t(3.0)), Some(Duration::from_seconds(0.1)));
+
+    // TAI-UTC was 37s in 2023
+    let tai_ut1 = provider.tai_ut1(t0).unwrap().to_seconds();
+    assert!((tai_ut1 - 37.0194).abs() < 1.0E-9);
+}
+
+#[test]
+fn eop_itrf_eme2000_rotation() {
+    let t0 = Epoch::from_str("2000-01-01T12:00:00 UTC").unwrap();
+
+    let zero = EopProvider::default().with_parameters(t0, eop(t0, 0.0, 0.0, 0.0));
+
+    // Greenwich meridian, at J2000: GMST = 280.46061837°
+    let fixed = orbit(t0, (6378.0, 0.0, 0.0, 0.0, 0.0, 0.0), EARTH_ITRF93);
+    let inertial = zero.itrf_to_eme2000(fixed).unwrap();
+
+    assert_eq!(inertial.frame, EARTH_J2000);
+
+    let ra = inertial
+        .radius_km
+        .y
+        .atan2(inertial.radius_km.x)
+        .to_degrees()
+        .rem_euclid(360.0);
+
+    assert!((ra - 280.46061837).abs() < 2.0E-3, "GMST: {}", ra);
+
+    // Earth rotation
+    let speed = inertial.velocity_km_s.norm();
+    assert!((speed - 6378.0 * 7.292115146706979E-5).abs() < 1.0E-9);
+
+    // UT1-UTC: rotation along the equator
+    let shifted = EopProvider::default().with_parameters(t0, eop(t0, 0.0, 0.0, 0.5));
+    let delta = shifted.itrf_to_eme2000(fixed).unwrap().radius_km - inertial.radius_km;
+
+    assert!(
+        (delta.norm() - 6378.0 * 7.292115146706979E-5 * 0.5).abs() < 1.0E-4,
+        "UT1-UTC: {}",
+        delta.norm()
+    );
+
+    // polar motion: tilts the pole
+    let pole = orbit(t0, (0.0, 0.0, 6378.0, 0.0, 0.0, 0.0), EARTH_ITRF93);
+
+    let tilted = EopProvider::default().with_parameters(t0, eop(t0, 0.3, 0.0, 0.0));
+    let delta = tilted.itrf_to_eme2000(pole).unwrap().radius_km
+        - zero.itrf_to_eme2000(pole).unwrap().radius_km;
+
+    assert!(
+        (delta.norm() - 6378.0 * 0.3 * ARCSEC_TO_RAD).abs() < 1.0E-6,
+        "polar motion: {}",
+        delta.norm()
+    );
+
+    // reciprocity
+    let t = Epoch::from_str("2023-03-14T16:51:12 GPST").unwrap();
+    let provider = EopProvider::default().with_parameters(t, eop(t, 0.1, 0.3, -0.02));
+
+    let fixed = orbit(t, (15600.0, 7540.0, 20140.0, -0.5, 2.1, 1.3), EARTH_ITRF93);
+
+    let inertial = provider.itrf_to_eme2000(fixed).unwrap();
+    let back = provider.eme2000_to_itrf(inertial).unwrap();
+
+    assert_eq!(back.frame, EARTH_ITRF93);
+    assert!((back.radius_km - fixed.radius_km).norm() < 1.0E-8);
+    assert!((back.velocity_km_s - fixed.velocity_km_s).norm() < 1.0E-12);
+    assert!((inertial.radius_km.norm() - fixed.radius_km.norm()).abs() < 1.0E-8);
+}
+
+#[test]
+fn eop_sofa_celestial_to_terrestrial() {
+    // SOFA cookbook (Tools for Earth Attitude), equinox based example
+    let t = Epoch::from_str("2007-04-05T12:00:00 UTC").unwrap();
+
+    let expected = Matrix3::new(
+        0.973104317712772,
+        0.230363826174782,
+        -0.000703163477127,
+        -0.230363800391868,
+        0.973104570648022,
+        0.000118545116892,
+        0.000711560100206,
+        0.000046626645796,
+        0.999999745754058,
+    );
+
+    let provider =
+        EopProvider::default().with_parameters(t, eop(t, 0.0349282, 0.4833163, -0.072073685));
+
+    let corrected = provider.with_celestial_pole_offsets(-0.0550655, -0.0063580);
+
+    // at GNSS altitude
+    let radius_km = 26600.0;
+
+    for axis in 0..3 {
+        let mut inertial = Vector3::zeros();
+        inertial[axis] = radius_km;
+
+        let inertial = orbit(
+            t,
+            (inertial.x, inertial.y, inertial.z, 0.0, 0.0, 0.0),
+            EARTH_J2000,
+        );
+
+        let expected = expected.column(axis) * radius_km;
+
+        let fixed = corrected.eme2000_to_itrf(inertial).unwrap();
+        let error_km = (fixed.radius_km - expected).norm();
+        assert!(error_km < 1.0E-4, "axis #{}: {} km", axis, error_km);
+
+        // celestial pole offsets are not broadcast: within 5 m
+        let fixed = provider.eme2000_to_itrf(inertial).unwrap();
+        let error_km = (fixed.radius_km - expected).norm();
+        assert!(error_km < 5.0E-3, "axis #{}: {} km", axis, error_km);
+    }
+}
+
+#[test]
+fn nav_eop_provider() {
+    let t0 = Epoch::from_str("2023-03-14T16:51:12 UTC").unwrap();
+    let t1 = t0 + Duration::from_days(1.0);
+
+    let mut rinex = Rinex::basic_nav();
+    let rec = rinex.record.as_mut_nav().unwrap();
+
+    for (sv, t, dut1) in [
+        ("G01", t0, -0.0194),
+        ("G02", t0, -0.0194),
+        ("G01", t1, -0.0196),
+    ] {
+        let key = NavKey {
+            epoch: t,
+            sv: SV::from_str(sv).unwrap(),
+            msgtype: NavMessageType::CNVX,
+            frmtype: NavFrameType::EarthOrientation,
+        };
+
+        rec.insert(key, NavFrame::EOP(eop(t, 0.1, 0.3, dut1)));
+    }
+
+    let provider = rinex.nav_eop_provider();
+    assert!(!provider.is_empty());
+
+    assert!(provider
+        .parameters(t0 - Duration::from_hours(1.0))
+        .is_none());
+    assert_eq!(provider.parameters(t0), Some((0.1, 0.3, -0.0194)));
+    assert_eq!(provider.parameters(t1), Some((0.1, 0.3, -0.0196)));
+
+    assert!(Rinex::basic_nav().nav_eop_provider().is_empty());
+
+    #[cfg(feature = "ut1")]
+    {
+        for delta in rinex.nav_delta_tai_ut1_iter() {
+            let expected = provider.tai_ut1(delta.epoch).unwrap();
+            assert_eq!(delta.delta_tai_minus_ut1, expected);
+        }
+
+        let ut1 = rinex.nav_ut1_provider().unwrap();
+
+        for t in [t0, t0 + Duration::from_hours(12.0), t1] {
+            let offset = t.ut1_offset(&ut1).unwrap().to_seconds();
+            let expected = provider.tai_ut1(t).unwrap().to_seconds();
+            assert!((offset - expected).abs() < 1.0E-6, "{}: {}", t, offset);
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use crate::prelude::Rinex;
+
+use std::io::{BufReader, BufWriter};
+
+const IONEX: &str =
+    "     1.0            IONOSPHERE MAPS     GPS                 IONEX VERSION / TYPE
+TEST                TEST                01-JAN-17 00:00     PGM / RUN BY / DATE
+  2017     1     1     0     0     0                        EPOCH OF FIRST MAP
+  2017     1     1     2     0     0                        EPOCH OF LAST MAP
+     2                                                      # OF MAPS IN FILE
+  6371.0                                                    BASE RADIUS
+     2                                                      MAP DIMENSION
+   450.0 450.0   0.0                                        HGT1 / HGT2 / DHGT
+     2.5  -2.5  -2.5                                        LAT1 / LAT2 / DLAT
+  -180.0 180.0  90.0                                        LON1 / LON2 / DLON
+    -1                                                      EXPONENT
+                                                            END OF HEADER
+     1                                                      START OF TEC MAP
+  2017     1     1     0     0     0                        EPOCH OF CURRENT MAP
+     2.5-180.0 180.0  90.0 450.0                            LAT/LON1/LON2/DLON/H
+   33   32 9999   30   29
+    -2.5-180.0 180.0  90.0 450.0                            LAT/LON1/LON2/DLON/H
+   36   35   34   33   32
+     1                                                      END OF TEC MAP
+     2                                                      START OF TEC MAP
+  2017     1     1     2     0     0                        EPOCH OF CURRENT MAP
+     2.5-180.0 180.0  90.0 450.0                            LAT/LON1/LON2/DLON/H
+   13   12   11   10    9
+    -2.5-180.0 180.0  90.0 450.0                            LAT/LON1/LON2/DLON/H
+   16   15   14   13   12
+     2                                                      END OF TEC MAP
+     1                                                      START OF RMS MAP
+  2017     1     1     0     0     0                        EPOCH OF CURRENT MAP
+     2.5-180.0 180.0  90.0 450.0                            LAT/LON1/LON2/DLON/H
+    1    2    3    4    5
+    -2.5-180.0 180.0  90.0 450.0                            LAT/LON1/LON2/DLON/H
+    6    7    8    9   10
+     1                                                      END OF RMS MAP
+                                                            END OF FILE
+";
+
+#[test]
+fn ionex_v1_reciprocal() {
+    let mut reader = BufReader::new(IONEX.as_bytes());
+    let model = Rinex::parse(&mut reader).unwrap();
+
+    let record = model.record.as_ionex().unwrap();
+    assert_eq!(record.len(), 19);
+    assert_eq!(
+        record
+            .iter()
+            .filter(|(_, tec)| tec.rms_tec().is_some())
+            .count(),
+        9
+    );
+
+    let mut buf = BufWriter::new(Vec::<u8>::new());
+    model.format(&mut buf).unwrap();
+
+    let content = String::from_utf8(buf.into_inner().unwrap()).unwrap();
+
+    let mut reader = BufReader::new(content.as_bytes());
+    let dut = Rinex::parse(&mut reader).unwrap();
+
+    assert_eq!(dut.header.ionex, model.header.ionex);
+    assert_eq!(dut.record.as_ionex(), model.record.as_ionex());
+}
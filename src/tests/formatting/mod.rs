@@ -1,6 +1,11 @@
 pub mod header;
 pub mod obs;
 
+#[cfg(feature = "ionex")]
+mod ionex;
+
+pub mod nav;
+
 use std::collections::HashMap;
 use std::{io::Write, str::from_utf8};
 
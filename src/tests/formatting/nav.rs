@@ -0,0 +1,135 @@
+use crate::{
+    navigation::{IonosphereModel, NavFrame, NavFrameType},
+    prelude::{Constellation, Rinex, TimeScale, Version},
+};
+
+use std::io::{BufReader, BufWriter};
+
+pub const NAV_V4: &str =
+    "     4.00           N: GNSS NAV DATA    M: MIXED            RINEX VERSION / TYPE
+TEST                TEST                20221212 000000 UTC PGM / RUN BY / DATE
+    18    18  2185     7                                    LEAP SECONDS
+                                                            END OF HEADER
+> STO G01 LNAV
+    2022 06 10 19 56 48 GPUT                                  UTC(USNO)
+     2.952840000000E+05 9.313225746155E-10 2.664535259100E-15 0.000000000000E+00
+> ION G01 LNAV
+    2022 06 10 19 56 48 2.887100000000E-08 7.450600000000E-09-1.192100000000E-07
+     0.000000000000E+00 1.331200000000E+05 0.000000000000E+00-2.621400000000E+05
+     0.000000000000E+00
+> ION E01 IFNV
+    2022 06 10 19 56 48 7.850000000000E+01 5.390600000000E-01 2.713000000000E-02
+     0.000000000000E+00
+> ION C21 CNVX
+    2022 06 10 19 56 48 7.616250000000E+00-1.578125000000E+00 3.130000000000E+00
+     1.150000000000E+00 3.475000000000E-01 1.262500000000E+00-2.052500000000E+00
+     1.000000000000E+00-1.075000000000E+00
+> EOP G27 CNVX
+    2022 06 10 19 56 48-4.024982452393E-02 3.957748413086E-05 0.000000000000E+00
+                        3.562908172607E-01 2.602100372314E-03 0.000000000000E+00
+     4.392000000000E+03-1.940387487411E-02-1.411736011505E-04 0.000000000000E+00
+";
+
+#[test]
+fn nav_v4_frames_reciprocal() {
+    let mut reader = BufReader::new(NAV_V4.as_bytes());
+    let model = Rinex::parse(&mut reader).unwrap();
+
+    let record = model.record.as_nav().unwrap();
+
+    for frmtype in [
+        NavFrameType::SystemTimeOffset,
+        NavFrameType::EarthOrientation,
+    ] {
+        assert_eq!(record.keys().filter(|k| k.frmtype == frmtype).count(), 1);
+    }
+
+    assert_eq!(
+        record
+            .keys()
+            .filter(|k| k.frmtype == NavFrameType::IonosphereModel)
+            .count(),
+        3
+    );
+
+    let mut buf = BufWriter::new(Vec::<u8>::new());
+    model.format(&mut buf).unwrap();
+
+    let content = String::from_utf8(buf.into_inner().unwrap()).unwrap();
+
+    let mut reader = BufReader::new(content.as_bytes());
+    let parsed = Rinex::parse(&mut reader).unwrap();
+
+    assert_eq!(
+        parsed.record, model.record,
+        "reciprocal failure:\n{}",
+        content
+    );
+}
+
+#[test]
+fn nav_v4_to_v3_header_fields() {
+    let mut reader = BufReader::new(NAV_V4.as_bytes());
+    let mut model = Rinex::parse(&mut reader).unwrap();
+    model.header.version = Version::new(3, 5);
+
+    let mut buf = BufWriter::new(Vec::<u8>::new());
+    model.format(&mut buf).unwrap();
+
+    let content = String::from_utf8(buf.into_inner().unwrap()).unwrap();
+
+    let mut reader = BufReader::new(content.as_bytes());
+    let parsed = Rinex::parse(&mut reader).unwrap();
+
+    // frames are now described in the header
+    let record = parsed.record.as_nav().unwrap();
+    assert!(record
+        .values()
+        .all(|frame| !matches!(frame, NavFrame::STO(_) | NavFrame::ION(_))));
+
+    let sto = model
+        .record
+        .as_nav()
+        .unwrap()
+        .values()
+        .find_map(|frame| frame.as_system_time())
+        .unwrap();
+
+    let time_offsets = &parsed.header.nav.as_ref().unwrap().time_offsets;
+    assert_eq!(time_offsets.len(), 1);
+
+    let time_offset = &time_offsets[0];
+    assert_eq!(time_offset.lhs, TimeScale::GPST);
+    assert_eq!(time_offset.rhs, TimeScale::UTC);
+    assert_eq!(time_offset.t_ref, sto.t_ref);
+    assert!((time_offset.polynomial.0 - sto.polynomial.0).abs() < 1.0E-19);
+    assert!((time_offset.polynomial.1 - sto.polynomial.1).abs() < 1.0E-23);
+
+    let kb = parsed
+        .header
+        .ionod_corrections
+        .get(&Constellation::GPS)
+        .and_then(IonosphereModel::as_klobuchar)
+        .unwrap();
+    assert_eq!(
+        kb.alpha,
+        (
+            2.887100000000E-08,
+            7.450600000000E-09,
+            -1.192100000000E-07,
+            0.0
+        )
+    );
+    assert_eq!(kb.beta, (1.331200000000E+05, 0.0, -2.621400000000E+05, 0.0));
+
+    let ng = parsed
+        .header
+        .ionod_corrections
+        .get(&Constellation::Galileo)
+        .and_then(IonosphereModel::as_nequick_g)
+        .unwrap();
+    assert_eq!(
+        ng.a,
+        (7.850000000000E+01, 5.390600000000E-01, 2.713000000000E-02)
+    );
+}
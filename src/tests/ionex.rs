@@ -1,9 +1,13 @@
 use crate::{
+    ionex::IonexKey,
     prelude::Rinex,
     tests::toolkit::{generic_ionex_test, TecPoint, TimeFrame},
 };
 
-use std::path::Path;
+use std::{
+    io::{BufReader, BufWriter},
+    path::Path,
+};
 
 #[test]
 #[cfg(feature = "flate2")]
@@ -225,3 +229,121 @@ fn v1_jplg0010_17i() {
         assert_eq!(k.coordinates.altitude_km(), 450.0);
     }
 }
+
+const IONEX_3D: &str =
+    "     1.0            IONOSPHERE MAPS     GPS                 IONEX VERSION / TYPE
+TEST                TEST                01-JAN-17 00:00     PGM / RUN BY / DATE
+  2017     1     1     0     0     0                        EPOCH OF FIRST MAP
+  2017     1     1     0     0     0                        EPOCH OF LAST MAP
+     1                                                      # OF MAPS IN FILE
+  6371.0                                                    BASE RADIUS
+     3                                                      MAP DIMENSION
+   100.0 300.0 100.0                                        HGT1 / HGT2 / DHGT
+     2.5  -2.5  -2.5                                        LAT1 / LAT2 / DLAT
+  -180.0 180.0  90.0                                        LON1 / LON2 / DLON
+    -1                                                      EXPONENT
+                                                            END OF HEADER
+     1                                                      START OF TEC MAP
+  2017     1     1     0     0     0                        EPOCH OF CURRENT MAP
+     2.5-180.0 180.0  90.0 300.0                            LAT/LON1/LON2/DLON/H
+   31   32   33   34   35
+    -2.5-180.0 180.0  90.0 300.0                            LAT/LON1/LON2/DLON/H
+   36   37   38   39 9999
+     2.5-180.0 180.0  90.0 200.0                            LAT/LON1/LON2/DLON/H
+   21   22   23   24   25
+    -2.5-180.0 180.0  90.0 200.0                            LAT/LON1/LON2/DLON/H
+   26   27   28   29   30
+     2.5-180.0 180.0  90.0 100.0                            LAT/LON1/LON2/DLON/H
+   11   12   13   14   15
+    -2.5-180.0 180.0  90.0 100.0                            LAT/LON1/LON2/DLON/H
+   16   17   18   19   20
+     1                                                      END OF TEC MAP
+     1                                                      START OF RMS MAP
+  2017     1     1     0     0     0                        EPOCH OF CURRENT MAP
+     2.5-180.0 180.0  90.0 200.0                            LAT/LON1/LON2/DLON/H
+    1    2    3    4    5
+    -2.5-180.0 180.0  90.0 200.0                            LAT/LON1/LON2/DLON/H
+    6    7    8    9   10
+     1                                                      END OF RMS MAP
+     1                                                      START OF HEIGHT MAP
+  2017     1     1     0     0     0                        EPOCH OF CURRENT MAP
+     2.5-180.0 180.0  90.0 100.0                            LAT/LON1/LON2/DLON/H
+ 1050 1060 1070 1080 1090
+     1                                                      END OF HEIGHT MAP
+                                                            END OF FILE
+";
+
+#[test]
+fn ionex_3d_isosurfaces() {
+    let mut reader = BufReader::new(IONEX_3D.as_bytes());
+    let rinex = Rinex::parse(&mut reader).unwrap();
+
+    assert!(rinex.is_ionex_3d());
+    assert_eq!(rinex.ionex_altitude_range_km(), Some((100.0, 300.0)));
+
+    let record = rinex.record.as_ionex().unwrap();
+    assert_eq!(record.len(), 29);
+
+    // layer by layer
+    let isosurfaces = rinex.ionex_tec_isosurface_iter().collect::<Vec<_>>();
+    assert_eq!(isosurfaces.len(), 29);
+
+    // quantized value, as described in this file
+    let quantized = |k: &IonexKey| {
+        let row = if k.coordinates.latitude_ddeg() > 0.0 {
+            1
+        } else {
+            6
+        };
+        let col = (k.coordinates.longitude_ddeg() + 180.0) / 90.0;
+        row as f64 + col
+    };
+
+    let mut altitude_km = 0.0;
+
+    for (k, tec) in isosurfaces.iter() {
+        assert!(k.coordinates.altitude_km() >= altitude_km);
+        altitude_km = k.coordinates.altitude_km();
+
+        let layer = altitude_km / 100.0;
+        assert_eq!(tec.tecu(), (10.0 * layer + quantized(k)) / 10.0, "{:?}", k);
+    }
+
+    assert_eq!(altitude_km, 300.0);
+
+    let rms = rinex.ionex_rms_tec_isosurface_iter().collect::<Vec<_>>();
+    assert_eq!(rms.len(), 10);
+
+    for (k, tec) in rms.iter() {
+        assert_eq!(k.coordinates.altitude_km(), 200.0);
+        assert_eq!(tec.rms_tec(), Some(quantized(k) / 10.0));
+    }
+
+    // height maps
+    let heights = isosurfaces
+        .iter()
+        .filter_map(|(k, tec)| Some((k.coordinates.latitude_ddeg(), tec.height_km()?)))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        heights,
+        vec![
+            (2.5, 105.0),
+            (2.5, 106.0),
+            (2.5, 107.0),
+            (2.5, 108.0),
+            (2.5, 109.0)
+        ]
+    );
+
+    // reciprocal
+    let mut buf = BufWriter::new(Vec::<u8>::new());
+    rinex.format(&mut buf).unwrap();
+
+    let content = String::from_utf8(buf.into_inner().unwrap()).unwrap();
+
+    let mut reader = BufReader::new(content.as_bytes());
+    let dut = Rinex::parse(&mut reader).unwrap();
+
+    assert_eq!(dut.record.as_ionex(), rinex.record.as_ionex());
+}
@@ -0,0 +1,589 @@
+//! Ionosphere models evaluation
+use crate::{
+    carrier::Carrier,
+    navigation::{
+        BdModel, BdNonBroadcast, IonosphereModel, KbModel, KbRegionCode, NavFrame, NavFrameType,
+        NavKey, NavMessageType, NgModel, NgRegionFlags, NgTables,
+    },
+    prelude::{nav::AzElRange, Constellation, Duration, Epoch, ParsingError, Rinex, SV},
+};
+
+use std::str::FromStr;
+
+const SPEED_OF_LIGHT_M_S: f64 = 299_792_458.0;
+
+fn kb_model(a0: f64) -> KbModel {
+    KbModel {
+        alpha: (a0, 0.0, 0.0, 0.0),
+        beta: (100.0E3, 0.0, 0.0, 0.0),
+        region: KbRegionCode::Worldwide,
+    }
+}
+
+fn azelrange(t: Epoch, azimuth_deg: f64, elevation_deg: f64) -> AzElRange {
+    AzElRange {
+        epoch: t,
+        azimuth_deg,
+        elevation_deg,
+        range_km: 20.0E3,
+        range_rate_km_s: 0.0,
+        obstructed_by: None,
+        light_time: Duration::ZERO,
+    }
+}
+
+fn assert_delay(delay: f64, expected: f64) {
+    assert!(
+        (delay - expected).abs() < 1.0E-6,
+        "{} m != {} m",
+        delay,
+        expected
+    );
+}
+
+/// IS-GPS-200 obliquity factor
+fn kb_gps_obliquity(elevation_deg: f64) -> f64 {
+    1.0 + 16.0 * (0.53 - elevation_deg / 180.0).powi(3)
+}
+
+#[test]
+fn klobuchar_gps_delay() {
+    let model = kb_model(1.0E-8);
+    let noon = Epoch::from_str("2024-06-10T14:00:00 GPST").unwrap();
+    let night = Epoch::from_str("2024-06-10T02:00:00 GPST").unwrap();
+
+    let f = kb_gps_obliquity(90.0);
+
+    // local 14:00 at the pierce point: peak
+    let delay = model.meters_delay(noon, Constellation::GPS, (0.0, 0.0), 0.0, 90.0, Carrier::L1);
+    assert_delay(delay, f * 1.5E-8 * SPEED_OF_LIGHT_M_S);
+
+    let delay = model.meters_delay(noon, Constellation::GPS, (0.0, 0.0), 0.0, 90.0, Carrier::L2);
+    let gamma = (Carrier::L1.frequency() / Carrier::L2.frequency()).powi(2);
+    assert_delay(delay, f * 1.5E-8 * SPEED_OF_LIGHT_M_S * gamma);
+
+    // local 14:00 is 6h later, 90° West
+    let delay = model.meters_delay(
+        noon + Duration::from_hours(6.0),
+        Constellation::GPS,
+        (0.0, -90.0),
+        0.0,
+        90.0,
+        Carrier::L1,
+    );
+    assert_delay(delay, f * 1.5E-8 * SPEED_OF_LIGHT_M_S);
+
+    // night time: constant vertical delay
+    let zenith = model.meters_delay(
+        night,
+        Constellation::GPS,
+        (0.0, 0.0),
+        0.0,
+        90.0,
+        Carrier::L1,
+    );
+    assert_delay(zenith, f * 5.0E-9 * SPEED_OF_LIGHT_M_S);
+
+    // obliquity
+    let delay = model.meters_delay(
+        night,
+        Constellation::GPS,
+        (0.0, 0.0),
+        45.0,
+        10.0,
+        Carrier::L1,
+    );
+
+    assert_delay(delay, kb_gps_obliquity(10.0) * 5.0E-9 * SPEED_OF_LIGHT_M_S);
+}
+
+#[test]
+fn klobuchar_gps_reference() {
+    // RTKLIB default parameters (ion_default, 2004/1/1)
+    let model = KbModel {
+        alpha: (0.1118E-07, -0.7451E-08, -0.5961E-07, 0.1192E-06),
+        beta: (0.1167E+06, -0.2294E+06, -0.1311E+06, 0.1049E+07),
+        region: KbRegionCode::Worldwide,
+    };
+
+    let t = Epoch::from_str("2024-06-10T04:00:00 GPST").unwrap();
+
+    // reference values: RTKLIB ionmodel() (IS-GPS-200), Tokyo
+    for (t, azimuth_deg, elevation_deg, expected) in [
+        (t, 30.0, 45.0, 5.626954645211463),
+        (t, 210.0, 10.0, 12.045568362002268),
+        (
+            t + Duration::from_hours(12.0),
+            30.0,
+            45.0,
+            2.025445813041283,
+        ),
+    ] {
+        let delay = model.meters_delay(
+            t,
+            Constellation::GPS,
+            (35.7, 139.7),
+            azimuth_deg,
+            elevation_deg,
+            Carrier::L1,
+        );
+
+        assert!(
+            (delay - expected).abs() < 1.0E-4,
+            "{} m != {} m",
+            delay,
+            expected
+        );
+    }
+}
+
+#[test]
+fn klobuchar_bds_delay() {
+    let model = kb_model(1.0E-8);
+    let noon = Epoch::from_str("2024-06-10T14:00:00 BDT").unwrap();
+    let night = Epoch::from_str("2024-06-10T02:00:00 BDT").unwrap();
+
+    let delay = model.meters_delay(
+        noon,
+        Constellation::BeiDou,
+        (0.0, 0.0),
+        0.0,
+        90.0,
+        Carrier::B1,
+    );
+
+    assert_delay(delay, 1.5E-8 * SPEED_OF_LIGHT_M_S);
+
+    let delay = model.meters_delay(
+        noon,
+        Constellation::BeiDou,
+        (0.0, 0.0),
+        0.0,
+        90.0,
+        Carrier::B3,
+    );
+
+    let gamma = (Carrier::B1.frequency() / Carrier::B3.frequency()).powi(2);
+    assert_delay(delay, 1.5E-8 * SPEED_OF_LIGHT_M_S * gamma);
+
+    // obliquity: 375 km thin shell
+    let zenith = model.meters_delay(
+        night,
+        Constellation::BeiDou,
+        (0.0, 0.0),
+        0.0,
+        90.0,
+        Carrier::B1,
+    );
+
+    assert_delay(zenith, 5.0E-9 * SPEED_OF_LIGHT_M_S);
+
+    let delay = model.meters_delay(
+        night,
+        Constellation::BeiDou,
+        (0.0, 0.0),
+        45.0,
+        10.0,
+        Carrier::B1,
+    );
+
+    let obliquity = 1.0 / (1.0 - (6378.0 / 6753.0 * 10.0_f64.to_radians().cos()).powi(2)).sqrt();
+    assert_delay(delay, zenith * obliquity);
+}
+
+#[test]
+fn klobuchar_model_selection() {
+    let g01 = SV::from_str("G01").unwrap();
+    let c01 = SV::from_str("C01").unwrap();
+    let e01 = SV::from_str("E01").unwrap();
+
+    let t0 = Epoch::from_str("2024-06-10T00:00:00 GPST").unwrap();
+    let t = |h: f64| t0 + Duration::from_hours(h);
+
+    let mut rinex = Rinex::basic_nav();
+
+    rinex.header.ionod_corrections.insert(
+        Constellation::GPS,
+        IonosphereModel::Klobuchar(kb_model(1.0E-8)),
+    );
+
+    let rec = rinex.record.as_mut_nav().unwrap();
+
+    for (sv, msgtype, model) in [
+        (g01, NavMessageType::LNAV, kb_model(2.0E-8)),
+        (c01, NavMessageType::D1, kb_model(3.0E-8)),
+    ] {
+        let key = NavKey {
+            epoch: t(12.0),
+            sv,
+            msgtype,
+            frmtype: NavFrameType::IonosphereModel,
+        };
+
+        rec.insert(key, NavFrame::ION(IonosphereModel::Klobuchar(model)));
+    }
+
+    // header model, published at midnight
+    let (t_i, model) = rinex
+        .nav_klobuchar_selection(Constellation::GPS, t(10.0))
+        .unwrap();
+
+    assert_eq!(t_i, t0);
+    assert_eq!(*model, kb_model(1.0E-8));
+
+    for h in [14.0, 35.0] {
+        let (t_i, model) = rinex
+            .nav_klobuchar_selection(Constellation::GPS, t(h))
+            .unwrap();
+
+        assert_eq!(t_i, t(12.0));
+        assert_eq!(*model, kb_model(2.0E-8));
+    }
+
+    // outdated
+    assert!(rinex
+        .nav_klobuchar_selection(Constellation::GPS, t(37.0))
+        .is_none());
+
+    let (_, model) = rinex
+        .nav_klobuchar_selection(Constellation::BeiDou, t(14.0))
+        .unwrap();
+
+    assert_eq!(*model, kb_model(3.0E-8));
+
+    assert!(rinex
+        .nav_klobuchar_selection(Constellation::Galileo, t(14.0))
+        .is_none());
+
+    let delay = rinex
+        .nav_klobuchar_ionosphere_delay(
+            g01,
+            t(14.0),
+            (0.0, 0.0),
+            &azelrange(t(14.0), 0.0, 90.0),
+            Carrier::L1,
+        )
+        .unwrap();
+
+    assert_delay(delay, kb_gps_obliquity(90.0) * 2.5E-8 * SPEED_OF_LIGHT_M_S);
+
+    assert!(rinex
+        .nav_klobuchar_ionosphere_delay(
+            e01,
+            t(14.0),
+            (0.0, 0.0),
+            &azelrange(t(14.0), 0.0, 90.0),
+            Carrier::E1,
+        )
+        .is_none());
+}
+
+/// Synthetic NeQuick-G tables: MODIP equals the latitude,
+/// foF2 is 8 MHz and M(3000)F2 is 3, worldwide and at all times.
+fn ng_tables() -> NgTables {
+    let modip = (0..39)
+        .map(|row| {
+            let lat = -95.0 + 5.0 * row as f64;
+            vec![format!("{:.1}", lat); 39].join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut ccir = vec![0.0; 2858];
+    ccir[0] = 8.0;
+    ccir[988] = 8.0;
+    ccir[1976] = 3.0;
+    ccir[2417] = 3.0;
+
+    let ccir = ccir
+        .iter()
+        .map(|value| format!("{:.8E}", value))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    NgTables::new(&modip, std::array::from_fn(|_| ccir.as_str())).unwrap()
+}
+
+#[test]
+fn nequick_g_tables() {
+    let tables = ng_tables();
+
+    for (lat, long) in [(0.0, 0.0), (45.2, -3.5), (-60.0, 175.0), (12.5, 180.0)] {
+        let modip = tables.modip(lat, long);
+        assert!(
+            (modip - lat).abs() < 1.0E-9,
+            "modip({}, {}) = {}",
+            lat,
+            long,
+            modip
+        );
+    }
+
+    assert_eq!(tables.modip(90.0, 0.0), 90.0);
+    assert_eq!(tables.modip(-90.0, 0.0), -90.0);
+
+    assert!(matches!(
+        NgTables::new("1.0 2.0", std::array::from_fn(|_| "")),
+        Err(ParsingError::NequickGModipTable)
+    ));
+
+    // synthetic tables, identical to ng_tables()
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("data/NEQUICK");
+
+    let tables = NgTables::from_files(dir.join("modip.asc"), &dir).unwrap();
+    assert_eq!(tables, ng_tables());
+
+    assert!(matches!(
+        NgTables::from_files(dir.join("modip.asc"), dir.join("ccir")),
+        Err(ParsingError::InputError(_))
+    ));
+}
+
+#[test]
+fn nequick_g_delay() {
+    let tables = ng_tables();
+    let model = NgModel {
+        a: (100.0, 0.0, 0.0),
+        region: NgRegionFlags::empty(),
+    };
+
+    assert_eq!(model.effective_ionisation_level(30.0), 100.0);
+    assert_eq!(NgModel::default().effective_ionisation_level(30.0), 63.7);
+
+    let t = Epoch::from_str("2024-06-10T12:00:00 UTC").unwrap();
+    let rx = (40.0, 5.0, 0.1);
+
+    // zenith
+    let vtec = model.slant_tec(&tables, t, rx, (40.0, 5.0, 23222.0));
+    assert!(vtec > 5.0 && vtec < 100.0, "vtec={}", vtec);
+
+    // slant and vertical integrations agree close to zenith
+    let near_zenith = model.slant_tec(&tables, t, rx, (40.0, 5.001, 23222.0));
+    assert!((near_zenith - vtec).abs() < 1.0E-2 * vtec);
+
+    // ~18° elevation
+    let sv = (0.0, 60.0, 23222.0);
+    let stec = model.slant_tec(&tables, t, rx, sv);
+    assert!(stec > 1.5 * vtec && stec < 4.0 * vtec, "stec={}", stec);
+
+    let e1 = model.meters_delay(&tables, t, rx, sv, Carrier::E1);
+    let e5a = model.meters_delay(&tables, t, rx, sv, Carrier::E5a);
+
+    assert_delay(e1, 40.3E16 / Carrier::E1.frequency().powi(2) * stec);
+    assert_delay(
+        e5a / e1,
+        (Carrier::E1.frequency() / Carrier::E5a.frequency()).powi(2),
+    );
+
+    // TEC accumulates along the ray
+    let leo = model.slant_tec(&tables, t, rx, (35.0, 12.0, 1200.0));
+    assert!(leo > 0.0 && leo < stec, "leo={}", leo);
+}
+
+#[test]
+fn nequick_g_model_selection() {
+    let tables = ng_tables();
+
+    let t0 = Epoch::from_str("2024-06-10T00:00:00 GST").unwrap();
+    let t = |h: f64| t0 + Duration::from_hours(h);
+
+    let rx = (40.0, 5.0, 0.1);
+    let sv = (0.0, 60.0, 23222.0);
+
+    let mut rinex = Rinex::basic_nav();
+
+    let header_model = NgModel {
+        a: (80.0, 0.0, 0.0),
+        region: NgRegionFlags::empty(),
+    };
+
+    let model = NgModel {
+        a: (120.0, 0.0, 0.0),
+        region: NgRegionFlags::empty(),
+    };
+
+    rinex.header.ionod_corrections.insert(
+        Constellation::Galileo,
+        IonosphereModel::NequickG(header_model),
+    );
+
+    let key = NavKey {
+        epoch: t(12.0),
+        sv: SV::from_str("E01").unwrap(),
+        msgtype: NavMessageType::INAV,
+        frmtype: NavFrameType::IonosphereModel,
+    };
+
+    let rec = rinex.record.as_mut_nav().unwrap();
+    rec.insert(key, NavFrame::ION(IonosphereModel::NequickG(model)));
+
+    // header model, published at midnight
+    let (t_i, selected) = rinex.nav_nequickg_selection(t(10.0)).unwrap();
+    assert_eq!(t_i, t0);
+    assert_eq!(*selected, header_model);
+
+    let (t_i, selected) = rinex.nav_nequickg_selection(t(14.0)).unwrap();
+    assert_eq!(t_i, t(12.0));
+    assert_eq!(*selected, model);
+
+    // outdated
+    assert!(rinex.nav_nequickg_selection(t(37.0)).is_none());
+
+    let delay = rinex
+        .nav_nequickg_ionosphere_delay(&tables, t(14.0), rx, sv, Carrier::E1)
+        .unwrap();
+
+    assert_delay(
+        delay,
+        model.meters_delay(&tables, t(14.0), rx, sv, Carrier::E1),
+    );
+
+    assert!(rinex
+        .nav_nequickg_ionosphere_delay(&tables, t(37.0), rx, sv, Carrier::E1)
+        .is_none());
+}
+
+fn bd_model(alpha: [f64; 9]) -> BdModel {
+    BdModel {
+        alpha: (
+            alpha[0], alpha[1], alpha[2], alpha[3], alpha[4], alpha[5], alpha[6], alpha[7],
+            alpha[8],
+        ),
+    }
+}
+
+#[test]
+fn bdgim_delay() {
+    let t = Epoch::from_str("2024-06-10T10:00:00 BDT").unwrap();
+    let beta = [0.0; 17];
+
+    // uniform ionosphere
+    let model = bd_model([10.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+
+    for lat_long in [(0.0, 0.0), (45.0, 120.0), (-70.0, -30.0)] {
+        assert!((model.vertical_tec(t, &beta, lat_long) - 10.0).abs() < 1.0E-9);
+    }
+
+    let zenith = model.meters_delay(t, &beta, (30.0, 114.0), 0.0, 90.0, Carrier::B1c);
+    assert_delay(zenith, 40.28E16 / Carrier::B1c.frequency().powi(2) * 10.0);
+
+    let b2a = model.meters_delay(t, &beta, (30.0, 114.0), 0.0, 90.0, Carrier::B2a);
+    assert_delay(
+        b2a / zenith,
+        (Carrier::B1c.frequency() / Carrier::B2a.frequency()).powi(2),
+    );
+
+    // 400 km thin shell
+    let delay = model.meters_delay(t, &beta, (30.0, 114.0), 60.0, 15.0, Carrier::B1c);
+    let mapping = 1.0 / (1.0 - (6378.0 / 6778.0 * 15.0_f64.to_radians().cos()).powi(2)).sqrt();
+    assert_delay(delay, zenith * mapping);
+
+    // first degree zonal term: maximal at the geomagnetic pole
+    let model = bd_model([0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+    let vtec = model.vertical_tec(t, &beta, (80.27, -72.58));
+    assert!((vtec - 3.0_f64.sqrt()).abs() < 1.0E-9);
+
+    // negative TEC is not physical
+    let model = bd_model([-1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+    assert_eq!(model.vertical_tec(t, &beta, (0.0, 0.0)), 0.0);
+}
+
+#[test]
+fn bdgim_non_broadcast_coefficients() {
+    let t = Epoch::from_str("2024-06-10T00:00:00 UTC").unwrap();
+
+    let mut non_broadcast = BdNonBroadcast::default();
+    assert_eq!(non_broadcast.predict(t), [0.0; 17]);
+
+    // constant term
+    non_broadcast.a[0][3] = 2.0;
+    // diurnal term
+    non_broadcast.a[1][5] = 1.0;
+
+    let beta = non_broadcast.predict(t);
+    assert_eq!(beta[3], 2.0);
+    assert!((beta[5] - 1.0).abs() < 1.0E-9);
+
+    let beta = non_broadcast.predict(t + Duration::from_hours(12.0));
+    assert!((beta[5] + 1.0).abs() < 1.0E-9);
+
+    // third degree zonal term contributes once predicted
+    let model = bd_model([0.0; 9]);
+    let mut non_broadcast = BdNonBroadcast::default();
+    non_broadcast.a[0][0] = 1.0;
+
+    let vtec = model.vertical_tec(t, &non_broadcast.predict(t), (80.27, -72.58));
+    assert!((vtec - 7.0_f64.sqrt()).abs() < 1.0E-9);
+}
+
+#[test]
+fn bdgim_model_selection() {
+    let t0 = Epoch::from_str("2024-06-10T00:00:00 BDT").unwrap();
+    let t = |h: f64| t0 + Duration::from_hours(h);
+
+    let mut rinex = Rinex::basic_nav();
+    let rec = rinex.record.as_mut_nav().unwrap();
+
+    for (h, alpha) in [(2.0, 10.0), (4.0, 20.0)] {
+        let key = NavKey {
+            epoch: t(h),
+            sv: SV::from_str("C19").unwrap(),
+            msgtype: NavMessageType::CNV1,
+            frmtype: NavFrameType::IonosphereModel,
+        };
+
+        let model = bd_model([alpha, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        rec.insert(key, NavFrame::ION(IonosphereModel::Bdgim(model)));
+    }
+
+    assert!(rinex.nav_bdgim_selection(t(1.0)).is_none());
+    assert!(rinex.nav_bdgim_selection(t(29.0)).is_none());
+
+    let (t_p, model) = rinex.nav_bdgim_selection(t(3.0)).unwrap();
+    assert_eq!(t_p, t(2.0));
+    assert_eq!(model.alpha.0, 10.0);
+
+    let (t_p, model) = rinex.nav_bdgim_selection(t(5.0)).unwrap();
+    assert_eq!(t_p, t(4.0));
+    assert_eq!(model.alpha.0, 20.0);
+
+    let delay = rinex
+        .nav_bdgim_ionosphere_delay(
+            t(5.0),
+            (30.0, 114.0),
+            &azelrange(t(5.0), 0.0, 90.0),
+            Carrier::B1c,
+            None,
+        )
+        .unwrap();
+
+    assert_delay(delay, 40.28E16 / Carrier::B1c.frequency().powi(2) * 20.0);
+
+    // non broadcast coefficients override
+    let mut non_broadcast = BdNonBroadcast::default();
+    non_broadcast.a[0][0] = 1.0;
+
+    let delay = rinex
+        .nav_bdgim_ionosphere_delay(
+            t(5.0),
+            (30.0, 114.0),
+            &azelrange(t(5.0), 0.0, 90.0),
+            Carrier::B1c,
+            Some(&non_broadcast),
+        )
+        .unwrap();
+
+    let (_, model) = rinex.nav_bdgim_selection(t(5.0)).unwrap();
+
+    let expected = model.meters_delay(
+        t(5.0),
+        &non_broadcast.predict(t(4.0)),
+        (30.0, 114.0),
+        0.0,
+        90.0,
+        Carrier::B1c,
+    );
+
+    assert_delay(delay, expected);
+    assert!((delay - 40.28E16 / Carrier::B1c.frequency().powi(2) * 20.0).abs() > 1.0E-3);
+}
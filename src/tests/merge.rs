@@ -268,4 +268,332 @@ mod test {
     //         assert_eq!(apc.unwrap(), expected_apc);
     //     }
     // }
+
+    use crate::{
+        navigation::{
+            gps::GpsQzssl1l2l5Health, Ephemeris, NavFrame, NavFrameType, NavKey, NavMessageType,
+            OrbitItem,
+        },
+        observation::{ObsKey, Observations, SignalObservation},
+        prelude::{
+            obs::{EpochFlag, LliFlags, SNR},
+            qc::{MergeConflict, MergePolicy, RinexMergeError},
+            Constellation, Epoch, Observable, SV,
+        },
+    };
+    use std::str::FromStr;
+
+    fn obs_rinex(content: &[(&str, Option<f64>, &[(&str, f64)])]) -> Rinex {
+        let mut rinex = Rinex::basic_obs();
+        let rec = rinex.record.as_mut_obs().unwrap();
+
+        for (epoch, clock, signals) in content {
+            let key = ObsKey {
+                epoch: Epoch::from_str(epoch).unwrap(),
+                flag: EpochFlag::Ok,
+            };
+
+            let mut observations = Observations::default();
+
+            if let Some(offset_s) = clock {
+                observations = observations.with_clock_offset_s(key.epoch, *offset_s);
+            }
+
+            for (observable, value) in signals.iter() {
+                observations.signals.push(SignalObservation::new(
+                    SV::from_str("G01").unwrap(),
+                    Observable::from_str(observable).unwrap(),
+                    *value,
+                ));
+            }
+
+            rec.insert(key, observations);
+        }
+
+        rinex
+    }
+
+    #[test]
+    fn merge_policy_observations() {
+        let t0 = "2024-01-01T00:00:00 GPST";
+        let t1 = "2024-01-01T00:00:30 GPST";
+        let t2 = "2024-01-01T00:01:00 GPST";
+
+        let lhs = obs_rinex(&[
+            (t0, Some(1.0E-3), &[("C1C", 1.0), ("L1C", 10.0)]),
+            (t1, None, &[("C1C", 2.0)]),
+        ]);
+
+        let rhs = obs_rinex(&[
+            (t0, Some(2.0E-3), &[("C1C", 1.5), ("L1C", 10.0)]),
+            (t2, None, &[("C1C", 3.0)]),
+        ]);
+
+        let c1c = |rinex: &Rinex, t: &str| {
+            let key = ObsKey {
+                epoch: Epoch::from_str(t).unwrap(),
+                flag: EpochFlag::Ok,
+            };
+            let rec = rinex.record.as_obs().unwrap();
+            rec.get(&key)
+                .unwrap()
+                .signals
+                .iter()
+                .find(|sig| sig.observable == Observable::from_str("C1C").unwrap())
+                .unwrap()
+                .clone()
+        };
+
+        // keep left (Merge default)
+        let (merged, report) = lhs.merge_with_policy(&rhs, MergePolicy::KeepLeft).unwrap();
+        assert_eq!(
+            merged.record.as_obs(),
+            lhs.merge(&rhs).unwrap().record.as_obs()
+        );
+        assert!(!report.rejected);
+        assert_eq!(report.conflicts.len(), 2);
+
+        assert!(report.conflicts.contains(&MergeConflict::ReceiverClock {
+            key: ObsKey {
+                epoch: Epoch::from_str(t0).unwrap(),
+                flag: EpochFlag::Ok,
+            },
+            lhs: 1.0E-3,
+            rhs: 2.0E-3,
+        }));
+
+        assert!(report.conflicts.contains(&MergeConflict::Signal {
+            key: ObsKey {
+                epoch: Epoch::from_str(t0).unwrap(),
+                flag: EpochFlag::Ok,
+            },
+            sv: SV::from_str("G01").unwrap(),
+            observable: Observable::from_str("C1C").unwrap(),
+            lhs: 1.0,
+            rhs: 1.5,
+        }));
+
+        assert_eq!(c1c(&merged, t0).value, 1.0);
+        assert_eq!(c1c(&merged, t2).value, 3.0);
+
+        // keep right
+        let (merged, report) = lhs.merge_with_policy(&rhs, MergePolicy::KeepRight).unwrap();
+        assert_eq!(report.conflicts.len(), 2);
+        assert_eq!(c1c(&merged, t0).value, 1.5);
+        assert_eq!(c1c(&merged, t1).value, 2.0);
+
+        // prefer healthy
+        let mut lhs_sane = lhs.clone();
+        let mut lhs_lol = lhs.clone();
+        let mut rhs_sane = rhs.clone();
+
+        for (rinex, lli, snr) in [
+            (&mut lhs_sane, LliFlags::OK_OR_UNKNOWN, SNR::DbHz42_47),
+            (&mut lhs_lol, LliFlags::LOCK_LOSS, SNR::DbHz42_47),
+            (&mut rhs_sane, LliFlags::OK_OR_UNKNOWN, SNR::DbHz42_47),
+        ] {
+            for (_, v) in rinex.record.as_mut_obs().unwrap().iter_mut() {
+                for sig in v.signals.iter_mut() {
+                    sig.lli = Some(lli);
+                    sig.snr = Some(snr);
+                }
+            }
+        }
+
+        let (merged, _) = lhs_sane
+            .merge_with_policy(&rhs_sane, MergePolicy::PreferHealthy)
+            .unwrap();
+
+        assert_eq!(c1c(&merged, t0).value, 1.0);
+
+        let (merged, _) = lhs_lol
+            .merge_with_policy(&rhs_sane, MergePolicy::PreferHealthy)
+            .unwrap();
+
+        assert_eq!(c1c(&merged, t0).value, 1.5);
+
+        // reject on conflict
+        let mut merged = lhs.clone();
+        let report = merged
+            .merge_with_policy_mut(&rhs, MergePolicy::RejectOnConflict)
+            .unwrap();
+
+        assert!(report.rejected);
+        assert_eq!(report.conflicts.len(), 2);
+        assert_eq!(merged.record.as_obs(), lhs.record.as_obs());
+
+        let rhs = obs_rinex(&[(t2, None, &[("C1C", 3.0)])]);
+
+        let report = merged
+            .merge_with_policy_mut(&rhs, MergePolicy::RejectOnConflict)
+            .unwrap();
+
+        assert!(report.is_clean());
+        assert!(!report.rejected);
+        assert_eq!(c1c(&merged, t2).value, 3.0);
+    }
+
+    #[test]
+    fn merge_policy_navigation() {
+        let key = NavKey {
+            epoch: Epoch::from_str("2024-01-01T00:00:00 GPST").unwrap(),
+            sv: SV::from_str("G01").unwrap(),
+            msgtype: NavMessageType::LNAV,
+            frmtype: NavFrameType::Ephemeris,
+        };
+
+        let nav_rinex = |iode: f64, health: u32| {
+            let ephemeris = Ephemeris::default()
+                .with_orbit("iode", OrbitItem::F64(iode))
+                .with_orbit(
+                    "health",
+                    OrbitItem::GpsQzssl1l2l5Health(GpsQzssl1l2l5Health::from(health)),
+                );
+
+            let mut rinex = Rinex::basic_nav();
+            let rec = rinex.record.as_mut_nav().unwrap();
+            rec.insert(key, NavFrame::EPH(ephemeris));
+            rinex
+        };
+
+        let iode = |rinex: &Rinex| {
+            let rec = rinex.record.as_nav().unwrap();
+            rec.get(&key)
+                .unwrap()
+                .as_ephemeris()
+                .unwrap()
+                .get_orbit_f64("iode")
+                .unwrap()
+        };
+
+        let lhs = nav_rinex(10.0, 0);
+
+        let (merged, report) = lhs
+            .merge_with_policy(&nav_rinex(10.0, 0), MergePolicy::KeepLeft)
+            .unwrap();
+
+        assert!(report.is_clean());
+        assert_eq!(iode(&merged), 10.0);
+
+        let (merged, report) = lhs
+            .merge_with_policy(&nav_rinex(11.0, 0), MergePolicy::KeepLeft)
+            .unwrap();
+
+        assert_eq!(
+            report.conflicts,
+            vec![MergeConflict::Navigation {
+                key,
+                lhs_iod: Some(10),
+                rhs_iod: Some(11),
+            }]
+        );
+
+        assert_eq!(iode(&merged), 10.0);
+
+        // latest IOD
+        let (merged, _) = lhs
+            .merge_with_policy(&nav_rinex(11.0, 0), MergePolicy::PreferHealthy)
+            .unwrap();
+
+        assert_eq!(iode(&merged), 11.0);
+
+        // healthy first
+        let (merged, _) = lhs
+            .merge_with_policy(&nav_rinex(11.0, 1), MergePolicy::PreferHealthy)
+            .unwrap();
+
+        assert_eq!(iode(&merged), 10.0);
+
+        let (merged, _) = nav_rinex(11.0, 1)
+            .merge_with_policy(&nav_rinex(9.0, 0), MergePolicy::PreferHealthy)
+            .unwrap();
+
+        assert_eq!(iode(&merged), 9.0);
+
+        let (merged, _) = lhs
+            .merge_with_policy(&nav_rinex(9.0, 0), MergePolicy::KeepRight)
+            .unwrap();
+
+        assert_eq!(iode(&merged), 9.0);
+    }
+
+    #[test]
+    fn merge_observation_header_specs() {
+        let t0 = "2024-01-01T00:00:00 GPST";
+        let t1 = "2024-01-01T00:00:30 GPST";
+
+        let l1c = Observable::from_str("L1C").unwrap();
+        let c1c = Observable::from_str("C1C").unwrap();
+
+        let lhs = obs_rinex(&[(t0, None, &[("C1C", 1.25), ("L1C", 10.5)])]);
+        let mut rhs = obs_rinex(&[
+            (t0, None, &[("C1C", 1.25), ("L1C", 1050.0)]),
+            (t1, None, &[("C1C", 2.0), ("L1C", 2000.0)]),
+        ]);
+
+        let specs = rhs.header.obs.as_mut().unwrap();
+        specs.scaling.insert((Constellation::GPS, l1c.clone()), 100);
+
+        // values are rescaled to the finest scaling
+        let (merged, report) = lhs.merge_with_policy(&rhs, MergePolicy::KeepLeft).unwrap();
+        assert!(report.is_clean(), "{:?}", report);
+
+        let specs = merged.header.obs.as_ref().unwrap();
+        assert_eq!(specs.scaling.len(), 1);
+        assert_eq!(
+            specs.scaling.get(&(Constellation::GPS, l1c.clone())),
+            Some(&100)
+        );
+
+        let values = merged
+            .signal_observations_iter()
+            .map(|(k, sig)| (k.epoch, sig.observable.clone(), sig.value))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            values,
+            vec![
+                (Epoch::from_str(t0).unwrap(), c1c.clone(), 1.25),
+                (Epoch::from_str(t0).unwrap(), l1c.clone(), 1050.0),
+                (Epoch::from_str(t1).unwrap(), c1c.clone(), 2.0),
+                (Epoch::from_str(t1).unwrap(), l1c.clone(), 2000.0),
+            ]
+        );
+
+        // GLONASS channels are unioned
+        let mut lhs = lhs.clone();
+        let r01 = SV::from_str("R01").unwrap();
+        let r02 = SV::from_str("R02").unwrap();
+
+        lhs.header.glo_channels.insert(r01, -4);
+        rhs.header.glo_channels.insert(r01, -4);
+        rhs.header.glo_channels.insert(r02, 1);
+
+        let merged = lhs.merge(&rhs).unwrap();
+        assert_eq!(merged.header.glo_channels.len(), 2);
+        assert_eq!(merged.header.glo_channels.get(&r02), Some(&1));
+
+        // conflicting channel assignments
+        rhs.header.glo_channels.insert(r01, 3);
+        assert!(lhs.merge(&rhs).is_err());
+
+        match lhs.merge_with_policy(&rhs, MergePolicy::KeepLeft) {
+            Err(RinexMergeError::GloChannelConflict { sv, lhs, rhs }) => {
+                assert_eq!(sv, r01);
+                assert_eq!(lhs, -4);
+                assert_eq!(rhs, 3);
+            },
+            other => panic!("expecting glonass channel conflict, got {:?}", other),
+        }
+
+        // receiver clock compensation must match
+        rhs.header.glo_channels.insert(r01, -4);
+        rhs.header.obs.as_mut().unwrap().clock_offset_applied = true;
+        assert!(lhs.merge(&rhs).is_err());
+
+        assert!(matches!(
+            lhs.merge_with_policy(&rhs, MergePolicy::KeepLeft),
+            Err(RinexMergeError::ClockOffsetAppliedMismatch)
+        ));
+    }
 }
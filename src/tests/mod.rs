@@ -7,6 +7,8 @@ mod crinex;
 mod filename;
 pub mod formatting;
 mod parsing;
+pub mod reader;
+mod writer;
 
 #[cfg(all(feature = "flate2", feature = "qc"))]
 mod sbas;
@@ -23,6 +25,21 @@ mod clock;
 #[cfg(feature = "processing")]
 mod processing;
 
+#[cfg(feature = "parallel")]
+mod parallel;
+
+#[cfg(feature = "obs")]
+mod cs;
+
+#[cfg(feature = "obs")]
+mod differential;
+
+#[cfg(all(feature = "rtcm", feature = "obs"))]
+mod rtcm;
+
+#[cfg(all(feature = "binex", feature = "nav"))]
+mod binex;
+
 #[cfg(feature = "doris")]
 mod doris;
 
@@ -35,6 +52,12 @@ mod meteo;
 #[cfg(feature = "nav")]
 mod nav;
 
+#[cfg(feature = "nav")]
+mod ionosphere;
+
+#[cfg(feature = "nav")]
+mod earth_orientation;
+
 #[cfg(all(feature = "flate2", feature = "nav"))]
 mod kepler;
 
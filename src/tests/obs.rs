@@ -1,13 +1,18 @@
 use crate::{
     observation::{EpochFlag, LliFlags, ObsKey, SignalObservation, SNR},
-    prelude::{Epoch, GeodeticMarker, Observable, Rinex, SV},
+    prelude::{Duration, Epoch, GeodeticMarker, Observable, Rinex, SV},
     tests::toolkit::{
         generic_null_rinex_test, generic_observation_rinex_test, generic_rinex_comparison,
         SignalDataPoint, TimeFrame,
     },
 };
 
-use std::{fs::remove_file, path::Path, str::FromStr};
+use std::{
+    fs::remove_file,
+    io::{BufReader, BufWriter},
+    path::Path,
+    str::FromStr,
+};
 
 #[test]
 fn v2_aopr0010_17o() {
@@ -624,3 +629,216 @@ fn obs_null_substract() {
         generic_null_rinex_test(&diffed);
     }
 }
+
+/// Parses this content
+fn parse_content(content: &[u8]) -> Rinex {
+    let mut reader = BufReader::new(content);
+    Rinex::parse(&mut reader).unwrap()
+}
+
+/// Formats [Rinex] to bytes
+fn format_content(rinex: &Rinex) -> Vec<u8> {
+    let mut w = BufWriter::new(Vec::new());
+    rinex.format(&mut w).unwrap();
+    w.into_inner().unwrap()
+}
+
+/// Verifies special events of this [Rinex] (three events, in the same context)
+fn special_events_test(dut: &Rinex) {
+    let t = Epoch::from_str("2020-06-25T00:00:30 GPST").unwrap();
+
+    let events = dut.observation_events_iter().collect::<Vec<_>>();
+    assert_eq!(events.len(), 3, "invalid events: {:?}", events);
+
+    assert_eq!(events[0].0.epoch, t);
+    assert_eq!(events[0].0.flag, EpochFlag::NewSiteOccupation);
+    assert_eq!(
+        events[0].1,
+        &[
+            "NEWSITE                                                     MARKER NAME",
+            "moving to new site                                          COMMENT",
+        ]
+    );
+
+    assert_eq!(events[1].0.epoch, t);
+    assert_eq!(events[1].0.flag, EpochFlag::HeaderInformationFollows);
+    assert_eq!(
+        events[1].1,
+        &["        1.0000        0.0000        0.0000                  ANTENNA: DELTA H/E/N"]
+    );
+
+    assert_eq!(
+        events[2].0.epoch,
+        Epoch::from_str("2020-06-25T00:01:15 GPST").unwrap()
+    );
+    assert_eq!(events[2].0.flag, EpochFlag::ExternalEvent);
+    assert!(events[2].1.is_empty());
+
+    // special records are not comments
+    assert!(dut
+        .comments
+        .values()
+        .flatten()
+        .all(|comment| !comment.contains("new site")));
+
+    // signals are preserved
+    assert_eq!(dut.signal_observations_iter().count(), 6);
+}
+
+#[test]
+fn v3_special_events() {
+    let content = "     3.04           OBSERVATION DATA    G                   RINEX VERSION / TYPE
+G    2 C1C L1C                                              SYS / # / OBS TYPES
+  2020     6    25     0     0    0.0000000     GPS         TIME OF FIRST OBS
+                                                            END OF HEADER
+> 2020 06 25 00 00  0.0000000  0  1
+G01  20001000.000   105268421.053
+> 2020 06 25 00 00 30.0000000  3  2
+NEWSITE                                                     MARKER NAME
+moving to new site                                          COMMENT
+>                              4  1
+        1.0000        0.0000        0.0000                  ANTENNA: DELTA H/E/N
+> 2020 06 25 00 01  0.0000000  0  1
+G01  20001010.000   105268473.684
+> 2020 06 25 00 01 15.0000000  5  0
+> 2020 06 25 00 01 30.0000000  0  1
+G01  20001020.000   105268526.316
+";
+
+    let dut = parse_content(content.as_bytes());
+    special_events_test(&dut);
+
+    // formatted back
+    let formatted = format_content(&dut);
+    let parsed = parse_content(&formatted);
+    special_events_test(&parsed);
+    assert_eq!(parsed.record, dut.record);
+
+    // CRINEX round trip
+    let compressed = format_content(&dut.rnx2crnx());
+    let parsed = parse_content(&compressed);
+    assert!(parsed.header.is_crinex());
+    special_events_test(&parsed);
+    assert_eq!(parsed.record, dut.record);
+}
+
+#[test]
+fn v2_special_events() {
+    let content = "     2.11           OBSERVATION DATA    G (GPS)             RINEX VERSION / TYPE
+     2    C1    L1                                          # / TYPES OF OBSERV
+  2020     6    25     0     0    0.0000000     GPS         TIME OF FIRST OBS
+                                                            END OF HEADER
+ 20  6 25  0  0  0.0000000  0  1G01
+  20001000.000   105268421.053
+ 20  6 25  0  0 30.0000000  3  2
+NEWSITE                                                     MARKER NAME
+moving to new site                                          COMMENT
+                            4  1
+        1.0000        0.0000        0.0000                  ANTENNA: DELTA H/E/N
+ 20  6 25  0  1  0.0000000  0  1G01
+  20001010.000   105268473.684
+ 20  6 25  0  1 15.0000000  5  0
+ 20  6 25  0  1 30.0000000  0  1G01
+  20001020.000   105268526.316
+";
+
+    let dut = parse_content(content.as_bytes());
+    special_events_test(&dut);
+
+    let formatted = format_content(&dut);
+    let parsed = parse_content(&formatted);
+    special_events_test(&parsed);
+    assert_eq!(parsed.record, dut.record);
+}
+
+#[test]
+fn special_events_sampling() {
+    let content = "     3.04           OBSERVATION DATA    G                   RINEX VERSION / TYPE
+G    2 C1C L1C                                              SYS / # / OBS TYPES
+  2020     6    25     0     0    0.0000000     GPS         TIME OF FIRST OBS
+                                                            END OF HEADER
+> 2020 06 25 00 00  0.0000000  0  1
+G01  20001000.000   105268421.053
+> 2020 06 25 00 00 30.0000000  0  1
+G01  20001005.000   105268447.368
+> 2020 06 25 00 00 45.0000000  4  1
+        1.0000        0.0000        0.0000                  ANTENNA: DELTA H/E/N
+>                              4  1
+antenna moved                                               COMMENT
+> 2020 06 25 00 01  0.0000000  0  1
+G01  20001010.000   105268473.684
+> 2020 06 25 00 01 30.0000000  0  1
+G01  20001020.000   105268526.316
+";
+
+    let dut = parse_content(content.as_bytes());
+
+    // events sharing the same timestamp are gathered
+    let events = dut.observation_events_iter().collect::<Vec<_>>();
+    assert_eq!(events.len(), 1, "invalid events: {:?}", events);
+
+    assert_eq!(
+        events[0].0.epoch,
+        Epoch::from_str("2020-06-25T00:00:45 GPST").unwrap()
+    );
+    assert_eq!(events[0].0.flag, EpochFlag::HeaderInformationFollows);
+    assert_eq!(
+        events[0].1,
+        &[
+            "        1.0000        0.0000        0.0000                  ANTENNA: DELTA H/E/N",
+            "antenna moved                                               COMMENT",
+        ]
+    );
+
+    // events are not samples
+    assert_eq!(
+        dut.sampling_histogram().collect::<Vec<_>>(),
+        vec![(Duration::from_seconds(30.0), 3)],
+    );
+    assert!(dut.steady_sampling());
+    assert_eq!(dut.data_gaps(None).count(), 0);
+
+    // formatted back
+    let formatted = format_content(&dut);
+    let parsed = parse_content(&formatted);
+    assert_eq!(parsed.record, dut.record);
+}
+
+#[test]
+fn epoch_anomalies() {
+    let content = "     3.04           OBSERVATION DATA    G                   RINEX VERSION / TYPE
+G    2 C1C L1C                                              SYS / # / OBS TYPES
+  2020     6    25     0     0    0.0000000     GPS         TIME OF FIRST OBS
+                                                            END OF HEADER
+> 2020 06 25 00 00  0.0000000  0  1
+G01  20001000.000   105268421.053
+> 2020 06 25 00 00 30.0000000  1  1
+G01  20001005.000   105268447.368
+> 2020 06 25 00 01  0.0000000  0  1
+G01  20001010.000   105268473.684
+> 2020 06 25 00 01 30.0000000  6  1
+G01  20001020.000   105268526.316
+";
+
+    let dut = parse_content(content.as_bytes());
+
+    // only epochs that were not flagged Ok
+    let anomalies = dut
+        .epoch_anomalies()
+        .map(|k| (k.epoch, k.flag))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        anomalies,
+        vec![
+            (
+                Epoch::from_str("2020-06-25T00:00:30 GPST").unwrap(),
+                EpochFlag::PowerFailure
+            ),
+            (
+                Epoch::from_str("2020-06-25T00:01:30 GPST").unwrap(),
+                EpochFlag::CycleSlip
+            ),
+        ]
+    );
+}
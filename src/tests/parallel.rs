@@ -0,0 +1,125 @@
+//! Parallel parsing tests
+use crate::{
+    prelude::*,
+    record::Record,
+    tests::{
+        formatting::nav::NAV_V4,
+        reader::{obs_rinex, to_bytes},
+    },
+};
+
+use std::{io::BufReader, str::from_utf8};
+
+/// Parses this record content one line at a time (sequential parsing),
+/// then in several chunks, and verifies both are identical.
+fn parallel_test(header: &Header, content: &str) -> (Record, Comments) {
+    let mut reader = BufReader::new(content.as_bytes());
+    let (model, model_comments) =
+        Record::parse_sequential(&mut header.clone(), &mut reader).unwrap();
+
+    for num_chunks in 1..16 {
+        let (record, comments) = Record::parse_chunks(header, content, num_chunks).unwrap();
+        assert_eq!(record, model, "failed for {} chunks", num_chunks);
+        assert_eq!(comments, model_comments, "failed for {} chunks", num_chunks);
+    }
+
+    (model, model_comments)
+}
+
+/// Splits this content into [Header] and record content
+fn split(content: &str) -> (Header, String) {
+    let end = content.find("END OF HEADER").unwrap();
+    let end = end + content[end..].find('\n').unwrap() + 1;
+    let mut reader = BufReader::new(&content.as_bytes()[..end]);
+    let header = Header::parse(&mut reader).unwrap();
+    (header, content[end..].to_string())
+}
+
+#[test]
+fn obs_parallel_parsing() {
+    let bytes = to_bytes(&obs_rinex());
+    let (header, content) = split(from_utf8(&bytes).unwrap());
+
+    // comments and special events (with and without timestamp) in the middle of the record
+    let nth = content.match_indices("\n>").nth(4).unwrap().0 + 1;
+    let content = format!(
+        "{}{}{}",
+        &content[..nth],
+        "test comment                                                COMMENT
+>                              4  1
+NEWSITE                                                     MARKER NAME
+>                              4  1
+moving to new site                                          COMMENT
+",
+        &content[nth..],
+    );
+
+    let nth = content.match_indices("\n>").nth(8).unwrap().0 + 1;
+    let content = format!(
+        "{}{}{}",
+        &content[..nth],
+        "> 2020 06 25 00 02 15.0000000  5  0
+> 2020 06 25 00 02 15.0000000  3  1
+NEWSITE2                                                    MARKER NAME
+",
+        &content[nth..],
+    );
+
+    let (record, _) = parallel_test(&header, &content);
+
+    let record = record.as_obs().unwrap();
+    assert_eq!(record.len(), 13);
+
+    let events = record
+        .iter()
+        .filter(|(k, _)| k.flag.is_event())
+        .collect::<Vec<_>>();
+
+    assert_eq!(events.len(), 3);
+
+    // events without timestamp are gathered
+    assert_eq!(events[0].1.special_records.len(), 2);
+}
+
+#[test]
+#[cfg(feature = "nav")]
+fn nav_parallel_parsing() {
+    let (header, content) = split(NAV_V4);
+    let (record, _) = parallel_test(&header, &content);
+    assert_eq!(record.as_nav().unwrap().len(), 5);
+}
+
+#[test]
+#[cfg(feature = "clock")]
+fn clock_parallel_parsing() {
+    let content = "     3.00           C                                       RINEX VERSION / TYPE
+GPS                                                         TIME SYSTEM ID
+                                                            END OF HEADER
+AS R20  2019 01 08 00 03 30.000000  1   -0.364887538519E-03
+AR PIE1 2019 01 08 00 03 30.000000  1   -0.434275035628E-03
+AS R20  2019 01 08 00 04  0.000000  1   -0.364887538529E-03
+test comment                                                COMMENT
+AR PIE1 2019 01 08 00 04  0.000000  1   -0.434275035638E-03
+AS R20  2019 01 08 00 04 30.000000  1   -0.364887538539E-03
+AR PIE1 2019 01 08 00 04 30.000000  1   -0.434275035648E-03
+";
+
+    let (header, content) = split(content);
+    let (record, _) = parallel_test(&header, &content);
+    assert_eq!(record.as_clock().unwrap().len(), 3);
+}
+
+#[test]
+fn parallel_rinex_parsing() {
+    let rinex = obs_rinex();
+    let bytes = to_bytes(&rinex);
+
+    let mut reader = BufReader::new(bytes.as_slice());
+    let parsed = Rinex::parse(&mut reader).unwrap();
+
+    let (mut header, content) = split(from_utf8(&bytes).unwrap());
+    let mut reader = BufReader::new(content.as_bytes());
+    let (model, _) = Record::parse_sequential(&mut header, &mut reader).unwrap();
+
+    assert_eq!(parsed.record, model);
+}
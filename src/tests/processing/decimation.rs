@@ -94,4 +94,275 @@ mod decimation {
         let count = rinex.epoch_iter().count();
         assert_eq!(count, 1013, "decimate(1'+1s): error",);
     }
+    #[test]
+    #[cfg(all(feature = "obs", feature = "clock", feature = "meteo", feature = "nav"))]
+    fn targeted_decimation() {
+        use crate::{
+            clock::{ClockKey, ClockProfile, ClockProfileType, ClockType},
+            navigation::{Ephemeris, NavFrame, NavFrameType, NavKey, NavMessageType},
+            observation::{EpochFlag, ObsKey, Observations, SignalObservation},
+        };
+        use qc_traits::FilterItem;
+        use std::collections::BTreeMap;
+        use std::str::FromStr;
+
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(30.0);
+        let dt_60s = DecimationFilter::duration(Duration::from_seconds(60.0));
+
+        let g01 = SV::from_str("G01").unwrap();
+        let g08 = SV::from_str("G08").unwrap();
+        let e01 = SV::from_str("E01").unwrap();
+        let c1c = Observable::from_str("C1C").unwrap();
+        let l1c = Observable::from_str("L1C").unwrap();
+
+        // Observation: only C1C gets decimated
+        let mut record = BTreeMap::new();
+        for i in 0..10 {
+            let mut obs = Observations::default();
+            for sv in [g01, e01] {
+                for observable in [&c1c, &l1c] {
+                    obs.signals
+                        .push(SignalObservation::new(sv, observable.clone(), 1.0));
+                }
+            }
+            let key = ObsKey {
+                epoch: t0 + dt * i as f64,
+                flag: EpochFlag::Ok,
+            };
+            record.insert(key, obs);
+        }
+
+        let mut rinex = Rinex::new(Header::basic_obs(), Record::ObsRecord(record));
+        rinex.decimate_mut(&dt_60s.with_item(FilterItem::ComplexItem(vec!["C1C".to_string()])));
+
+        let rec = rinex.record.as_obs().unwrap();
+        assert_eq!(rec.len(), 10, "targeted decimation dropped epochs");
+
+        let c1c_count = rec
+            .values()
+            .flat_map(|obs| obs.signals.iter())
+            .filter(|sig| sig.observable == c1c)
+            .count();
+        assert_eq!(c1c_count, 10, "C1C should be decimated (2 SV x 5 epochs)");
+
+        let l1c_count = rec
+            .values()
+            .flat_map(|obs| obs.signals.iter())
+            .filter(|sig| sig.observable == l1c)
+            .count();
+        assert_eq!(l1c_count, 20, "L1C should not be decimated");
+
+        // Constellation target: only Galileo gets decimated
+        rinex.decimate_mut(
+            &DecimationFilter::modulo(5)
+                .with_item(FilterItem::ConstellationItem(vec![Constellation::Galileo])),
+        );
+
+        let rec = rinex.record.as_obs().unwrap();
+        let gal_count = rec
+            .values()
+            .flat_map(|obs| obs.signals.iter())
+            .filter(|sig| sig.sv == e01)
+            .count();
+        assert_eq!(gal_count, 3, "E01 C1C (5 -> 1) and L1C (10 -> 2)");
+
+        let gps_count = rec
+            .values()
+            .flat_map(|obs| obs.signals.iter())
+            .filter(|sig| sig.sv == g01)
+            .count();
+        assert_eq!(gps_count, 15, "G01 should not be decimated");
+
+        // Navigation: only G08 gets decimated
+        let mut record = BTreeMap::new();
+        for i in 0..10 {
+            for sv in [g01, g08] {
+                let key = NavKey {
+                    epoch: t0 + dt * i as f64,
+                    sv,
+                    msgtype: NavMessageType::LNAV,
+                    frmtype: NavFrameType::Ephemeris,
+                };
+                record.insert(key, NavFrame::EPH(Ephemeris::default()));
+            }
+        }
+
+        let mut rinex = Rinex::new(Header::basic_nav(), Record::NavRecord(record));
+        rinex.decimate_mut(&dt_60s.with_item(FilterItem::SvItem(vec![g08])));
+
+        let rec = rinex.record.as_nav().unwrap();
+        assert_eq!(rec.keys().filter(|k| k.sv == g08).count(), 5);
+        assert_eq!(rec.keys().filter(|k| k.sv == g01).count(), 10);
+
+        // Meteo: only pressure gets decimated
+        let pressure = Observable::Pressure;
+        let temperature = Observable::Temperature;
+
+        let mut record = BTreeMap::new();
+        for i in 0..10 {
+            for observable in [&pressure, &temperature] {
+                let key = MeteoKey {
+                    epoch: t0 + dt * i as f64,
+                    observable: observable.clone(),
+                };
+                record.insert(key, 1.0);
+            }
+        }
+
+        let mut rinex = Rinex::new(Header::default(), Record::MeteoRecord(record));
+        rinex.decimate_mut(&dt_60s.with_item(FilterItem::ComplexItem(vec!["PR".to_string()])));
+
+        let rec = rinex.record.as_meteo().unwrap();
+        assert_eq!(rec.keys().filter(|k| k.observable == pressure).count(), 5);
+        assert_eq!(
+            rec.keys().filter(|k| k.observable == temperature).count(),
+            10
+        );
+
+        // Clock: only G01 gets decimated, station clock is preserved
+        let g01_key = ClockKey {
+            clock_type: ClockType::SV(g01),
+            profile_type: ClockProfileType::AS,
+        };
+        let station_key = ClockKey {
+            clock_type: ClockType::Station("ESBC".to_string()),
+            profile_type: ClockProfileType::AR,
+        };
+
+        let mut record = BTreeMap::new();
+        for i in 0..10 {
+            let mut inner = BTreeMap::new();
+            inner.insert(g01_key.clone(), ClockProfile::default());
+            inner.insert(station_key.clone(), ClockProfile::default());
+            record.insert(t0 + dt * i as f64, inner);
+        }
+
+        let mut rinex = Rinex::new(Header::default(), Record::ClockRecord(record));
+        rinex.decimate_mut(&dt_60s.with_item(FilterItem::SvItem(vec![g01])));
+
+        let rec = rinex.record.as_clock().unwrap();
+        assert_eq!(rec.len(), 10);
+        assert_eq!(rec.values().filter(|v| v.contains_key(&g01_key)).count(), 5);
+        assert_eq!(
+            rec.values()
+                .filter(|v| v.contains_key(&station_key))
+                .count(),
+            10
+        );
+    }
+    #[test]
+    #[cfg(feature = "ionex")]
+    fn ionex_decimation() {
+        use crate::ionex::{IonexKey, QuantizedCoordinates, TEC};
+        use qc_traits::FilterItem;
+        use std::collections::BTreeMap;
+        use std::str::FromStr;
+
+        let t0 = Epoch::from_str("2022-01-02T00:00:00 UTC").unwrap();
+        let dt = Duration::from_hours(1.0);
+        let dt_2h = DecimationFilter::duration(Duration::from_hours(2.0));
+
+        let equator = QuantizedCoordinates::new(0.0, 1, 10.0, 1, 350.0, 1);
+        let north = QuantizedCoordinates::new(40.0, 1, 10.0, 1, 350.0, 1);
+
+        let mut record = BTreeMap::new();
+        for i in 0..6 {
+            for coordinates in [equator, north] {
+                let key = IonexKey {
+                    epoch: t0 + dt * i as f64,
+                    coordinates,
+                };
+                record.insert(key, TEC::from_tecu(10.0));
+            }
+        }
+
+        let rinex = Rinex::new(Header::basic_ionex(), Record::IonexRecord(record));
+        let count = |rinex: &Rinex, coordinates| {
+            rinex
+                .record
+                .as_ionex()
+                .unwrap()
+                .keys()
+                .filter(|k| k.coordinates == coordinates)
+                .count()
+        };
+
+        // entire maps
+        let decimated = rinex.decimate(&dt_2h);
+        assert_eq!(count(&decimated, equator), 3);
+        assert_eq!(count(&decimated, north), 3);
+
+        let decimated = rinex.decimate(&DecimationFilter::modulo(3));
+        assert_eq!(count(&decimated, equator), 2);
+        assert_eq!(count(&decimated, north), 2);
+
+        // geographic bounds: only the northern point gets decimated
+        let item = FilterItem::from_str("lat:30:50,alt:300:400").unwrap();
+        let decimated = rinex.decimate(&dt_2h.with_item(item));
+        assert_eq!(count(&decimated, equator), 6);
+        assert_eq!(count(&decimated, north), 3);
+
+        // from this epoch onwards
+        let item = FilterItem::EpochItem(t0 + dt * 2.0);
+        let decimated = rinex.decimate(&dt_2h.with_item(item));
+        assert_eq!(count(&decimated, equator), 4);
+        assert_eq!(count(&decimated, north), 4);
+
+        // items that may not be targeted leave the record untouched
+        for item in [
+            FilterItem::ComplexItem(vec!["C1C".to_string()]),
+            FilterItem::SvItem(vec![SV::from_str("G01").unwrap()]),
+        ] {
+            let decimated = rinex.decimate(&dt_2h.with_item(item));
+            assert_eq!(decimated.record, rinex.record);
+        }
+    }
+    #[test]
+    #[cfg(all(feature = "nav", feature = "meteo"))]
+    fn untargetable_decimation() {
+        use crate::navigation::{Ephemeris, NavFrame, NavFrameType, NavKey, NavMessageType};
+        use qc_traits::FilterItem;
+        use std::collections::BTreeMap;
+        use std::str::FromStr;
+
+        let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+        let dt = Duration::from_seconds(30.0);
+        let dt_60s = DecimationFilter::duration(Duration::from_seconds(60.0));
+        let g01 = SV::from_str("G01").unwrap();
+
+        let mut nav = BTreeMap::new();
+        let mut meteo = BTreeMap::new();
+
+        for i in 0..10 {
+            let key = NavKey {
+                epoch: t0 + dt * i as f64,
+                sv: g01,
+                msgtype: NavMessageType::LNAV,
+                frmtype: NavFrameType::Ephemeris,
+            };
+            nav.insert(key, NavFrame::EPH(Ephemeris::default()));
+
+            let key = MeteoKey {
+                epoch: t0 + dt * i as f64,
+                observable: Observable::Pressure,
+            };
+            meteo.insert(key, 1.0);
+        }
+
+        let nav = Rinex::new(Header::basic_nav(), Record::NavRecord(nav));
+        let meteo = Rinex::new(Header::default(), Record::MeteoRecord(meteo));
+
+        // items that do not describe a part of the record leave it untouched
+        for item in [FilterItem::SNRItem(30.0), FilterItem::ClockItem] {
+            let decimated = nav.decimate(&dt_60s.with_item(item.clone()));
+            assert_eq!(decimated.record, nav.record);
+
+            let decimated = meteo.decimate(&dt_60s.with_item(item));
+            assert_eq!(decimated.record, meteo.record);
+        }
+
+        let decimated = meteo.decimate(&dt_60s.with_item(FilterItem::SvItem(vec![g01])));
+        assert_eq!(decimated.record, meteo.record);
+    }
 }
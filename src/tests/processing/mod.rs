@@ -1,2 +1,3 @@
 mod decimation;
 mod masking;
+mod timeshift;
@@ -0,0 +1,425 @@
+use crate::{
+    clock::{ClockKey, ClockProfile, ClockProfileType, ClockType, HeaderFields as ClockHeader},
+    ionex::HeaderFields as IonexHeader,
+    meteo::MeteoKey,
+    prelude::{Constellation, Duration, Epoch, Header, Observable, Rinex, TimeScale, SV},
+    record::Record,
+};
+
+use qc_traits::{TimeCorrection, TimeCorrectionsDB, Timeshift};
+
+use hifitime::Polynomial;
+
+use std::{collections::BTreeMap, str::FromStr};
+
+#[test]
+fn meteo_timeshift() {
+    let t0 = Epoch::from_str("2020-06-25T00:00:00 UTC").unwrap();
+
+    let mut rec = BTreeMap::new();
+    for i in 0..4 {
+        let key = MeteoKey {
+            epoch: t0 + Duration::from_seconds(30.0 * i as f64),
+            observable: Observable::Temperature,
+        };
+        rec.insert(key, 20.0 + i as f64);
+    }
+
+    let dut = Rinex::new(Header::default(), Record::MeteoRecord(rec));
+
+    let shifted = dut.timeshift(TimeScale::GPST);
+    let shifted_rec = shifted.record.as_meteo().unwrap();
+
+    assert_eq!(shifted_rec.len(), 4);
+
+    for ((k, value), (shifted_k, shifted_value)) in dut
+        .record
+        .as_meteo()
+        .unwrap()
+        .iter()
+        .zip(shifted_rec.iter())
+    {
+        assert_eq!(shifted_k.epoch.time_scale, TimeScale::GPST);
+        assert_eq!(shifted_k.epoch, k.epoch, "same instant expected");
+        assert_eq!(shifted_value, value);
+    }
+}
+
+#[test]
+fn clock_precise_correction() {
+    let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+    let g01 = SV::from_str("G01").unwrap();
+
+    let key = ClockKey {
+        clock_type: ClockType::SV(g01),
+        profile_type: ClockProfileType::AS,
+    };
+
+    let profile = ClockProfile {
+        bias: 1.0E-6,
+        ..Default::default()
+    };
+
+    let mut rec = BTreeMap::new();
+    for i in 0..4 {
+        let mut inner = BTreeMap::new();
+        inner.insert(key.clone(), profile.clone());
+        rec.insert(t0 + Duration::from_seconds(30.0 * i as f64), inner);
+    }
+
+    let header = Header {
+        clock: Some(ClockHeader::default().timescale(TimeScale::GPST)),
+        ..Default::default()
+    };
+
+    let dut = Rinex::new(header, Record::ClockRecord(rec));
+
+    // |GPST - GST| = 10ns
+    let mut db = TimeCorrectionsDB::default();
+    db.add(TimeCorrection::from_reference_epoch(
+        t0,
+        Duration::from_days(1.0),
+        TimeScale::GST,
+        Polynomial::from_constant_offset_nanoseconds(10.0),
+    ));
+
+    let corrected = dut.precise_correction(&db, TimeScale::GST).unwrap();
+
+    assert_eq!(
+        corrected.header.clock.as_ref().unwrap().timescale,
+        Some(TimeScale::GST)
+    );
+
+    let rec = corrected.record.as_clock().unwrap();
+    assert_eq!(rec.len(), 4);
+
+    for (i, (t, inner)) in rec.iter().enumerate() {
+        assert_eq!(t.time_scale, TimeScale::GST);
+
+        let expected = t0 + Duration::from_seconds(30.0 * i as f64);
+        let dt = (*t - expected).abs();
+        assert!(
+            (dt - Duration::from_nanoseconds(10.0)).abs() < Duration::from_nanoseconds(1.0),
+            "invalid correction {}",
+            dt
+        );
+
+        assert_eq!(inner.get(&key), Some(&profile));
+    }
+
+    // no correction available: dataset is left untouched
+    let mut failed = dut.clone();
+    assert!(failed.precise_correction_mut(&db, TimeScale::BDT).is_err());
+    assert_eq!(failed.header, dut.header);
+    assert_eq!(failed.record, dut.record);
+}
+
+#[test]
+#[cfg(feature = "nav")]
+fn nav_v4_time_corrections() {
+    use crate::navigation::{NavFrame, NavFrameType, NavKey, NavMessageType, TimeOffset};
+
+    let t = |s: &str| Epoch::from_str(s).unwrap();
+
+    let mut dut = Rinex::basic_nav();
+    let rec = dut.record.as_mut_nav().unwrap();
+
+    for (sv, msgtype, t_ref, t_tm, lhs, rhs, a0) in [
+        // identical publications, from two SV
+        (
+            "G01",
+            NavMessageType::LNAV,
+            "2022-06-10T19:56:48 GPST",
+            "2022-06-08T10:01:24 GPST",
+            TimeScale::GPST,
+            TimeScale::UTC,
+            10.0E-9,
+        ),
+        (
+            "G02",
+            NavMessageType::LNAV,
+            "2022-06-10T19:56:48 GPST",
+            "2022-06-08T10:30:00 GPST",
+            TimeScale::GPST,
+            TimeScale::UTC,
+            10.0E-9,
+        ),
+        // following publication, referenced further in time
+        (
+            "G01",
+            NavMessageType::LNAV,
+            "2022-06-11T00:00:00 GPST",
+            "2022-06-09T10:00:00 GPST",
+            TimeScale::GPST,
+            TimeScale::UTC,
+            20.0E-9,
+        ),
+        (
+            "E01",
+            NavMessageType::IFNV,
+            "2022-06-08T00:00:00 GST",
+            "2022-06-08T10:00:07 GST",
+            TimeScale::GST,
+            TimeScale::GPST,
+            5.0E-9,
+        ),
+        (
+            "C01",
+            NavMessageType::D1D2,
+            "2022-06-08T00:00:00 BDT",
+            "2022-06-08T00:00:00 BDT",
+            TimeScale::BDT,
+            TimeScale::UTC,
+            -3.0E-9,
+        ),
+    ] {
+        let (_, t_tm) = t(t_tm).to_time_of_week();
+
+        let offset =
+            TimeOffset::from_epoch(t(t_ref), lhs, rhs, (a0, 0.0, 0.0)).with_transmission_time(t_tm);
+
+        let key = NavKey {
+            epoch: offset.reference_epoch(),
+            sv: SV::from_str(sv).unwrap(),
+            msgtype,
+            frmtype: NavFrameType::SystemTimeOffset,
+        };
+
+        rec.insert(key, NavFrame::STO(offset));
+    }
+
+    let db = dut.time_corrections_database().unwrap();
+
+    // (epoch, target, expected correction, closest database reference is the latest publication)
+    for (t_in, target, expected_ns, db_applies) in [
+        ("2022-06-08T12:00:00 GPST", TimeScale::UTC, -10.0, true),
+        ("2022-06-09T09:59:59 GPST", TimeScale::UTC, -10.0, false),
+        ("2022-06-09T10:00:00 GPST", TimeScale::UTC, -20.0, true),
+        ("2022-06-10T19:56:48 GPST", TimeScale::UTC, -20.0, true),
+        ("2022-06-09T12:00:00 GST", TimeScale::GPST, -5.0, true),
+        ("2022-06-09T12:00:00 GPST", TimeScale::GST, 5.0, true),
+        ("2022-06-09T12:00:00 BDT", TimeScale::UTC, 3.0, true),
+    ] {
+        let t_in = t(t_in);
+
+        let corrected = dut.nav_precise_epoch_correction(t_in, target).unwrap();
+        assert_eq!(corrected.time_scale, target);
+
+        let dt = (corrected - t_in.to_time_scale(target)).to_seconds() * 1.0E9;
+        assert!(
+            (dt - expected_ns).abs() < 1.0E-6,
+            "{} to {}: invalid correction {}ns",
+            t_in,
+            target,
+            dt
+        );
+
+        if db_applies {
+            let corrected = db.precise_epoch_correction(t_in, target).unwrap();
+            assert_eq!(corrected.time_scale, target);
+
+            let dt = (corrected - t_in.to_time_scale(target)).to_seconds() * 1.0E9;
+            assert!(
+                (dt - expected_ns).abs() <= 1.0,
+                "{} to {}: invalid database correction {}ns",
+                t_in,
+                target,
+                dt
+            );
+        }
+    }
+
+    // prior any transmission
+    assert!(dut
+        .nav_precise_epoch_correction(t("2022-06-08T09:00:00 GPST"), TimeScale::UTC)
+        .is_none());
+
+    // NAV keys remain expressed in the SV timescale
+    let shifted = dut.timeshift(TimeScale::UTC);
+
+    for (k, shifted_k) in dut
+        .record
+        .as_nav()
+        .unwrap()
+        .keys()
+        .zip(shifted.record.as_nav().unwrap().keys())
+    {
+        assert_eq!(shifted_k, k);
+        assert_eq!(shifted_k.epoch.time_scale, k.epoch.time_scale);
+    }
+
+    let mut gps = dut.clone();
+
+    gps.record
+        .as_mut_nav()
+        .unwrap()
+        .retain(|k, _| k.sv.constellation == Constellation::GPS);
+
+    let corrected = gps.precise_correction(&db, TimeScale::UTC).unwrap();
+
+    for (k, corrected_k) in gps
+        .record
+        .as_nav()
+        .unwrap()
+        .keys()
+        .zip(corrected.record.as_nav().unwrap().keys())
+    {
+        assert_eq!(corrected_k.epoch.time_scale, TimeScale::GPST);
+
+        let dt = (corrected_k.epoch - k.epoch).to_seconds() * 1.0E9;
+        assert!((dt + 20.0).abs() <= 1.0, "invalid correction {}ns", dt);
+    }
+
+    // precise correction of other products
+    let t0 = t("2022-06-09T10:00:00 GPST");
+
+    let mut rec = BTreeMap::new();
+    for i in -1..2 {
+        let key = MeteoKey {
+            epoch: t0 + Duration::from_seconds(i as f64),
+            observable: Observable::Temperature,
+        };
+        rec.insert(key, 20.0);
+    }
+
+    let meteo = Rinex::new(Header::default(), Record::MeteoRecord(rec));
+
+    // the database selects the closest reference: the next publication, right before its transmission
+    let corrected = meteo.precise_correction(&db, TimeScale::UTC).unwrap();
+    let rec = corrected.record.as_meteo().unwrap();
+
+    for ((k, _), (i, expected_ns)) in rec.iter().zip([(-1, -20.0), (0, -20.0), (1, -20.0)]) {
+        assert_eq!(k.epoch.time_scale, TimeScale::UTC);
+
+        let t_in = t0 + Duration::from_seconds(i as f64);
+        let dt = (k.epoch - t_in).to_seconds() * 1.0E9;
+        assert!(
+            (dt - expected_ns).abs() <= 1.0,
+            "invalid correction {}ns",
+            dt
+        );
+    }
+
+    // the most recent publication applies
+    let corrected = meteo.precise_nav_correction(&dut, TimeScale::UTC).unwrap();
+    let rec = corrected.record.as_meteo().unwrap();
+
+    for ((k, _), (i, expected_ns)) in rec.iter().zip([(-1, -10.0), (0, -20.0), (1, -20.0)]) {
+        assert_eq!(k.epoch.time_scale, TimeScale::UTC);
+
+        let t_in = t0 + Duration::from_seconds(i as f64);
+        let dt = (k.epoch - t_in).to_seconds() * 1.0E9;
+        assert!(
+            (dt - expected_ns).abs() < 1.0E-6,
+            "invalid correction {}ns",
+            dt
+        );
+    }
+
+    // no correction available: dataset is left untouched
+    let mut failed = meteo.clone();
+    assert!(failed
+        .precise_nav_correction_mut(&dut, TimeScale::BDT)
+        .is_err());
+    assert_eq!(failed.record, meteo.record);
+}
+
+#[test]
+#[cfg(feature = "nav")]
+fn nav_v4_time_corrections_drift() {
+    use crate::navigation::{NavFrame, NavFrameType, NavKey, NavMessageType, TimeOffset};
+
+    let t = |s: &str| Epoch::from_str(s).unwrap();
+
+    let mut dut = Rinex::basic_nav();
+    let rec = dut.record.as_mut_nav().unwrap();
+
+    // GPST/UTC publications, transmitted a day apart, with drift and drift rate
+    let publications = [
+        (
+            "2022-06-10T00:00:00 GPST",
+            "2022-06-08T10:00:00 GPST",
+            (10.0E-9, 5.0E-14, 1.0E-19),
+        ),
+        (
+            "2022-06-11T00:00:00 GPST",
+            "2022-06-09T10:00:00 GPST",
+            (20.0E-9, -5.0E-14, 0.0),
+        ),
+    ];
+
+    for (t_ref, t_tm, polynomial) in publications {
+        let (_, t_tm) = t(t_tm).to_time_of_week();
+
+        let offset = TimeOffset::from_epoch(t(t_ref), TimeScale::GPST, TimeScale::UTC, polynomial)
+            .with_transmission_time(t_tm);
+
+        let key = NavKey {
+            epoch: offset.reference_epoch(),
+            sv: SV::from_str("G01").unwrap(),
+            msgtype: NavMessageType::LNAV,
+            frmtype: NavFrameType::SystemTimeOffset,
+        };
+
+        rec.insert(key, NavFrame::STO(offset));
+    }
+
+    let t_next = t("2022-06-09T10:00:00 GPST");
+
+    for (t_in, nth) in [
+        (t("2022-06-08T10:00:00 GPST"), 0),
+        (t("2022-06-08T23:00:00 GPST"), 0),
+        (t("2022-06-09T09:59:59 GPST"), 0),
+        (t_next - Duration::from_nanoseconds(1.0), 0),
+        (t_next, 1),
+        (t("2022-06-10T12:00:00 GPST"), 1),
+    ] {
+        // polynomial evaluated at its own reference
+        let (t_ref, _, (a0, a1, a2)) = publications[nth];
+        let dt = (t_in - t(t_ref)).to_seconds();
+        let expected_s = a0 + a1 * dt + a2 * dt.powi(2);
+
+        let offset = dut
+            .nav_time_offset_selection(TimeScale::GPST, TimeScale::UTC, t_in)
+            .unwrap();
+
+        assert_eq!(offset.polynomial, (a0, a1, a2));
+        assert!((offset.evaluate(t_in) - expected_s).abs() < 1.0E-18);
+
+        // Epoch resolution is 1 ns
+        let corrected = dut
+            .nav_precise_epoch_correction(t_in, TimeScale::UTC)
+            .unwrap();
+
+        let dt = (corrected - t_in.to_time_scale(TimeScale::UTC)).to_seconds() * 1.0E9;
+        assert!(
+            (dt + expected_s * 1.0E9).abs() <= 0.5,
+            "{}: invalid correction {}ns (expecting {}ns)",
+            t_in,
+            dt,
+            -expected_s * 1.0E9
+        );
+    }
+}
+
+#[test]
+fn ionex_header_timeshift() {
+    let header = Header {
+        ionex: Some(IonexHeader {
+            epoch_of_first_map: Epoch::from_str("2022-01-02T00:00:00 UTC").unwrap(),
+            epoch_of_last_map: Epoch::from_str("2022-01-03T00:00:00 UTC").unwrap(),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let shifted = header.timeshift(TimeScale::GPST);
+    let ionex = shifted.ionex.as_ref().unwrap();
+
+    assert_eq!(ionex.epoch_of_first_map.time_scale, TimeScale::GPST);
+    assert_eq!(ionex.epoch_of_last_map.time_scale, TimeScale::GPST);
+    assert_eq!(
+        ionex.epoch_of_first_map,
+        Epoch::from_str("2022-01-02T00:00:00 UTC").unwrap()
+    );
+}
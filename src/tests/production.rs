@@ -2,7 +2,68 @@
 mod test {
     use crate::tests::toolkit::{generic_rinex_comparison, random_name};
     use crate::*;
-    use std::path::Path;
+    use std::{
+        io::{BufReader, BufWriter},
+        path::Path,
+    };
+
+    const CLOCK_V2: &str =
+        "     2.00           C                                       RINEX VERSION / TYPE
+CCLOCK              IGSACC @ GA MIT                         PGM / RUN BY / DATE
+     2    AR    AS                                          # / TYPES OF DATA
+IGS  IGSACC @ GA MIT                                        ANALYSIS CENTER
+                                                            END OF HEADER
+AR USNO 1994 07 14 20 59  0.000000  2   -0.123456789012E+00 -0.123456789012E+01
+AS G16  1994 07 14 20 59  0.000000  2   -0.123456789012E-03 -0.123456789012E-04
+AR USNO 1994 07 14 21 00  0.000000  6   -0.123456789012E+00 -0.123456789012E+01
+    -0.123456789012E-03 -0.123456789012E-04 -0.123456789012E-07 -0.123456789012E-08
+AS G16  1994 07 14 21 00  0.000000  1   -0.123456789012E-03
+";
+
+    const CLOCK_V3: &str =
+        "     3.00           C                                       RINEX VERSION / TYPE
+CCLOCK              IGSACC @ GA MIT     19960403 001000 UTC PGM / RUN BY / DATE
+GPS                                                         TIME SYSTEM ID
+     2    AR    AS                                          # / TYPES OF DATA
+IGS  IGSACC @ GA MIT                                        ANALYSIS CENTER
+                                                            END OF HEADER
+AR PIE1 2019 01 08 00 03 30.000000  1   -0.434275035628E-03
+AS R20  2019 01 08 00 03 30.000000  1   -0.364887538519E-03
+AS E05  2019 01 08 00 03 30.000000  2    0.835484069663E-11  0.128645614326E-10
+AR PIE1 2019 01 08 00 04  0.000000  1   -0.434275035638E-03
+AS R20  2019 01 08 00 04  0.000000  1   -0.364887538529E-03
+";
+
+    const ANTEX_V1: &str = "     1.4            M                                       ANTEX VERSION / SYST
+A                                                           PCV TYPE / REFANT
+                                                            END OF HEADER
+                                                            START OF ANTENNA
+BLOCK IIA           G01                 G032      1992-079A TYPE / SERIAL NO
+                                             0    29-JAN-17 METH / BY / # / DATE
+     0.0                                                    DAZI
+     0.0  17.0   1.0                                        ZEN1 / ZEN2 / DZEN
+     1                                                      # OF FREQUENCIES
+   G01                                                      START OF FREQUENCY
+    279.00      0.00   2319.50                              NORTH / EAST / UP
+   NOAZI   -0.80   -0.90   -0.90   -0.80   -0.40    0.20    0.80    1.30    1.40    1.20    0.70    0.00   -0.40   -0.70   -0.90   -0.90   -0.90   -0.90
+   G01                                                      END OF FREQUENCY
+                                                            END OF ANTENNA
+                                                            START OF ANTENNA
+TRM59800.00     NONE                                        TYPE / SERIAL NO
+ROBOT               Geo++ GmbH               1    16-APR-10 METH / BY / # / DATE
+     5.0                                                    DAZI
+     0.0  90.0   5.0                                        ZEN1 / ZEN2 / DZEN
+     2                                                      # OF FREQUENCIES
+   G01                                                      START OF FREQUENCY
+      1.31     -0.31     89.51                              NORTH / EAST / UP
+   NOAZI    0.00   -0.06   -0.24   -0.51   -0.84   -1.18   -1.46   -1.62   -1.60   -1.36   -0.88   -0.18    0.71    1.73    2.78    3.78    4.58    5.09    5.14
+   G01                                                      END OF FREQUENCY
+   G02                                                      START OF FREQUENCY
+      0.21      0.07    118.03                              NORTH / EAST / UP
+   NOAZI    0.00   -0.14   -0.50   -1.00   -1.52   -1.95   -2.17   -2.14   -1.86   -1.38   -0.76   -0.08    0.58    1.20    1.74    2.22    2.60    2.81    2.72
+   G02                                                      END OF FREQUENCY
+                                                            END OF ANTENNA
+";
 
     fn testbench(path: &str) {
         println!("Parsing model \"{}\"", path);
@@ -28,6 +89,57 @@ mod test {
         let _ = std::fs::remove_file(tmp_path);
     }
 
+    /// Formatting test on this embedded content, that does not require the data folder
+    fn embedded_testbench(content: &str) -> Rinex {
+        let model = Rinex::parse(&mut BufReader::new(content.as_bytes())).unwrap();
+
+        let mut w = BufWriter::new(Vec::new());
+        model.format(&mut w).unwrap();
+
+        let formatted = w.into_inner().unwrap();
+        let dut = Rinex::parse(&mut BufReader::new(formatted.as_slice())).unwrap();
+
+        generic_rinex_comparison(&dut, &model);
+        dut
+    }
+
+    #[test]
+    fn clocks_v2_embedded() {
+        let dut = embedded_testbench(CLOCK_V2);
+        let record = dut.record.as_clock().unwrap();
+        assert_eq!(record.len(), 2);
+        assert_eq!(
+            record
+                .values()
+                .map(|profiles| profiles.len())
+                .sum::<usize>(),
+            4
+        );
+    }
+
+    #[test]
+    fn clocks_v3_embedded() {
+        let dut = embedded_testbench(CLOCK_V3);
+        let record = dut.record.as_clock().unwrap();
+        assert_eq!(record.len(), 2);
+        assert_eq!(
+            record
+                .values()
+                .map(|profiles| profiles.len())
+                .sum::<usize>(),
+            5
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "antex")]
+    fn antex_v1_embedded() {
+        let dut = embedded_testbench(ANTEX_V1);
+        let record = dut.record.as_antex().unwrap();
+        assert_eq!(record.len(), 2);
+        assert_eq!(record[1].1.len(), 2);
+    }
+
     #[test]
     #[cfg(feature = "flate2")]
     fn obs_v2() {
@@ -123,7 +235,6 @@ mod test {
 
     #[test]
     #[cfg(feature = "flate2")]
-    #[ignore]
     fn clocks_v2() {
         let folder = env!("CARGO_MANIFEST_DIR").to_owned() + "/data/CLK/V2/";
         for file in std::fs::read_dir(folder).unwrap() {
@@ -133,6 +244,39 @@ mod test {
         }
     }
 
+    #[test]
+    #[cfg(feature = "flate2")]
+    fn clocks_v3() {
+        let folder = env!("CARGO_MANIFEST_DIR").to_owned() + "/data/CLK/V3/";
+        for file in std::fs::read_dir(folder).unwrap() {
+            let fp = file.unwrap();
+            let fp = fp.path();
+            testbench(fp.to_str().unwrap());
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "flate2", feature = "ionex"))]
+    fn ionex_v1() {
+        let folder = env!("CARGO_MANIFEST_DIR").to_owned() + "/data/IONEX/V1/";
+        for file in std::fs::read_dir(folder).unwrap() {
+            let fp = file.unwrap();
+            let fp = fp.path();
+            testbench(fp.to_str().unwrap());
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "flate2", feature = "antex"))]
+    fn antex_v1() {
+        let folder = env!("CARGO_MANIFEST_DIR").to_owned() + "/data/ATX/V1/";
+        for file in std::fs::read_dir(folder).unwrap() {
+            let fp = file.unwrap();
+            let fp = fp.path();
+            testbench(fp.to_str().unwrap());
+        }
+    }
+
     #[test]
     #[cfg(feature = "flate2")]
     fn nav_v2() {
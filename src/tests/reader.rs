@@ -0,0 +1,159 @@
+//! Streamed parsing tests
+use crate::{
+    observation::{EpochFlag, HeaderFields, ObsKey, Observations, SignalObservation},
+    prelude::*,
+};
+
+use std::{
+    collections::BTreeMap,
+    io::{BufReader, BufWriter},
+    str::FromStr,
+};
+
+/// Builds a small Observation RINEX
+pub fn obs_rinex() -> Rinex {
+    let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST").unwrap();
+    let dt = Duration::from_seconds(30.0);
+
+    let c1c = Observable::from_str("C1C").unwrap();
+    let l1c = Observable::from_str("L1C").unwrap();
+
+    let mut fields = HeaderFields::default().with_timeof_first_obs(t0);
+    fields
+        .codes
+        .insert(Constellation::GPS, vec![c1c.clone(), l1c.clone()]);
+    fields
+        .codes
+        .insert(Constellation::Galileo, vec![c1c.clone(), l1c.clone()]);
+
+    let header = Header::basic_obs().with_observation_fields(fields);
+
+    let mut record = BTreeMap::new();
+
+    for i in 0..10 {
+        let mut observations = Observations::default();
+        for (sv, offset) in [("G01", 1.0), ("G08", 2.0), ("E05", 3.0)] {
+            let sv = SV::from_str(sv).unwrap();
+            let value = 20_000_000.0 + offset * 1000.0 + i as f64 * 10.0;
+            observations
+                .signals
+                .push(SignalObservation::new(sv, c1c.clone(), value));
+            observations
+                .signals
+                .push(SignalObservation::new(sv, l1c.clone(), value / 0.19));
+        }
+
+        let key = ObsKey {
+            epoch: t0 + dt * i as f64,
+            flag: EpochFlag::Ok,
+        };
+
+        record.insert(key, observations);
+    }
+
+    Rinex::new(header, Record::ObsRecord(record))
+}
+
+/// Formats [Rinex] to bytes
+pub fn to_bytes(rinex: &Rinex) -> Vec<u8> {
+    let mut w = BufWriter::new(Vec::new());
+    rinex.format(&mut w).unwrap();
+    w.into_inner().unwrap()
+}
+
+/// Streams this content and verifies it matches [Rinex::parse]
+fn stream_obs_test(bytes: &[u8]) {
+    let mut reader = BufReader::new(bytes);
+    let model = Rinex::parse(&mut reader).unwrap();
+    let model = model.record.as_obs().unwrap();
+    assert_eq!(model.len(), 10);
+
+    let reader = Reader::new(bytes).unwrap();
+    assert!(reader.header().obs.is_some());
+
+    let mut nth = 0;
+    for (entry, (model_k, model_v)) in reader.zip(model.iter()) {
+        match entry.unwrap() {
+            RecordEntry::Obs(k, v) => {
+                assert_eq!(k, *model_k);
+                assert_eq!(v, *model_v);
+            },
+            entry => panic!("invalid entry {:?}", entry),
+        }
+        nth += 1;
+    }
+
+    assert_eq!(nth, 10);
+}
+
+#[test]
+fn obs_stream() {
+    let rinex = obs_rinex();
+    let bytes = to_bytes(&rinex);
+    stream_obs_test(&bytes);
+}
+
+#[test]
+fn crinex_stream() {
+    let rinex = obs_rinex().rnx2crnx();
+    let bytes = to_bytes(&rinex);
+
+    let reader = Reader::new(bytes.as_slice()).unwrap();
+    assert!(reader.header().is_crinex());
+
+    stream_obs_test(&bytes);
+}
+
+#[test]
+#[cfg(feature = "flate2")]
+fn gzip_stream() {
+    use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+    use std::io::Write;
+
+    let rinex = obs_rinex();
+    let bytes = to_bytes(&rinex);
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::new(5));
+    encoder.write_all(&bytes).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let reader = Reader::new(GzDecoder::new(compressed.as_slice())).unwrap();
+
+    let streamed = reader
+        .map(|entry| match entry.unwrap() {
+            RecordEntry::Obs(k, v) => (k, v),
+            entry => panic!("invalid entry {:?}", entry),
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let mut reader = BufReader::new(bytes.as_slice());
+    let model = Rinex::parse(&mut reader).unwrap();
+    assert_eq!(&streamed, model.record.as_obs().unwrap());
+}
+
+#[test]
+#[cfg(feature = "meteo")]
+fn meteo_stream() {
+    let content = "     3.01           METEOROLOGICAL DATA                     RINEX VERSION / TYPE
+     2    PR    TD                                          # / TYPES OF OBSERV
+                                                            END OF HEADER
+ 2020  6 25  0  0  0 1013.2   20.5
+ 2020  6 25  0  1  0 1014.2   21.5
+ 2020  6 25  0  2  0 1015.2   22.5
+";
+
+    let mut reader = BufReader::new(content.as_bytes());
+    let model = Rinex::parse(&mut reader).unwrap();
+    let model = model.record.as_meteo().unwrap();
+    assert_eq!(model.len(), 6);
+
+    let streamed = Reader::new(content.as_bytes())
+        .unwrap()
+        .map(|entry| match entry.unwrap() {
+            RecordEntry::Meteo(k, v) => (k, v),
+            entry => panic!("invalid entry {:?}", entry),
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    assert_eq!(&streamed, model);
+}
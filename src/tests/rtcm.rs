@@ -0,0 +1,635 @@
+//! RTCM streams interpretation
+use crate::{
+    hardware::{Antenna, Receiver},
+    navigation::{Ephemeris, NavMessageType},
+    observation::{LliFlags, SignalObservation, SNR},
+    prelude::*,
+    production::{Postponing, SnapshotMode},
+    tests::reader::to_bytes,
+};
+
+use rtcm_rs::{
+    msg::{
+        GloSigId, GpsSigId, Msg1019T, Msg1020T, Msg1042T, Msg1046T, Msg1077Sig, Msg1077T,
+        Msg1087Sig, Msg1087T, Msm57GloSat, Msm57Sat,
+    },
+    next_msg_frame,
+    util::DataVec,
+    Message, MessageBuilder,
+};
+
+use std::{io::BufReader, str::FromStr};
+
+/// GPS time of week of 2024-06-10T12:00:00 GPST (monday)
+const TOW_MS: u32 = 129_600_000;
+
+/// GLONASS time of day (Moscow time) of 2024-06-10T12:00:00 GPST
+const TOD_MS: u32 = 53_982_000;
+
+fn t_ref() -> Epoch {
+    Epoch::from_str("2024-06-10T12:00:00 GPST").unwrap()
+}
+
+fn encode(msg: &Message) -> Vec<u8> {
+    let mut builder = MessageBuilder::new();
+    builder.build_message(msg).unwrap().to_vec()
+}
+
+/// GPS MSM7 with G05 (L1C and L2W) and G12 (L1C)
+fn gps_msm7(tow_ms: u32, lock_time: u16) -> Vec<u8> {
+    let mut msg = Msg1077T {
+        gps_epoch_time_ms: tow_ms,
+        ..Default::default()
+    };
+
+    let mut satellites = DataVec::new();
+    for prn in [5, 12] {
+        satellites.push(Msm57Sat {
+            satellite_id: prn,
+            gnss_satellite_rough_range_integer_ms: Some(70 + prn),
+            gnss_satellite_rough_range_mod1ms_ms: 0.5,
+            gnss_satellite_rough_phaserange_rates_m_s: Some(-100),
+            ..Default::default()
+        });
+    }
+
+    let mut signals = DataVec::new();
+    for (prn, band, attr) in [(5, 1, 'C'), (5, 2, 'W'), (12, 1, 'C')] {
+        signals.push(Msg1077Sig {
+            satellite_id: prn,
+            signal_id: GpsSigId::new(band, attr),
+            gnss_signal_fine_pseudorange_ext_ms: Some(1.0E-4),
+            gnss_signal_fine_phaserange_ext_ms: Some(2.0E-4),
+            gnss_phaserange_lock_time_ext_ind: lock_time,
+            half_cycle_ambiguity_ind: 0,
+            gnss_signal_cnr_ext_dbhz: Some(45.0),
+            gnss_signal_fine_phaserange_rate_m_s: Some(0.5),
+        });
+    }
+
+    msg.data_segment.satellite_data = satellites;
+    msg.data_segment.signal_data = signals;
+    encode(&Message::Msg1077(msg))
+}
+
+/// Glonass MSM7 with R07 (channel -2) on L1C
+fn glo_msm7(tod_ms: u32) -> Vec<u8> {
+    let mut msg = Msg1087T {
+        glo_day_of_week: Some(1),
+        glo_epoch_time_ms: tod_ms,
+        ..Default::default()
+    };
+
+    let mut satellites = DataVec::new();
+    satellites.push(Msm57GloSat {
+        satellite_id: 7,
+        gnss_satellite_rough_range_integer_ms: Some(68),
+        glonass_satellite_frequency_channel_number: Some(-2),
+        gnss_satellite_rough_range_mod1ms_ms: 0.25,
+        gnss_satellite_rough_phaserange_rates_m_s: Some(200),
+    });
+
+    let mut signals = DataVec::new();
+    signals.push(Msg1087Sig {
+        satellite_id: 7,
+        signal_id: GloSigId::new(1, 'C'),
+        gnss_signal_fine_pseudorange_ext_ms: Some(-1.0E-4),
+        gnss_signal_fine_phaserange_ext_ms: Some(-2.0E-4),
+        gnss_phaserange_lock_time_ext_ind: 100,
+        half_cycle_ambiguity_ind: 0,
+        gnss_signal_cnr_ext_dbhz: Some(40.0),
+        gnss_signal_fine_phaserange_rate_m_s: Some(0.0),
+    });
+
+    msg.data_segment.satellite_data = satellites;
+    msg.data_segment.signal_data = signals;
+    encode(&Message::Msg1087(msg))
+}
+
+/// Ephemerides of G05, E11, R07 and C30, at 2024-06-10T12:00:00
+fn ephemerides() -> Vec<u8> {
+    let mut stream = encode(&Message::Msg1019(Msg1019T {
+        gps_satellite_id: 5,
+        gps_week_number: 2318 % 1024,
+        ura_index: 2,
+        iode: 10,
+        iodc: 10,
+        toc_s: 129_600.0,
+        toe_s: 129_600.0,
+        af0_s: 1.0E-4,
+        m0_sc: 0.5,
+        eccentricity: 0.01,
+        sqrt_a_sqrt_m: 5153.5,
+        ..Default::default()
+    }));
+
+    stream.extend(encode(&Message::Msg1046(Msg1046T {
+        gal_satellite_id: 11,
+        gal_week_number: 2318 - 1024,
+        iodnav: 20,
+        sisa_e1_e5b_index: 107,
+        toc_s: 129_600.0,
+        toe_s: 129_600.0,
+        af0_s: -2.0E-4,
+        i0_sc: 0.3,
+        sqrt_a_sqrt_m: 5440.5,
+        e5b_sig_health_ind: 1,
+        ..Default::default()
+    })));
+
+    stream.extend(encode(&Message::Msg1020(Msg1020T {
+        glo_satellite_id: 7,
+        glo_satellite_freq_chan_number: -2,
+        tk_h: 14,
+        tk_min: 59,
+        tk_s: 30,
+        // 15:00 Moscow time
+        tb_min: 900,
+        xn_km: 10_000.0,
+        yn_first_deriv_km_s: 2.5,
+        zn_km: -15_000.0,
+        tau_n_s: 1.0E-5,
+        en_d: 1,
+        glo_m_n4_year: 2024,
+        ..Default::default()
+    })));
+
+    stream.extend(encode(&Message::Msg1042(Msg1042T {
+        bds_satellite_id: 30,
+        bds_week_number: 2318 - 1356,
+        aode: 1,
+        aodc: 1,
+        toc_s: 129_600.0,
+        toe_s: 129_600.0,
+        tgd1_s: 1.0E-9,
+        sqrt_a_sqrt_m: 5282.5,
+        ..Default::default()
+    })));
+
+    stream
+}
+
+/// Decodes all messages contained in this stream
+fn decode(stream: &[u8]) -> Vec<Message> {
+    let mut messages = Vec::new();
+    let mut offset = 0;
+    while offset < stream.len() {
+        let (consumed, frame) = next_msg_frame(&stream[offset..]);
+        match frame {
+            Some(frame) => messages.push(frame.get_message()),
+            None => break,
+        }
+        offset += consumed;
+    }
+    messages
+}
+
+fn ephemeris(rinex: &Rinex, sv: SV) -> (Epoch, NavMessageType, Ephemeris) {
+    rinex
+        .nav_ephemeris_frames_iter()
+        .find_map(|(k, eph)| {
+            if k.sv == sv {
+                Some((k.epoch, k.msgtype, eph.clone()))
+            } else {
+                None
+            }
+        })
+        .unwrap_or_else(|| panic!("missing {} ephemeris", sv))
+}
+
+fn observation(rinex: &Rinex, t: Epoch, sv: SV, observable: &str) -> SignalObservation {
+    let observable = Observable::from_str(observable).unwrap();
+    rinex
+        .signal_observations_iter()
+        .find_map(|(k, sig)| {
+            if k.epoch == t && sig.sv == sv && sig.observable == observable {
+                Some(sig.clone())
+            } else {
+                None
+            }
+        })
+        .unwrap_or_else(|| panic!("missing {}({}) @{}", sv, observable, t))
+}
+
+#[test]
+fn rtcm2rnx_msm7() {
+    let t0 = t_ref();
+    let t1 = t0 + Duration::from_seconds(1.0);
+
+    let mut stream = gps_msm7(TOW_MS, 100);
+    stream.extend(glo_msm7(TOD_MS));
+    stream.extend(gps_msm7(TOW_MS + 1000, 10));
+
+    let rtcm2rnx = RTCM2RNX::new_system_time(
+        false,
+        t0 - Duration::from_days(2.0),
+        SnapshotMode::DailyMidnight,
+        Postponing::None,
+        stream.as_slice(),
+    );
+
+    let snapshots = rtcm2rnx.collect::<Vec<_>>();
+    assert_eq!(snapshots.len(), 1);
+
+    let rinex = &snapshots[0];
+    let record = rinex.record.as_obs().unwrap();
+    assert_eq!(record.len(), 2);
+    assert_eq!(rinex.first_epoch(), Some(t0));
+    assert_eq!(rinex.last_epoch(), Some(t1));
+
+    let g05 = SV::from_str("G05").unwrap();
+    let r07 = SV::from_str("R07").unwrap();
+
+    let obs = rinex.header.obs.as_ref().unwrap();
+    assert_eq!(obs.timeof_first_obs, Some(t0));
+    assert_eq!(obs.codes[&Constellation::GPS].len(), 8);
+    assert_eq!(obs.codes[&Constellation::Glonass].len(), 4);
+    assert_eq!(rinex.header.glo_channels.get(&r07), Some(&-2));
+
+    // pseudo range
+    let c1c = observation(rinex, t0, g05, "C1C");
+    assert!((c1c.value - 75.5001 * 299_792.458).abs() < 1.0E-3);
+    assert_eq!(c1c.snr, Some(SNR::from(45.0)));
+
+    // phase range, in cycles
+    let l1 = Carrier::L1.wavelength();
+    let l1c = observation(rinex, t0, g05, "L1C");
+    assert!((l1c.value - 75.5002 * 299_792.458 / l1).abs() < 1.0E-2);
+    assert_eq!(l1c.lli, Some(LliFlags::OK_OR_UNKNOWN));
+
+    // lock time decreased: loss of lock
+    let l1c = observation(rinex, t1, g05, "L1C");
+    assert_eq!(l1c.lli, Some(LliFlags::LOCK_LOSS));
+
+    // doppler
+    let d1c = observation(rinex, t0, g05, "D1C");
+    assert!((d1c.value - 99.5 / l1).abs() < 1.0E-3);
+
+    let s2w = observation(rinex, t0, g05, "S2W");
+    assert_eq!(s2w.value, 45.0);
+
+    // glonass FDMA
+    let g1 = Carrier::G1(Some(-2)).wavelength();
+    let l1c = observation(rinex, t0, r07, "L1C");
+    assert!((l1c.value - 68.2498 * 299_792.458 / g1).abs() < 1.0E-2);
+
+    // formatted and parsed back
+    let bytes = to_bytes(rinex);
+    let mut reader = BufReader::new(bytes.as_slice());
+    let parsed = Rinex::parse(&mut reader).unwrap();
+    assert_eq!(parsed.record.as_obs().unwrap().len(), 2);
+    assert_eq!(parsed.signal_observations_iter().count(), 28);
+}
+
+#[test]
+fn rtcm2rnx_snapshots() {
+    let t0 = t_ref();
+
+    let mut stream = gps_msm7(TOW_MS - 1000, 100);
+    stream.extend(gps_msm7(TOW_MS, 100));
+    stream.extend(gps_msm7(TOW_MS + 1000, 100));
+
+    // hourly snapshot
+    let rtcm2rnx = RTCM2RNX::new_system_time(
+        true,
+        t0,
+        SnapshotMode::Hourly,
+        Postponing::None,
+        stream.as_slice(),
+    );
+
+    let snapshots = rtcm2rnx.collect::<Vec<_>>();
+    assert_eq!(snapshots.len(), 2);
+    assert!(snapshots[0].header.is_crinex());
+    assert_eq!(snapshots[0].record.as_obs().unwrap().len(), 1);
+    assert_eq!(snapshots[1].first_epoch(), Some(t0));
+    assert_eq!(snapshots[1].record.as_obs().unwrap().len(), 2);
+
+    // postponed deployment
+    let rtcm2rnx = RTCM2RNX::new_system_time(
+        false,
+        t0,
+        SnapshotMode::Hourly,
+        Postponing::Messages(2),
+        stream.as_slice(),
+    );
+
+    let snapshots = rtcm2rnx.collect::<Vec<_>>();
+    assert_eq!(snapshots.len(), 1);
+    assert_eq!(
+        snapshots[0].first_epoch(),
+        Some(t0 + Duration::from_seconds(1.0))
+    );
+}
+
+#[test]
+fn rtcm2rnx_ephemeris() {
+    let t0 = t_ref();
+
+    let mut stream = gps_msm7(TOW_MS, 100);
+    stream.extend(ephemerides());
+
+    let rtcm2rnx = RTCM2RNX::new_system_time(
+        false,
+        t0 - Duration::from_days(2.0),
+        SnapshotMode::DailyMidnight,
+        Postponing::None,
+        stream.as_slice(),
+    );
+
+    let snapshots = rtcm2rnx.collect::<Vec<_>>();
+    assert_eq!(snapshots.len(), 2);
+    assert!(snapshots[0].is_observation_rinex());
+
+    let rinex = &snapshots[1];
+    assert!(rinex.is_navigation_rinex());
+    assert_eq!(rinex.header.version, Version::new(4, 0));
+    assert_eq!(rinex.nav_ephemeris_frames_iter().count(), 4);
+
+    let g05 = SV::from_str("G05").unwrap();
+    let e11 = SV::from_str("E11").unwrap();
+    let r07 = SV::from_str("R07").unwrap();
+    let c30 = SV::from_str("C30").unwrap();
+
+    let (toc, msgtype, eph) = ephemeris(rinex, g05);
+    assert_eq!(toc, t0);
+    assert_eq!(msgtype, NavMessageType::LNAV);
+    assert!((eph.clock_bias - 1.0E-4).abs() < 1.0E-9);
+    assert_eq!(eph.get_orbit_f64("iode"), Some(10.0));
+    assert_eq!(eph.get_orbit_f64("accuracy"), Some(4.85));
+    assert!((eph.get_orbit_f64("m0").unwrap() - 0.5 * std::f64::consts::PI).abs() < 1.0E-9);
+    assert_eq!(eph.toe(g05), Some(t0));
+    assert!(eph.sv_healthy());
+
+    let (toc, msgtype, eph) = ephemeris(rinex, e11);
+    assert_eq!(toc, Epoch::from_str("2024-06-10T12:00:00 GST").unwrap());
+    assert_eq!(msgtype, NavMessageType::INAV);
+    assert_eq!(eph.get_orbit_f64("week"), Some(2318.0));
+    assert!((eph.get_orbit_f64("sisa").unwrap() - 3.12).abs() < 1.0E-9);
+    assert_eq!(eph.get_orbit_f64("health"), Some(128.0));
+
+    let (toc, msgtype, eph) = ephemeris(rinex, r07);
+    assert_eq!(toc, Epoch::from_str("2024-06-10T12:00:00 UTC").unwrap());
+    assert_eq!(msgtype, NavMessageType::FDMA);
+    assert!((eph.clock_bias + 1.0E-5).abs() < 1.0E-9);
+    assert_eq!(eph.glonass_freq_channel(), Some(-2));
+    assert_eq!(eph.get_orbit_f64("satPosX"), Some(10_000.0));
+    assert_eq!(eph.get_orbit_f64("satPosZ"), Some(-15_000.0));
+    // message frame time: monday, 11:59:30 UTC
+    assert_eq!(eph.clock_drift_rate, 86_400.0 + 43_170.0);
+
+    let (toc, msgtype, eph) = ephemeris(rinex, c30);
+    assert_eq!(toc, Epoch::from_str("2024-06-10T12:00:00 BDT").unwrap());
+    assert_eq!(msgtype, NavMessageType::D1);
+    assert_eq!(eph.get_orbit_f64("week"), Some(962.0));
+    assert!((eph.get_orbit_f64("tgdb1b3").unwrap() - 1.0E-9).abs() < 1.0E-12);
+
+    // formatted and parsed back
+    let bytes = to_bytes(rinex);
+    let mut reader = BufReader::new(bytes.as_slice());
+    let parsed = Rinex::parse(&mut reader).unwrap();
+    assert_eq!(parsed.nav_ephemeris_frames_iter().count(), 4);
+
+    for (key, eph) in rinex.nav_ephemeris_frames_iter() {
+        let (toc, msgtype, parsed) = ephemeris(&parsed, key.sv);
+        assert_eq!(toc, key.epoch);
+        assert_eq!(msgtype, key.msgtype);
+        assert_eq!(parsed.get_orbit_f64("sqrta"), eph.get_orbit_f64("sqrta"));
+    }
+
+    // RINEX3 production
+    let rtcm2rnx = RTCM2RNX::new_system_time(
+        false,
+        t0,
+        SnapshotMode::DailyMidnight,
+        Postponing::None,
+        stream.as_slice(),
+    )
+    .with_nav_version(Version::new(3, 5));
+
+    let snapshots = rtcm2rnx.collect::<Vec<_>>();
+    let rinex = &snapshots[1];
+    assert_eq!(rinex.header.version, Version::new(3, 5));
+    assert!(rinex
+        .nav_ephemeris_frames_iter()
+        .all(|(k, _)| k.msgtype == NavMessageType::LNAV));
+
+    let (_, _, eph) = ephemeris(rinex, c30);
+    assert!((eph.get_orbit_f64("tgd1b1b3").unwrap() - 1.0E-9).abs() < 1.0E-12);
+
+    let bytes = to_bytes(rinex);
+    let mut reader = BufReader::new(bytes.as_slice());
+    let parsed = Rinex::parse(&mut reader).unwrap();
+    assert_eq!(parsed.nav_ephemeris_frames_iter().count(), 4);
+    assert_eq!(ephemeris(&parsed, r07).2.glonass_freq_channel(), Some(-2));
+}
+
+#[test]
+fn rnx2rtcm_msm7() {
+    let t0 = t_ref();
+    let t1 = t0 + Duration::from_seconds(1.0);
+
+    let mut stream = gps_msm7(TOW_MS, 100);
+    stream.extend(glo_msm7(TOD_MS));
+    stream.extend(gps_msm7(TOW_MS + 1000, 10));
+
+    let mut snapshots = RTCM2RNX::new_system_time(
+        false,
+        t0,
+        SnapshotMode::DailyMidnight,
+        Postponing::None,
+        stream.as_slice(),
+    )
+    .collect::<Vec<_>>();
+
+    let mut rinex = snapshots.remove(0);
+    rinex.header.rx_position = Some((4_027_881.8, 306_998.6, 4_919_499.0));
+    rinex.header.rcvr = Some(
+        Receiver::default()
+            .with_model("SEPT POLARX5")
+            .with_serial_number("3001376")
+            .with_firmware("5.3.2"),
+    );
+    rinex.header.rcvr_antenna = Some(
+        Antenna::default()
+            .with_model("TRM59800.00     NONE")
+            .with_serial_number("5000118436")
+            .with_height(0.25)
+            .with_eastern_component(0.1),
+    );
+
+    let mut rnx2rtcm = rinex.rnx2rtcm(1234).unwrap();
+    let mut encoded = Vec::new();
+    let size = rnx2rtcm.write_all(&mut encoded).unwrap();
+    assert_eq!(size, encoded.len());
+    assert_eq!(rnx2rtcm.epoch(), Some(t1));
+
+    // 1006, 1033, then GPS + GLO MSM7 at t0 and GPS MSM7 at t1
+    let messages = decode(&encoded);
+    assert_eq!(messages.len(), 5);
+
+    match &messages[0] {
+        Message::Msg1006(msg) => {
+            assert_eq!(msg.reference_station_id, 1234);
+
+            // ARP = marker + eccentricities (local east and up unit vectors,
+            // at geodetic latitude 50.7981°N and longitude 4.3586°E)
+            let (sin_lat, cos_lat) = 50.7981_f64.to_radians().sin_cos();
+            let (sin_long, cos_long) = 4.3586_f64.to_radians().sin_cos();
+
+            let arp = (
+                4_027_881.8 - 0.1 * sin_long + 0.25 * cos_lat * cos_long,
+                306_998.6 + 0.1 * cos_long + 0.25 * cos_lat * sin_long,
+                4_919_499.0 + 0.25 * sin_lat,
+            );
+
+            assert!((msg.antenna_ref_point_ecef_x_m - arp.0).abs() < 1.0E-3);
+            assert!((msg.antenna_ref_point_ecef_y_m - arp.1).abs() < 1.0E-3);
+            assert!((msg.antenna_ref_point_ecef_z_m - arp.2).abs() < 1.0E-3);
+
+            // antenna height above marker
+            assert!((msg.antenna_height_m - 0.25).abs() < 1.0E-3);
+        },
+        msg => panic!("unexpected message {:?}", msg),
+    }
+
+    match &messages[1] {
+        Message::Msg1033(msg) => {
+            assert_eq!(
+                msg.antenna_descriptor_str.chars().collect::<String>(),
+                "TRM59800.00     NONE"
+            );
+            assert_eq!(
+                msg.receiver_type_descriptor_str.chars().collect::<String>(),
+                "SEPT POLARX5"
+            );
+            assert_eq!(
+                msg.receiver_firmware_version_str
+                    .chars()
+                    .collect::<String>(),
+                "5.3.2"
+            );
+        },
+        msg => panic!("unexpected message {:?}", msg),
+    }
+
+    match (&messages[2], &messages[3], &messages[4]) {
+        (Message::Msg1077(gps), Message::Msg1087(glo), Message::Msg1077(last)) => {
+            assert_eq!(gps.reference_station_id, 1234);
+            assert_eq!(gps.gps_epoch_time_ms, TOW_MS);
+            assert_eq!(gps.msm_multiple_message_flag, 1);
+            assert_eq!(glo.glo_epoch_time_ms, TOD_MS);
+            assert_eq!(glo.msm_multiple_message_flag, 0);
+            assert_eq!(last.gps_epoch_time_ms, TOW_MS + 1000);
+            assert_eq!(last.msm_multiple_message_flag, 0);
+        },
+        msgs => panic!("unexpected messages {:?}", msgs),
+    }
+
+    // station messages are optional
+    let mut rnx2rtcm = rinex.rnx2rtcm(1234).unwrap();
+    rnx2rtcm.skip_header = true;
+    assert_eq!(rnx2rtcm.count(), 3);
+
+    // replayed
+    let mut snapshots = RTCM2RNX::new_system_time(
+        false,
+        t0,
+        SnapshotMode::DailyMidnight,
+        Postponing::None,
+        encoded.as_slice(),
+    )
+    .collect::<Vec<_>>();
+
+    let replayed = snapshots.remove(0);
+    assert_eq!(
+        replayed.signal_observations_iter().count(),
+        rinex.signal_observations_iter().count()
+    );
+
+    for (k, sig) in rinex.signal_observations_iter() {
+        let replayed = observation(&replayed, k.epoch, sig.sv, &sig.observable.to_string());
+        assert!(
+            (replayed.value - sig.value).abs() < 1.0E-2,
+            "{}({}) @{}: {} != {}",
+            sig.sv,
+            sig.observable,
+            k.epoch,
+            replayed.value,
+            sig.value
+        );
+        assert_eq!(replayed.snr, sig.snr);
+    }
+
+    let g05 = SV::from_str("G05").unwrap();
+    let l1c = observation(&replayed, t1, g05, "L1C");
+    assert_eq!(l1c.lli, Some(LliFlags::LOCK_LOSS));
+}
+
+#[test]
+fn rnx2rtcm_ephemeris() {
+    let t0 = t_ref();
+
+    let mut stream = gps_msm7(TOW_MS, 100);
+    stream.extend(ephemerides());
+
+    for version in [Version::new(4, 0), Version::new(3, 5)] {
+        let snapshots = RTCM2RNX::new_system_time(
+            false,
+            t0,
+            SnapshotMode::DailyMidnight,
+            Postponing::None,
+            stream.as_slice(),
+        )
+        .with_nav_version(version)
+        .collect::<Vec<_>>();
+
+        let rinex = &snapshots[1];
+
+        // no station messages in NAV
+        let mut rnx2rtcm = rinex.rnx2rtcm(0).unwrap();
+        let mut encoded = Vec::new();
+        rnx2rtcm.write_all(&mut encoded).unwrap();
+        assert_eq!(decode(&encoded).len(), 4);
+
+        // replayed (along with observations, that define the snapshot)
+        let mut replay = gps_msm7(TOW_MS, 100);
+        replay.extend(encoded);
+
+        let snapshots = RTCM2RNX::new_system_time(
+            false,
+            t0,
+            SnapshotMode::DailyMidnight,
+            Postponing::None,
+            replay.as_slice(),
+        )
+        .with_nav_version(version)
+        .collect::<Vec<_>>();
+
+        let replayed = &snapshots[1];
+        assert_eq!(replayed.nav_ephemeris_frames_iter().count(), 4);
+
+        for (key, eph) in rinex.nav_ephemeris_frames_iter() {
+            let (toc, msgtype, replayed) = ephemeris(replayed, key.sv);
+            assert_eq!(toc, key.epoch, "{} toc", key.sv);
+            assert_eq!(msgtype, key.msgtype, "{} msgtype", key.sv);
+            assert!((replayed.clock_bias - eph.clock_bias).abs() < 1.0E-12);
+            assert_eq!(replayed.clock_drift_rate, eph.clock_drift_rate);
+
+            for (field, value) in eph.orbits.iter() {
+                let value = value.as_f64();
+                let replayed = replayed
+                    .get_orbit_f64(field)
+                    .unwrap_or_else(|| panic!("{} missing {}", key.sv, field));
+                assert!(
+                    (replayed - value).abs() <= 1.0E-6 * value.abs().max(1.0),
+                    "{}: {} {} != {}",
+                    key.sv,
+                    field,
+                    replayed,
+                    value
+                );
+            }
+        }
+    }
+}
@@ -18,6 +18,35 @@ pub struct TecPoint<'a> {
     pub rms: Option<f64>,
 }
 
+/// [Rinex] against [Rinex] model verification
+pub fn generic_comparison(dut: &Rinex, model: &Rinex) {
+    let rec_dut = dut
+        .record
+        .as_ionex()
+        .expect("failed to unwrap rinex record");
+
+    let rec_model = model
+        .record
+        .as_ionex()
+        .expect("failed to unwrap rinex record");
+
+    for (k, v) in rec_model.iter() {
+        if let Some(dut_v) = rec_dut.get(k) {
+            assert_eq!(v, dut_v, "invalid tec value at {:?}", k);
+        } else {
+            panic!("missing entry at {:?}", k);
+        }
+    }
+
+    for (k, _) in rec_dut.iter() {
+        assert!(
+            rec_model.get(k).is_some(),
+            "found unexpected content: {:?}",
+            k
+        );
+    }
+}
+
 /// Basic tests for Observation [Rinex]
 fn basic_header_tests(
     dut: &Header,
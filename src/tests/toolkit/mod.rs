@@ -15,7 +15,7 @@ pub use observation::{
 mod ionex;
 
 #[cfg(feature = "ionex")]
-pub use ionex::{generic_ionex_test, TecPoint};
+pub use ionex::{generic_comparison as generic_ionex_comparison, generic_ionex_test, TecPoint};
 
 // NAV RINEX dedicated tools
 #[cfg(feature = "nav")]
@@ -237,13 +237,20 @@ pub fn generic_rinex_comparison(dut: &Rinex, model: &Rinex) {
     } else if dut.is_navigation_rinex() && model.is_navigation_rinex() {
         #[cfg(feature = "nav")]
         generic_navigation_comparison(&dut, &model);
+    } else if dut.is_clock_rinex() && model.is_clock_rinex() {
+        generic_clock_comparison(dut, model);
+    } else if dut.is_ionex() && model.is_ionex() {
+        #[cfg(feature = "ionex")]
+        generic_ionex_comparison(dut, model);
+    } else if dut.is_antex() && model.is_antex() {
+        assert_eq!(dut.record.as_antex(), model.record.as_antex());
     }
 }
 
 /*
  * CLOCK Rinex thorough comparison
  */
-fn clocks_against_model(dut: &Rinex, model: &Rinex, filename: &str, _epsilon: f64) {
+fn generic_clock_comparison(dut: &Rinex, model: &Rinex) {
     let rec_dut = dut
         .record
         .as_clock()
@@ -252,19 +259,18 @@ fn clocks_against_model(dut: &Rinex, model: &Rinex, filename: &str, _epsilon: f6
         .record
         .as_clock()
         .expect("failed to unwrap rinex record");
-    for (e_model, model_types) in rec_model.iter() {
-        if let Some(dut_types) = rec_dut.get(e_model) {
-            for (model_data, _model_systems) in model_types.iter() {
-                if let Some(_systems) = dut_types.get(model_data) {
+    for (e_model, model_profiles) in rec_model.iter() {
+        if let Some(dut_profiles) = rec_dut.get(e_model) {
+            for (key, model_profile) in model_profiles.iter() {
+                if let Some(dut_profile) = dut_profiles.get(key) {
+                    assert_eq!(dut_profile, model_profile, "{:?} - {:?}", e_model, key);
                 } else {
-                    panic!(
-                        "\"{}\" - {:?} - missing data {:?}",
-                        filename, e_model, model_data
-                    );
+                    panic!("{:?} - missing data {:?}", e_model, key);
                 }
             }
         } else {
-            panic!("\"{}\" - missing epoch {:?}", filename, e_model);
+            panic!("missing epoch {:?}", e_model);
         }
     }
+    assert_eq!(rec_dut.len(), rec_model.len(), "found unexpected epochs");
 }
@@ -313,7 +313,7 @@ pub fn generic_observation_epoch_decoding_test(
     // PARSE
     let mut obs = Observations::default();
 
-    let key = parse_epoch(&header, content, ts, &mut obs).unwrap();
+    let key = parse_epoch(&header, content, ts, None, &mut obs).unwrap();
 
     assert_eq!(key.epoch, key_epoch);
     assert_eq!(key.flag, key_flag);
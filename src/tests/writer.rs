@@ -0,0 +1,187 @@
+//! Streamed formatting tests
+use crate::{
+    prelude::*,
+    tests::{
+        formatting::nav::NAV_V4,
+        reader::{obs_rinex, to_bytes},
+    },
+};
+
+use std::io::BufReader;
+
+#[cfg(feature = "nav")]
+use crate::navigation::{Ephemeris, NavFrame, NavFrameType, NavKey, NavMessageType};
+
+#[cfg(feature = "nav")]
+use std::str::FromStr;
+
+/// Streams all [RecordEntry]s of this content through [Writer]
+fn stream(content: &[u8]) -> Vec<u8> {
+    let reader = Reader::new(content).unwrap();
+    let mut writer = Writer::new(Vec::new(), reader.header()).unwrap();
+
+    for entry in reader {
+        writer.write(&entry.unwrap()).unwrap();
+    }
+
+    writer.finish().unwrap()
+}
+
+/// Parses this content
+fn parse(content: &[u8]) -> Rinex {
+    let mut reader = BufReader::new(content);
+    Rinex::parse(&mut reader).unwrap()
+}
+
+#[test]
+fn obs_writer() {
+    let rinex = obs_rinex();
+    let bytes = to_bytes(&rinex);
+
+    // streamed content is identical to formatted content
+    let streamed = stream(&bytes);
+    assert_eq!(streamed, bytes);
+
+    let dut = parse(&streamed);
+    assert_eq!(dut.record, parse(&bytes).record);
+}
+
+#[test]
+fn crinex_writer() {
+    let rinex = obs_rinex().rnx2crnx();
+    let bytes = to_bytes(&rinex);
+
+    let streamed = stream(&bytes);
+    assert_eq!(streamed, bytes);
+
+    let dut = parse(&streamed);
+    assert!(dut.header.is_crinex());
+    assert_eq!(dut.record, parse(&bytes).record);
+}
+
+#[test]
+#[cfg(feature = "meteo")]
+fn meteo_writer() {
+    let content = "     3.01           METEOROLOGICAL DATA                     RINEX VERSION / TYPE
+     2    PR    TD                                          # / TYPES OF OBSERV
+                                                            END OF HEADER
+ 2020  6 25  0  0  0 1013.2   20.5
+ 2020  6 25  0  1  0 1014.2   21.5
+ 2020  6 25  0  2  0 1015.2   22.5
+";
+
+    let model = parse(content.as_bytes());
+
+    let streamed = stream(content.as_bytes());
+    let dut = parse(&streamed);
+
+    assert_eq!(dut.record.as_meteo().unwrap().len(), 6);
+    assert_eq!(dut.record, model.record);
+}
+
+#[test]
+#[cfg(feature = "nav")]
+fn nav_writer() {
+    let model = parse(NAV_V4.as_bytes());
+
+    let streamed = stream(NAV_V4.as_bytes());
+    let dut = parse(&streamed);
+
+    assert_eq!(dut.record.as_nav().unwrap().len(), 5);
+    assert_eq!(dut.record, model.record);
+}
+
+#[test]
+#[cfg(feature = "nav")]
+fn nav_v3_writer() {
+    let mut model = parse(NAV_V4.as_bytes());
+    model.header.version = Version::new(3, 5);
+
+    let frames = model
+        .record
+        .as_nav()
+        .unwrap()
+        .iter()
+        .map(|(k, v)| RecordEntry::Nav(*k, v.clone()))
+        .collect::<Vec<_>>();
+
+    // system time offsets and ionosphere models are latched into the header
+    let mut writer = Writer::new(Vec::new(), &model.header).unwrap();
+
+    for entry in frames.iter() {
+        writer.write(entry).unwrap();
+    }
+
+    let streamed = writer.finish().unwrap();
+    assert_eq!(streamed, to_bytes(&model));
+
+    let dut = parse(&streamed);
+    assert_eq!(dut.header.nav.unwrap().time_offsets.len(), 1);
+    for constellation in [Constellation::GPS, Constellation::Galileo] {
+        assert!(dut.header.ionod_corrections.contains_key(&constellation));
+    }
+
+    // once the header has been formatted, they may no longer be described
+    let sto = frames
+        .iter()
+        .find(|entry| matches!(entry, RecordEntry::Nav(_, NavFrame::STO(_))))
+        .unwrap();
+
+    let eph = RecordEntry::Nav(
+        NavKey {
+            epoch: Epoch::from_str("2022-06-10T20:00:00 GPST").unwrap(),
+            sv: SV::from_str("G01").unwrap(),
+            msgtype: NavMessageType::LNAV,
+            frmtype: NavFrameType::Ephemeris,
+        },
+        NavFrame::EPH(Ephemeris::default()),
+    );
+
+    let mut writer = Writer::new(Vec::new(), &model.header).unwrap();
+    writer.write(&eph).unwrap();
+
+    assert!(matches!(
+        writer.write(sto),
+        Err(FormattingError::NavHeaderAlreadyFormatted)
+    ));
+}
+
+#[test]
+#[cfg(feature = "flate2")]
+fn gzip_writer() {
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    let rinex = obs_rinex();
+    let bytes = to_bytes(&rinex);
+
+    let reader = Reader::new(&bytes[..]).unwrap();
+    let mut writer = Writer::new_gzip(Vec::new(), reader.header(), 6).unwrap();
+
+    for entry in reader {
+        writer.write(&entry.unwrap()).unwrap();
+    }
+
+    // gzip stream is complete
+    let compressed = writer.finish().unwrap();
+
+    let mut decompressed = Vec::new();
+    GzDecoder::new(&compressed.get_ref()[..])
+        .read_to_end(&mut decompressed)
+        .unwrap();
+
+    assert_eq!(decompressed, bytes);
+}
+
+#[test]
+fn writer_type_mismatch() {
+    let rinex = obs_rinex();
+    let mut writer = Writer::new(Vec::new(), &rinex.header).unwrap();
+
+    let key = MeteoKey {
+        epoch: Epoch::default(),
+        observable: Observable::Pressure,
+    };
+
+    assert!(writer.write(&RecordEntry::Meteo(key, 1013.0)).is_err());
+}
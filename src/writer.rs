@@ -1,50 +1,272 @@
-//! Generic Buffered Writer, for efficient record production,
-//! with integrated optionnal .gz compression
-use std::io::{BufWriter, Error, Write}; // Seek, SeekFrom};
+//! Streamed RINEX formatting, that does not require the whole record in memory.
+use crate::{
+    hatanaka::Compressor,
+    meteo::{format_epoch as format_meteo_epoch, Record as MeteoRecord},
+    navigation::{format_frame as format_nav_frame, v2v3_latch_frame, NavFrame},
+    prelude::{Epoch, FormattingError, Header, RinexType},
+    record::RecordEntry,
+};
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
 
 #[cfg(feature = "flate2")]
 use flate2::{write::GzEncoder, Compression};
 
-/// [BufferedWriter] is an Output abstraction to propose seamless
-/// Gzip, Readable and Hatanaka compressed content streaming.
-#[derive(Debug)]
-pub enum BufferedWriter<W: Write> {
-    /// Readable stream
-    Plain(BufWriter<W>),
-    /// Gzip compressed stream (non readable)
-    #[cfg(feature = "flate2")]
-    Gz(BufWriter<GzEncoder<W>>),
+/// [Writer] formats the [Header] section once, then accepts the
+/// record one [RecordEntry] at a time, which must be presented in chronological order.
+/// This is the mirror operation of [crate::reader::Reader], and allows
+/// real-time RINEX production without buffering a complete [crate::Rinex].
+/// Supported formats are Observation RINEX (CRINEX is compressed on the fly,
+/// when [Header] describes it), Meteo and Navigation RINEX.
+///
+/// V2/V3 Navigation: system time offsets and ionosphere models are described
+/// in the [Header] in these revisions. The [Header] is therefore formatted along the first
+/// ephemeris frame (or when [Writer::finish]ing), and these frames must precede it.
+///
+/// Rolling over (hourly, daily...) simply consists in [Writer::finish]ing
+/// this [Writer] and deploying a new one.
+///
+/// ```
+/// use std::str::FromStr;
+/// use rinex::prelude::*;
+/// use rinex::observation::{EpochFlag, HeaderFields, ObsKey, Observations, SignalObservation};
+///
+/// let t0 = Epoch::from_str("2020-06-25T00:00:00 GPST")
+///     .unwrap();
+///
+/// let c1c = Observable::from_str("C1C")
+///     .unwrap();
+///
+/// let mut fields = HeaderFields::default();
+/// fields.timeof_first_obs = Some(t0);
+/// fields.codes.insert(Constellation::GPS, vec![c1c.clone()]);
+///
+/// let header = Header::basic_obs()
+///     .with_observation_fields(fields);
+///
+/// // any writable I/O interface, like [Writer::to_file]
+/// let mut writer = Writer::new(Vec::new(), &header)
+///     .unwrap();
+///
+/// let g01 = SV::from_str("G01")
+///     .unwrap();
+///
+/// for i in 0..10 {
+///     let key = ObsKey {
+///         epoch: t0 + Duration::from_seconds(30.0 * i as f64),
+///         flag: EpochFlag::Ok,
+///     };
+///
+///     let mut observations = Observations::default();
+///
+///     observations
+///         .signals
+///         .push(SignalObservation::new(g01, c1c.clone(), 20_000_000.0 + i as f64));
+///
+///     writer.write(&RecordEntry::Obs(key, observations))
+///         .unwrap();
+/// }
+///
+/// let bytes = writer.finish()
+///     .unwrap();
+///
+/// // streamed back
+/// let reader = Reader::new(bytes.as_slice())
+///     .unwrap();
+///
+/// assert_eq!(reader.count(), 10);
+/// ```
+pub struct Writer<W: Write> {
+    /// [Header] that was formatted
+    header: Header,
+    /// [BufWriter]
+    writer: BufWriter<W>,
+    /// CRINEX [Compressor], persistent accross epochs
+    compressor: Option<Compressor>,
+    /// Meteo observations of pending epoch
+    meteo: MeteoRecord,
+    /// V2/V3 NAV: [Header] is formatted along the first ephemeris
+    header_pending: bool,
+    /// Completes the I/O interface (like gzip streams), when finishing
+    finalize: Option<fn(&mut W) -> std::io::Result<()>>,
 }
 
-impl<W: Write> BufferedWriter<W> {
-    /// Creates new Readable [BufferedWriter]
-    pub fn plain(w: W) -> Self {
-        Self::Plain(BufWriter::new(w))
+impl<W: Write> Writer<W> {
+    /// Builds a new [Writer] by formatting this [Header] into writable I/O interface.
+    pub fn new(writer: W, header: &Header) -> Result<Self, FormattingError> {
+        let mut writer = BufWriter::new(writer);
+
+        let header_pending =
+            header.rinex_type == RinexType::NavigationData && header.version.major < 4;
+
+        if !header_pending {
+            header.format(&mut writer)?;
+        }
+
+        let compressor = if header.is_crinex() {
+            let mut compressor = Compressor::default();
+            compressor.v3 = header.version.major > 2;
+            Some(compressor)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            writer,
+            compressor,
+            header_pending,
+            header: header.clone(),
+            meteo: Default::default(),
+            finalize: None,
+        })
     }
-    #[cfg(feature = "flate2")]
-    /// Creates new [BufferedWriter] to streamed gzip encoded content with
-    /// desired compression level. The higher the order, the lower the performance.
-    pub fn gzip(w: W, compression_level: u32) -> Self {
-        Self::Gz(BufWriter::new(GzEncoder::new(
-            w,
-            Compression::new(compression_level),
-        )))
+
+    /// Returns [Header] that was (or will be) formatted.
+    pub fn header(&self) -> &Header {
+        &self.header
     }
-}
 
-impl<W: Write> Write for BufferedWriter<W> {
-    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
-        match self {
-            Self::Plain(ref mut writer) => writer.write(buf),
-            #[cfg(feature = "flate2")]
-            Self::Gz(ref mut writer) => writer.write(buf),
+    /// Formats a new [RecordEntry], which must follow the previous one in chronological order.
+    pub fn write(&mut self, entry: &RecordEntry) -> Result<(), FormattingError> {
+        match (self.header.rinex_type, entry) {
+            (RinexType::ObservationData, RecordEntry::Obs(k, v)) => {
+                let obs = self
+                    .header
+                    .obs
+                    .as_ref()
+                    .ok_or(FormattingError::MissingObservableDefinition)?;
+
+                // Compressed format (non readable yet still ASCII)
+                // following the Hatanaka Compression algorithm.
+                if let Some(compressor) = &mut self.compressor {
+                    compressor.format_epoch(&mut self.writer, k, v, obs)?;
+                } else {
+                    v.format(self.header.version.major == 2, k, obs, &mut self.writer)?;
+                }
+
+                Ok(())
+            },
+            (RinexType::MeteoData, RecordEntry::Meteo(k, v)) => {
+                // epoch is formatted once completed
+                if let Some(pending) = self.meteo_pending_epoch() {
+                    if pending != k.epoch {
+                        self.format_meteo_epoch(pending)?;
+                    }
+                }
+
+                self.meteo.insert(k.clone(), *v);
+                Ok(())
+            },
+            (RinexType::NavigationData, RecordEntry::Nav(k, v)) => {
+                if self.header.version.major < 4 {
+                    match v {
+                        NavFrame::EPH(_) => {},
+                        NavFrame::STO(_) | NavFrame::ION(_) => {
+                            if !self.header_pending {
+                                return Err(FormattingError::NavHeaderAlreadyFormatted);
+                            }
+                            v2v3_latch_frame(&mut self.header, k, v);
+                            return Ok(());
+                        },
+                        // not described in V2/V3
+                        _ => return Ok(()),
+                    }
+                }
+
+                self.format_pending_header()?;
+                format_nav_frame(&mut self.writer, k, v, &self.header)
+            },
+            _ => Err(FormattingError::UnsupportedRecordEntry),
         }
     }
-    fn flush(&mut self) -> Result<(), Error> {
-        match self {
-            Self::Plain(ref mut writer) => writer.flush(),
-            #[cfg(feature = "flate2")]
-            Self::Gz(ref mut writer) => writer.flush(),
+
+    /// Formats pending content, flushes, completes and returns the inner I/O interface.
+    pub fn finish(mut self) -> Result<W, FormattingError> {
+        self.format_pending_header()?;
+
+        if let Some(pending) = self.meteo_pending_epoch() {
+            self.format_meteo_epoch(pending)?;
         }
+
+        let mut inner = self
+            .writer
+            .into_inner()
+            .map_err(|e| FormattingError::OutputError(e.into_error()))?;
+
+        if let Some(finalize) = self.finalize {
+            finalize(&mut inner)?;
+        }
+
+        Ok(inner)
+    }
+
+    /// Formats the [Header], if still pending
+    fn format_pending_header(&mut self) -> Result<(), FormattingError> {
+        if self.header_pending {
+            self.header.format(&mut self.writer)?;
+            self.header_pending = false;
+        }
+        Ok(())
+    }
+
+    /// Returns [Epoch] of pending Meteo observations
+    fn meteo_pending_epoch(&self) -> Option<Epoch> {
+        self.meteo.keys().next().map(|k| k.epoch)
+    }
+
+    /// Formats pending Meteo observations
+    fn format_meteo_epoch(&mut self, epoch: Epoch) -> Result<(), FormattingError> {
+        format_meteo_epoch(&mut self.writer, epoch, &self.meteo, &self.header)?;
+        self.meteo.clear();
+        Ok(())
+    }
+}
+
+impl Writer<File> {
+    /// Builds a new [Writer] to local file.
+    pub fn to_file<P: AsRef<Path>>(path: P, header: &Header) -> Result<Self, FormattingError> {
+        let fd = File::create(path)?;
+        Self::new(fd, header)
+    }
+}
+
+#[cfg(feature = "flate2")]
+#[cfg_attr(docsrs, doc(cfg(feature = "flate2")))]
+impl<W: Write> Writer<GzEncoder<W>> {
+    /// Builds a new [Writer] that gzip compresses into writable I/O interface, with desired
+    /// compression level. The higher the level, the lower the performance.
+    /// [Writer::finish] completes the gzip stream.
+    pub fn new_gzip(
+        writer: W,
+        header: &Header,
+        compression_level: u32,
+    ) -> Result<Self, FormattingError> {
+        let mut writer = Self::new(
+            GzEncoder::new(writer, Compression::new(compression_level)),
+            header,
+        )?;
+
+        writer.finalize = Some(GzEncoder::try_finish);
+        Ok(writer)
+    }
+}
+
+#[cfg(feature = "flate2")]
+#[cfg_attr(docsrs, doc(cfg(feature = "flate2")))]
+impl Writer<GzEncoder<File>> {
+    /// Builds a new [Writer] to local gzip compressed file, with desired
+    /// compression level. The higher the level, the lower the performance.
+    /// [Writer::finish] completes the gzip stream.
+    pub fn to_gzip_file<P: AsRef<Path>>(
+        path: P,
+        header: &Header,
+        compression_level: u32,
+    ) -> Result<Self, FormattingError> {
+        let fd = File::create(path)?;
+        Self::new_gzip(fd, header, compression_level)
     }
 }